@@ -7,7 +7,7 @@ struct Actor {
     name: String,
 }
 
-#[allow(unused_mut)]
+#[allow(unused_mut, unused_variables)]
 fn main() {
     smol::block_on(async {
         let actor1 = Actor {
@@ -29,7 +29,7 @@ fn main() {
 
             smol::spawn(async move {
                 tx.unbounded_send(i).expect("Failed to send");
-                let _ = rx.try_next();
+                let _ = rx.try_recv();
             })
             .detach();
         }
@@ -46,7 +46,7 @@ fn main() {
 
             smol::spawn(async move {
                 tx.try_send(i).expect("Failed to send");
-                let _ = rx.try_next();
+                let _ = rx.try_recv();
             })
             .detach();
         }
@@ -41,7 +41,8 @@ fn main() {
 
         println!("\nStream example completed!");
 
-        // Give stats collector time to process final events
-        Timer::after(Duration::from_millis(100)).await;
+        // Give stats collector time to process final events, and keep the
+        // metrics server reachable long enough for the HTTP endpoint tests
+        Timer::after(Duration::from_millis(2000)).await;
     })
 }
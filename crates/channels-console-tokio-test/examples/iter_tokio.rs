@@ -3,7 +3,7 @@ struct Actor {
     name: String,
 }
 
-#[allow(unused_mut)]
+#[allow(unused_mut, unused_variables)]
 #[tokio::main]
 async fn main() {
     let actor1 = Actor {
@@ -0,0 +1,61 @@
+use tokio::time::{sleep, Duration};
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    println!("Pipeline Example:");
+    println!("- raw-lines -> parsed-numbers -> totals\n");
+
+    let (raw_tx, mut raw_rx) = tokio::sync::mpsc::channel::<String>(10);
+    #[cfg(feature = "channels-console")]
+    let (raw_tx, mut raw_rx) = channels_console::channel!(
+        (raw_tx, raw_rx),
+        label = "raw-lines",
+        links = ["parsed-numbers"]
+    );
+
+    let (parsed_tx, mut parsed_rx) = tokio::sync::mpsc::unbounded_channel::<u64>();
+    #[cfg(feature = "channels-console")]
+    let (parsed_tx, mut parsed_rx) = channels_console::channel!(
+        (parsed_tx, parsed_rx),
+        links = ["totals"],
+        label = "parsed-numbers"
+    );
+
+    let (totals_tx, totals_rx) = tokio::sync::oneshot::channel::<u64>();
+    #[cfg(feature = "channels-console")]
+    let (totals_tx, totals_rx) =
+        channels_console::channel!((totals_tx, totals_rx), label = "totals");
+
+    let parser = tokio::spawn(async move {
+        while let Some(line) = raw_rx.recv().await {
+            if let Ok(n) = line.parse() {
+                parsed_tx.send(n).expect("Failed to send");
+            }
+        }
+    });
+
+    let summer = tokio::spawn(async move {
+        let mut total = 0;
+        while let Some(n) = parsed_rx.recv().await {
+            total += n;
+        }
+        let _ = totals_tx.send(total);
+    });
+
+    for i in 1..=5 {
+        raw_tx.send(i.to_string()).await.expect("Failed to send");
+        sleep(Duration::from_millis(50)).await;
+    }
+    drop(raw_tx);
+
+    parser.await.expect("Parser task failed");
+    summer.await.expect("Summer task failed");
+    println!(
+        "Total: {}",
+        totals_rx.await.expect("Failed to receive total")
+    );
+
+    // Keep the metrics server reachable for inspection
+    sleep(Duration::from_millis(2000)).await;
+}
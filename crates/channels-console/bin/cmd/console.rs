@@ -1,6 +1,28 @@
 pub(crate) mod app;
+pub(crate) mod columns;
+pub(crate) mod diff;
+pub(crate) mod export;
+pub(crate) mod filter;
+pub(crate) mod grouping;
 pub(crate) mod http;
+pub(crate) mod keymap;
+pub(crate) mod replay;
+pub(crate) mod settings;
+pub(crate) mod snapshot;
+pub(crate) mod sort;
+pub(crate) mod source;
+pub(crate) mod split;
+pub(crate) mod theme;
+pub(crate) mod top;
+pub(crate) mod trend;
 pub(crate) mod views;
+pub(crate) mod watch;
 pub(crate) mod widgets;
 
 pub use app::ConsoleArgs;
+pub use diff::DiffArgs;
+pub use replay::ReplayArgs;
+pub use snapshot::SnapshotArgs;
+pub use source::Target;
+pub use top::TopArgs;
+pub use watch::WatchArgs;
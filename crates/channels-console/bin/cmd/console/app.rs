@@ -1,19 +1,67 @@
-use channels_console::{ChannelLogs, LogEntry, SerializableChannelStats};
+use channels_console::actors::ChannelActorsJson;
+use channels_console::alerts::Alert;
+use channels_console::config::FileConfig;
+use channels_console::control::CollectionMode;
+use channels_console::correlation::Journey;
+use channels_console::info::InfoJson;
+use channels_console::labels::{label_depth, matches_prefix};
+use channels_console::latency::LatencyHistogramJson;
+use channels_console::samples::ChannelHistoryJson;
+use channels_console::summary::Summary;
+use channels_console::{
+    ChannelLogs, ChannelState, ChannelStates, ChannelsJson, CombinedJson, LogEntry, LogQuery,
+    SerializableChannelStats, SerializableStreamStats,
+};
 use clap::Parser;
+use crossterm::clipboard::CopyToClipboard;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::execute;
 use eyre::Result;
 use ratatui::{
     layout::{Constraint, Layout},
     widgets::TableState,
     DefaultTerminal, Frame,
 };
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use std::{collections::HashMap, io};
 
-use super::http::{fetch_channel_logs, fetch_channels};
+use super::columns::{Column, Columns};
+use super::export::{write_export, ChannelExport};
+use super::filter::Filter;
+use super::grouping::Grouping;
+use super::http::{base_url, connect, schema_notice, version_notice};
+use super::keymap::KeyMap;
+use super::replay::Replay;
+use super::settings::{load_settings, ConsoleSettings, LayoutSettings};
+use super::sort::Sort;
+use super::source::{Source, Target};
+use super::split::{SplitDirection, SplitRatio};
+use super::theme::Theme;
+use super::trend::QueueHistory;
+use super::views::alerts_bar::render_alerts_bar;
 use super::views::bottom_bar::render_bottom_bar;
+use super::views::detail::render_detail_view;
+use super::views::help::render_help_popup;
+use super::views::inspect::{message_text, InspectState};
 use super::views::main_view::render_main_view;
+use super::views::stalled_bar::render_stalled_bar;
+use super::views::summary_bar::render_summary_bar;
+use super::views::toast::render_toast;
 use super::views::top_bar::render_top_bar;
+use super::widgets::json::{json_depth, message_json};
+
+/// How far `[` and `]` seek in a replayed recording
+const SEEK_STEP_MS: f64 = 10_000.0;
+
+/// Max number of sent and received entries kept in the logs panel scrollback each
+const SCROLLBACK_LIMIT: usize = 1000;
+
+/// Number of log entries `PageUp` and `PageDown` move by
+const LOGS_PAGE: usize = 10;
+
+/// How long notifications like the path of an export are shown
+const TOAST_DURATION: Duration = Duration::from_secs(4);
 
 /// Represents which UI component has focus
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,28 +69,211 @@ pub(crate) enum Focus {
     Channels,
     Logs,
     Inspect,
+    /// Full-screen view of the selected channel
+    Detail,
+}
+
+/// State of the connection to the metrics server, shown in the footer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Connection {
+    /// No response received yet
+    Connecting,
+    Connected,
+    /// The last request failed
+    Disconnected,
+}
+
+/// Notification shown in the corner of the screen for `TOAST_DURATION`
+struct Toast {
+    message: String,
+    is_error: bool,
+    shown_at: Instant,
+}
+
+impl Toast {
+    fn new(message: String, is_error: bool) -> Self {
+        Self {
+            message,
+            is_error,
+            shown_at: Instant::now(),
+        }
+    }
 }
 
-/// Cached logs with a lookup map for received entries
+/// Which log entries the logs panel lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum LogsView {
+    #[default]
+    Sent,
+    Received,
+    /// Sent and received entries merged by time
+    Interleaved,
+}
+
+impl LogsView {
+    fn next(self) -> Self {
+        match self {
+            Self::Sent => Self::Received,
+            Self::Received => Self::Interleaved,
+            Self::Interleaved => Self::Sent,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Sent => "Sent",
+            Self::Received => "Received",
+            Self::Interleaved => "Interleaved",
+        }
+    }
+}
+
+/// Whether a log entry was recorded on the send or the receive side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Sent,
+    Received,
+}
+
+/// Cached logs with lookup maps for matching sent and received entries
 pub(crate) struct CachedLogs {
+    channel_id: u64,
+    /// Entries of all fetches since the channel was selected, most recent first, so entries the
+    /// server doesn't retain anymore can still be scrolled back to
     pub(crate) logs: ChannelLogs,
+    pub(crate) sent_map: HashMap<u64, LogEntry>,
     pub(crate) received_map: HashMap<u64, LogEntry>,
 }
 
+impl CachedLogs {
+    fn new(channel_id: u64, logs: ChannelLogs) -> Self {
+        let sent_map = logs
+            .sent_logs
+            .iter()
+            .map(|entry| (entry.index, entry.clone()))
+            .collect();
+        let received_map = logs
+            .received_logs
+            .iter()
+            .map(|entry| (entry.index, entry.clone()))
+            .collect();
+        Self {
+            channel_id,
+            logs,
+            sent_map,
+            received_map,
+        }
+    }
+
+    /// Adds the entries of a newer fetch to the scrollback
+    fn merge(self, newer: ChannelLogs) -> Self {
+        let mut logs = self.logs;
+        merge_entries(&mut logs.sent_logs, newer.sent_logs);
+        merge_entries(&mut logs.received_logs, newer.received_logs);
+        Self::new(self.channel_id, logs)
+    }
+
+    /// Entries listed in `view`, most recent first
+    pub(crate) fn entries(&self, view: LogsView) -> Vec<(Direction, &LogEntry)> {
+        let sent = self
+            .logs
+            .sent_logs
+            .iter()
+            .map(|entry| (Direction::Sent, entry));
+        let received = self
+            .logs
+            .received_logs
+            .iter()
+            .map(|entry| (Direction::Received, entry));
+
+        match view {
+            LogsView::Sent => sent.collect(),
+            LogsView::Received => received.collect(),
+            LogsView::Interleaved => {
+                // Receives come first on ties, as they can't precede their send
+                let mut entries: Vec<_> = received.chain(sent).collect();
+                entries.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.timestamp));
+                entries
+            }
+        }
+    }
+
+    fn entry(&self, view: LogsView, i: usize) -> Option<LogEntry> {
+        self.entries(view).get(i).map(|(_, entry)| (*entry).clone())
+    }
+
+    /// Position of an entry in `view`
+    fn position(&self, view: LogsView, direction: Direction, index: u64) -> Option<usize> {
+        self.entries(view)
+            .iter()
+            .position(|(d, entry)| *d == direction && entry.index == index)
+    }
+}
+
+/// Replaces entries with their newer version, and drops the oldest ones beyond the scrollback
+/// limit
+fn merge_entries(entries: &mut Vec<LogEntry>, newer: Vec<LogEntry>) {
+    entries.retain(|entry| newer.iter().all(|newer| newer.index != entry.index));
+    entries.extend(newer);
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.timestamp));
+    entries.truncate(SCROLLBACK_LIMIT);
+}
+
 #[derive(Debug, Parser)]
 pub struct ConsoleArgs {
-    /// Port for the metrics server
-    #[arg(long, default_value = "6770")]
-    pub metrics_port: u16,
+    /// Port for the metrics server [default: 6770]
+    #[arg(long)]
+    pub metrics_port: Option<u16>,
+
+    /// Host of the metrics server [default: 127.0.0.1]
+    #[arg(long)]
+    pub host: Option<String>,
+
+    /// Base URL of the metrics server, e.g. `http://10.0.0.5:6770`. Takes precedence over
+    /// `--host` and `--metrics-port`. Can also be set with `CHANNELS_CONSOLE_URL`
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Metrics server of a monitored process as `name=url`, e.g. `app1=10.0.0.5:6770`.
+    /// Repeat to monitor several processes and switch between them with <Tab>. Takes
+    /// precedence over `--url`
+    #[arg(long = "target", value_name = "NAME=URL")]
+    pub targets: Vec<Target>,
+
+    /// Bearer token sent to metrics servers started with `CHANNELS_CONSOLE_TOKEN`. Can also
+    /// be set with `CHANNELS_CONSOLE_TOKEN`
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Unix domain socket of a metrics server started with `CHANNELS_CONSOLE_METRICS_SOCKET`.
+    /// Takes precedence over `--url` and `--target`
+    #[arg(long)]
+    pub socket: Option<PathBuf>,
+
+    /// Only show channels with labels under this path prefix, e.g. `ingest/parser`
+    #[arg(long)]
+    pub prefix: Option<String>,
+
+    /// Console config file with key remaps, theme and saved layout
+    /// [default: ~/.config/channels-console/config.toml]
+    #[arg(long)]
+    pub config: Option<PathBuf>,
 }
 
 pub(crate) struct App {
+    /// Channels shown in the table, filtered and sorted
     stats: Vec<SerializableChannelStats>,
+    /// All channels of the last refresh
+    all_stats: Vec<SerializableChannelStats>,
+    /// Response of the last refresh before filtering by prefix, following refreshes only fetch
+    /// the channels that changed since
+    channels: Option<ChannelsJson>,
     error: Option<String>,
     exit: bool,
     last_refresh: Instant,
     last_successful_fetch: Option<Instant>,
-    metrics_port: u16,
+    source: Source,
+    refresh_interval: Duration,
     last_render_duration: Duration,
     table_state: TableState,
     logs_table_state: TableState,
@@ -51,26 +282,140 @@ pub(crate) struct App {
     logs: Option<CachedLogs>,
     paused: bool,
     inspected_log: Option<LogEntry>,
-    agent: ureq::Agent,
+    inspected_journey: Option<Journey>,
+    inspect_state: InspectState,
+    /// Whether the queue depth chart of the selected channel is shown, toggled with `d`
+    show_chart: bool,
+    chart_history: Option<ChannelHistoryJson>,
     current_elapsed_ns: u64,
+    schema_notice: Option<String>,
+    /// Process and library version of the server, fetched once per connection
+    server_info: Option<InfoJson>,
+    /// Whether the server payloads were checked to be readable on this connection
+    compatibility_checked: bool,
+    /// Whether the server stopped collecting per-message details, see `POST /control/pause`
+    collection_paused: bool,
+    /// Whether `r` was pressed and resetting the statistics awaits confirmation
+    confirming_reset: bool,
+    prefix: Option<String>,
+    collapse_level: usize,
+    split: SplitDirection,
+    split_ratio: SplitRatio,
+    active_alerts: Vec<Alert>,
+    stalled_streams: Vec<SerializableStreamStats>,
+    /// Streams listed below the channels in the table, before and after filtering
+    all_streams: Vec<SerializableStreamStats>,
+    streams: Vec<SerializableStreamStats>,
+    sort: Sort,
+    filter: Filter,
+    /// Text the logs panel is narrowed down to, entered after pressing `/` in the panel
+    log_search: Filter,
+    queue_history: QueueHistory,
+    /// State transitions of the channel shown in the detail view
+    states: Option<ChannelStates>,
+    /// Latency distribution of the channel shown in the detail view
+    latency_histogram: Option<LatencyHistogramJson>,
+    actors: Option<ChannelActorsJson>,
+    logs_view: LogsView,
+    grouping: Grouping,
+    /// Optional columns shown in the channels table
+    columns: Columns,
+    /// Row selected in the columns popup opened with `v`, `None` while it's closed
+    column_chooser: Option<usize>,
+    key_map: KeyMap,
+    theme: Theme,
+    /// Whether the help popup opened with `?` is shown
+    show_help: bool,
+    /// Whether the logs panel keeps the newest entry selected, toggled with `f`
+    follow_logs: bool,
+    /// Whether log entries show the wall-clock time instead of their age, toggled with `w`
+    wall_clock: bool,
+    toast: Option<Toast>,
+    /// Processes given with `--target`, the source points at `targets[target_idx]`
+    targets: Vec<Target>,
+    target_idx: usize,
 }
 
 impl ConsoleArgs {
     pub fn run(&self) -> Result<()> {
-        let config = ureq::Agent::config_builder()
-            .timeout_connect(Some(Duration::from_millis(2000)))
-            .timeout_recv_body(Some(Duration::from_millis(1500)))
-            .build();
+        self.run_sorted(None)
+    }
+
+    /// Starts the TUI with the layout saved on the previous exit, and the channels table sorted
+    /// by `sort` if given instead of the saved order
+    pub(crate) fn run_sorted(&self, sort: Option<Sort>) -> Result<()> {
+        // CLI arguments take precedence over the [console] section of the config file
+        let file_config = match FileConfig::from_env() {
+            Some(result) => result.map_err(|e| eyre::eyre!("Invalid config file: {}", e))?,
+            None => FileConfig::default(),
+        };
+        let base_url = match self.targets.first() {
+            Some(target) => target.base_url.clone(),
+            None => base_url(
+                self.url.clone(),
+                self.host.clone(),
+                self.metrics_port,
+                &file_config.console,
+            ),
+        };
+        let refresh_interval = std::env::var("CHANNELS_CONSOLE_TUI_REFRESH_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .or(file_config.console.refresh_ms)
+            .map(Duration::from_millis)
+            .unwrap_or(Duration::from_millis(200));
+
+        let client = connect(
+            base_url,
+            self.socket.clone(),
+            self.token.clone(),
+            &file_config.console,
+        );
+
+        let (settings_path, settings) = load_settings(self.config.as_ref())?;
+        let saved_layout = settings.layout.clone();
+
+        let source = Source::Http(client);
+        let mut app = App::new(source, refresh_interval, self.prefix.clone())
+            .with_targets(self.targets.clone())
+            .with_settings(&settings)?;
+        if let Some(sort) = sort {
+            app = app.with_sort(sort);
+        }
 
-        let agent: ureq::Agent = config.into();
+        let mut terminal = ratatui::init();
+        let app_result = app.run(&mut terminal);
+        ratatui::restore();
+
+        if let Some(path) = settings_path {
+            let mut layout = app.layout();
+            // An explicit order, like the one of `top`, isn't remembered
+            if sort.is_some() {
+                layout.sort = saved_layout.sort;
+                layout.reversed = saved_layout.reversed;
+            }
+            if layout != saved_layout {
+                if let Err(e) = layout.save(&path) {
+                    eprintln!("Failed to save layout to {}: {}", path.display(), e);
+                }
+            }
+        }
+        app_result.map_err(|e| eyre::eyre!("TUI error: {}", e))
+    }
+}
 
-        let mut app = App {
+impl App {
+    pub(crate) fn new(source: Source, refresh_interval: Duration, prefix: Option<String>) -> Self {
+        Self {
             stats: Vec::new(),
+            all_stats: Vec::new(),
+            channels: None,
             error: None,
             exit: false,
             last_refresh: Instant::now(),
             last_successful_fetch: None,
-            metrics_port: self.metrics_port,
+            source,
+            refresh_interval,
             last_render_duration: Duration::from_millis(0),
             table_state: TableState::default().with_selected(0),
             logs_table_state: TableState::default(),
@@ -79,29 +424,99 @@ impl ConsoleArgs {
             logs: None,
             paused: false,
             inspected_log: None,
-            agent,
+            inspected_journey: None,
+            inspect_state: InspectState::default(),
+            show_chart: false,
+            chart_history: None,
             current_elapsed_ns: 0,
+            schema_notice: None,
+            server_info: None,
+            compatibility_checked: false,
+            prefix,
+            collapse_level: 0,
+            split: SplitDirection::default(),
+            split_ratio: SplitRatio::default(),
+            collection_paused: false,
+            confirming_reset: false,
+            active_alerts: Vec::new(),
+            stalled_streams: Vec::new(),
+            all_streams: Vec::new(),
+            streams: Vec::new(),
+            sort: Sort::default(),
+            filter: Filter::default(),
+            log_search: Filter::default(),
+            queue_history: QueueHistory::default(),
+            states: None,
+            latency_histogram: None,
+            actors: None,
+            logs_view: LogsView::default(),
+            grouping: Grouping::default(),
+            columns: Columns::default(),
+            column_chooser: None,
+            key_map: KeyMap::default(),
+            theme: Theme::default(),
+            show_help: false,
+            follow_logs: false,
+            wall_clock: false,
+            toast: None,
+            targets: Vec::new(),
+            target_idx: 0,
+        }
+    }
+
+    pub(crate) fn with_targets(mut self, targets: Vec<Target>) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    pub(crate) fn with_sort(mut self, sort: Sort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Applies the key remaps, theme and layout of the console config file
+    pub(crate) fn with_settings(mut self, settings: &ConsoleSettings) -> Result<Self> {
+        self.key_map = KeyMap::new(&settings.keys)
+            .map_err(|e| eyre::eyre!("Invalid [keys] in config file: {}", e))?;
+        self.theme = Theme::new(&settings.theme)
+            .map_err(|e| eyre::eyre!("Invalid [theme] in config file: {}", e))?;
+        Ok(self.with_layout(&settings.layout))
+    }
+
+    /// Restores the columns, sort order and filters saved on a previous exit
+    fn with_layout(mut self, layout: &LayoutSettings) -> Self {
+        self.columns = layout.columns.clone();
+        self.sort = Sort {
+            column: layout.sort,
+            reversed: layout.reversed,
+            frozen: false,
         };
+        self.filter = Filter::new(layout.filter.clone(), layout.hide_closed);
+        self.split = layout.split;
+        self.split_ratio = layout.split_ratio;
+        self.wall_clock = layout.wall_clock;
+        self
+    }
 
-        let mut terminal = ratatui::init();
-        let app_result = app.run(&mut terminal);
-        ratatui::restore();
-        app_result.map_err(|e| eyre::eyre!("TUI error: {}", e))
+    /// Columns, sort order, filters and panes to save on exit
+    pub(crate) fn layout(&self) -> LayoutSettings {
+        LayoutSettings {
+            columns: self.columns.clone(),
+            sort: self.sort.column,
+            reversed: self.sort.reversed,
+            filter: self.filter.query().to_string(),
+            hide_closed: self.filter.hides_closed(),
+            split: self.split,
+            split_ratio: self.split_ratio,
+            wall_clock: self.wall_clock,
+        }
     }
-}
 
-impl App {
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        let refresh_interval = std::env::var("CHANNELS_CONSOLE_TUI_REFRESH_MS")
-            .ok()
-            .and_then(|s| s.parse::<u64>().ok())
-            .map(Duration::from_millis)
-            .unwrap_or(Duration::from_millis(200));
-
         self.refresh_data();
 
         while !self.exit {
-            if !self.paused && self.last_refresh.elapsed() >= refresh_interval {
+            if !self.paused && self.last_refresh.elapsed() >= self.refresh_interval {
                 self.refresh_data();
             }
 
@@ -115,50 +530,143 @@ impl App {
     }
 
     fn refresh_data(&mut self) {
-        let selected_channel_id = self
-            .table_state
-            .selected()
-            .and_then(|idx| self.stats.get(idx))
-            .map(|stat| stat.id);
+        let selected_channel_id = self.selected_channel_id();
+
+        if !self.compatibility_checked {
+            if let Err(e) = self.source.check_compatibility() {
+                self.error = Some(format!("Failed to fetch metrics: {}", e));
+                self.last_refresh = Instant::now();
+                return;
+            }
+            self.compatibility_checked = true;
+        }
 
-        match fetch_channels(&self.agent, self.metrics_port) {
-            Ok(channels) => {
+        match self.source.channels(self.channels.as_ref()) {
+            Ok(mut channels) => {
+                self.channels = Some(channels.clone());
+                if let Some(prefix) = &self.prefix {
+                    channels.retain_prefix(prefix);
+                }
                 self.current_elapsed_ns = channels.current_elapsed_ns;
-                self.stats = channels.channels;
+                // Servers predating `/info` respond with 404 and stay unidentified
+                if self.server_info.is_none() && !self.source.is_replay() {
+                    self.server_info = self.source.info().ok();
+                }
+                self.schema_notice = schema_notice(channels.schema_version)
+                    .or_else(|| self.server_info.as_ref().and_then(version_notice));
+                self.all_stats = channels.channels;
+                // Recordings predating streams and failed requests leave the table channels only
+                self.all_streams = match self.source.streams() {
+                    Ok(mut streams) => {
+                        if let Some(prefix) = &self.prefix {
+                            streams.retain_prefix(prefix);
+                        }
+                        streams.streams
+                    }
+                    Err(_) => Vec::new(),
+                };
+                self.queue_history.record(&self.all_stats);
+                self.update_rows(selected_channel_id);
                 self.error = None;
                 self.last_successful_fetch = Some(Instant::now());
 
-                // Try to restore selection to the same channel ID
-                if let Some(channel_id) = selected_channel_id {
-                    // Find the new index of the previously selected channel
-                    if let Some(new_idx) = self.stats.iter().position(|stat| stat.id == channel_id)
-                    {
-                        self.table_state.select(Some(new_idx));
-                    } else {
-                        // Channel no longer exists, select the last one if available
-                        if !self.stats.is_empty() {
-                            self.table_state.select(Some(self.stats.len() - 1));
-                        }
-                    }
-                } else if let Some(selected) = self.table_state.selected() {
-                    if selected >= self.stats.len() && !self.stats.is_empty() {
-                        self.table_state.select(Some(self.stats.len() - 1));
-                    }
+                if self.logs_visible() {
+                    self.refresh_logs();
+                }
+                if self.focus == Focus::Detail {
+                    self.refresh_states();
+                }
+                if self.show_chart {
+                    self.refresh_chart();
+                }
+                if self.focus == Focus::Inspect {
+                    self.refresh_journey();
                 }
 
-                if self.show_logs {
-                    self.refresh_logs();
+                // Servers predating the collection mode respond without it, so default to full
+                self.collection_paused = self
+                    .source
+                    .summary()
+                    .is_ok_and(|summary| summary.collection_mode == CollectionMode::Paused);
+
+                // Servers predating alerts respond with 404, so errors just clear the banner
+                self.active_alerts = self
+                    .source
+                    .alerts()
+                    .map(|alerts| alerts.active)
+                    .unwrap_or_default();
+                if let Some(prefix) = &self.prefix {
+                    self.active_alerts
+                        .retain(|alert| matches_prefix(&alert.channel_label, prefix));
                 }
+
+                self.stalled_streams = self
+                    .all_streams
+                    .iter()
+                    .filter(|stream| stream.state == ChannelState::Stalled)
+                    .cloned()
+                    .collect();
             }
             Err(e) => {
                 self.error = Some(format!("Failed to fetch metrics: {}", e));
+                // The process may be restarted with another version
+                self.channels = None;
+                self.server_info = None;
+                self.compatibility_checked = false;
             }
         }
         self.last_refresh = Instant::now();
     }
 
+    fn selected_channel_id(&self) -> Option<u64> {
+        self.table_state
+            .selected()
+            .and_then(|idx| self.stats.get(idx))
+            .map(|stat| stat.id)
+    }
+
+    /// Filters and sorts the table rows, keeping the selected channel if it's still shown
+    fn update_rows(&mut self, selected_channel_id: Option<u64>) {
+        // A frozen order keeps rows where they were, new channels go last
+        let frozen_order: Option<HashMap<u64, usize>> = self.sort.frozen.then(|| {
+            self.stats
+                .iter()
+                .enumerate()
+                .map(|(idx, stat)| (stat.id, idx))
+                .collect()
+        });
+
+        self.stats = self.all_stats.clone();
+        self.streams = self.all_streams.clone();
+        self.filter.apply(&mut self.stats, &mut self.streams);
+        match frozen_order {
+            Some(order) => self
+                .stats
+                .sort_by_key(|stat| order.get(&stat.id).copied().unwrap_or(usize::MAX)),
+            None => self.sort.apply(&mut self.stats),
+        }
+
+        // Try to restore selection to the same channel ID
+        if let Some(channel_id) = selected_channel_id {
+            // Find the new index of the previously selected channel
+            if let Some(new_idx) = self.stats.iter().position(|stat| stat.id == channel_id) {
+                self.table_state.select(Some(new_idx));
+            } else {
+                // Channel no longer exists, select the last one if available
+                if !self.stats.is_empty() {
+                    self.table_state.select(Some(self.stats.len() - 1));
+                }
+            }
+        } else if let Some(selected) = self.table_state.selected() {
+            if selected >= self.stats.len() && !self.stats.is_empty() {
+                self.table_state.select(Some(self.stats.len() - 1));
+            }
+        }
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
         self.render_ui(frame);
+        self.theme.apply(frame.buffer_mut());
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
@@ -166,6 +674,11 @@ impl App {
             if let Event::Key(key_event) = event::read()? {
                 if key_event.kind == KeyEventKind::Press {
                     self.handle_key_event(key_event);
+                    let inspected_id = self.inspected_log.as_ref().and_then(|e| e.correlation_id);
+                    let journey_id = self.inspected_journey.as_ref().map(|j| j.correlation_id);
+                    if inspected_id != journey_id {
+                        self.refresh_journey();
+                    }
                 }
             }
         }
@@ -173,15 +686,101 @@ impl App {
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if self.confirming_reset {
+            self.confirming_reset = false;
+            if matches!(key_event.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.reset_stats();
+            }
+            return;
+        }
+        if self.filter.is_editing() {
+            self.handle_filter_key_event(key_event);
+            return;
+        }
+        if self.log_search.is_editing() {
+            self.handle_log_search_key_event(key_event);
+            return;
+        }
+        // Typed text isn't remapped, so it's translated after the input modes
+        let key_event = self.key_map.translate(key_event);
+        if self.show_help {
+            self.handle_help_key_event(key_event);
+            return;
+        }
+        if self.column_chooser.is_some() {
+            self.handle_columns_key_event(key_event);
+            return;
+        }
+        if self.focus == Focus::Detail {
+            self.handle_detail_key_event(key_event);
+            return;
+        }
+
         match key_event.code {
-            KeyCode::Char('q') | KeyCode::Char('Q') => self.exit(),
-            KeyCode::Char('o') | KeyCode::Char('O') => match self.focus {
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') if self.grouped() => {
+                self.grouping.toggle_selected(&self.stats)
+            }
+            KeyCode::Up | KeyCode::Char('k') if self.grouped() => self.grouping.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') if self.grouped() => {
+                self.grouping.select_next(&self.stats)
+            }
+            // Logs and details are per channel, so they're off while grouped
+            KeyCode::Char('o' | 'i' | 't') if self.grouped() => {}
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Char('?') => self.show_help = true,
+            KeyCode::Char('e') => self.export(),
+            KeyCode::Char('y') if self.focus == Focus::Inspect => self.copy_inspected_message(),
+            KeyCode::Tab => self.switch_target(1),
+            KeyCode::BackTab => self.switch_target(self.targets.len().saturating_sub(1)),
+            KeyCode::Char('o') => match self.focus {
                 Focus::Inspect => self.close_inspect_and_refocus_channels(),
                 Focus::Logs => self.hide_logs(),
-                Focus::Channels => self.toggle_logs(),
+                Focus::Channels | Focus::Detail => self.toggle_logs(),
             },
-            KeyCode::Char('p') | KeyCode::Char('P') => self.toggle_pause(),
-            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => {
+            KeyCode::Char('p') => self.toggle_pause(),
+            KeyCode::Char('r') if !self.source.is_replay() => self.confirming_reset = true,
+            KeyCode::Char('z') => self.cycle_collapse_level(),
+            KeyCode::Char('g') => self.cycle_grouping(),
+            KeyCode::Char('c') => self.change_filter(Filter::toggle_closed),
+            KeyCode::Char('/') if self.focus == Focus::Logs => self.log_search.start_editing(),
+            KeyCode::Esc if self.focus == Focus::Logs && self.log_search.is_active() => {
+                self.change_log_search(Filter::clear)
+            }
+            KeyCode::Char('/') => self.start_filter(),
+            KeyCode::Esc if self.filter.is_active() => self.change_filter(Filter::clear),
+            KeyCode::Char('s') => self.change_sort(Sort::cycle_column),
+            KeyCode::Char('S') => self.change_sort(Sort::reverse),
+            KeyCode::Char('f') if self.focus == Focus::Logs => self.toggle_follow_logs(),
+            KeyCode::Char('f') => self.change_sort(Sort::toggle_frozen),
+            KeyCode::PageUp if self.focus == Focus::Inspect => {
+                self.inspect_state.scroll = self.inspect_state.scroll.saturating_sub(LOGS_PAGE)
+            }
+            KeyCode::PageDown if self.focus == Focus::Inspect => {
+                self.inspect_state.scroll += LOGS_PAGE
+            }
+            KeyCode::Home if self.focus == Focus::Inspect => self.inspect_state.scroll = 0,
+            // Clamped to the last page when rendered
+            KeyCode::End if self.focus == Focus::Inspect => self.inspect_state.scroll = usize::MAX,
+            KeyCode::Char('-') if self.focus == Focus::Inspect => self.fold_inspected(true),
+            KeyCode::Char('+') | KeyCode::Char('=') if self.focus == Focus::Inspect => {
+                self.fold_inspected(false)
+            }
+            KeyCode::PageUp if self.focus == Focus::Logs => {
+                self.select_log(|i, _| i.saturating_sub(LOGS_PAGE))
+            }
+            KeyCode::PageDown if self.focus == Focus::Logs => self.select_log(|i, _| i + LOGS_PAGE),
+            KeyCode::Home if self.focus == Focus::Logs => self.select_log(|_, _| 0),
+            KeyCode::End if self.focus == Focus::Logs => self.select_log(|_, last| last),
+            KeyCode::Char('v') => self.open_column_chooser(),
+            KeyCode::Char('d') => self.toggle_chart(),
+            KeyCode::Char('|') => self.split = self.split.toggle(),
+            KeyCode::Char('<') => self.split_ratio = self.split_ratio.shrink(),
+            KeyCode::Char('>') => self.split_ratio = self.split_ratio.grow(),
+            KeyCode::Char('[') => self.seek(|replay| replay.seek_by(-SEEK_STEP_MS)),
+            KeyCode::Char(']') => self.seek(|replay| replay.seek_by(SEEK_STEP_MS)),
+            KeyCode::Home => self.seek(Replay::seek_to_start),
+            KeyCode::End => self.seek(Replay::seek_to_end),
+            KeyCode::Left | KeyCode::Char('h') => {
                 if self.focus == Focus::Inspect {
                     self.close_inspect_only();
                 } else {
@@ -189,19 +788,107 @@ impl App {
                 }
             }
             KeyCode::Right | KeyCode::Char('l') => self.focus_logs(),
-            KeyCode::Char('i') | KeyCode::Char('I') => self.toggle_inspect(),
+            KeyCode::Char('i') => self.toggle_inspect(),
+            KeyCode::Char('t') => self.cycle_logs_view(),
+            KeyCode::Char('w') => self.wall_clock = !self.wall_clock,
+            KeyCode::Enter if self.focus == Focus::Channels => self.open_detail(),
             KeyCode::Up | KeyCode::Char('k') => match self.focus {
-                Focus::Channels => self.select_previous_channel(),
+                Focus::Channels | Focus::Detail => self.select_previous_channel(),
                 Focus::Logs | Focus::Inspect => self.select_previous_log(),
             },
             KeyCode::Down | KeyCode::Char('j') => match self.focus {
-                Focus::Channels => self.select_next_channel(),
+                Focus::Channels | Focus::Detail => self.select_next_channel(),
                 Focus::Logs | Focus::Inspect => self.select_next_log(),
             },
             _ => {}
         }
     }
 
+    /// Detail view: navigates between channels without leaving the view
+    fn handle_detail_key_event(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Enter | KeyCode::Esc => self.close_detail(),
+            KeyCode::Char('?') => self.show_help = true,
+            KeyCode::Char('e') => self.export(),
+            KeyCode::Char('p') => self.toggle_pause(),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.select_previous_channel();
+                self.refresh_states();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.select_next_channel();
+                self.refresh_states();
+            }
+            _ => {}
+        }
+    }
+
+    fn open_detail(&mut self) {
+        if self.collapse_level > 0 || self.selected_channel_id().is_none() {
+            return;
+        }
+        self.focus = Focus::Detail;
+        if self.frozen() {
+            self.logs = None;
+        } else {
+            self.refresh_logs();
+        }
+        self.refresh_states();
+    }
+
+    fn close_detail(&mut self) {
+        self.focus = Focus::Channels;
+        self.states = None;
+        self.latency_histogram = None;
+        self.actors = None;
+        if !self.show_logs {
+            self.logs = None;
+        }
+    }
+
+    /// Fetches state transitions, the latency distribution and actors of the selected channel
+    fn refresh_states(&mut self) {
+        if self.frozen() {
+            return;
+        }
+        self.states = self
+            .selected_channel_id()
+            .and_then(|id| self.source.channel_states(id).ok());
+        self.latency_histogram = self
+            .selected_channel_id()
+            .and_then(|id| self.source.latency_histogram(id).ok());
+        self.actors = self
+            .selected_channel_id()
+            .and_then(|id| self.source.channel_actors(id).ok());
+    }
+
+    /// Fetches the queue depth samples of the selected channel for the chart pane
+    fn refresh_chart(&mut self) {
+        if self.frozen() {
+            return;
+        }
+        // Servers predating `/channels/:id/history` respond with 404, so the chart falls back
+        // to the samples taken on each refresh
+        self.chart_history = self
+            .selected_channel_id()
+            .and_then(|id| self.source.channel_history(id).ok());
+    }
+
+    fn toggle_chart(&mut self) {
+        self.show_chart = !self.show_chart;
+        if self.show_chart {
+            self.refresh_chart();
+        } else {
+            self.chart_history = None;
+        }
+    }
+
+    /// Whether logs of the selected channel are on screen, in the side panel or the detail view
+    fn logs_visible(&self) -> bool {
+        self.show_logs || self.focus == Focus::Detail
+    }
+
     fn select_previous_channel(&mut self) {
         if !self.stats.is_empty() {
             let i = match self.table_state.selected() {
@@ -210,11 +897,14 @@ impl App {
             };
             self.table_state.select(Some(i));
 
-            if self.paused && self.show_logs {
+            if self.frozen() && self.logs_visible() {
                 self.logs = None;
-            } else if self.show_logs {
+            } else if self.logs_visible() {
                 self.refresh_logs();
             }
+            if self.show_chart {
+                self.refresh_chart();
+            }
         }
     }
 
@@ -226,11 +916,14 @@ impl App {
             };
             self.table_state.select(Some(i));
 
-            if self.paused && self.show_logs {
+            if self.frozen() && self.logs_visible() {
                 self.logs = None;
-            } else if self.show_logs {
+            } else if self.logs_visible() {
                 self.refresh_logs();
             }
+            if self.show_chart {
+                self.refresh_chart();
+            }
         }
     }
 
@@ -246,7 +939,7 @@ impl App {
                 self.hide_logs();
             } else {
                 self.show_logs = true;
-                if self.paused {
+                if self.frozen() {
                     self.logs = None;
                 } else {
                     self.refresh_logs();
@@ -262,30 +955,51 @@ impl App {
         self.focus = Focus::Channels;
     }
 
+    /// Fetches the logs of the selected channel, adding them to the scrollback of the channel
     fn refresh_logs(&mut self) {
-        if self.paused {
+        if self.frozen() {
             return;
         }
 
-        self.logs = None;
+        let previous = self.logs.take();
+        // The selected entry is kept selected as newer ones are listed above it
+        let selected_entry = self.logs_table_state.selected().and_then(|i| {
+            let cached_logs = previous.as_ref()?;
+            cached_logs
+                .entries(self.logs_view)
+                .get(i)
+                .map(|(direction, entry)| (*direction, entry.index))
+        });
 
         if let Some(selected) = self.table_state.selected() {
             if !self.stats.is_empty() && selected < self.stats.len() {
                 let channel_id = self.stats[selected].id;
-                if let Ok(logs) = fetch_channel_logs(&self.agent, self.metrics_port, channel_id) {
-                    let received_map: std::collections::HashMap<u64, LogEntry> = logs
-                        .received_logs
-                        .iter()
-                        .map(|entry| (entry.index, entry.clone()))
-                        .collect();
-
-                    self.logs = Some(CachedLogs { logs, received_map });
+                if let Ok(mut logs) = self.source.channel_logs(channel_id) {
+                    if self.log_search.is_active() {
+                        logs.apply(&LogQuery {
+                            contains: Some(self.log_search.query().to_string()),
+                            ..LogQuery::default()
+                        });
+                    }
+                    self.logs = Some(match previous {
+                        Some(previous) if previous.channel_id == channel_id => previous.merge(logs),
+                        _ => CachedLogs::new(channel_id, logs),
+                    });
 
                     // Ensure logs table selection is valid
                     if let Some(ref cached_logs) = self.logs {
-                        let log_count = cached_logs.logs.sent_logs.len();
+                        let log_count = cached_logs.entries(self.logs_view).len();
                         if let Some(selected) = self.logs_table_state.selected() {
-                            if selected >= log_count && log_count > 0 {
+                            let position = if self.follow_logs {
+                                Some(0)
+                            } else {
+                                selected_entry.and_then(|(direction, index)| {
+                                    cached_logs.position(self.logs_view, direction, index)
+                                })
+                            };
+                            if let Some(position) = position {
+                                self.logs_table_state.select(Some(position));
+                            } else if selected >= log_count && log_count > 0 {
                                 self.logs_table_state.select(Some(log_count - 1));
                             }
                         }
@@ -295,8 +1009,300 @@ impl App {
         }
     }
 
+    /// Cycles through collapsing hierarchical labels at each level, then back to all channels
+    fn cycle_collapse_level(&mut self) {
+        let max_depth = self
+            .stats
+            .iter()
+            .map(|stat| label_depth(&stat.label))
+            .max()
+            .unwrap_or(1);
+
+        self.collapse_level = (self.collapse_level + 1) % max_depth;
+        if self.collapse_level > 0 {
+            self.grouping.disable();
+            self.close_inspect_and_refocus_channels();
+        }
+    }
+
+    /// Cycles aggregating channels created at the same call site, or sharing a tag, into
+    /// expandable groups
+    fn cycle_grouping(&mut self) {
+        self.grouping.cycle(&self.stats);
+        if self.grouping.is_enabled() {
+            self.collapse_level = 0;
+            self.close_inspect_and_refocus_channels();
+        }
+    }
+
+    /// Whether the table shows groups instead of channels
+    fn grouped(&self) -> bool {
+        self.grouping.is_enabled() && self.collapse_level == 0
+    }
+
+    /// Re-sorts the channels table right away, keeping the selected channel
+    fn change_sort(&mut self, change: impl FnOnce(&mut Sort)) {
+        let selected_channel_id = self.selected_channel_id();
+        change(&mut self.sort);
+        self.update_rows(selected_channel_id);
+    }
+
+    /// Help popup: keys are ignored until it's closed
+    fn handle_help_key_event(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('?') | KeyCode::Esc => self.show_help = false,
+            KeyCode::Char('q') => self.exit(),
+            _ => {}
+        }
+    }
+
+    fn open_column_chooser(&mut self) {
+        self.close_inspect_only();
+        self.column_chooser = Some(0);
+    }
+
+    /// Columns popup: toggled columns are shown or hidden right away
+    fn handle_columns_key_event(&mut self, key_event: KeyEvent) {
+        let Some(selected) = self.column_chooser else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('v') | KeyCode::Esc => self.column_chooser = None,
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.column_chooser = Some(selected.saturating_sub(1))
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.column_chooser = Some((selected + 1).min(Column::ALL.len() - 1))
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => self.columns.toggle(Column::ALL[selected]),
+            _ => {}
+        }
+    }
+
+    /// Search input mode: typed characters edit the query and rows are filtered live
+    fn handle_filter_key_event(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Enter => self.filter.accept(),
+            KeyCode::Esc => self.change_filter(Filter::clear),
+            KeyCode::Backspace => self.change_filter(Filter::pop),
+            KeyCode::Char(c) => self.change_filter(|filter| filter.push(c)),
+            _ => {}
+        }
+    }
+
+    fn start_filter(&mut self) {
+        self.close_inspect_only();
+        self.filter.start_editing();
+    }
+
+    fn change_filter(&mut self, change: impl FnOnce(&mut Filter)) {
+        let selected_channel_id = self.selected_channel_id();
+        change(&mut self.filter);
+        self.update_rows(selected_channel_id);
+        if self.show_logs && !self.frozen() {
+            self.refresh_logs();
+        }
+    }
+
+    /// Logs search input mode: typed characters edit the text and the logs panel is narrowed
+    /// down live
+    fn handle_log_search_key_event(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Enter => self.log_search.accept(),
+            KeyCode::Esc => self.change_log_search(Filter::clear),
+            KeyCode::Backspace => self.change_log_search(Filter::pop),
+            KeyCode::Char(c) => self.change_log_search(|search| search.push(c)),
+            _ => {}
+        }
+    }
+
+    fn change_log_search(&mut self, change: impl FnOnce(&mut Filter)) {
+        change(&mut self.log_search);
+        if self.frozen() {
+            return;
+        }
+        // Entries scrolled back to may not match the new search
+        self.logs = None;
+        self.refresh_logs();
+        let log_count = self
+            .logs
+            .as_ref()
+            .map_or(0, |cached_logs| cached_logs.entries(self.logs_view).len());
+        self.logs_table_state.select((log_count > 0).then_some(0));
+    }
+
+    /// Writes the selected channel with its logs and details when they're on screen, or all
+    /// channels and streams otherwise, to a JSON file and shows its path
+    fn export(&mut self) {
+        let result = match self.focus {
+            Focus::Channels => self.export_snapshot(),
+            Focus::Logs | Focus::Inspect | Focus::Detail => self.export_channel(),
+        };
+        self.toast = Some(match result {
+            Ok(path) => Toast::new(format!("Exported to {}", path.display()), false),
+            Err(e) => Toast::new(format!("Export failed: {}", e), true),
+        });
+    }
+
+    /// Copies the inspected message to the system clipboard with an OSC 52 escape sequence,
+    /// which terminals also honor over SSH
+    fn copy_inspected_message(&mut self) {
+        let Some(message) = self.inspected_log.as_ref().and_then(message_text) else {
+            self.toast = Some(Toast::new("Nothing to copy".to_string(), true));
+            return;
+        };
+        self.toast = Some(
+            match execute!(
+                std::io::stdout(),
+                CopyToClipboard::to_clipboard_from(&message)
+            ) {
+                Ok(()) => Toast::new("Copied message to clipboard".to_string(), false),
+                Err(e) => Toast::new(format!("Copy failed: {}", e), true),
+            },
+        );
+    }
+
+    fn export_snapshot(&self) -> Result<PathBuf> {
+        let Some(channels) = &self.channels else {
+            eyre::bail!("no statistics fetched yet");
+        };
+        let snapshot = CombinedJson {
+            schema_version: channels.schema_version,
+            current_elapsed_ns: self.current_elapsed_ns,
+            start_unix_ms: channels.start_unix_ms,
+            channels: self.all_stats.clone(),
+            streams: self.all_streams.clone(),
+            closed_total: channels.closed_total,
+            process: self
+                .server_info
+                .as_ref()
+                .map(InfoJson::process_json)
+                .unwrap_or_default(),
+        };
+        write_export("snapshot", &snapshot)
+    }
+
+    fn export_channel(&self) -> Result<PathBuf> {
+        let Some(stat) = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.stats.get(idx))
+        else {
+            eyre::bail!("no channel selected");
+        };
+        let fetched_logs;
+        let logs = match &self.logs {
+            Some(cached_logs) if cached_logs.channel_id == stat.id => Some(&cached_logs.logs),
+            _ => {
+                fetched_logs = self.source.channel_logs(stat.id).ok();
+                fetched_logs.as_ref()
+            }
+        };
+        let export = ChannelExport {
+            schema_version: self
+                .channels
+                .as_ref()
+                .map_or(0, |channels| channels.schema_version),
+            current_elapsed_ns: self.current_elapsed_ns,
+            channel: stat,
+            logs,
+            states: self.source.channel_states(stat.id).ok(),
+            latency_histogram: self.source.latency_histogram(stat.id).ok(),
+            actors: self.source.channel_actors(stat.id).ok(),
+        };
+        write_export(&stat.label, &export)
+    }
+
+    /// Zeroes the statistics of the monitored process and drops the trends drawn so far
+    fn reset_stats(&mut self) {
+        if let Err(e) = self.source.reset() {
+            self.error = Some(format!("Failed to reset statistics: {}", e));
+            return;
+        }
+        self.queue_history.clear();
+        self.logs = None;
+        self.refresh_data();
+    }
+
     fn toggle_pause(&mut self) {
         self.paused = !self.paused;
+        if let Some(replay) = self.source.replay_mut() {
+            replay.set_playing(!self.paused);
+        }
+    }
+
+    /// Points the console at the process `offset` positions after the current one. Statistics
+    /// of the previous process are dropped, as channel ids are only unique within a process.
+    fn switch_target(&mut self, offset: usize) {
+        if self.targets.len() < 2 {
+            return;
+        }
+        self.target_idx = (self.target_idx + offset) % self.targets.len();
+        self.source.set_target(&self.targets[self.target_idx]);
+
+        self.close_inspect_and_refocus_channels();
+        self.stats.clear();
+        self.all_stats.clear();
+        self.channels = None;
+        self.states = None;
+        self.latency_histogram = None;
+        self.actors = None;
+        self.active_alerts.clear();
+        self.stalled_streams.clear();
+        self.all_streams.clear();
+        self.streams.clear();
+        self.collection_paused = false;
+        self.queue_history.clear();
+        self.table_state.select(Some(0));
+        self.error = None;
+        self.schema_notice = None;
+        self.server_info = None;
+        self.compatibility_checked = false;
+        self.last_successful_fetch = None;
+        self.refresh_data();
+    }
+
+    /// Name and position of the monitored process, if several were given
+    fn target_status(&self) -> Option<String> {
+        if self.targets.len() < 2 {
+            return None;
+        }
+        Some(format!(
+            "{} ({}/{})",
+            self.targets[self.target_idx].name,
+            self.target_idx + 1,
+            self.targets.len()
+        ))
+    }
+
+    /// Connection to the metrics server, `None` when replaying a recording
+    fn connection(&self) -> Option<Connection> {
+        if self.source.is_replay() {
+            None
+        } else if self.error.is_some() {
+            Some(Connection::Disconnected)
+        } else if self.last_successful_fetch.is_some() {
+            Some(Connection::Connected)
+        } else {
+            Some(Connection::Connecting)
+        }
+    }
+
+    /// Whether live data is frozen by a pause. Recordings can still be browsed while paused.
+    fn frozen(&self) -> bool {
+        self.paused && !self.source.is_replay()
+    }
+
+    /// Seeks the replayed recording and shows the statistics at the new position
+    fn seek(&mut self, seek: impl FnOnce(&mut Replay)) {
+        if let Some(replay) = self.source.replay_mut() {
+            seek(replay);
+            // Samples and log entries from before the jump would be misleading
+            self.queue_history.clear();
+            self.logs = None;
+            self.refresh_data();
+        }
     }
 
     fn focus_channels(&mut self) {
@@ -310,7 +1316,7 @@ impl App {
             self.toggle_logs();
         } else if !self.stats.is_empty() {
             if let Some(ref cached_logs) = self.logs {
-                if !cached_logs.logs.sent_logs.is_empty() {
+                if !cached_logs.entries(self.logs_view).is_empty() {
                     self.focus = Focus::Logs;
                     if self.logs_table_state.selected().is_none() {
                         self.logs_table_state.select(Some(0));
@@ -321,42 +1327,65 @@ impl App {
     }
 
     fn select_previous_log(&mut self) {
+        self.select_log(|i, _| i.saturating_sub(1));
+    }
+
+    fn select_next_log(&mut self) {
+        self.select_log(|i, _| i + 1);
+    }
+
+    /// Moves the logs selection to `select(selected, last)`. Selecting older entries stops
+    /// following the newest one.
+    fn select_log(&mut self, select: impl FnOnce(usize, usize) -> usize) {
         if let Some(ref cached_logs) = self.logs {
-            let log_count = cached_logs.logs.sent_logs.len();
+            let log_count = cached_logs.entries(self.logs_view).len();
             if log_count > 0 {
                 let i = match self.logs_table_state.selected() {
-                    Some(i) => i.saturating_sub(1),
+                    Some(i) => select(i, log_count - 1).min(log_count - 1),
                     None => 0,
                 };
                 self.logs_table_state.select(Some(i));
+                if i > 0 {
+                    self.follow_logs = false;
+                }
 
                 // Update inspected log if inspect popup is open
                 if self.focus == Focus::Inspect {
-                    if let Some(entry) = cached_logs.logs.sent_logs.get(i) {
-                        self.inspected_log = Some(entry.clone());
+                    if let Some(entry) = cached_logs.entry(self.logs_view, i) {
+                        if self.inspected_log.as_ref().map(|log| log.index) != Some(entry.index) {
+                            self.inspect_state = InspectState::default();
+                        }
+                        self.inspected_log = Some(entry);
                     }
                 }
             }
         }
     }
 
-    fn select_next_log(&mut self) {
-        if let Some(ref cached_logs) = self.logs {
-            let log_count = cached_logs.logs.sent_logs.len();
-            if log_count > 0 {
-                let i = match self.logs_table_state.selected() {
-                    Some(i) => (i + 1).min(log_count - 1),
-                    None => 0,
-                };
-                self.logs_table_state.select(Some(i));
+    /// Keeps the newest log entry selected as new ones arrive
+    fn toggle_follow_logs(&mut self) {
+        self.follow_logs = !self.follow_logs;
+        if self.follow_logs {
+            self.select_log(|_, _| 0);
+        }
+    }
 
-                // Update inspected log if inspect popup is open
-                if self.focus == Focus::Inspect {
-                    if let Some(entry) = cached_logs.logs.sent_logs.get(i) {
-                        self.inspected_log = Some(entry.clone());
-                    }
-                }
-            }
+    /// Switches the logs panel between sent, received and interleaved entries
+    fn cycle_logs_view(&mut self) {
+        if !self.show_logs || self.focus == Focus::Inspect {
+            return;
+        }
+        self.logs_view = self.logs_view.next();
+
+        let log_count = self
+            .logs
+            .as_ref()
+            .map_or(0, |cached_logs| cached_logs.entries(self.logs_view).len());
+        if log_count == 0 {
+            self.focus_channels();
+        } else if let Some(selected) = self.logs_table_state.selected() {
+            self.logs_table_state
+                .select(Some(selected.min(log_count - 1)));
         }
     }
 
@@ -369,8 +1398,9 @@ impl App {
             // Opening inspect popup - capture the current log entry
             if let Some(selected) = self.logs_table_state.selected() {
                 if let Some(ref cached_logs) = self.logs {
-                    if let Some(entry) = cached_logs.logs.sent_logs.get(selected) {
-                        self.inspected_log = Some(entry.clone());
+                    if let Some(entry) = cached_logs.entry(self.logs_view, selected) {
+                        self.inspected_log = Some(entry);
+                        self.inspect_state = InspectState::default();
                         self.focus = Focus::Inspect;
                     }
                 }
@@ -378,6 +1408,35 @@ impl App {
         }
     }
 
+    /// Folds the deepest unfolded level of the inspected JSON payload, or unfolds the
+    /// shallowest folded one. The top-level object or array stays unfolded.
+    fn fold_inspected(&mut self, fold: bool) {
+        let Some(depth) = self
+            .inspected_log
+            .as_ref()
+            .and_then(message_json)
+            .map(|json| json_depth(&json))
+        else {
+            return;
+        };
+        let current = self.inspect_state.fold_depth.unwrap_or(depth);
+        let next = if fold {
+            current.saturating_sub(1).max(1)
+        } else {
+            current + 1
+        };
+        self.inspect_state.fold_depth = (next < depth).then_some(next);
+    }
+
+    /// Fetches the journey of the inspected message, if it carries a correlation id
+    fn refresh_journey(&mut self) {
+        self.inspected_journey = self
+            .inspected_log
+            .as_ref()
+            .and_then(|entry| entry.correlation_id)
+            .and_then(|id| self.source.journey(id).ok());
+    }
+
     fn close_inspect_and_refocus_channels(&mut self) {
         self.inspected_log = None;
         self.hide_logs();
@@ -413,28 +1472,144 @@ impl App {
             frame,
             chunks[0],
             self.paused,
+            self.source.replay_status().as_deref(),
             self.last_successful_fetch,
             self.error.is_some(),
             !self.stats.is_empty(),
+            self.schema_notice.as_deref(),
+            self.target_status().as_deref(),
+            self.server_info.as_ref(),
         );
 
+        // Totals across all channels take a row above the table
+        let main_area = if self.all_stats.is_empty() {
+            chunks[1]
+        } else {
+            let [summary_area, main_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(chunks[1]);
+            render_summary_bar(frame, summary_area, &Summary::new(&self.all_stats));
+            main_area
+        };
+
+        // Active alerts take a highlighted row above the main view
+        let main_area = if self.active_alerts.is_empty() {
+            main_area
+        } else {
+            let [alerts_area, main_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(main_area);
+            render_alerts_bar(frame, alerts_area, &self.active_alerts);
+            main_area
+        };
+
+        // Stalled streams aren't listed in the table, so they're flagged in a row of their own
+        let main_area = if self.stalled_streams.is_empty() {
+            main_area
+        } else {
+            let [stalled_area, main_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(main_area);
+            render_stalled_bar(frame, stalled_area, &self.stalled_streams);
+            main_area
+        };
+
+        let detail_stat = if self.focus == Focus::Detail {
+            self.table_state
+                .selected()
+                .and_then(|idx| self.stats.get(idx))
+        } else {
+            None
+        };
+
         // Render main content area
-        render_main_view(
+        let frozen = self.frozen();
+        if let Some(stat) = detail_stat {
+            render_detail_view(
+                stat,
+                &self.logs,
+                self.states
+                    .as_ref()
+                    .map_or(&[], |states| &states.transitions[..]),
+                self.latency_histogram.as_ref(),
+                self.actors.as_ref(),
+                self.current_elapsed_ns,
+                main_area,
+                frame,
+            );
+        } else {
+            render_main_view(
+                frame,
+                main_area,
+                &self.stats,
+                &self.streams,
+                &self.error,
+                &self.source.location(),
+                &mut self.table_state,
+                &mut self.logs_table_state,
+                self.focus,
+                self.show_logs,
+                &self.logs,
+                self.logs_view,
+                self.follow_logs,
+                self.wall_clock,
+                frozen,
+                &self.inspected_log,
+                &self.inspected_journey,
+                &mut self.inspect_state,
+                self.current_elapsed_ns,
+                self.collapse_level,
+                self.split,
+                self.split_ratio,
+                self.sort,
+                &self.filter,
+                &self.log_search,
+                &self.queue_history,
+                self.show_chart,
+                self.chart_history.as_ref(),
+                self.refresh_interval,
+                &mut self.grouping,
+                &self.columns,
+                self.column_chooser,
+                &self.key_map,
+            );
+        }
+
+        let location = self.source.location();
+        if self.show_help {
+            render_help_popup(
+                &self.key_map,
+                &location,
+                self.target_status().as_deref(),
+                self.refresh_interval,
+                self.server_info.as_ref(),
+                chunks[1],
+                frame,
+            );
+        }
+
+        if let Some(toast) = &self.toast {
+            if toast.shown_at.elapsed() < TOAST_DURATION {
+                render_toast(&toast.message, toast.is_error, chunks[1], frame);
+            } else {
+                self.toast = None;
+            }
+        }
+
+        render_bottom_bar(
             frame,
-            chunks[1],
-            &self.stats,
-            &self.error,
-            self.metrics_port,
-            &mut self.table_state,
-            &mut self.logs_table_state,
+            chunks[2],
             self.focus,
-            self.show_logs,
-            &self.logs,
-            self.paused,
-            &self.inspected_log,
-            self.current_elapsed_ns,
+            if self.log_search.is_editing() {
+                &self.log_search
+            } else {
+                &self.filter
+            },
+            self.source.is_replay(),
+            self.collection_paused,
+            self.confirming_reset,
+            self.column_chooser.is_some(),
+            &self.key_map,
+            self.connection()
+                .map(|connection| (connection, location.as_str())),
+            self.last_render_duration,
         );
-
-        render_bottom_bar(frame, chunks[2], self.focus, self.last_render_duration);
     }
 }
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// Optional column of the channels table, toggled in the columns popup opened with `v`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Column {
+    /// Sends per refresh over the last few refreshes, colored by how recent they are
+    Activity,
+    /// Size of a single message
+    TypeSize,
+    /// `Send/s` and `Recv/s`
+    Rates,
+    /// Bytes sent per second
+    Throughput,
+    /// Largest queue seen since the console started
+    HighWater,
+    Idle,
+    /// `file:line` the channel was instrumented at
+    Source,
+}
+
+impl Column {
+    /// Columns in the order they're listed in the popup
+    pub(crate) const ALL: [Column; 7] = [
+        Column::Activity,
+        Column::TypeSize,
+        Column::Rates,
+        Column::Throughput,
+        Column::HighWater,
+        Column::Idle,
+        Column::Source,
+    ];
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Activity => "Send activity",
+            Self::TypeSize => "Type size",
+            Self::Rates => "Send/s and Recv/s",
+            Self::Throughput => "Bytes/s",
+            Self::HighWater => "High-water mark",
+            Self::Idle => "Idle",
+            Self::Source => "Source path",
+        }
+    }
+}
+
+/// Optional columns shown in the channels table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct Columns {
+    visible: Vec<Column>,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Self {
+            visible: vec![Column::Activity, Column::Rates, Column::Idle],
+        }
+    }
+}
+
+impl Columns {
+    pub(crate) fn is_visible(&self, column: Column) -> bool {
+        self.visible.contains(&column)
+    }
+
+    pub(crate) fn toggle(&mut self, column: Column) {
+        if self.is_visible(column) {
+            self.visible.retain(|visible| *visible != column);
+        } else {
+            self.visible.push(column);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use channels_console::diff::SnapshotDiff;
+use channels_console::{ChannelState, CombinedJson};
+use clap::{Parser, ValueEnum};
+use eyre::Result;
+use prettytable::{Cell, Row, Table};
+use std::path::{Path, PathBuf};
+
+/// Output format of the `diff` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Parser)]
+pub struct DiffArgs {
+    /// Snapshot saved with `channels-console snapshot --format json` or the guard's JSON formats
+    pub before: PathBuf,
+
+    /// Snapshot to compare with `before`
+    pub after: PathBuf,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = DiffFormat::Table)]
+    pub format: DiffFormat,
+}
+
+impl DiffArgs {
+    pub fn run(&self) -> Result<()> {
+        let before = read_snapshot(&self.before)?;
+        let after = read_snapshot(&self.after)?;
+        let diff = SnapshotDiff::between(&before, &after);
+
+        match self.format {
+            DiffFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+            DiffFormat::Table if diff.is_empty() => println!("No differences."),
+            DiffFormat::Table => print!("{}", render(&diff)),
+        }
+        Ok(())
+    }
+}
+
+fn read_snapshot(path: &Path) -> Result<CombinedJson> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| eyre::eyre!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| eyre::eyre!("Invalid snapshot {}: {}", path.display(), e))
+}
+
+fn render(diff: &SnapshotDiff) -> String {
+    let mut out = String::new();
+    push_labels(&mut out, "Channels added", '+', &diff.added_channels);
+    push_labels(&mut out, "Channels removed", '-', &diff.removed_channels);
+
+    if !diff.changed_channels.is_empty() {
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![
+            Cell::new("Channel"),
+            Cell::new("Sent"),
+            Cell::new("Received"),
+            Cell::new("Queued"),
+            Cell::new("Mem"),
+            Cell::new("State"),
+        ]));
+        for channel in &diff.changed_channels {
+            table.add_row(Row::new(vec![
+                Cell::new(&channel.label),
+                Cell::new(&format!("{:+}", channel.sent_delta)),
+                Cell::new(&format!("{:+}", channel.received_delta)),
+                Cell::new(&format!("{:+}", channel.queued_delta)),
+                Cell::new(&format!("{:+} B", channel.queued_bytes_delta)),
+                Cell::new(&state_change(channel.state_change)),
+            ]));
+        }
+        out.push_str(&format!(
+            "\nChannels changed ({}):\n",
+            diff.changed_channels.len()
+        ));
+        out.push_str(&table.to_string());
+    }
+
+    push_labels(&mut out, "Streams added", '+', &diff.added_streams);
+    push_labels(&mut out, "Streams removed", '-', &diff.removed_streams);
+
+    if !diff.changed_streams.is_empty() {
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![
+            Cell::new("Stream"),
+            Cell::new("Yielded"),
+            Cell::new("State"),
+        ]));
+        for stream in &diff.changed_streams {
+            table.add_row(Row::new(vec![
+                Cell::new(&stream.label),
+                Cell::new(&format!("{:+}", stream.yielded_delta)),
+                Cell::new(&state_change(stream.state_change)),
+            ]));
+        }
+        out.push_str(&format!(
+            "\nStreams changed ({}):\n",
+            diff.changed_streams.len()
+        ));
+        out.push_str(&table.to_string());
+    }
+    out
+}
+
+fn push_labels(out: &mut String, title: &str, marker: char, labels: &[String]) {
+    if labels.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n{} ({}):\n", title, labels.len()));
+    for label in labels {
+        out.push_str(&format!("  {} {}\n", marker, label));
+    }
+}
+
+fn state_change(change: Option<(ChannelState, ChannelState)>) -> String {
+    match change {
+        Some((before, after)) => format!("{} → {}", before, after),
+        None => String::new(),
+    }
+}
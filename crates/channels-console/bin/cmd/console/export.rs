@@ -0,0 +1,42 @@
+use channels_console::actors::ChannelActorsJson;
+use channels_console::latency::LatencyHistogramJson;
+use channels_console::{ChannelLogs, ChannelStates, SerializableChannelStats};
+use eyre::Result;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Selected channel written with `e`, along with everything the detail view shows
+#[derive(Debug, Serialize)]
+pub(crate) struct ChannelExport<'a> {
+    pub(crate) schema_version: u32,
+    pub(crate) current_elapsed_ns: u64,
+    pub(crate) channel: &'a SerializableChannelStats,
+    /// Entries of the logs panel scrollback, or of a fresh fetch
+    pub(crate) logs: Option<&'a ChannelLogs>,
+    pub(crate) states: Option<ChannelStates>,
+    pub(crate) latency_histogram: Option<LatencyHistogramJson>,
+    pub(crate) actors: Option<ChannelActorsJson>,
+}
+
+/// Writes `value` as pretty-printed JSON to a timestamped file in the current directory, e.g.
+/// `channels-console-snapshot-20250101-120000-000.json`, and returns its path.
+pub(crate) fn write_export(name: &str, value: &impl Serialize) -> Result<PathBuf> {
+    let path = PathBuf::from(format!(
+        "channels-console-{}-{}.json",
+        file_name_part(name),
+        chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")
+    ));
+    std::fs::write(&path, serde_json::to_string_pretty(value)?)?;
+    Ok(path)
+}
+
+/// Label turned into a file name part, e.g. `ingest/parser` into `ingest-parser`
+fn file_name_part(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,136 @@
+use channels_console::{ChannelState, SerializableChannelStats, SerializableStreamStats};
+
+/// Search query narrowing down the channels table, entered after pressing `/`
+#[derive(Debug, Default)]
+pub(crate) struct Filter {
+    query: String,
+    editing: bool,
+    /// Number of channels and streams hidden by the query on the last refresh
+    hidden: usize,
+    /// Whether closed channels are hidden, toggled with `c`
+    hide_closed: bool,
+    /// Number of closed channels and streams matching the query hidden on the last refresh
+    closed_hidden: usize,
+}
+
+impl Filter {
+    /// Filter restored from a saved layout, not in input mode
+    pub(crate) fn new(query: String, hide_closed: bool) -> Self {
+        Self {
+            query,
+            hide_closed,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub(crate) fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    pub(crate) fn hidden(&self) -> usize {
+        self.hidden
+    }
+
+    pub(crate) fn hides_closed(&self) -> bool {
+        self.hide_closed
+    }
+
+    pub(crate) fn closed_hidden(&self) -> usize {
+        self.closed_hidden
+    }
+
+    /// Hides or shows closed channels, independently of the query
+    pub(crate) fn toggle_closed(&mut self) {
+        self.hide_closed = !self.hide_closed;
+    }
+
+    pub(crate) fn start_editing(&mut self) {
+        self.editing = true;
+    }
+
+    pub(crate) fn push(&mut self, c: char) {
+        self.query.push(c);
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.query.pop();
+    }
+
+    /// Stops editing and keeps the query applied
+    pub(crate) fn accept(&mut self) {
+        self.editing = false;
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.query.clear();
+        self.editing = false;
+    }
+
+    /// Drops the channels and streams not matching the query, and closed ones if hidden, and
+    /// remembers how many were hidden.
+    pub(crate) fn apply(
+        &mut self,
+        stats: &mut Vec<SerializableChannelStats>,
+        streams: &mut Vec<SerializableStreamStats>,
+    ) {
+        let total = stats.len() + streams.len();
+        stats.retain(|stat| matches(stat, &self.query));
+        streams.retain(|stream| matches_stream(stream, &self.query));
+        let matching = stats.len() + streams.len();
+        self.hidden = total - matching;
+
+        if self.hide_closed {
+            stats.retain(|stat| stat.state != ChannelState::Closed);
+            streams.retain(|stream| stream.state != ChannelState::Closed);
+        }
+        self.closed_hidden = matching - stats.len() - streams.len();
+    }
+}
+
+/// Every whitespace-separated term has to fuzzy match the label, source, type name, state or
+/// one of the tags.
+fn matches(stat: &SerializableChannelStats, query: &str) -> bool {
+    let fields = [
+        stat.label.as_str(),
+        stat.source.as_str(),
+        stat.type_name.as_str(),
+        stat.state.as_str(),
+    ]
+    .into_iter()
+    .chain(stat.tags.iter().map(String::as_str));
+    matches_fields(fields, query)
+}
+
+/// Streams have no tags, so terms only match the label, source, item type or state.
+fn matches_stream(stream: &SerializableStreamStats, query: &str) -> bool {
+    let fields = [
+        stream.label.as_str(),
+        stream.source.as_str(),
+        stream.type_name.as_str(),
+        stream.state.as_str(),
+    ];
+    matches_fields(fields.into_iter(), query)
+}
+
+fn matches_fields<'a>(fields: impl Iterator<Item = &'a str> + Clone, query: &str) -> bool {
+    query
+        .split_whitespace()
+        .all(|term| fields.clone().any(|field| fuzzy_match(term, field)))
+}
+
+/// Case-insensitive subsequence match, e.g. `ingprs` matches `ingest/parser`.
+fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|p| text.any(|t| t == p))
+}
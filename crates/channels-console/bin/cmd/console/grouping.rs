@@ -0,0 +1,193 @@
+use channels_console::groups::{group_by_source, group_by_tag, SourceGroup, TagGroup};
+use channels_console::SerializableChannelStats;
+use ratatui::widgets::TableState;
+use std::collections::HashSet;
+
+/// What the channels table is grouped by, cycled with `g`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GroupBy {
+    /// Channels created at the same call site
+    Source,
+    /// Channels sharing a tag, a channel with several tags is in several groups
+    Tag,
+}
+
+impl GroupBy {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Source => "source",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+/// Aggregated counters of a group of channels
+pub(crate) struct Group {
+    /// Source location or tag the channels share
+    pub(crate) key: String,
+    pub(crate) label: String,
+    pub(crate) channels: usize,
+    pub(crate) sent_count: u64,
+    pub(crate) received_count: u64,
+    pub(crate) queued: u64,
+    pub(crate) max_queued: u64,
+    pub(crate) queued_bytes: u64,
+}
+
+impl From<SourceGroup> for Group {
+    fn from(group: SourceGroup) -> Self {
+        Self {
+            key: group.source,
+            label: group.label,
+            channels: group.channels,
+            sent_count: group.sent_count,
+            received_count: group.received_count,
+            queued: group.queued,
+            max_queued: group.max_queued,
+            queued_bytes: group.queued_bytes,
+        }
+    }
+}
+
+impl From<TagGroup> for Group {
+    fn from(group: TagGroup) -> Self {
+        Self {
+            key: group.tag.clone(),
+            label: group.tag,
+            channels: group.channels,
+            sent_count: group.sent_count,
+            received_count: group.received_count,
+            queued: group.queued,
+            max_queued: group.max_queued,
+            queued_bytes: group.queued_bytes,
+        }
+    }
+}
+
+/// Row of the grouped channels table
+pub(crate) enum GroupRow<'a> {
+    Group {
+        group: Group,
+        expanded: bool,
+    },
+    /// Channel of an expanded group
+    Channel(&'a SerializableChannelStats),
+}
+
+/// Grouping of channels by source location or tag into expandable groups
+#[derive(Debug, Default)]
+pub(crate) struct Grouping {
+    by: Option<GroupBy>,
+    /// Keys of the groups showing their channels
+    expanded: HashSet<String>,
+    pub(crate) table_state: TableState,
+}
+
+impl Grouping {
+    pub(crate) fn by(&self) -> Option<GroupBy> {
+        self.by
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.by.is_some()
+    }
+
+    /// Groups by source, then by tag if any channel is tagged, then back to plain channels
+    pub(crate) fn cycle(&mut self, stats: &[SerializableChannelStats]) {
+        let tagged = stats.iter().any(|stat| !stat.tags.is_empty());
+        self.by = match self.by {
+            None => Some(GroupBy::Source),
+            Some(GroupBy::Source) if tagged => Some(GroupBy::Tag),
+            Some(_) => None,
+        };
+        self.expanded.clear();
+        self.table_state.select(Some(0));
+    }
+
+    pub(crate) fn disable(&mut self) {
+        self.by = None;
+    }
+
+    fn groups(&self, stats: &[SerializableChannelStats]) -> Vec<Group> {
+        match self.by {
+            Some(GroupBy::Source) => group_by_source(stats)
+                .into_iter()
+                .map(Group::from)
+                .collect(),
+            Some(GroupBy::Tag) => group_by_tag(stats).into_iter().map(Group::from).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn in_group(&self, stat: &SerializableChannelStats, key: &str) -> bool {
+        match self.by {
+            Some(GroupBy::Source) => stat.source == key,
+            Some(GroupBy::Tag) => stat.tags.iter().any(|tag| tag == key),
+            None => false,
+        }
+    }
+
+    /// Groups sorted by label, each followed by its channels if expanded
+    pub(crate) fn rows<'a>(&self, stats: &'a [SerializableChannelStats]) -> Vec<GroupRow<'a>> {
+        let mut rows = Vec::new();
+        for group in self.groups(stats) {
+            let expanded = self.expanded.contains(&group.key);
+            let key = group.key.clone();
+            rows.push(GroupRow::Group { group, expanded });
+            if expanded {
+                rows.extend(
+                    stats
+                        .iter()
+                        .filter(|stat| self.in_group(stat, &key))
+                        .map(GroupRow::Channel),
+                );
+            }
+        }
+        rows
+    }
+
+    /// Expands or collapses the selected group, or the group of the selected channel
+    pub(crate) fn toggle_selected(&mut self, stats: &[SerializableChannelStats]) {
+        let Some(selected) = self.table_state.selected() else {
+            return;
+        };
+        let rows = self.rows(stats);
+        // A channel belongs to the closest group row above it
+        let key = rows[..rows.len().min(selected + 1)]
+            .iter()
+            .rev()
+            .find_map(|row| match row {
+                GroupRow::Group { group, .. } => Some(group.key.clone()),
+                GroupRow::Channel(_) => None,
+            });
+        let Some(key) = key else {
+            return;
+        };
+
+        if !self.expanded.remove(&key) {
+            self.expanded.insert(key.clone());
+        }
+
+        // Keep the cursor on the toggled group
+        let group_idx = self
+            .rows(stats)
+            .iter()
+            .position(|row| matches!(row, GroupRow::Group { group, .. } if group.key == key));
+        self.table_state.select(group_idx);
+    }
+
+    pub(crate) fn select_previous(&mut self) {
+        if let Some(selected) = self.table_state.selected() {
+            self.table_state.select(Some(selected.saturating_sub(1)));
+        }
+    }
+
+    pub(crate) fn select_next(&mut self, stats: &[SerializableChannelStats]) {
+        let last = self.rows(stats).len().saturating_sub(1);
+        let next = self
+            .table_state
+            .selected()
+            .map_or(0, |selected| (selected + 1).min(last));
+        self.table_state.select(Some(next));
+    }
+}
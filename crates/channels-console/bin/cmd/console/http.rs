@@ -1,28 +1,461 @@
-use channels_console::{ChannelLogs, ChannelsJson, StreamsJson};
+use channels_console::actors::ChannelActorsJson;
+use channels_console::alerts::AlertsJson;
+use channels_console::config::{ConsoleSection, FileConfig};
+use channels_console::control::ControlJson;
+use channels_console::correlation::Journey;
+use channels_console::delta::ChannelsDeltaJson;
+use channels_console::history::HistoryJson;
+use channels_console::info::{InfoJson, VersionJson};
+use channels_console::latency::LatencyHistogramJson;
+use channels_console::samples::ChannelHistoryJson;
+use channels_console::summary::SummaryJson;
+use channels_console::{
+    ChannelLogs, ChannelStates, ChannelsJson, CombinedJson, StreamsJson, SCHEMA_VERSION,
+};
+use clap::Args;
 use eyre::Result;
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Connection to a metrics server
+#[derive(Debug, Clone)]
+pub(crate) enum Client {
+    Tcp {
+        agent: ureq::Agent,
+        base_url: String,
+    },
+    /// Server started with `CHANNELS_CONSOLE_METRICS_SOCKET`
+    Unix {
+        path: PathBuf,
+        token: Option<String>,
+    },
+}
+
+impl Client {
+    /// URL of the metrics server, or path of the socket
+    pub(crate) fn location(&self) -> String {
+        match self {
+            Self::Tcp { base_url, .. } => base_url.clone(),
+            Self::Unix { path, .. } => format!("unix:{}", path.display()),
+        }
+    }
+
+    fn get_json(&self, path: &str) -> Result<serde_json::Value> {
+        match self {
+            Self::Tcp { agent, base_url } => Ok(agent
+                .get(format!("{}{}", base_url, path))
+                .call()?
+                .body_mut()
+                .read_json()?),
+            Self::Unix {
+                path: socket,
+                token,
+            } => Ok(serde_json::from_str(&unix_request(
+                socket,
+                "GET",
+                path,
+                token.as_deref(),
+            )?)?),
+        }
+    }
+
+    /// Sends a bodyless POST, e.g. to the `/control` endpoints
+    fn post_json(&self, path: &str) -> Result<serde_json::Value> {
+        match self {
+            Self::Tcp { agent, base_url } => Ok(agent
+                .post(format!("{}{}", base_url, path))
+                .send_empty()?
+                .body_mut()
+                .read_json()?),
+            Self::Unix {
+                path: socket,
+                token,
+            } => Ok(serde_json::from_str(&unix_request(
+                socket,
+                "POST",
+                path,
+                token.as_deref(),
+            )?)?),
+        }
+    }
+}
+
+/// Minimal HTTP client for Unix domain sockets, returns the response body.
+#[cfg(unix)]
+fn unix_request(
+    socket: &std::path::Path,
+    method: &str,
+    path: &str,
+    token: Option<&str>,
+) -> Result<String> {
+    use std::io::{Read, Write};
+    use std::time::Duration;
+
+    let mut stream = std::os::unix::net::UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(Duration::from_millis(1500)))?;
+
+    // HTTP/1.0 gets an unchunked response, followed by the server closing the connection
+    let mut request = format!(
+        "{} {} HTTP/1.0\r\nHost: localhost\r\nConnection: close\r\n",
+        method, path
+    );
+    if method == "POST" {
+        request.push_str("Content-Length: 0\r\n");
+    }
+    if let Some(token) = token {
+        request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| eyre::eyre!("Malformed response"))?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| eyre::eyre!("Malformed response"))?;
+    if status != 200 {
+        eyre::bail!("http status: {}", status);
+    }
+    Ok(body.to_string())
+}
+
+#[cfg(not(unix))]
+fn unix_request(
+    _socket: &std::path::Path,
+    _method: &str,
+    _path: &str,
+    _token: Option<&str>,
+) -> Result<String> {
+    eyre::bail!("Unix sockets are not supported on this platform")
+}
+
+/// Connection options of the commands printing statistics of a running metrics server
+#[derive(Debug, Args)]
+pub struct ServerArgs {
+    /// Port for the metrics server [default: 6770]
+    #[arg(long)]
+    pub metrics_port: Option<u16>,
+
+    /// Host of the metrics server [default: 127.0.0.1]
+    #[arg(long)]
+    pub host: Option<String>,
+
+    /// Base URL of the metrics server, e.g. `http://10.0.0.5:6770`. Takes precedence over
+    /// `--host` and `--metrics-port`. Can also be set with `CHANNELS_CONSOLE_URL`
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Bearer token sent to metrics servers started with `CHANNELS_CONSOLE_TOKEN`. Can also
+    /// be set with `CHANNELS_CONSOLE_TOKEN`
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Unix domain socket of a metrics server started with `CHANNELS_CONSOLE_METRICS_SOCKET`.
+    /// Takes precedence over `--url`
+    #[arg(long)]
+    pub socket: Option<PathBuf>,
+
+    /// Only print channels and streams with labels under this path prefix, e.g. `ingest/parser`
+    #[arg(long)]
+    pub prefix: Option<String>,
+}
+
+impl ServerArgs {
+    /// Client for the metrics server, CLI arguments take precedence over the config file.
+    pub(crate) fn client(&self) -> Result<Client> {
+        let file_config = match FileConfig::from_env() {
+            Some(result) => result.map_err(|e| eyre::eyre!("Invalid config file: {}", e))?,
+            None => FileConfig::default(),
+        };
+        let base_url = base_url(
+            self.url.clone(),
+            self.host.clone(),
+            self.metrics_port,
+            &file_config.console,
+        );
+        Ok(connect(
+            base_url,
+            self.socket.clone(),
+            self.token.clone(),
+            &file_config.console,
+        ))
+    }
+}
+
+/// Base URL of the metrics server from `--url`, `CHANNELS_CONSOLE_URL`, or `--host` and
+/// `--metrics-port`, falling back to the `[console]` section of the config file.
+pub(crate) fn base_url(
+    url: Option<String>,
+    host: Option<String>,
+    metrics_port: Option<u16>,
+    console: &ConsoleSection,
+) -> String {
+    let url = url
+        .or_else(|| std::env::var("CHANNELS_CONSOLE_URL").ok())
+        .or_else(|| console.url.clone());
+    match url {
+        Some(url) => normalize_url(&url),
+        None => {
+            let host = host
+                .or_else(|| console.host.clone())
+                .unwrap_or_else(|| "127.0.0.1".to_string());
+            let metrics_port = metrics_port.or(console.metrics_port).unwrap_or(6770);
+            format!("http://{}:{}", host, metrics_port)
+        }
+    }
+}
+
+/// Client for the metrics server at `base_url`, or listening on `socket` when given. The
+/// token falls back to `CHANNELS_CONSOLE_TOKEN` and the config file.
+pub(crate) fn connect(
+    base_url: String,
+    socket: Option<PathBuf>,
+    token: Option<String>,
+    console: &ConsoleSection,
+) -> Client {
+    let token = token
+        .or_else(|| std::env::var("CHANNELS_CONSOLE_TOKEN").ok())
+        .or_else(|| console.token.clone());
+
+    match socket {
+        Some(path) => Client::Unix { path, token },
+        None => {
+            let mut config = ureq::Agent::config_builder()
+                .timeout_connect(Some(Duration::from_millis(2000)))
+                .timeout_recv_body(Some(Duration::from_millis(1500)));
+            if let Some(token) = token {
+                config = config.middleware(bearer_auth(token));
+            }
+            let agent: ureq::Agent = config.build().into();
+            Client::Tcp { agent, base_url }
+        }
+    }
+}
+
+/// Base URL of the metrics server given with `--url`, e.g. `10.0.0.5:6770` or
+/// `https://example.com/channels-console`. Defaults to the `http` scheme.
+pub(crate) fn normalize_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("http://{}", url)
+    }
+}
+
+/// Adds an `Authorization: Bearer` header to every request
+pub(crate) fn bearer_auth(token: String) -> impl ureq::middleware::Middleware {
+    let header = format!("Bearer {}", token);
+    move |mut request: ureq::http::Request<ureq::SendBody>,
+          next: ureq::middleware::MiddlewareNext| {
+        if let Ok(value) = ureq::http::HeaderValue::from_str(&header) {
+            request
+                .headers_mut()
+                .insert(ureq::http::header::AUTHORIZATION, value);
+        }
+        next.handle(request)
+    }
+}
 
 /// Fetches channel metrics from the HTTP server
-pub(crate) fn fetch_channels(agent: &ureq::Agent, port: u16) -> Result<ChannelsJson> {
-    let url = format!("http://127.0.0.1:{}/channels", port);
-    let channels: ChannelsJson = agent.get(&url).call()?.body_mut().read_json()?;
+pub(crate) fn fetch_channels(client: &Client) -> Result<ChannelsJson> {
+    fetch_versioned(client, "/channels")
+}
+
+/// Brings channels fetched earlier up to date, transferring only the ones that changed.
+/// Servers predating `/channels/delta` respond with 404, so all channels are fetched again.
+pub(crate) fn fetch_channels_since(
+    client: &Client,
+    previous: &ChannelsJson,
+) -> Result<ChannelsJson> {
+    let path = format!("/channels/delta?since_ns={}", previous.current_elapsed_ns);
+    let delta: ChannelsDeltaJson = match fetch_versioned(client, &path) {
+        Ok(delta) => delta,
+        Err(e) if is_not_found(&e) => return fetch_channels(client),
+        Err(e) => return Err(e),
+    };
+    // A restarted process reuses channel ids, so nothing fetched earlier can be kept
+    if delta.current_elapsed_ns < previous.current_elapsed_ns {
+        return fetch_channels(client);
+    }
+    let mut channels = previous.clone();
+    delta.apply(&mut channels);
     Ok(channels)
 }
 
-#[allow(dead_code)]
 /// Fetches stream metrics from the HTTP server
-pub(crate) fn fetch_streams(agent: &ureq::Agent, port: u16) -> Result<StreamsJson> {
-    let url = format!("http://127.0.0.1:{}/streams", port);
-    let streams: StreamsJson = agent.get(&url).call()?.body_mut().read_json()?;
-    Ok(streams)
+pub(crate) fn fetch_streams(client: &Client) -> Result<StreamsJson> {
+    fetch_versioned(client, "/streams")
+}
+
+/// Fetches channel and stream metrics, keeping those with labels under `prefix`.
+/// Servers predating `/all` respond with 404, so channels and streams are fetched separately.
+pub(crate) fn fetch_combined(client: &Client, prefix: Option<&str>) -> Result<CombinedJson> {
+    let mut combined = match fetch_versioned::<CombinedJson>(client, "/all") {
+        Ok(combined) => combined,
+        Err(e) if is_not_found(&e) => fetch_combined_separately(client)?,
+        Err(e) => return Err(e),
+    };
+    if let Some(prefix) = prefix {
+        combined.retain_prefix(prefix);
+    }
+    Ok(combined)
+}
+
+fn fetch_combined_separately(client: &Client) -> Result<CombinedJson> {
+    let channels = fetch_channels(client)?;
+    let streams = fetch_streams(client)?;
+    // Servers predating `/info` leave the process unknown
+    let process = fetch_info(client)
+        .map(|info| info.process_json())
+        .unwrap_or_default();
+
+    Ok(CombinedJson {
+        schema_version: channels.schema_version,
+        current_elapsed_ns: channels.current_elapsed_ns,
+        start_unix_ms: channels.start_unix_ms,
+        channels: channels.channels,
+        streams: streams.streams,
+        closed_total: channels.closed_total,
+        process,
+    })
 }
 
 /// Fetches logs for a specific channel from the HTTP server
-pub(crate) fn fetch_channel_logs(
-    agent: &ureq::Agent,
-    port: u16,
+pub(crate) fn fetch_channel_logs(client: &Client, channel_id: u64) -> Result<ChannelLogs> {
+    fetch_versioned(client, &format!("/channels/{}/logs", channel_id))
+}
+
+/// Fetches state transitions of a specific channel from the HTTP server
+pub(crate) fn fetch_channel_states(client: &Client, channel_id: u64) -> Result<ChannelStates> {
+    fetch_versioned(client, &format!("/channels/{}/states", channel_id))
+}
+
+/// Fetches the queue depth samples of a specific channel from the HTTP server
+pub(crate) fn fetch_channel_history(
+    client: &Client,
+    channel_id: u64,
+) -> Result<ChannelHistoryJson> {
+    fetch_versioned(client, &format!("/channels/{}/history", channel_id))
+}
+
+/// Fetches the persisted history snapshots from the HTTP server
+pub(crate) fn fetch_history(client: &Client) -> Result<HistoryJson> {
+    fetch_versioned(client, "/history")
+}
+
+/// Fetches the latency distribution of a specific channel from the HTTP server
+pub(crate) fn fetch_latency_histogram(
+    client: &Client,
     channel_id: u64,
-) -> Result<ChannelLogs> {
-    let url = format!("http://127.0.0.1:{}/channels/{}/logs", port, channel_id);
-    let logs: ChannelLogs = agent.get(&url).call()?.body_mut().read_json()?;
-    Ok(logs)
+) -> Result<LatencyHistogramJson> {
+    fetch_versioned(
+        client,
+        &format!("/channels/{}/latency_histogram", channel_id),
+    )
+}
+
+/// Fetches the top senders and receivers of a specific channel from the HTTP server
+pub(crate) fn fetch_channel_actors(client: &Client, channel_id: u64) -> Result<ChannelActorsJson> {
+    fetch_versioned(client, &format!("/channels/{}/actors", channel_id))
+}
+
+/// Fetches the journey of a correlated message across channels
+pub(crate) fn fetch_journey(client: &Client, correlation_id: u64) -> Result<Journey> {
+    fetch_versioned(client, &format!("/journeys/{}", correlation_id))
+}
+
+/// Fetches the version and process info of the HTTP server
+pub(crate) fn fetch_info(client: &Client) -> Result<InfoJson> {
+    fetch_versioned(client, "/info")
+}
+
+/// Fetches totals and the collection mode from the HTTP server
+pub(crate) fn fetch_summary(client: &Client) -> Result<SummaryJson> {
+    fetch_versioned(client, "/summary")
+}
+
+/// Zeroes the statistics of the monitored process, keeping its channels registered
+pub(crate) fn reset_stats(client: &Client) -> Result<ControlJson> {
+    Ok(serde_json::from_value(client.post_json("/control/reset")?)?)
+}
+
+/// Fetches active and recent alerts from the HTTP server
+pub(crate) fn fetch_alerts(client: &Client) -> Result<AlertsJson> {
+    fetch_versioned(client, "/alerts")
+}
+
+/// Fetches a JSON payload and decodes it, reporting schema version skew when decoding fails.
+fn fetch_versioned<T: DeserializeOwned>(client: &Client, path: &str) -> Result<T> {
+    let value = client.get_json(path)?;
+    let server_version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+
+    serde_json::from_value(value).map_err(|e| match schema_notice(server_version) {
+        Some(notice) => eyre::eyre!("{} ({})", notice, e),
+        None => eyre::eyre!("Invalid response: {}", e),
+    })
+}
+
+/// Checks that this binary can read the payloads of the HTTP server, failing with an upgrade
+/// hint otherwise. Servers predating `/version` pass, their version skew is reported by
+/// [`schema_notice`].
+pub(crate) fn check_compatibility(client: &Client) -> Result<()> {
+    let version: VersionJson = match client.get_json("/version") {
+        Ok(value) => serde_json::from_value(value)?,
+        Err(e) if is_not_found(&e) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    if SCHEMA_VERSION < version.min_client_schema_version {
+        eyre::bail!(
+            "Server schema v{} (library v{}) can't be read by this CLI supporting v{} - upgrade the channels-console CLI",
+            version.schema_version,
+            version.version,
+            SCHEMA_VERSION
+        );
+    }
+    Ok(())
+}
+
+fn is_not_found(e: &eyre::Report) -> bool {
+    matches!(
+        e.downcast_ref::<ureq::Error>(),
+        Some(ureq::Error::StatusCode(404))
+    ) || e.to_string() == "http status: 404"
+}
+
+/// Describes a mismatch between the library version of the server and the version of this
+/// binary. Returns `None` when both match.
+pub(crate) fn version_notice(info: &InfoJson) -> Option<String> {
+    let cli_version = env!("CARGO_PKG_VERSION");
+    (info.version != cli_version).then(|| {
+        format!(
+            "Library v{} differs from CLI v{}",
+            info.version, cli_version
+        )
+    })
+}
+
+/// Describes a mismatch between the server schema version and the one this binary understands.
+/// Returns `None` when both sides speak the same version.
+pub(crate) fn schema_notice(server_version: u32) -> Option<String> {
+    match server_version.cmp(&SCHEMA_VERSION) {
+        std::cmp::Ordering::Equal => None,
+        std::cmp::Ordering::Less => Some(format!(
+            "Server schema v{} is older than v{}, some data is unavailable - upgrade the channels-console library",
+            server_version, SCHEMA_VERSION
+        )),
+        std::cmp::Ordering::Greater => Some(format!(
+            "Server schema v{} is newer than v{} - upgrade the channels-console CLI",
+            server_version, SCHEMA_VERSION
+        )),
+    }
 }
@@ -0,0 +1,334 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::HashMap;
+
+/// Action of the TUI that can be bound to another key in the `[keys]` table of the console
+/// config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Quit,
+    ToggleLogs,
+    Pause,
+    Reset,
+    Collapse,
+    Group,
+    HideClosed,
+    Sort,
+    ReverseSort,
+    Freeze,
+    Columns,
+    Search,
+    Inspect,
+    LogsView,
+    Details,
+    Export,
+    Copy,
+    Fold,
+    Unfold,
+    ToggleSplit,
+    ShrinkTable,
+    GrowTable,
+    Chart,
+    WallClock,
+    Help,
+}
+
+impl Action {
+    const ALL: [Action; 29] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Quit,
+        Action::ToggleLogs,
+        Action::Pause,
+        Action::Reset,
+        Action::Collapse,
+        Action::Group,
+        Action::HideClosed,
+        Action::Sort,
+        Action::ReverseSort,
+        Action::Freeze,
+        Action::Columns,
+        Action::Search,
+        Action::Inspect,
+        Action::LogsView,
+        Action::Details,
+        Action::Export,
+        Action::Copy,
+        Action::Fold,
+        Action::Unfold,
+        Action::ToggleSplit,
+        Action::ShrinkTable,
+        Action::GrowTable,
+        Action::Chart,
+        Action::WallClock,
+        Action::Help,
+    ];
+
+    /// Name of the action in the `[keys]` table
+    fn name(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Quit => "quit",
+            Self::ToggleLogs => "toggle_logs",
+            Self::Pause => "pause",
+            Self::Reset => "reset",
+            Self::Collapse => "collapse",
+            Self::Group => "group",
+            Self::HideClosed => "hide_closed",
+            Self::Sort => "sort",
+            Self::ReverseSort => "reverse_sort",
+            Self::Freeze => "freeze",
+            Self::Columns => "columns",
+            Self::Search => "search",
+            Self::Inspect => "inspect",
+            Self::LogsView => "logs_view",
+            Self::Details => "details",
+            Self::Export => "export",
+            Self::Copy => "copy",
+            Self::Fold => "fold",
+            Self::Unfold => "unfold",
+            Self::ToggleSplit => "toggle_split",
+            Self::ShrinkTable => "shrink_table",
+            Self::GrowTable => "grow_table",
+            Self::Chart => "chart",
+            Self::WallClock => "wall_clock",
+            Self::Help => "help",
+        }
+    }
+
+    /// Key the action is handled on, remapped keys are translated to it
+    fn default_key(self) -> KeyCode {
+        match self {
+            Self::Up => KeyCode::Char('k'),
+            Self::Down => KeyCode::Char('j'),
+            Self::Left => KeyCode::Char('h'),
+            Self::Right => KeyCode::Char('l'),
+            Self::Quit => KeyCode::Char('q'),
+            Self::ToggleLogs => KeyCode::Char('o'),
+            Self::Pause => KeyCode::Char('p'),
+            Self::Reset => KeyCode::Char('r'),
+            Self::Collapse => KeyCode::Char('z'),
+            Self::Group => KeyCode::Char('g'),
+            Self::HideClosed => KeyCode::Char('c'),
+            Self::Sort => KeyCode::Char('s'),
+            Self::ReverseSort => KeyCode::Char('S'),
+            Self::Freeze => KeyCode::Char('f'),
+            Self::Columns => KeyCode::Char('v'),
+            Self::Search => KeyCode::Char('/'),
+            Self::Inspect => KeyCode::Char('i'),
+            Self::LogsView => KeyCode::Char('t'),
+            Self::Details => KeyCode::Enter,
+            Self::Export => KeyCode::Char('e'),
+            Self::Copy => KeyCode::Char('y'),
+            Self::Fold => KeyCode::Char('-'),
+            Self::Unfold => KeyCode::Char('+'),
+            Self::ToggleSplit => KeyCode::Char('|'),
+            Self::ShrinkTable => KeyCode::Char('<'),
+            Self::GrowTable => KeyCode::Char('>'),
+            Self::Chart => KeyCode::Char('d'),
+            Self::WallClock => KeyCode::Char('w'),
+            Self::Help => KeyCode::Char('?'),
+        }
+    }
+}
+
+/// Keys remapped in the `[keys]` table, e.g. `up = "w"` for users who can't use `hjkl`.
+///
+/// Every key is translated to the default key of its action before being handled, so a remapped
+/// action no longer fires on its default key. Letters work in either case, unless the other case
+/// is the key of another action, like `s` and `S`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KeyMap {
+    bound: HashMap<Action, KeyCode>,
+}
+
+impl KeyMap {
+    /// Parses the `[keys]` table, mapping action names to keys.
+    pub(crate) fn new(keys: &HashMap<String, String>) -> Result<Self, String> {
+        let mut key_map = Self::default();
+        for (name, key) in keys {
+            let action = Action::ALL
+                .into_iter()
+                .find(|action| action.name() == name)
+                .ok_or_else(|| format!("unknown action '{}'", name))?;
+            let key =
+                parse_key(key).ok_or_else(|| format!("invalid key '{}' for {}", key, name))?;
+            if key_map.bound.values().any(|bound| *bound == key) {
+                return Err(format!(
+                    "key '{}' is bound to several actions",
+                    format_key(key)
+                ));
+            }
+            key_map.bound.insert(action, key);
+        }
+        Ok(key_map)
+    }
+
+    /// Translates a key to the default key of its action. Default keys of remapped actions are
+    /// translated to [`KeyCode::Null`], keys of no action are kept.
+    pub(crate) fn translate(&self, mut key_event: KeyEvent) -> KeyEvent {
+        let code = key_event.code;
+        key_event.code = match self.action(code) {
+            Some(action) => action.default_key(),
+            None if self.is_unbound(code) || self.is_unbound(swap_case(code)) => KeyCode::Null,
+            None => code,
+        };
+        key_event
+    }
+
+    /// Action fired by the key, trying the other case of letters bound to no action
+    fn action(&self, code: KeyCode) -> Option<Action> {
+        self.exact_action(code).or_else(|| {
+            if self.is_unbound(code) {
+                None
+            } else {
+                self.exact_action(swap_case(code))
+            }
+        })
+    }
+
+    fn exact_action(&self, code: KeyCode) -> Option<Action> {
+        let remapped = self.bound.iter().find(|(_, key)| **key == code);
+        remapped.map(|(action, _)| *action).or_else(|| {
+            Action::ALL
+                .into_iter()
+                .find(|action| !self.bound.contains_key(action) && action.default_key() == code)
+        })
+    }
+
+    /// Whether the key is the default key of a remapped action
+    fn is_unbound(&self, code: KeyCode) -> bool {
+        self.bound.keys().any(|action| action.default_key() == code)
+    }
+
+    /// Key of the action shown in the controls bar
+    pub(crate) fn label(&self, action: Action) -> String {
+        format_key(
+            self.bound
+                .get(&action)
+                .copied()
+                .unwrap_or_else(|| action.default_key()),
+        )
+    }
+
+    /// Navigation keys shown in the controls bar, `hjkl` unless remapped
+    pub(crate) fn navigation_label(&self) -> String {
+        format!(
+            "←↑↓→/{}{}{}{}",
+            self.label(Action::Left),
+            self.label(Action::Down),
+            self.label(Action::Up),
+            self.label(Action::Right)
+        )
+    }
+}
+
+fn swap_case(code: KeyCode) -> KeyCode {
+    match code {
+        KeyCode::Char(c) if c.is_lowercase() => KeyCode::Char(c.to_ascii_uppercase()),
+        KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+        code => code,
+    }
+}
+
+/// Parses a single character, or a key name like `up`, `enter` or `space`
+fn parse_key(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let code = match key.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "space" => KeyCode::Char(' '),
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+    };
+    Some(code)
+}
+
+fn format_key(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        key => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key_map(keys: &[(&str, &str)]) -> KeyMap {
+        let keys = keys
+            .iter()
+            .map(|(action, key)| (action.to_string(), key.to_string()))
+            .collect();
+        KeyMap::new(&keys).unwrap()
+    }
+
+    fn translate(key_map: &KeyMap, c: char) -> KeyCode {
+        key_map
+            .translate(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+            .code
+    }
+
+    #[test]
+    fn test_letters_work_in_either_case() {
+        let key_map = KeyMap::default();
+        assert_eq!(translate(&key_map, 'Q'), KeyCode::Char('q'));
+        assert_eq!(translate(&key_map, 's'), KeyCode::Char('s'));
+        assert_eq!(translate(&key_map, 'S'), KeyCode::Char('S'));
+        assert_eq!(translate(&key_map, 'Y'), KeyCode::Char('y'));
+        assert_eq!(translate(&key_map, ']'), KeyCode::Char(']'));
+    }
+
+    #[test]
+    fn test_remapped_action_leaves_its_default_key() {
+        let key_map = key_map(&[("pause", "b"), ("sort", "n")]);
+        assert_eq!(translate(&key_map, 'b'), KeyCode::Char('p'));
+        assert_eq!(translate(&key_map, 'B'), KeyCode::Char('p'));
+        assert_eq!(translate(&key_map, 'p'), KeyCode::Null);
+        assert_eq!(translate(&key_map, 'P'), KeyCode::Null);
+        // `S` still reverses the order, but `s` doesn't fall back to it
+        assert_eq!(translate(&key_map, 'n'), KeyCode::Char('s'));
+        assert_eq!(translate(&key_map, 's'), KeyCode::Null);
+        assert_eq!(translate(&key_map, 'S'), KeyCode::Char('S'));
+    }
+
+    #[test]
+    fn test_remap_onto_default_key_of_another_action() {
+        let key_map = key_map(&[("hide_closed", "h"), ("left", "a")]);
+        assert_eq!(translate(&key_map, 'h'), KeyCode::Char('c'));
+        assert_eq!(translate(&key_map, 'H'), KeyCode::Char('c'));
+        assert_eq!(translate(&key_map, 'a'), KeyCode::Char('h'));
+        assert_eq!(translate(&key_map, 'c'), KeyCode::Null);
+        assert_eq!(translate(&key_map, 'C'), KeyCode::Null);
+    }
+}
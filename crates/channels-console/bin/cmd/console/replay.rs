@@ -0,0 +1,306 @@
+use channels_console::config::FileConfig;
+use channels_console::history::{read_history, HistoryRecord};
+use channels_console::samples::{ChannelHistoryJson, ChannelSample};
+use channels_console::{
+    ChannelLogs, ChannelStates, ChannelsJson, LogEntry, StateTransition, StreamsJson,
+};
+use clap::Parser;
+use eyre::Result;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use super::app::App;
+use super::http::{base_url, connect, fetch_history};
+use super::settings::load_settings;
+use super::source::Source;
+
+/// Max number of log entries shown per channel, like the library default `log_limit`
+const LOG_LIMIT: usize = 50;
+
+/// Refresh interval of the replay TUI
+const REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Parser)]
+pub struct ReplayArgs {
+    /// Recording created with CHANNELS_CONSOLE_RECORD, or history file created with
+    /// CHANNELS_CONSOLE_HISTORY_PATH
+    #[arg(required_unless_present_any = ["url", "socket"])]
+    pub path: Option<PathBuf>,
+
+    /// Replay the persisted history served by the metrics server at this URL, e.g.
+    /// `http://10.0.0.5:6770`
+    #[arg(long, conflicts_with = "path")]
+    pub url: Option<String>,
+
+    /// Bearer token sent to metrics servers started with `CHANNELS_CONSOLE_TOKEN`
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Replay the persisted history served on this Unix domain socket
+    #[arg(long, conflicts_with = "path")]
+    pub socket: Option<PathBuf>,
+
+    /// Playback speed, e.g. `2` plays twice as fast
+    #[arg(long, default_value_t = 1.0)]
+    pub speed: f64,
+
+    /// Only show channels with labels under this path prefix, e.g. `ingest/parser`
+    #[arg(long)]
+    pub prefix: Option<String>,
+
+    /// Console config file with key remaps, theme and saved layout
+    /// [default: ~/.config/channels-console/config.toml]
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+}
+
+impl ReplayArgs {
+    pub fn run(&self) -> Result<()> {
+        let (location, records) = self.load()?;
+        if records.is_empty() {
+            eyre::bail!("No records found in {}", location);
+        }
+        if self.speed <= 0.0 {
+            eyre::bail!("Playback speed must be positive");
+        }
+
+        // Recordings are shown with the saved layout, but don't change it
+        let (_, settings) = load_settings(self.config.as_ref())?;
+
+        let replay = Replay::new(location, records, self.speed);
+        let mut app = App::new(
+            Source::Replay(replay),
+            REFRESH_INTERVAL,
+            self.prefix.clone(),
+        )
+        .with_settings(&settings)?;
+
+        let mut terminal = ratatui::init();
+        let app_result = app.run(&mut terminal);
+        ratatui::restore();
+        app_result.map_err(|e| eyre::eyre!("TUI error: {}", e))
+    }
+
+    /// Records from the given file, or from the `/history` endpoint of a metrics server.
+    fn load(&self) -> Result<(String, Vec<HistoryRecord>)> {
+        if let Some(path) = &self.path {
+            let records = read_history(path)
+                .map_err(|e| eyre::eyre!("Failed to read {}: {}", path.display(), e))?;
+            return Ok((path.display().to_string(), records));
+        }
+
+        let file_config = match FileConfig::from_env() {
+            Some(result) => result.map_err(|e| eyre::eyre!("Invalid config file: {}", e))?,
+            None => FileConfig::default(),
+        };
+        let client = connect(
+            base_url(self.url.clone(), None, None, &file_config.console),
+            self.socket.clone(),
+            self.token.clone(),
+            &file_config.console,
+        );
+        let history = fetch_history(&client).map_err(|e| {
+            eyre::eyre!("Failed to fetch history from {}: {}", client.location(), e)
+        })?;
+        Ok((client.location(), history.records))
+    }
+}
+
+/// Plays back recorded snapshots in real time (scaled by `speed`), with pause and seek.
+pub(crate) struct Replay {
+    /// Path of the recording, or URL of the metrics server it was fetched from
+    location: String,
+    records: Vec<HistoryRecord>,
+    /// Index of the record currently shown
+    position: usize,
+    /// Wall-clock time of the recording being played, in milliseconds since the Unix epoch
+    playhead_ms: f64,
+    speed: f64,
+    playing: bool,
+    last_tick: Instant,
+}
+
+impl Replay {
+    pub(crate) fn new(location: String, records: Vec<HistoryRecord>, speed: f64) -> Self {
+        let playhead_ms = records.first().map_or(0, |r| r.timestamp_ms) as f64;
+        Self {
+            location,
+            records,
+            position: 0,
+            playhead_ms,
+            speed,
+            playing: true,
+            last_tick: Instant::now(),
+        }
+    }
+
+    pub(crate) fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Advances the playhead by the time elapsed since the previous tick.
+    fn tick(&mut self) {
+        let now = Instant::now();
+        if self.playing {
+            let elapsed_ms = now.duration_since(self.last_tick).as_secs_f64() * 1000.0;
+            self.seek_to(self.playhead_ms + elapsed_ms * self.speed);
+        }
+        self.last_tick = now;
+    }
+
+    pub(crate) fn set_playing(&mut self, playing: bool) {
+        self.playing = playing;
+        self.last_tick = Instant::now();
+    }
+
+    /// Moves the playhead by `delta_ms` (negative to rewind).
+    pub(crate) fn seek_by(&mut self, delta_ms: f64) {
+        self.seek_to(self.playhead_ms + delta_ms);
+    }
+
+    pub(crate) fn seek_to_start(&mut self) {
+        self.seek_to(f64::MIN);
+    }
+
+    pub(crate) fn seek_to_end(&mut self) {
+        self.seek_to(f64::MAX);
+    }
+
+    fn seek_to(&mut self, playhead_ms: f64) {
+        let first = self.records[0].timestamp_ms as f64;
+        let last = self.records[self.records.len() - 1].timestamp_ms as f64;
+        self.playhead_ms = playhead_ms.clamp(first, last);
+        self.position = self
+            .records
+            .partition_point(|record| record.timestamp_ms as f64 <= self.playhead_ms)
+            .saturating_sub(1);
+    }
+
+    fn current(&self) -> &HistoryRecord {
+        &self.records[self.position]
+    }
+
+    /// Channel statistics at the playhead.
+    pub(crate) fn channels(&mut self) -> ChannelsJson {
+        self.tick();
+        let record = self.current();
+        ChannelsJson {
+            schema_version: record.schema_version,
+            current_elapsed_ns: record.elapsed_ns,
+            start_unix_ms: record
+                .timestamp_ms
+                .saturating_sub(record.elapsed_ns / 1_000_000),
+            channels: record.channels.clone(),
+            closed_total: Default::default(),
+            next_cursor: None,
+        }
+    }
+
+    /// Stream statistics at the playhead.
+    pub(crate) fn streams(&self) -> StreamsJson {
+        let record = self.current();
+        StreamsJson {
+            schema_version: record.schema_version,
+            current_elapsed_ns: record.elapsed_ns,
+            start_unix_ms: record
+                .timestamp_ms
+                .saturating_sub(record.elapsed_ns / 1_000_000),
+            streams: record.streams.clone(),
+        }
+    }
+
+    /// Log entries of a channel recorded up to the playhead, most recent first.
+    pub(crate) fn channel_logs(&self, channel_id: u64) -> ChannelLogs {
+        let id = channel_id.to_string();
+        let mut sent_logs: Vec<LogEntry> = Vec::new();
+        let mut received_logs: Vec<LogEntry> = Vec::new();
+
+        for record in self.records[..=self.position].iter().rev() {
+            if sent_logs.len() >= LOG_LIMIT && received_logs.len() >= LOG_LIMIT {
+                break;
+            }
+            if let Some(logs) = record.logs.iter().find(|logs| logs.id == id) {
+                sent_logs.extend(logs.sent_logs.iter().rev().cloned());
+                received_logs.extend(logs.received_logs.iter().rev().cloned());
+            }
+        }
+        sent_logs.truncate(LOG_LIMIT);
+        received_logs.truncate(LOG_LIMIT);
+
+        ChannelLogs {
+            schema_version: self.current().schema_version,
+            id,
+            sent_logs,
+            received_logs,
+            next_cursor: None,
+        }
+    }
+
+    /// State changes of a channel seen in the records up to the playhead, oldest first.
+    pub(crate) fn channel_states(&self, channel_id: u64) -> ChannelStates {
+        let mut transitions: Vec<StateTransition> = Vec::new();
+        for record in &self.records[..=self.position] {
+            let Some(channel) = record.channels.iter().find(|c| c.id == channel_id) else {
+                continue;
+            };
+            if transitions.last().map(|t| t.state) != Some(channel.state) {
+                transitions.push(StateTransition {
+                    state: channel.state,
+                    timestamp: record.elapsed_ns,
+                });
+            }
+        }
+
+        ChannelStates {
+            schema_version: self.current().schema_version,
+            id: channel_id.to_string(),
+            transitions,
+        }
+    }
+
+    /// Queue depth of a channel in each record up to the playhead, oldest first.
+    pub(crate) fn channel_history(&self, channel_id: u64) -> ChannelHistoryJson {
+        let records = &self.records[..=self.position];
+        let samples = records
+            .iter()
+            .filter_map(|record| {
+                let channel = record.channels.iter().find(|c| c.id == channel_id)?;
+                Some(ChannelSample {
+                    elapsed_ns: record.elapsed_ns,
+                    queued: channel.queued,
+                    queued_bytes: channel.queued_bytes,
+                    sent_count: channel.sent_count,
+                    received_count: channel.received_count,
+                })
+            })
+            .collect();
+
+        ChannelHistoryJson {
+            schema_version: self.current().schema_version,
+            id: channel_id.to_string(),
+            interval_ms: match records {
+                [.., previous, last] => last.timestamp_ms.saturating_sub(previous.timestamp_ms),
+                _ => 0,
+            },
+            samples,
+        }
+    }
+
+    /// Playback state for the status bar, e.g. `⏵ 00:12 / 01:30 (2x)`.
+    pub(crate) fn status(&self) -> String {
+        let start = self.records[0].timestamp_ms as f64;
+        let end = self.records[self.records.len() - 1].timestamp_ms as f64;
+        format!(
+            "{} {} / {} ({}x)",
+            if self.playing { "⏵" } else { "⏸" },
+            format_position(self.playhead_ms - start),
+            format_position(end - start),
+            self.speed
+        )
+    }
+}
+
+fn format_position(ms: f64) -> String {
+    let secs = (ms / 1000.0) as u64;
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::columns::Columns;
+use super::sort::SortColumn;
+use super::split::{SplitDirection, SplitRatio};
+use super::theme::ThemeSettings;
+
+/// Table of the console config file the layout is saved in
+const LAYOUT_KEY: &str = "layout";
+
+/// Default console config file, `$XDG_CONFIG_HOME/channels-console/config.toml` or
+/// `~/.config/channels-console/config.toml`
+pub(crate) fn config_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("channels-console").join("config.toml"))
+}
+
+/// Loads the config file given with `--config`, or the default one. Returns its path, to save
+/// the layout to, along with its contents.
+pub(crate) fn load_settings(
+    config: Option<&PathBuf>,
+) -> eyre::Result<(Option<PathBuf>, ConsoleSettings)> {
+    let path = config.cloned().or_else(config_path);
+    let settings = match &path {
+        Some(path) => ConsoleSettings::load(path)
+            .map_err(|e| eyre::eyre!("Invalid config file {}: {}", path.display(), e))?,
+        None => ConsoleSettings::default(),
+    };
+    Ok((path, settings))
+}
+
+/// Contents of the console config file.
+///
+/// ```toml
+/// [keys]
+/// up = "w"
+/// down = "s"
+/// sort = "o"
+///
+/// [theme]
+/// name = "light"
+///
+/// [theme.colors]
+/// yellow = "#b58900"
+///
+/// [layout]
+/// columns = ["activity", "rates", "idle"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct ConsoleSettings {
+    /// Keys bound to actions, see [`KeyMap`](super::keymap::KeyMap)
+    pub(crate) keys: HashMap<String, String>,
+    pub(crate) theme: ThemeSettings,
+    pub(crate) layout: LayoutSettings,
+}
+
+impl ConsoleSettings {
+    /// Parses the config file, a missing file gives defaults.
+    pub(crate) fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents).map_err(|e| e.to_string()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Columns, sort order, filters and panes of the TUI, restored on startup and saved on exit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct LayoutSettings {
+    pub(crate) columns: Columns,
+    pub(crate) sort: SortColumn,
+    pub(crate) reversed: bool,
+    /// Search query of the channels table
+    pub(crate) filter: String,
+    pub(crate) hide_closed: bool,
+    pub(crate) split: SplitDirection,
+    /// Percentage of the screen taken by the channels table next to the logs panel
+    pub(crate) split_ratio: SplitRatio,
+    /// Whether log entries show the wall-clock time instead of their age
+    pub(crate) wall_clock: bool,
+}
+
+impl LayoutSettings {
+    /// Writes the `[layout]` table of the config file, keeping the keys and theme.
+    pub(crate) fn save(&self, path: &Path) -> Result<(), String> {
+        let mut table = match std::fs::read_to_string(path) {
+            Ok(contents) => contents.parse::<toml::Table>().map_err(|e| e.to_string())?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml::Table::new(),
+            Err(e) => return Err(e.to_string()),
+        };
+        let layout = toml::Value::try_from(self).map_err(|e| e.to_string())?;
+        table.insert(LAYOUT_KEY.to_string(), layout);
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let contents = toml::to_string_pretty(&table).map_err(|e| e.to_string())?;
+        std::fs::write(path, contents).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,45 @@
+use channels_console::report::{render_csv, render_table};
+use clap::{Parser, ValueEnum};
+use eyre::Result;
+
+use super::http::{check_compatibility, fetch_combined, ServerArgs};
+
+/// Output format of the `snapshot` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnapshotFormat {
+    Json,
+    Table,
+    Csv,
+}
+
+#[derive(Debug, Parser)]
+pub struct SnapshotArgs {
+    #[command(flatten)]
+    pub server: ServerArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = SnapshotFormat::Table)]
+    pub format: SnapshotFormat,
+}
+
+impl SnapshotArgs {
+    /// Fetches channels and streams once and prints them, failing when the server is unreachable.
+    pub fn run(&self) -> Result<()> {
+        let client = self.server.client()?;
+        let combined = check_compatibility(&client)
+            .and_then(|_| fetch_combined(&client, self.server.prefix.as_deref()))
+            .map_err(|e| eyre::eyre!("Failed to fetch from {}: {}", client.location(), e))?;
+
+        match self.format {
+            SnapshotFormat::Json => println!("{}", serde_json::to_string(&combined)?),
+            SnapshotFormat::Table
+                if combined.channels.is_empty() && combined.streams.is_empty() =>
+            {
+                println!("No instrumented channels or streams found.");
+            }
+            SnapshotFormat::Table => print!("{}", render_table(&combined)),
+            SnapshotFormat::Csv => print!("{}", render_csv(&combined)),
+        }
+        Ok(())
+    }
+}
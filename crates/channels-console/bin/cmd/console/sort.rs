@@ -0,0 +1,107 @@
+use channels_console::{ChannelState, SerializableChannelStats};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Column the channels table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SortColumn {
+    /// Order served by the metrics server: custom labels first, then by source
+    #[default]
+    Default,
+    Queued,
+    QueuedBytes,
+    SendRate,
+    State,
+    Label,
+}
+
+impl SortColumn {
+    fn next(self) -> Self {
+        match self {
+            Self::Default => Self::Queued,
+            Self::Queued => Self::QueuedBytes,
+            Self::QueuedBytes => Self::SendRate,
+            Self::SendRate => Self::State,
+            Self::State => Self::Label,
+            Self::Label => Self::Default,
+        }
+    }
+}
+
+/// Client-side ordering of the channels table
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Sort {
+    pub(crate) column: SortColumn,
+    pub(crate) reversed: bool,
+    /// Rows keep their current order, so they don't jump around while being read
+    pub(crate) frozen: bool,
+}
+
+impl Sort {
+    pub(crate) fn cycle_column(&mut self) {
+        self.column = self.column.next();
+    }
+
+    pub(crate) fn reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    pub(crate) fn toggle_frozen(&mut self) {
+        self.frozen = !self.frozen;
+    }
+
+    /// Arrow shown next to the header of the sorted column, if it's `column`
+    pub(crate) fn marker(&self, column: SortColumn) -> &'static str {
+        match (self.column == column, self.reversed) {
+            (false, _) => "",
+            (true, false) => " ▼",
+            (true, true) => " ▲",
+        }
+    }
+
+    /// Sorts `stats` in place. Numeric columns put the largest values first, labels sort
+    /// alphabetically. Ties keep the server order.
+    pub(crate) fn apply(&self, stats: &mut [SerializableChannelStats]) {
+        if self.column == SortColumn::Default {
+            if self.reversed {
+                stats.reverse();
+            }
+            return;
+        }
+
+        stats.sort_by(|a, b| {
+            let ordering = compare(self.column, a, b);
+            if self.reversed {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+fn compare(
+    column: SortColumn,
+    a: &SerializableChannelStats,
+    b: &SerializableChannelStats,
+) -> Ordering {
+    match column {
+        SortColumn::Default => Ordering::Equal,
+        SortColumn::Queued => b.queued.cmp(&a.queued),
+        SortColumn::QueuedBytes => b.queued_bytes.cmp(&a.queued_bytes),
+        SortColumn::SendRate => b.send_rate.last_10s.total_cmp(&a.send_rate.last_10s),
+        SortColumn::State => state_rank(a.state).cmp(&state_rank(b.state)),
+        SortColumn::Label => a.label.cmp(&b.label),
+    }
+}
+
+/// Full channels come first, closed ones last
+fn state_rank(state: ChannelState) -> u8 {
+    match state {
+        ChannelState::Full | ChannelState::Stalled => 0,
+        ChannelState::Active | ChannelState::Unknown => 1,
+        ChannelState::Notified => 2,
+        ChannelState::Closed => 3,
+    }
+}
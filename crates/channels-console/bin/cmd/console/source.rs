@@ -0,0 +1,183 @@
+use channels_console::actors::ChannelActorsJson;
+use channels_console::alerts::AlertsJson;
+use channels_console::correlation::Journey;
+use channels_console::info::InfoJson;
+use channels_console::latency::LatencyHistogramJson;
+use channels_console::samples::ChannelHistoryJson;
+use channels_console::summary::SummaryJson;
+use channels_console::{ChannelLogs, ChannelStates, ChannelsJson, StreamsJson};
+use eyre::Result;
+use std::str::FromStr;
+
+use super::http::{
+    check_compatibility, fetch_alerts, fetch_channel_actors, fetch_channel_history,
+    fetch_channel_logs, fetch_channel_states, fetch_channels, fetch_channels_since, fetch_info,
+    fetch_journey, fetch_latency_histogram, fetch_streams, fetch_summary, normalize_url,
+    reset_stats, Client,
+};
+use super::replay::Replay;
+
+/// Metrics server of one of the monitored processes, given with `--target name=url`
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub(crate) name: String,
+    pub(crate) base_url: String,
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    /// Parses `name=url`. A bare url is named after itself.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, url) = s.split_once('=').unwrap_or((s, s));
+        if name.is_empty() || url.is_empty() {
+            return Err(format!("expected NAME=URL, got '{}'", s));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            base_url: normalize_url(url),
+        })
+    }
+}
+
+/// Where the console reads statistics from
+pub(crate) enum Source {
+    /// Metrics server of a running program
+    Http(Client),
+    /// Recording played back from a file
+    Replay(Replay),
+}
+
+impl Source {
+    /// All channels, updating `previous` from the same process with only the changes if given
+    pub(crate) fn channels(&mut self, previous: Option<&ChannelsJson>) -> Result<ChannelsJson> {
+        match self {
+            Self::Http(client) => match previous {
+                Some(previous) => fetch_channels_since(client, previous),
+                None => fetch_channels(client),
+            },
+            Self::Replay(replay) => Ok(replay.channels()),
+        }
+    }
+
+    pub(crate) fn channel_logs(&self, channel_id: u64) -> Result<ChannelLogs> {
+        match self {
+            Self::Http(client) => fetch_channel_logs(client, channel_id),
+            Self::Replay(replay) => Ok(replay.channel_logs(channel_id)),
+        }
+    }
+
+    pub(crate) fn channel_states(&self, channel_id: u64) -> Result<ChannelStates> {
+        match self {
+            Self::Http(client) => fetch_channel_states(client, channel_id),
+            Self::Replay(replay) => Ok(replay.channel_states(channel_id)),
+        }
+    }
+
+    pub(crate) fn channel_history(&self, channel_id: u64) -> Result<ChannelHistoryJson> {
+        match self {
+            Self::Http(client) => fetch_channel_history(client, channel_id),
+            Self::Replay(replay) => Ok(replay.channel_history(channel_id)),
+        }
+    }
+
+    pub(crate) fn latency_histogram(&self, channel_id: u64) -> Result<LatencyHistogramJson> {
+        match self {
+            Self::Http(client) => fetch_latency_histogram(client, channel_id),
+            Self::Replay(_) => eyre::bail!("Latency histograms are not recorded"),
+        }
+    }
+
+    pub(crate) fn channel_actors(&self, channel_id: u64) -> Result<ChannelActorsJson> {
+        match self {
+            Self::Http(client) => fetch_channel_actors(client, channel_id),
+            Self::Replay(_) => eyre::bail!("Actors are not recorded"),
+        }
+    }
+
+    pub(crate) fn journey(&self, correlation_id: u64) -> Result<Journey> {
+        match self {
+            Self::Http(client) => fetch_journey(client, correlation_id),
+            Self::Replay(_) => eyre::bail!("Journeys are not recorded"),
+        }
+    }
+
+    pub(crate) fn alerts(&self) -> Result<AlertsJson> {
+        match self {
+            Self::Http(client) => fetch_alerts(client),
+            Self::Replay(_) => eyre::bail!("Alerts are not recorded"),
+        }
+    }
+
+    /// Fails with an upgrade hint if the server payloads can't be read. Recordings are decoded
+    /// leniently, so they always pass.
+    pub(crate) fn check_compatibility(&self) -> Result<()> {
+        match self {
+            Self::Http(client) => check_compatibility(client),
+            Self::Replay(_) => Ok(()),
+        }
+    }
+
+    pub(crate) fn info(&self) -> Result<InfoJson> {
+        match self {
+            Self::Http(client) => fetch_info(client),
+            Self::Replay(_) => eyre::bail!("Process info is not recorded"),
+        }
+    }
+
+    /// Zeroes the statistics of the monitored process
+    pub(crate) fn reset(&self) -> Result<()> {
+        match self {
+            Self::Http(client) => reset_stats(client).map(|_| ()),
+            Self::Replay(_) => eyre::bail!("Recordings can't be reset"),
+        }
+    }
+
+    pub(crate) fn summary(&self) -> Result<SummaryJson> {
+        match self {
+            Self::Http(client) => fetch_summary(client),
+            Self::Replay(_) => eyre::bail!("Collection mode is not recorded"),
+        }
+    }
+
+    pub(crate) fn streams(&self) -> Result<StreamsJson> {
+        match self {
+            Self::Http(client) => fetch_streams(client),
+            Self::Replay(replay) => Ok(replay.streams()),
+        }
+    }
+
+    /// URL of the metrics server, or path of the recording
+    pub(crate) fn location(&self) -> String {
+        match self {
+            Self::Http(client) => client.location(),
+            Self::Replay(replay) => replay.location().to_string(),
+        }
+    }
+
+    /// Points an HTTP source at another metrics server
+    pub(crate) fn set_target(&mut self, target: &Target) {
+        if let Self::Http(Client::Tcp { base_url, .. }) = self {
+            *base_url = target.base_url.clone();
+        }
+    }
+
+    pub(crate) fn is_replay(&self) -> bool {
+        matches!(self, Self::Replay(_))
+    }
+
+    /// Playback state when replaying a recording
+    pub(crate) fn replay_status(&self) -> Option<String> {
+        match self {
+            Self::Http(_) => None,
+            Self::Replay(replay) => Some(replay.status()),
+        }
+    }
+
+    pub(crate) fn replay_mut(&mut self) -> Option<&mut Replay> {
+        match self {
+            Self::Http(_) => None,
+            Self::Replay(replay) => Some(replay),
+        }
+    }
+}
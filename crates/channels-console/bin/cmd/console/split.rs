@@ -0,0 +1,66 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use serde::{Deserialize, Serialize};
+
+/// How the channels table and the logs panel share the screen, toggled with `|`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SplitDirection {
+    /// Logs panel next to the table
+    #[default]
+    Horizontal,
+    /// Logs panel below the table
+    Vertical,
+}
+
+impl SplitDirection {
+    pub(crate) fn toggle(self) -> Self {
+        match self {
+            Self::Horizontal => Self::Vertical,
+            Self::Vertical => Self::Horizontal,
+        }
+    }
+
+    /// Splits `area` into the table and logs panel areas
+    pub(crate) fn split(self, area: Rect, ratio: SplitRatio) -> (Rect, Rect) {
+        let constraints = [
+            Constraint::Percentage(ratio.percent()),
+            Constraint::Percentage(100 - ratio.percent()),
+        ];
+        let [table_area, logs_area] = match self {
+            Self::Horizontal => Layout::horizontal(constraints).areas(area),
+            Self::Vertical => Layout::vertical(constraints).areas(area),
+        };
+        (table_area, logs_area)
+    }
+}
+
+/// Percentage of the screen taken by the channels table while the logs panel is open, resized
+/// with `<` and `>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct SplitRatio(u16);
+
+impl Default for SplitRatio {
+    fn default() -> Self {
+        Self(50)
+    }
+}
+
+impl SplitRatio {
+    const STEP: u16 = 5;
+    const MIN: u16 = 20;
+    const MAX: u16 = 80;
+
+    /// Percentage kept in bounds, as the config file may hold any value
+    pub(crate) fn percent(self) -> u16 {
+        self.0.clamp(Self::MIN, Self::MAX)
+    }
+
+    pub(crate) fn shrink(self) -> Self {
+        Self(self.percent().saturating_sub(Self::STEP).max(Self::MIN))
+    }
+
+    pub(crate) fn grow(self) -> Self {
+        Self((self.percent() + Self::STEP).min(Self::MAX))
+    }
+}
@@ -0,0 +1,78 @@
+use ratatui::{buffer::Buffer, style::Color};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Built-in color scheme, picked with `name` in the `[theme]` table of the console config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ThemeName {
+    /// Colors the views are drawn with, made for dark terminals
+    #[default]
+    Dark,
+    /// Darker text colors, readable on light terminal backgrounds
+    Light,
+    /// Terminal default colors only, selections stay visible as reversed text
+    NoColor,
+}
+
+/// `[theme]` table of the console config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct ThemeSettings {
+    pub(crate) name: ThemeName,
+    /// Colors replacing the ones of the theme, e.g. `yellow = "#b58900"`
+    pub(crate) colors: HashMap<String, String>,
+}
+
+/// Colors the rendered frame is repainted with.
+///
+/// Views are drawn with the dark theme colors, which are then swapped in the frame buffer, so
+/// views don't have to know about themes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Theme {
+    no_color: bool,
+    palette: HashMap<Color, Color>,
+}
+
+impl Theme {
+    pub(crate) fn new(settings: &ThemeSettings) -> Result<Self, String> {
+        let mut palette = match settings.name {
+            ThemeName::Dark | ThemeName::NoColor => HashMap::new(),
+            ThemeName::Light => HashMap::from([
+                (Color::White, Color::Black),
+                (Color::Gray, Color::DarkGray),
+                (Color::DarkGray, Color::Gray),
+                (Color::Yellow, Color::Rgb(0x9a, 0x67, 0x00)),
+                (Color::Green, Color::Rgb(0x1a, 0x7f, 0x37)),
+                (Color::Cyan, Color::Rgb(0x00, 0x6b, 0x80)),
+                (Color::Blue, Color::Rgb(0x03, 0x47, 0xa6)),
+            ]),
+        };
+        for (from, to) in &settings.colors {
+            let from = Color::from_str(from).map_err(|_| format!("invalid color '{}'", from))?;
+            let to = Color::from_str(to).map_err(|_| format!("invalid color '{}'", to))?;
+            palette.insert(from, to);
+        }
+        Ok(Self {
+            no_color: settings.name == ThemeName::NoColor,
+            palette,
+        })
+    }
+
+    /// Repaints the cells of a rendered frame with the theme colors.
+    pub(crate) fn apply(&self, buffer: &mut Buffer) {
+        if !self.no_color && self.palette.is_empty() {
+            return;
+        }
+        for cell in &mut buffer.content {
+            if self.no_color {
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            } else {
+                cell.fg = self.palette.get(&cell.fg).copied().unwrap_or(cell.fg);
+                cell.bg = self.palette.get(&cell.bg).copied().unwrap_or(cell.bg);
+            }
+        }
+    }
+}
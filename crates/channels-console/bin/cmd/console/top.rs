@@ -0,0 +1,38 @@
+use clap::{Parser, ValueEnum};
+use eyre::Result;
+
+use super::app::ConsoleArgs;
+use super::sort::{Sort, SortColumn};
+
+/// Column `top` ranks channels by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TopSort {
+    /// Number of queued messages
+    Queued,
+    /// Memory held by queued messages
+    Bytes,
+}
+
+#[derive(Debug, Parser)]
+pub struct TopArgs {
+    #[command(flatten)]
+    pub console: ConsoleArgs,
+
+    /// Column the channels are ranked by, largest backlog first
+    #[arg(long, value_enum, default_value_t = TopSort::Queued)]
+    pub sort: TopSort,
+}
+
+impl TopArgs {
+    /// Starts the TUI ranked by backlog, like `htop` for channels
+    pub fn run(&self) -> Result<()> {
+        let column = match self.sort {
+            TopSort::Queued => SortColumn::Queued,
+            TopSort::Bytes => SortColumn::QueuedBytes,
+        };
+        self.console.run_sorted(Some(Sort {
+            column,
+            ..Sort::default()
+        }))
+    }
+}
@@ -0,0 +1,73 @@
+use channels_console::SerializableChannelStats;
+use std::collections::{HashMap, VecDeque};
+
+/// Number of queue depth samples kept per channel
+pub(crate) const HISTORY_LEN: usize = 60;
+
+/// Number of refreshes the send activity of each channel is kept for
+pub(crate) const ACTIVITY_LEN: usize = 8;
+
+/// Recent queue depths and send counts of each channel, one sample per refresh, oldest first
+#[derive(Debug, Default)]
+pub(crate) struct QueueHistory {
+    samples: HashMap<u64, VecDeque<u64>>,
+    /// Messages sent between consecutive refreshes
+    sends: HashMap<u64, VecDeque<u64>>,
+    /// Sent count of each channel at the previous refresh
+    sent_counts: HashMap<u64, u64>,
+    /// Largest queue depth sampled for each channel, beyond the samples kept
+    high_water: HashMap<u64, u64>,
+}
+
+impl QueueHistory {
+    /// Appends the current queue depths and forgets channels that are gone.
+    pub(crate) fn record(&mut self, stats: &[SerializableChannelStats]) {
+        self.samples
+            .retain(|id, _| stats.iter().any(|stat| stat.id == *id));
+        self.high_water
+            .retain(|id, _| self.samples.contains_key(id));
+        self.sends.retain(|id, _| self.samples.contains_key(id));
+        self.sent_counts
+            .retain(|id, _| self.samples.contains_key(id));
+
+        for stat in stats {
+            let samples = self.samples.entry(stat.id).or_default();
+            if samples.len() == HISTORY_LEN {
+                samples.pop_front();
+            }
+            samples.push_back(stat.queued);
+            let high_water = self.high_water.entry(stat.id).or_default();
+            *high_water = (*high_water).max(stat.queued);
+
+            // Counters going down after a reset count as no sends
+            if let Some(previous) = self.sent_counts.insert(stat.id, stat.sent_count) {
+                let sends = self.sends.entry(stat.id).or_default();
+                if sends.len() == ACTIVITY_LEN {
+                    sends.pop_front();
+                }
+                sends.push_back(stat.sent_count.saturating_sub(previous));
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.samples.clear();
+        self.high_water.clear();
+        self.sends.clear();
+        self.sent_counts.clear();
+    }
+
+    pub(crate) fn get(&self, channel_id: u64) -> Option<&VecDeque<u64>> {
+        self.samples.get(&channel_id)
+    }
+
+    /// Messages the channel sent between each of the last `ACTIVITY_LEN` refreshes
+    pub(crate) fn sends(&self, channel_id: u64) -> Option<&VecDeque<u64>> {
+        self.sends.get(&channel_id)
+    }
+
+    /// Largest queue depth of the channel seen since the console started, or the last clear
+    pub(crate) fn high_water(&self, channel_id: u64) -> Option<u64> {
+        self.high_water.get(&channel_id).copied()
+    }
+}
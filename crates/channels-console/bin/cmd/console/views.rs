@@ -1,6 +1,14 @@
+pub(crate) mod alerts_bar;
 pub(crate) mod bottom_bar;
 pub(crate) mod channels;
+pub(crate) mod chart;
+pub(crate) mod columns;
+pub(crate) mod detail;
+pub(crate) mod help;
 pub(crate) mod inspect;
 pub(crate) mod logs;
 pub(crate) mod main_view;
+pub(crate) mod stalled_bar;
+pub(crate) mod summary_bar;
+pub(crate) mod toast;
 pub(crate) mod top_bar;
@@ -0,0 +1,30 @@
+use channels_console::alerts::Alert;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// Renders a highlighted row listing the currently active alerts
+pub fn render_alerts_bar(frame: &mut Frame, area: Rect, alerts: &[Alert]) {
+    let mut spans = vec![Span::from(format!(
+        " ⚠ {} active alert{}: ",
+        alerts.len(),
+        if alerts.len() == 1 { "" } else { "s" }
+    ))
+    .bold()];
+
+    for (idx, alert) in alerts.iter().enumerate() {
+        if idx > 0 {
+            spans.push(" | ".into());
+        }
+        spans.push(alert.rule.clone().bold());
+        spans.push(format!(" '{}' {}", alert.channel_label, alert.condition).into());
+    }
+
+    let paragraph =
+        Paragraph::new(Line::from(spans)).style(Style::default().fg(Color::White).bg(Color::Red));
+    frame.render_widget(paragraph, area);
+}
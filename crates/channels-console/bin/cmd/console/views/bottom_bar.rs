@@ -8,52 +8,180 @@ use ratatui::{
 };
 use std::time::Duration;
 
-use crate::cmd::console::app::Focus;
+use crate::cmd::console::app::{Connection, Focus};
+use crate::cmd::console::filter::Filter;
+use crate::cmd::console::keymap::{Action, KeyMap};
 
 /// Renders the bottom controls bar showing context-aware keybindings
+#[allow(clippy::too_many_arguments)]
 pub fn render_bottom_bar(
     frame: &mut Frame,
     area: Rect,
     focus: Focus,
+    filter: &Filter,
+    replay: bool,
+    collection_paused: bool,
+    confirming_reset: bool,
+    choosing_columns: bool,
+    keys: &KeyMap,
+    connection: Option<(Connection, &str)>,
     _last_render_duration: Duration,
 ) {
-    let controls_line = match focus {
+    let mut controls_line = match focus {
+        _ if confirming_reset => Line::from(vec![
+            " Reset all statistics and logs of the process? "
+                .yellow()
+                .bold(),
+            "<y> ".blue().bold(),
+            " | Cancel ".into(),
+            "<any key> ".blue().bold(),
+        ]),
+        _ if choosing_columns => Line::from(vec![
+            " Navigate ".into(),
+            format!(
+                "<↑↓/{}{}> ",
+                keys.label(Action::Down),
+                keys.label(Action::Up)
+            )
+            .blue()
+            .bold(),
+            " | Toggle Column ".into(),
+            "<Space/Enter> ".blue().bold(),
+            " | Close ".into(),
+            format!("<{}/Esc> ", keys.label(Action::Columns))
+                .blue()
+                .bold(),
+        ]),
+        _ if filter.is_editing() => Line::from(vec![
+            " Search ".into(),
+            format!("/{}▏", filter.query()).yellow().bold(),
+            " | Apply ".into(),
+            "<Enter> ".blue().bold(),
+            " | Clear ".into(),
+            "<Esc> ".blue().bold(),
+        ]),
         Focus::Channels => Line::from(vec![
             " Quit ".into(),
-            "<q> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::Quit)).blue().bold(),
             " | Navigate ".into(),
-            "<←↑↓→/hjkl> ".blue().bold(),
+            format!("<{}> ", keys.navigation_label()).blue().bold(),
             " | Toggle Logs ".into(),
-            "<o> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::ToggleLogs))
+                .blue()
+                .bold(),
             " | Pause ".into(),
-            "<p> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::Pause)).blue().bold(),
+            " | Reset ".into(),
+            format!("<{}> ", keys.label(Action::Reset)).blue().bold(),
+            " | Collapse ".into(),
+            format!("<{}> ", keys.label(Action::Collapse)).blue().bold(),
+            " | Group ".into(),
+            format!("<{}> ", keys.label(Action::Group)).blue().bold(),
+            " | Hide Closed ".into(),
+            format!("<{}> ", keys.label(Action::HideClosed))
+                .blue()
+                .bold(),
+            " | Sort ".into(),
+            format!(
+                "<{}/{}> ",
+                keys.label(Action::Sort),
+                keys.label(Action::ReverseSort)
+            )
+            .blue()
+            .bold(),
+            " | Freeze ".into(),
+            format!("<{}> ", keys.label(Action::Freeze)).blue().bold(),
+            " | Columns ".into(),
+            format!("<{}> ", keys.label(Action::Columns)).blue().bold(),
+            " | Search ".into(),
+            format!("<{}> ", keys.label(Action::Search)).blue().bold(),
+            " | Details ".into(),
+            format!("<{}> ", keys.label(Action::Details)).blue().bold(),
+            " | Export ".into(),
+            format!("<{}> ", keys.label(Action::Export)).blue().bold(),
+            " | Help ".into(),
+            format!("<{}> ", keys.label(Action::Help)).blue().bold(),
         ]),
         Focus::Logs => Line::from(vec![
             " Quit ".into(),
-            "<q> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::Quit)).blue().bold(),
             " | Navigate ".into(),
-            "<←↑↓→/hjkl> ".blue().bold(),
+            format!("<{}> ", keys.navigation_label()).blue().bold(),
             " | Toggle Logs ".into(),
-            "<o> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::ToggleLogs))
+                .blue()
+                .bold(),
             " | Pause ".into(),
-            "<p> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::Pause)).blue().bold(),
             " | Inspect ".into(),
-            "<i> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::Inspect)).blue().bold(),
+            " | View ".into(),
+            format!("<{}> ", keys.label(Action::LogsView)).blue().bold(),
+            " | Follow ".into(),
+            format!("<{}> ", keys.label(Action::Freeze)).blue().bold(),
+            " | Scroll ".into(),
+            "<PgUp PgDn Home End> ".blue().bold(),
+            " | Search ".into(),
+            format!("<{}> ", keys.label(Action::Search)).blue().bold(),
+        ]),
+        Focus::Detail => Line::from(vec![
+            " Quit ".into(),
+            format!("<{}> ", keys.label(Action::Quit)).blue().bold(),
+            " | Navigate ".into(),
+            format!(
+                "<↑↓/{}{}> ",
+                keys.label(Action::Down),
+                keys.label(Action::Up)
+            )
+            .blue()
+            .bold(),
+            " | Pause ".into(),
+            format!("<{}> ", keys.label(Action::Pause)).blue().bold(),
+            " | Export ".into(),
+            format!("<{}> ", keys.label(Action::Export)).blue().bold(),
+            " | Close ".into(),
+            "<Enter/Esc> ".blue().bold(),
         ]),
         Focus::Inspect => Line::from(vec![
             " Quit ".into(),
-            "<q> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::Quit)).blue().bold(),
             " | Navigate ".into(),
-            "<←↑↓→/hjkl> ".blue().bold(),
+            format!("<{}> ", keys.navigation_label()).blue().bold(),
             " | Toggle Logs ".into(),
-            "<o> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::ToggleLogs))
+                .blue()
+                .bold(),
             " | Pause ".into(),
-            "<p> ".blue().bold(),
+            format!("<{}> ", keys.label(Action::Pause)).blue().bold(),
+            " | Copy ".into(),
+            format!("<{}> ", keys.label(Action::Copy)).blue().bold(),
+            " | Scroll ".into(),
+            "<PgUp PgDn Home End> ".blue().bold(),
+            " | Fold ".into(),
+            format!(
+                "<{}/{}> ",
+                keys.label(Action::Fold),
+                keys.label(Action::Unfold)
+            )
+            .blue()
+            .bold(),
             " | Close ".into(),
-            "<i/o/h> ".blue().bold(),
+            format!(
+                "<{}/{}/{}> ",
+                keys.label(Action::Inspect),
+                keys.label(Action::ToggleLogs),
+                keys.label(Action::Left)
+            )
+            .blue()
+            .bold(),
         ]),
     };
 
+    if replay && !filter.is_editing() && !confirming_reset && !choosing_columns {
+        controls_line.push_span(" | Seek ");
+        controls_line.push_span("<[ ] Home End> ".blue().bold());
+    }
+
     #[cfg(feature = "dev")]
     let block = {
         use ratatui::text::Line;
@@ -72,6 +200,25 @@ pub fn render_bottom_bar(
         .title(" Controls ")
         .border_set(border::PLAIN);
 
+    let block = if collection_paused {
+        block.title(" ⏸ Collection paused, counters only ".yellow().bold())
+    } else {
+        block
+    };
+
+    let block = match connection {
+        Some((connection, location)) => {
+            let indicator = match connection {
+                Connection::Connecting => "◌ Connecting to ".yellow(),
+                Connection::Connected => "● Connected to ".green(),
+                Connection::Disconnected => "● Disconnected from ".red(),
+            };
+            block
+                .title(Line::from(vec![indicator, format!("{} ", location).into()]).right_aligned())
+        }
+        None => block,
+    };
+
     let paragraph = Paragraph::new(controls_line).block(block).left_aligned();
 
     frame.render_widget(paragraph, area);
@@ -1,6 +1,18 @@
 use crate::cmd::console::app::Focus;
-use crate::cmd::console::widgets::formatters::{queue_status, truncate_left};
-use channels_console::{format_bytes, ChannelState, ChannelType, SerializableChannelStats};
+use crate::cmd::console::columns::{Column, Columns};
+use crate::cmd::console::filter::Filter;
+use crate::cmd::console::grouping::{GroupBy, GroupRow};
+use crate::cmd::console::sort::{Sort, SortColumn};
+use crate::cmd::console::trend::{QueueHistory, ACTIVITY_LEN};
+use crate::cmd::console::widgets::formatters::{
+    format_byte_rate, format_delay, format_rate, format_time_ago, queue_status, sparkline,
+    truncate_left,
+};
+use channels_console::labels::LabelGroup;
+use channels_console::rates::RateStatus;
+use channels_console::{
+    format_bytes, ChannelState, ChannelType, SerializableChannelStats, SerializableStreamStats,
+};
 use ratatui::{
     layout::{Constraint, Rect},
     style::{Color, Modifier, Style},
@@ -9,11 +21,17 @@ use ratatui::{
     widgets::{Block, Cell, HighlightSpacing, Row, Table, TableState},
     Frame,
 };
+use std::collections::VecDeque;
 
-/// Renders the channels table with channel statistics
+/// Relative width of the queue depth sparkline column
+const TREND_WEIGHT: u16 = 10;
+
+/// Renders the channels table with channel statistics, followed by the streams. Stream rows
+/// leave the queue columns empty and can't be selected.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn render_channels_panel(
     stats: &[SerializableChannelStats],
+    streams: &[SerializableStreamStats],
     area: Rect,
     frame: &mut Frame,
     table_state: &mut TableState,
@@ -21,6 +39,11 @@ pub(crate) fn render_channels_panel(
     focus: Focus,
     channel_position: usize,
     total_channels: usize,
+    sort: Sort,
+    filter: &Filter,
+    queue_history: &QueueHistory,
+    current_elapsed_ns: u64,
+    columns: &Columns,
 ) {
     let available_width = area.width.saturating_sub(10);
     let channel_width = ((available_width as f32 * 0.22) as usize).max(36);
@@ -29,52 +52,149 @@ pub(crate) fn render_channels_panel(
         .fg(Color::Yellow)
         .add_modifier(Modifier::BOLD);
 
-    let header = Row::new(vec![
-        Cell::from("Channel"),
-        Cell::from("Type"),
-        Cell::from("State"),
-        Cell::from("Sent"),
-        Cell::from("Received"),
-        Cell::from("Queue"),
-        Cell::from("Mem"),
-    ])
+    // Each column as its header, relative width and, if optional, the column toggling it
+    let layout: Vec<(String, u16, Option<Column>)> = vec![
+        (format!("Name{}", sort.marker(SortColumn::Label)), 14, None),
+        ("Kind".to_string(), 5, None),
+        ("Activity".to_string(), 5, Some(Column::Activity)),
+        ("Source".to_string(), 12, Some(Column::Source)),
+        ("Type".to_string(), 6, None),
+        ("Size".to_string(), 5, Some(Column::TypeSize)),
+        (format!("State{}", sort.marker(SortColumn::State)), 7, None),
+        ("Sent".to_string(), 6, None),
+        ("Received".to_string(), 6, None),
+        (
+            format!("Send/s{}", sort.marker(SortColumn::SendRate)),
+            6,
+            Some(Column::Rates),
+        ),
+        ("Recv/s".to_string(), 6, Some(Column::Rates)),
+        ("Bytes/s".to_string(), 7, Some(Column::Throughput)),
+        ("p99".to_string(), 6, None),
+        ("Blocked".to_string(), 6, None),
+        (format!("Queue{}", sort.marker(SortColumn::Queued)), 8, None),
+        ("Peak".to_string(), 5, Some(Column::HighWater)),
+        ("Trend".to_string(), TREND_WEIGHT, None),
+        (
+            format!("Mem{}", sort.marker(SortColumn::QueuedBytes)),
+            6,
+            None,
+        ),
+        ("Expected".to_string(), 7, None),
+        ("Idle".to_string(), 6, Some(Column::Idle)),
+    ];
+    let shown: Vec<bool> = layout
+        .iter()
+        .map(|(_, _, column)| column.is_none_or(|column| columns.is_visible(column)))
+        .collect();
+    let total_weight: u16 = layout
+        .iter()
+        .zip(&shown)
+        .filter_map(|((_, weight, _), shown)| shown.then_some(*weight))
+        .sum();
+    let trend_width =
+        (available_width as usize * TREND_WEIGHT as usize / total_weight as usize).max(1);
+    let visible = |cells: Vec<Cell<'static>>| -> Vec<Cell<'static>> {
+        cells
+            .into_iter()
+            .zip(&shown)
+            .filter_map(|(cell, shown)| shown.then_some(cell))
+            .collect()
+    };
+
+    let header = Row::new(visible(
+        layout
+            .iter()
+            .map(|(title, _, _)| Cell::from(title.clone()))
+            .collect(),
+    ))
     .style(header_style)
     .height(1);
 
-    let rows: Vec<Row> = stats
+    // Dim the rows if logs are shown and channels table is not focused
+    let dimmed = show_logs && !matches!(focus, Focus::Channels);
+
+    let mut rows: Vec<Row> = stats
         .iter()
         .map(|stat| {
-            let (state_text, state_style) = match stat.state {
-                ChannelState::Active => (stat.state.to_string(), Style::default().fg(Color::Green)),
-                ChannelState::Closed => {
-                    (stat.state.to_string(), Style::default().fg(Color::Yellow))
-                }
-                ChannelState::Full => {
-                    (format!("⚠ {}", stat.state), Style::default().fg(Color::Red))
-                }
-                ChannelState::Notified => {
-                    (stat.state.to_string(), Style::default().fg(Color::Blue))
-                }
-            };
-
             let mem_cell = match &stat.channel_type {
                 ChannelType::Unbounded => Cell::from("N/A"),
                 _ => Cell::from(format_bytes(stat.queued_bytes)),
             };
             let queue_cell = queue_status(stat.queued, &stat.channel_type, 8);
+            let trend_cell = match queue_history.get(stat.id) {
+                Some(samples) => {
+                    // Bounded channels are scaled to their capacity, others to the recent peak
+                    let max = match stat.channel_type {
+                        ChannelType::Bounded(cap) => cap as u64,
+                        ChannelType::Oneshot => 1,
+                        ChannelType::Unbounded => samples.iter().copied().max().unwrap_or(0),
+                    };
+                    Cell::from(sparkline(samples, max, trend_width))
+                        .style(Style::default().fg(Color::Cyan))
+                }
+                None => Cell::from("-"),
+            };
+            let blocked_cell = match &stat.channel_type {
+                ChannelType::Bounded(_) => Cell::from(format_delay(stat.blocked_ns)),
+                _ => Cell::from("-"),
+            };
+            let expected_cell = match (stat.expected_rate, stat.rate_status) {
+                (Some(rate), Some(status)) => {
+                    let (marker, color) = match status {
+                        RateStatus::Ok => ("✓", Color::Green),
+                        RateStatus::Above => ("▲", Color::Red),
+                        RateStatus::Below => ("▼", Color::Yellow),
+                    };
+                    Cell::from(format!("{} {}", format_rate(rate), marker))
+                        .style(Style::default().fg(color))
+                }
+                _ => Cell::from("-"),
+            };
 
-            let row = Row::new(vec![
-                Cell::from(truncate_left(&stat.label, channel_width)),
+            // Custom labels shared by channels instrumented at different places
+            let label_cell = if stat.label_conflict {
+                Cell::from(format!(
+                    "⚠ {}",
+                    truncate_left(&stat.label, channel_width.saturating_sub(2))
+                ))
+                .style(Style::default().fg(Color::Yellow))
+            } else {
+                Cell::from(truncate_left(&stat.label, channel_width))
+            };
+
+            let row = Row::new(visible(vec![
+                label_cell,
+                Cell::from("channel"),
+                activity_cell(queue_history.sends(stat.id)),
+                Cell::from(truncate_left(&stat.source, channel_width)),
                 Cell::from(stat.channel_type.to_string()),
-                Cell::from(state_text).style(state_style),
+                Cell::from(format_bytes(stat.type_size as u64)),
+                state_cell(stat.state),
                 Cell::from(stat.sent_count.to_string()),
                 Cell::from(stat.received_count.to_string()),
+                Cell::from(format_rate(stat.send_rate.last_10s)),
+                Cell::from(format_rate(stat.recv_rate.last_10s)),
+                Cell::from(format_byte_rate(stat.send_byte_rate.last_10s)),
+                Cell::from(
+                    stat.latency
+                        .map(|latency| format_delay(latency.p99))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                blocked_cell,
                 queue_cell,
+                Cell::from(
+                    queue_history
+                        .high_water(stat.id)
+                        .map_or_else(|| "-".to_string(), |high_water| high_water.to_string()),
+                ),
+                trend_cell,
                 mem_cell,
-            ]);
+                expected_cell,
+                idle_cell(stat, current_elapsed_ns),
+            ]));
 
-            // Dim the row if logs are shown and channels table is not focused
-            if show_logs && !matches!(focus, Focus::Channels) {
+            if dimmed {
                 row.style(Style::default().fg(Color::DarkGray))
             } else {
                 row
@@ -82,15 +202,46 @@ pub(crate) fn render_channels_panel(
         })
         .collect();
 
-    let widths = [
-        Constraint::Percentage(30), // Channel
-        Constraint::Percentage(14), // Type
-        Constraint::Percentage(10), // State
-        Constraint::Percentage(9),  // Sent
-        Constraint::Percentage(11), // Received
-        Constraint::Percentage(16), // Queue
-        Constraint::Percentage(10), // Mem
-    ];
+    // Yielded items are listed as sent, columns of the queue are left empty
+    rows.extend(streams.iter().map(|stream| {
+        let empty = || Cell::from("-");
+        let last_activity = stream.last_yield_ns.unwrap_or(stream.created_ns);
+        let row = Row::new(visible(vec![
+            Cell::from(truncate_left(&stream.label, channel_width)),
+            Cell::from("stream"),
+            empty(),
+            Cell::from(truncate_left(&stream.source, channel_width)),
+            empty(),
+            Cell::from(format_bytes(stream.type_size as u64)),
+            state_cell(stream.state),
+            Cell::from(stream.items_yielded.to_string()),
+            empty(),
+            Cell::from(format_rate(stream.yield_rate.last_10s)),
+            empty(),
+            empty(),
+            empty(),
+            empty(),
+            empty(),
+            empty(),
+            empty(),
+            empty(),
+            empty(),
+            Cell::from(format_time_ago(
+                current_elapsed_ns.saturating_sub(last_activity),
+            )),
+        ]));
+        if dimmed {
+            row.style(Style::default().fg(Color::DarkGray))
+        } else {
+            row.style(Style::default().fg(Color::Gray))
+        }
+    }));
+
+    let widths: Vec<Constraint> = layout
+        .iter()
+        .zip(&shown)
+        .filter_map(|((_, weight, _), shown)| shown.then_some(Constraint::Fill(*weight)))
+        .collect();
 
     let selected_row_style = Style::default()
         .add_modifier(Modifier::REVERSED)
@@ -103,7 +254,7 @@ pub(crate) fn render_channels_panel(
             border::PLAIN
         };
         Block::bordered()
-            .title(format!(" [{}/{}] ", channel_position, total_channels))
+            .title(table_title(channel_position, total_channels, filter, sort))
             .border_set(border_set)
             .style(if focus == Focus::Channels {
                 Style::default()
@@ -112,7 +263,7 @@ pub(crate) fn render_channels_panel(
             })
     } else {
         Block::bordered()
-            .title(format!(" [{}/{}] ", channel_position, total_channels))
+            .title(table_title(channel_position, total_channels, filter, sort))
             .border_set(border::THICK)
     };
 
@@ -126,3 +277,224 @@ pub(crate) fn render_channels_panel(
 
     frame.render_stateful_widget(table, area, table_state);
 }
+
+/// State colored by severity, with a warning sign for full and stalled channels or streams
+fn state_cell(state: ChannelState) -> Cell<'static> {
+    let (text, color) = match state {
+        ChannelState::Active => (state.to_string(), Color::Green),
+        ChannelState::Closed => (state.to_string(), Color::Yellow),
+        ChannelState::Full | ChannelState::Stalled => (format!("⚠ {}", state), Color::Red),
+        ChannelState::Notified => (state.to_string(), Color::Blue),
+        ChannelState::Unknown => (state.to_string(), Color::Gray),
+    };
+    Cell::from(text).style(Style::default().fg(color))
+}
+
+/// Sparkline of the sends per refresh, scaled to the busiest refresh. Green when the channel
+/// sent since the previous refresh, yellow when it did recently, gray when dormant.
+fn activity_cell(sends: Option<&VecDeque<u64>>) -> Cell<'static> {
+    let Some(sends) = sends else {
+        return Cell::from("-");
+    };
+    let max = sends.iter().copied().max().unwrap_or(0);
+    let color = match sends.back() {
+        Some(&latest) if latest > 0 => Color::Green,
+        _ if max > 0 => Color::Yellow,
+        _ => Color::DarkGray,
+    };
+    Cell::from(sparkline(sends, max, ACTIVITY_LEN)).style(Style::default().fg(color))
+}
+
+/// Time since the last send or receive, computed from the timestamps rather than `idle_ns` so
+/// that channels left out of `/channels/delta` responses keep aging
+fn idle_cell(stat: &SerializableChannelStats, current_elapsed_ns: u64) -> Cell<'static> {
+    let last_activity = stat
+        .last_send_ns
+        .max(stat.last_recv_ns)
+        .unwrap_or(stat.created_ns);
+    Cell::from(format_time_ago(
+        current_elapsed_ns.saturating_sub(last_activity),
+    ))
+}
+
+/// Position of the selected channel, the active filter with the number of hidden channels,
+/// and whether the row order is frozen
+fn table_title(
+    channel_position: usize,
+    total_channels: usize,
+    filter: &Filter,
+    sort: Sort,
+) -> String {
+    let mut title = format!(" [{}/{}] ", channel_position, total_channels);
+    if filter.is_active() {
+        title.push_str(&format!(
+            "/{} ({} hidden) ",
+            filter.query(),
+            filter.hidden()
+        ));
+    }
+    if filter.hides_closed() {
+        title.push_str(&format!("(+{} closed) ", filter.closed_hidden()));
+    }
+    if sort.frozen {
+        title.push_str("order frozen ");
+    }
+    title
+}
+
+/// Renders channels collapsed into label groups at the given hierarchy level
+pub(crate) fn render_groups_panel(
+    groups: &[LabelGroup],
+    level: usize,
+    area: Rect,
+    frame: &mut Frame,
+) {
+    let available_width = area.width.saturating_sub(10);
+    let group_width = ((available_width as f32 * 0.4) as usize).max(36);
+
+    let header_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    let header = Row::new(vec![
+        Cell::from("Group"),
+        Cell::from("Channels"),
+        Cell::from("Sent"),
+        Cell::from("Received"),
+        Cell::from("Queued"),
+        Cell::from("Mem"),
+    ])
+    .style(header_style)
+    .height(1);
+
+    let rows: Vec<Row> = groups
+        .iter()
+        .map(|group| {
+            Row::new(vec![
+                Cell::from(truncate_left(&group.label, group_width)),
+                Cell::from(group.channels.to_string()),
+                Cell::from(group.sent_count.to_string()),
+                Cell::from(group.received_count.to_string()),
+                Cell::from(group.queued.to_string()),
+                Cell::from(format_bytes(group.queued_bytes)),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Percentage(40), // Group
+        Constraint::Percentage(12), // Channels
+        Constraint::Percentage(12), // Sent
+        Constraint::Percentage(12), // Received
+        Constraint::Percentage(12), // Queued
+        Constraint::Percentage(12), // Mem
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::bordered()
+                .title(format!(" [groups, level {}] ", level))
+                .border_set(border::THICK),
+        )
+        .column_spacing(1);
+
+    frame.render_widget(table, area);
+}
+
+/// Renders channels grouped by source location or tag, with expanded groups listing their channels
+pub(crate) fn render_grouped_panel(
+    rows: &[GroupRow],
+    by: GroupBy,
+    area: Rect,
+    frame: &mut Frame,
+    table_state: &mut TableState,
+) {
+    let available_width = area.width.saturating_sub(10);
+    let label_width = ((available_width as f32 * 0.4) as usize).max(36);
+
+    let header_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    let header = Row::new(vec![
+        Cell::from("Group"),
+        Cell::from("Channels"),
+        Cell::from("Sent"),
+        Cell::from("Received"),
+        Cell::from("Queued"),
+        Cell::from("Max Queue"),
+        Cell::from("Mem"),
+    ])
+    .style(header_style)
+    .height(1);
+
+    let mut group_count = 0;
+    let table_rows: Vec<Row> = rows
+        .iter()
+        .map(|row| match row {
+            GroupRow::Group { group, expanded } => {
+                group_count += 1;
+                let marker = if *expanded { "▾" } else { "▸" };
+                Row::new(vec![
+                    Cell::from(format!(
+                        "{} {}",
+                        marker,
+                        truncate_left(&group.label, label_width.saturating_sub(2))
+                    )),
+                    Cell::from(group.channels.to_string()),
+                    Cell::from(group.sent_count.to_string()),
+                    Cell::from(group.received_count.to_string()),
+                    Cell::from(group.queued.to_string()),
+                    Cell::from(group.max_queued.to_string()),
+                    Cell::from(format_bytes(group.queued_bytes)),
+                ])
+            }
+            GroupRow::Channel(stat) => Row::new(vec![
+                Cell::from(format!(
+                    "    {}",
+                    truncate_left(&stat.label, label_width.saturating_sub(4))
+                )),
+                Cell::from(""),
+                Cell::from(stat.sent_count.to_string()),
+                Cell::from(stat.received_count.to_string()),
+                Cell::from(stat.queued.to_string()),
+                Cell::from(""),
+                Cell::from(format_bytes(stat.queued_bytes)),
+            ])
+            .style(Style::default().fg(Color::Gray)),
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Percentage(40), // Group
+        Constraint::Percentage(10), // Channels
+        Constraint::Percentage(10), // Sent
+        Constraint::Percentage(10), // Received
+        Constraint::Percentage(10), // Queued
+        Constraint::Percentage(10), // Max Queue
+        Constraint::Percentage(10), // Mem
+    ];
+
+    let selected_row_style = Style::default()
+        .add_modifier(Modifier::REVERSED)
+        .bg(Color::DarkGray);
+
+    let table = Table::new(table_rows, widths)
+        .header(header)
+        .block(
+            Block::bordered()
+                .title(format!(
+                    " [grouped by {}, {} groups] ",
+                    by.as_str(),
+                    group_count
+                ))
+                .border_set(border::THICK),
+        )
+        .column_spacing(1)
+        .row_highlight_style(selected_row_style)
+        .highlight_symbol(Text::from(">"))
+        .highlight_spacing(HighlightSpacing::Always);
+
+    frame.render_stateful_widget(table, area, table_state);
+}
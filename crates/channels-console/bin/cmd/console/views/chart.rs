@@ -0,0 +1,104 @@
+use crate::cmd::console::trend::QueueHistory;
+use channels_console::samples::ChannelHistoryJson;
+use channels_console::{ChannelType, SerializableChannelStats};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    symbols::{self, border},
+    text::Line,
+    widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph},
+    Frame,
+};
+use std::time::Duration;
+
+/// Height of the chart pane below the channels table
+pub(crate) const CHART_HEIGHT: u16 = 12;
+
+/// Renders the queue depth of the selected channel over time. Samples come from the
+/// `/channels/:id/history` endpoint, or from the ones taken on each refresh for servers
+/// predating it.
+pub(crate) fn render_chart_panel(
+    stat: &SerializableChannelStats,
+    history: Option<&ChannelHistoryJson>,
+    queue_history: &QueueHistory,
+    refresh_interval: Duration,
+    area: Rect,
+    frame: &mut Frame,
+) {
+    // Seconds before the newest sample, and queue depth
+    let points: Vec<(f64, f64)> = match history {
+        Some(history) if history.id == stat.id.to_string() && !history.samples.is_empty() => {
+            let newest_ns = history.samples[history.samples.len() - 1].elapsed_ns;
+            history
+                .samples
+                .iter()
+                .map(|sample| {
+                    let age_secs = newest_ns.saturating_sub(sample.elapsed_ns) as f64 / 1e9;
+                    (-age_secs, sample.queued as f64)
+                })
+                .collect()
+        }
+        _ => queue_history
+            .get(stat.id)
+            .map(|samples| {
+                let interval_secs = refresh_interval.as_secs_f64();
+                samples
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &queued)| {
+                        let age = (samples.len() - 1 - i) as f64 * interval_secs;
+                        (-age, queued as f64)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    let block = Block::bordered()
+        .title(format!(" Queue depth: {} ", stat.label))
+        .border_set(border::PLAIN);
+
+    if points.len() < 2 {
+        let paragraph = Paragraph::new(vec![
+            Line::from(""),
+            Line::from("Collecting samples...").dark_gray().centered(),
+        ])
+        .block(block);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let window_secs = -points[0].0;
+    let peak = points.iter().map(|&(_, queued)| queued).fold(0.0, f64::max);
+    // Bounded channels are scaled to their capacity, others to the peak of the window
+    let max = match stat.channel_type {
+        ChannelType::Bounded(cap) => cap as f64,
+        ChannelType::Oneshot => 1.0,
+        ChannelType::Unbounded => peak,
+    }
+    .max(1.0);
+
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Color::Cyan))
+        .data(&points);
+    let x_axis = Axis::default()
+        .style(Style::default().fg(Color::DarkGray))
+        .bounds([-window_secs, 0.0])
+        .labels([format!("-{:.0}s", window_secs), "now".to_string()]);
+    let y_axis = Axis::default()
+        .style(Style::default().fg(Color::DarkGray))
+        .bounds([0.0, max])
+        .labels([
+            "0".to_string(),
+            format!("{:.0}", max / 2.0),
+            format!("{:.0}", max),
+        ]);
+
+    let chart = Chart::new(vec![dataset])
+        .block(block)
+        .x_axis(x_axis)
+        .y_axis(y_axis);
+    frame.render_widget(chart, area);
+}
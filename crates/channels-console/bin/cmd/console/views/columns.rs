@@ -0,0 +1,59 @@
+use crate::cmd::console::columns::{Column, Columns};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::border,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState},
+    Frame,
+};
+
+/// Renders a centered popup listing the optional columns of the channels table, with a
+/// checkbox for the visible ones
+pub(crate) fn render_columns_popup(
+    columns: &Columns,
+    selected: usize,
+    area: Rect,
+    frame: &mut Frame,
+) {
+    let popup_width = 36.min(area.width);
+    let popup_height = (Column::ALL.len() as u16 + 2).min(area.height);
+    let popup_area = Rect {
+        x: area.x + area.width.saturating_sub(popup_width) / 2,
+        y: area.y + area.height.saturating_sub(popup_height) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    let items: Vec<ListItem> = Column::ALL
+        .iter()
+        .map(|column| {
+            let checkbox = if columns.is_visible(*column) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            ListItem::new(format!("{} {}", checkbox, column.name()))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::bordered()
+                .title(" Columns ")
+                .border_set(border::DOUBLE),
+        )
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::REVERSED)
+                .bg(Color::DarkGray),
+        )
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always);
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_stateful_widget(
+        list,
+        popup_area,
+        &mut ListState::default().with_selected(Some(selected)),
+    );
+}
@@ -0,0 +1,390 @@
+use crate::cmd::console::app::CachedLogs;
+use crate::cmd::console::widgets::formatters::{
+    format_byte_rate, format_delay, format_rate, format_time_ago, truncate_message,
+};
+use channels_console::actors::{ActorStats, ChannelActorsJson};
+use channels_console::latency::LatencyHistogramJson;
+use channels_console::rates::RateWindows;
+use channels_console::{
+    format_bytes, ChannelType, LogEntry, SerializableChannelStats, StateTransition,
+};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Block, Paragraph, Row, Table},
+    Frame,
+};
+
+const MAX_PRODUCERS_HEIGHT: u16 = 8;
+
+const MAX_ACTORS_HEIGHT: u16 = 7;
+
+const LATENCY_CHART_HEIGHT: u16 = 10;
+
+/// Renders a full-screen view of a single channel: its metadata, counters, rates, latency,
+/// state changes, producers and consumers, top senders and receivers, latency distribution, and sent and
+/// received logs side by side
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_detail_view(
+    stat: &SerializableChannelStats,
+    logs: &Option<CachedLogs>,
+    transitions: &[StateTransition],
+    latency_histogram: Option<&LatencyHistogramJson>,
+    actors: Option<&ChannelActorsJson>,
+    current_elapsed_ns: u64,
+    area: Rect,
+    frame: &mut Frame,
+) {
+    let mut title = format!(" {} ", stat.label);
+    if !stat.tags.is_empty() {
+        title.push_str(&format!("[{}] ", stat.tags.join(" ")));
+    }
+    if stat.label_conflict {
+        title.push_str("⚠ label used at several places ");
+    }
+    let block = Block::bordered().title(title).border_set(border::THICK);
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Producers wrapped with `sender!` and consumers wrapped with `receiver!` side by side,
+    // one per line
+    let producers_height = if stat.producers.is_empty() && stat.consumers.is_empty() {
+        0
+    } else {
+        (stat.producers.len().max(stat.consumers.len()) as u16 + 2).min(MAX_PRODUCERS_HEIGHT)
+    };
+    // Tasks and threads of inline channels, senders and receivers side by side
+    let actors = actors.filter(|actors| !actors.senders.is_empty() || !actors.receivers.is_empty());
+    let actors_height = actors.map_or(0, |actors| {
+        (actors.senders.len().max(actors.receivers.len()) as u16 + 2).min(MAX_ACTORS_HEIGHT)
+    });
+    let latency_histogram = latency_histogram.filter(|histogram| histogram.count > 0);
+    let chart_height = if latency_histogram.is_some() {
+        LATENCY_CHART_HEIGHT
+    } else {
+        0
+    };
+    let [summary_area, producers_area, actors_area, chart_area, logs_area] = Layout::vertical([
+        Constraint::Length(12),
+        Constraint::Length(producers_height),
+        Constraint::Length(actors_height),
+        Constraint::Length(chart_height),
+        Constraint::Min(0),
+    ])
+    .areas(inner_area);
+    let [info_area, counters_area, latency_area, states_area] = Layout::horizontal([
+        Constraint::Percentage(34),
+        Constraint::Percentage(22),
+        Constraint::Percentage(22),
+        Constraint::Percentage(22),
+    ])
+    .areas(summary_area);
+
+    let capacity = match stat.channel_type {
+        ChannelType::Bounded(cap) => cap.to_string(),
+        ChannelType::Oneshot => "1".to_string(),
+        ChannelType::Unbounded => "unbounded".to_string(),
+    };
+    let mut info = vec![
+        field("Type", stat.channel_type.to_string()),
+        field("Source", stat.source.clone()),
+        field("Message", stat.type_name.clone()),
+        field("Size", format_bytes(stat.type_size as u64)),
+        field("Capacity", capacity),
+        field("State", stat.state.to_string()),
+        field("Handles", format_handles(stat)),
+    ];
+    if let Some(description) = &stat.description {
+        info.push(field("About", description.clone()));
+    }
+    render_section(" Channel ", info, info_area, frame);
+
+    let expected = stat
+        .expected_rate
+        .map(format_rate)
+        .unwrap_or_else(|| "-".to_string());
+    render_section(
+        " Counters ",
+        vec![
+            field("Sent", stat.sent_count.to_string()),
+            field("Received", stat.received_count.to_string()),
+            field("Queued", stat.queued.to_string()),
+            field("Mem", format_bytes(stat.queued_bytes)),
+            field("Send/s", format_rates(&stat.send_rate)),
+            field("Recv/s", format_rates(&stat.recv_rate)),
+            field(
+                "Bytes",
+                format!(
+                    "{} / {}",
+                    format_bytes(stat.bytes_sent_total),
+                    format_bytes(stat.bytes_recv_total)
+                ),
+            ),
+            field(
+                "Bytes/s",
+                format!(
+                    "{} / {}",
+                    format_byte_rate(stat.send_byte_rate.last_10s),
+                    format_byte_rate(stat.recv_byte_rate.last_10s)
+                ),
+            ),
+            field("Expected", expected),
+            field(
+                "Rejected",
+                format!(
+                    "{} full / {} closed",
+                    stat.send_failures_full, stat.send_failures_closed
+                ),
+            ),
+        ],
+        counters_area,
+        frame,
+    );
+
+    let latency_lines = match stat.latency {
+        Some(latency) => vec![
+            field("p50", format_delay(latency.p50)),
+            field("p95", format_delay(latency.p95)),
+            field("p99", format_delay(latency.p99)),
+            field("max", format_delay(latency.max)),
+            field("Samples", latency.count.to_string()),
+            field("Blocked", format_delay(stat.blocked_ns)),
+            field("Max blocked", format_delay(stat.max_blocked_ns)),
+        ],
+        None => vec![
+            Line::from("No samples yet").fg(Color::DarkGray),
+            field("Blocked", format_delay(stat.blocked_ns)),
+            field("Max blocked", format_delay(stat.max_blocked_ns)),
+        ],
+    };
+    render_section(" Latency ", latency_lines, latency_area, frame);
+
+    // Most recent state changes first
+    let state_lines = transitions
+        .iter()
+        .rev()
+        .map(|transition| {
+            field(
+                transition.state.as_str(),
+                format_time_ago(current_elapsed_ns.saturating_sub(transition.timestamp)),
+            )
+        })
+        .collect();
+    render_section(" State History ", state_lines, states_area, frame);
+
+    match (stat.producers.is_empty(), stat.consumers.is_empty()) {
+        (false, true) => render_section(" Producers ", producer_lines(stat), producers_area, frame),
+        (true, false) => render_section(" Consumers ", consumer_lines(stat), producers_area, frame),
+        (false, false) => {
+            let [producers_area, consumers_area] =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(producers_area);
+            render_section(" Producers ", producer_lines(stat), producers_area, frame);
+            render_section(" Consumers ", consumer_lines(stat), consumers_area, frame);
+        }
+        (true, true) => {}
+    }
+
+    if let Some(actors) = actors {
+        let [senders_area, receivers_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(actors_area);
+        render_section(
+            " Top Senders ",
+            actor_lines(&actors.senders, "sent"),
+            senders_area,
+            frame,
+        );
+        render_section(
+            " Top Receivers ",
+            actor_lines(&actors.receivers, "received"),
+            receivers_area,
+            frame,
+        );
+    }
+
+    if let Some(histogram) = latency_histogram {
+        render_latency_chart(histogram, chart_area, frame);
+    }
+
+    let [sent_area, received_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .areas(logs_area);
+    let (sent_logs, received_logs): (&[LogEntry], &[LogEntry]) = match logs {
+        Some(cached_logs) => (&cached_logs.logs.sent_logs, &cached_logs.logs.received_logs),
+        None => (&[], &[]),
+    };
+    render_log_table(" Sent ", sent_logs, current_elapsed_ns, sent_area, frame);
+    render_log_table(
+        " Received ",
+        received_logs,
+        current_elapsed_ns,
+        received_area,
+        frame,
+    );
+}
+
+/// Producers by number of sent messages, with their share of all producer sends
+fn producer_lines(stat: &SerializableChannelStats) -> Vec<Line<'static>> {
+    let total: u64 = stat.producers.iter().map(|p| p.sent_count).sum();
+    let mut producers: Vec<_> = stat.producers.iter().collect();
+    producers.sort_by_key(|producer| std::cmp::Reverse(producer.sent_count));
+    producers
+        .into_iter()
+        .map(|producer| {
+            let share = if total > 0 {
+                producer.sent_count as f64 / total as f64 * 100.0
+            } else {
+                0.0
+            };
+            field(
+                &producer.label,
+                format!("{} sent ({:.0}%)", producer.sent_count, share),
+            )
+        })
+        .collect()
+}
+
+/// Consumers by number of received messages, with their share of all consumer receives
+fn consumer_lines(stat: &SerializableChannelStats) -> Vec<Line<'static>> {
+    let total: u64 = stat.consumers.iter().map(|c| c.received_count).sum();
+    let mut consumers: Vec<_> = stat.consumers.iter().collect();
+    consumers.sort_by_key(|consumer| std::cmp::Reverse(consumer.received_count));
+    consumers
+        .into_iter()
+        .map(|consumer| {
+            let share = if total > 0 {
+                consumer.received_count as f64 / total as f64 * 100.0
+            } else {
+                0.0
+            };
+            field(
+                &consumer.label,
+                format!("{} received ({:.0}%)", consumer.received_count, share),
+            )
+        })
+        .collect()
+}
+
+/// Tasks or threads by number of messages, most active first
+fn actor_lines(actors: &[ActorStats], verb: &str) -> Vec<Line<'static>> {
+    actors
+        .iter()
+        .map(|actor| field(&actor.actor, format!("{} {}", actor.count, verb)))
+        .collect()
+}
+
+/// Live senders and receivers, `-` if the channel type doesn't expose them
+fn format_handles(stat: &SerializableChannelStats) -> String {
+    match (stat.senders_alive, stat.receivers_alive) {
+        (Some(senders), Some(receivers)) => format!(
+            "{} sender{}, {} receiver{}",
+            senders,
+            if senders == 1 { "" } else { "s" },
+            receivers,
+            if receivers == 1 { "" } else { "s" },
+        ),
+        _ => "-".to_string(),
+    }
+}
+
+/// Bar chart of latency samples per bucket, labeled with bucket upper bounds
+fn render_latency_chart(histogram: &LatencyHistogramJson, area: Rect, frame: &mut Frame) {
+    let block = Block::bordered()
+        .title(format!(
+            " Latency Distribution ({} samples) ",
+            histogram.count
+        ))
+        .border_set(border::PLAIN);
+
+    let bars: Vec<Bar> = histogram
+        .buckets
+        .iter()
+        .map(|bucket| {
+            Bar::default()
+                .value(bucket.count)
+                .label(Line::from(format!("≤{}", format_delay(bucket.upper_ns))))
+                .text_value(bucket.count.to_string())
+        })
+        .collect();
+
+    // Bars share the width, wide enough for their labels
+    let bar_count = bars.len().max(1) as u16;
+    let bar_width = (area.width.saturating_sub(2) / bar_count)
+        .saturating_sub(1)
+        .clamp(3, 10);
+
+    let chart = BarChart::default()
+        .block(block)
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Cyan))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+    frame.render_widget(chart, area);
+}
+
+fn field(name: &str, value: String) -> Line<'static> {
+    Line::from(vec![format!("{}: ", name).yellow(), value.into()])
+}
+
+/// Rates over the last 1s, 10s and 60s
+fn format_rates(rates: &RateWindows) -> String {
+    format!(
+        "{} {} {}",
+        format_rate(rates.last_1s),
+        format_rate(rates.last_10s),
+        format_rate(rates.last_60s)
+    )
+}
+
+fn render_section(title: &str, lines: Vec<Line>, area: Rect, frame: &mut Frame) {
+    let block = Block::bordered()
+        .title(title.to_string())
+        .border_set(border::PLAIN);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_log_table(
+    title: &str,
+    entries: &[LogEntry],
+    current_elapsed_ns: u64,
+    area: Rect,
+    frame: &mut Frame,
+) {
+    let block = Block::bordered()
+        .title(title.to_string())
+        .border_set(border::PLAIN);
+
+    let msg_width = (area.width.saturating_sub(26) as usize).max(10);
+
+    let header = Row::new(vec!["Index", "Message", "Ago"])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1);
+
+    let rows: Vec<Row> = entries
+        .iter()
+        .map(|entry| {
+            Row::new(vec![
+                entry.index.to_string(),
+                truncate_message(entry.message.as_deref().unwrap_or(""), msg_width),
+                format_time_ago(current_elapsed_ns.saturating_sub(entry.timestamp)),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(6),  // Index
+        Constraint::Min(10),    // Message
+        Constraint::Length(13), // Ago
+    ];
+
+    let table = Table::new(rows, widths).header(header).block(block);
+    frame.render_widget(table, area);
+}
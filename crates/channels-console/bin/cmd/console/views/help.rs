@@ -0,0 +1,170 @@
+use crate::cmd::console::keymap::{Action, KeyMap};
+use channels_console::info::InfoJson;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+use std::time::Duration;
+
+/// Renders a full-screen popup listing all keybindings, with the monitored process and the
+/// versions of the console and the library
+pub(crate) fn render_help_popup(
+    keys: &KeyMap,
+    location: &str,
+    target_status: Option<&str>,
+    refresh_interval: Duration,
+    server_info: Option<&InfoJson>,
+    area: Rect,
+    frame: &mut Frame,
+) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title(" Help ")
+        .title_bottom(
+            Line::from(format!(" Close <{}/Esc> ", keys.label(Action::Help))).right_aligned(),
+        )
+        .border_set(border::DOUBLE);
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [info_area, bindings_area] =
+        Layout::vertical([Constraint::Length(6), Constraint::Min(0)]).areas(inner_area);
+
+    let mut info_lines = vec![
+        info_line("Source", location.to_string()),
+        info_line(
+            "Refresh interval",
+            format!("{}ms", refresh_interval.as_millis()),
+        ),
+        info_line("Console version", env!("CARGO_PKG_VERSION").to_string()),
+    ];
+    info_lines.push(match server_info {
+        Some(info) => info_line(
+            "Library version",
+            format!("{} ({} pid {})", info.version, info.process, info.pid),
+        ),
+        None => info_line("Library version", "unknown".to_string()),
+    });
+    if let Some(target) = target_status {
+        info_lines.push(info_line("Process", target.to_string()));
+    }
+    frame.render_widget(Paragraph::new(info_lines), info_area);
+
+    let up_down = format!("↑↓/{}{}", keys.label(Action::Down), keys.label(Action::Up));
+    let sections: [(&str, Vec<(String, &str)>); 4] = [
+        (
+            "Channels",
+            vec![
+                (up_down.clone(), "Select channel"),
+                (keys.label(Action::Details), "Open channel details"),
+                (keys.label(Action::ToggleLogs), "Toggle logs panel"),
+                (keys.label(Action::Chart), "Toggle queue depth chart"),
+                (
+                    format!("→/{}", keys.label(Action::Right)),
+                    "Focus logs panel",
+                ),
+                (keys.label(Action::Search), "Search channels"),
+                ("Esc".to_string(), "Clear search"),
+                (
+                    format!(
+                        "{}/{}",
+                        keys.label(Action::Sort),
+                        keys.label(Action::ReverseSort)
+                    ),
+                    "Cycle sort column, reverse order",
+                ),
+                (keys.label(Action::Freeze), "Freeze row order"),
+                (keys.label(Action::Collapse), "Cycle label collapse levels"),
+                (keys.label(Action::Group), "Group by source, then by tag"),
+                (keys.label(Action::HideClosed), "Hide closed channels"),
+                (keys.label(Action::Columns), "Choose columns"),
+                (
+                    keys.label(Action::ToggleSplit),
+                    "Logs panel beside or below",
+                ),
+                (
+                    format!(
+                        "{}/{}",
+                        keys.label(Action::ShrinkTable),
+                        keys.label(Action::GrowTable)
+                    ),
+                    "Resize the panes",
+                ),
+            ],
+        ),
+        (
+            "Logs",
+            vec![
+                (up_down, "Select entry"),
+                (
+                    format!("←/{}", keys.label(Action::Left)),
+                    "Focus channels table",
+                ),
+                (keys.label(Action::Inspect), "Inspect message"),
+                (keys.label(Action::Copy), "Copy inspected message"),
+                (
+                    format!(
+                        "{}/{}",
+                        keys.label(Action::Fold),
+                        keys.label(Action::Unfold)
+                    ),
+                    "Fold or unfold nested JSON",
+                ),
+                (
+                    keys.label(Action::LogsView),
+                    "Sent, received or interleaved",
+                ),
+                (keys.label(Action::WallClock), "Wall-clock or relative time"),
+                (keys.label(Action::Search), "Search entries"),
+                (keys.label(Action::Freeze), "Follow newest entry"),
+                ("PgUp/PgDn".to_string(), "Scroll by page"),
+                ("Home/End".to_string(), "Newest or oldest entry"),
+            ],
+        ),
+        (
+            "General",
+            vec![
+                (keys.label(Action::Pause), "Pause refreshing"),
+                (keys.label(Action::Reset), "Reset statistics"),
+                (keys.label(Action::Export), "Export snapshot or channel"),
+                ("Tab/BackTab".to_string(), "Switch process"),
+                (keys.label(Action::Help), "Toggle this help"),
+                (keys.label(Action::Quit), "Quit"),
+            ],
+        ),
+        (
+            "Replay",
+            vec![
+                ("[ ]".to_string(), "Seek 10s back or forward"),
+                ("Home/End".to_string(), "Seek to start or end"),
+            ],
+        ),
+    ];
+
+    let columns =
+        Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(bindings_area);
+    let (left, right) = sections.split_at(2);
+    for (column_area, sections) in columns.iter().zip([left, right]) {
+        let mut lines = Vec::new();
+        for (title, bindings) in sections {
+            lines.push(Line::from(*title).yellow().bold());
+            for (key, description) in bindings {
+                lines.push(Line::from(vec![
+                    format!("  {:<14}", format!("<{}>", key)).blue().bold(),
+                    (*description).into(),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+        frame.render_widget(Paragraph::new(lines), *column_area);
+    }
+}
+
+fn info_line(name: &str, value: String) -> Line<'static> {
+    Line::from(vec![format!("{:<18}", name).bold(), value.into()])
+}
@@ -1,15 +1,45 @@
-use crate::cmd::console::widgets::formatters::format_timestamp;
+use crate::cmd::console::widgets::formatters::{
+    format_delay, format_timestamp, format_wall_time, split_at_width,
+};
+use crate::cmd::console::widgets::json::{json_lines, message_json};
+use channels_console::correlation::Journey;
 use channels_console::LogEntry;
 use ratatui::{
     layout::Rect,
+    style::Stylize,
     symbols::border,
-    text::Line,
-    widgets::{Block, Clear},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
+
+/// Scroll offset and fold depth of the inspect popup, reset when another entry is inspected
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct InspectState {
+    /// First visible line, clamped to the content when rendered
+    pub(crate) scroll: usize,
+    /// JSON objects and arrays nested this deep or more are folded, `None` unfolds all
+    pub(crate) fold_depth: Option<usize>,
+}
 
-/// Renders a centered popup displaying the full log message
-pub(crate) fn render_inspect_popup(entry: &LogEntry, area: Rect, frame: &mut Frame) {
+/// Full text of the logged message, shown in the popup and copied with `y`. JSON payloads
+/// are pretty-printed.
+pub(crate) fn message_text(entry: &LogEntry) -> Option<String> {
+    message_json(entry)
+        .and_then(|json| serde_json::to_string_pretty(&json).ok())
+        .or_else(|| entry.message.clone())
+}
+
+/// Renders a centered popup displaying the full log message, and its journey across
+/// channels for correlated messages. JSON payloads are syntax-highlighted and can be folded.
+pub(crate) fn render_inspect_popup(
+    entry: &LogEntry,
+    journey: Option<&Journey>,
+    state: &mut InspectState,
+    area: Rect,
+    frame: &mut Frame,
+) {
     // Center the popup at 80% of screen size
     let popup_width = (area.width as f32 * 0.8) as u16;
     let popup_height = (area.height as f32 * 0.8) as u16;
@@ -23,50 +53,118 @@ pub(crate) fn render_inspect_popup(entry: &LogEntry, area: Rect, frame: &mut Fra
         height: popup_height,
     };
 
-    let message = entry
-        .message
-        .as_deref()
-        .unwrap_or("(missing \"log = true\")");
-
     frame.render_widget(Clear, popup_area);
 
-    let block = Block::bordered()
-        .title(format!(
-            " Message (Index: {}) - {} ",
-            entry.index,
-            format_timestamp(entry.timestamp)
-        ))
-        .border_set(border::DOUBLE);
-
+    let mut title = format!(
+        " Message (Index: {}) - {} ",
+        entry.index,
+        format_timestamp(entry.timestamp)
+    );
+    if entry.unix_ms > 0 {
+        title.push_str(&format!("({}) ", format_wall_time(entry.unix_ms, true)));
+    }
+    let block = Block::bordered().title(title).border_set(border::DOUBLE);
     let inner_area = block.inner(popup_area);
 
+    let message_lines: Vec<Vec<Span<'static>>> = match message_json(entry) {
+        Some(json) => json_lines(&json, state.fold_depth),
+        None => entry
+            .message
+            .as_deref()
+            .unwrap_or("(missing \"log = true\")")
+            .lines()
+            .map(|line| vec![Span::raw(line.to_string())])
+            .collect(),
+    };
+    let max_width = inner_area.width.saturating_sub(2) as usize;
+    let mut text_lines: Vec<Line> = message_lines
+        .into_iter()
+        .flat_map(|spans| wrap_spans(spans, max_width))
+        .collect();
+
+    if let Some(span_id) = entry.span_id {
+        text_lines.push(Line::from(""));
+        text_lines.push(Line::from(format!("Tracing span: {}", span_id)).bold());
+    }
+
+    if let Some(journey) = journey {
+        text_lines.extend(journey_lines(journey));
+    }
+
+    let height = inner_area.height as usize;
+    state.scroll = state.scroll.min(text_lines.len().saturating_sub(height));
+    let block = if text_lines.len() > height {
+        block.title_bottom(
+            Line::from(format!(
+                " {}-{}/{} ",
+                state.scroll + 1,
+                (state.scroll + height).min(text_lines.len()),
+                text_lines.len()
+            ))
+            .right_aligned(),
+        )
+    } else {
+        block
+    };
     frame.render_widget(block, popup_area);
 
-    let text_lines: Vec<Line> = message
-        .lines()
-        .flat_map(|line| {
-            let max_width = inner_area.width.saturating_sub(2) as usize;
-            if line.len() <= max_width {
-                vec![Line::from(line)]
-            } else {
-                let mut wrapped = Vec::new();
-                let mut remaining = line;
-                while !remaining.is_empty() {
-                    let split_at = remaining
-                        .char_indices()
-                        .nth(max_width)
-                        .map(|(i, _)| i)
-                        .unwrap_or(remaining.len());
-                    wrapped.push(Line::from(&remaining[..split_at]));
-                    remaining = &remaining[split_at..];
-                }
-                wrapped
-            }
-        })
+    let visible_lines: Vec<Line> = text_lines
+        .into_iter()
+        .skip(state.scroll)
+        .take(height)
         .collect();
+    frame.render_widget(Paragraph::new(visible_lines), inner_area);
+}
+
+/// Wraps a styled line to `max_width` columns, keeping the style of split spans
+fn wrap_spans(spans: Vec<Span<'static>>, max_width: usize) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    let mut width = 0;
+    for span in spans {
+        let mut remaining: &str = &span.content;
+        while !remaining.is_empty() {
+            if width >= max_width && !line.is_empty() {
+                lines.push(Line::from(std::mem::take(&mut line)));
+                width = 0;
+            }
+            let (head, tail) = split_at_width(remaining, max_width.saturating_sub(width));
+            width += head.width();
+            line.push(Span::styled(head.to_string(), span.style));
+            remaining = tail;
+        }
+    }
+    lines.push(Line::from(line));
+    lines
+}
+
+/// Formats the stages of a correlated message, with the time spent in each channel
+fn journey_lines(journey: &Journey) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(format!(
+            "Journey (correlation id: {})",
+            journey.correlation_id
+        ))
+        .bold(),
+    ];
+
+    for (i, hop) in journey.hops.iter().enumerate() {
+        let latency = match hop.latency_ns {
+            Some(latency_ns) => format_delay(latency_ns),
+            None => "in flight".to_string(),
+        };
+        lines.push(Line::from(format!(
+            "  {}. {} ({})",
+            i + 1,
+            hop.label,
+            latency
+        )));
+    }
 
-    let paragraph =
-        ratatui::widgets::Paragraph::new(text_lines).wrap(ratatui::widgets::Wrap { trim: false });
+    if let Some(total_ns) = journey.total_ns {
+        lines.push(Line::from(format!("  Total: {}", format_delay(total_ns))));
+    }
 
-    frame.render_widget(paragraph, inner_area);
+    lines
 }
@@ -1,5 +1,7 @@
-use crate::cmd::console::app::CachedLogs;
-use crate::cmd::console::widgets::formatters::{format_delay, format_time_ago, truncate_message};
+use crate::cmd::console::app::{CachedLogs, Direction, LogsView};
+use crate::cmd::console::widgets::formatters::{
+    format_delay, format_time_ago, format_wall_time, truncate_message,
+};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -8,6 +10,7 @@ use ratatui::{
     widgets::{Block, HighlightSpacing, Row, Table, TableState},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
 /// Renders a placeholder when no logs are available
 pub(crate) fn render_logs_placeholder(
@@ -23,7 +26,7 @@ pub(crate) fn render_logs_placeholder(
     let inner_area = block.inner(area);
     frame.render_widget(block, area);
 
-    let message_width = message.len() as u16;
+    let message_width = message.width() as u16;
     let x = inner_area.x + (inner_area.width.saturating_sub(message_width)) / 2;
     let y = inner_area.y + inner_area.height / 2;
 
@@ -34,9 +37,16 @@ pub(crate) fn render_logs_placeholder(
     }
 }
 
-/// Renders the logs panel with sent and received log entries
+/// Renders the logs panel with sent, received or interleaved log entries, narrowed down to those
+/// containing `search`, and whether the newest entry is followed. With `wall_clock` entries show
+/// the local time they were recorded at instead of their age.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn render_logs_panel(
     cached_logs: &CachedLogs,
+    logs_view: LogsView,
+    follow: bool,
+    wall_clock: bool,
+    search: &str,
     channel_label: &str,
     area: Rect,
     frame: &mut Frame,
@@ -50,8 +60,16 @@ pub(crate) fn render_logs_panel(
         border::PLAIN
     };
 
+    let mut title = if search.is_empty() {
+        format!(" {} - {} ", channel_label, logs_view.name())
+    } else {
+        format!(" {} - {} /{} ", channel_label, logs_view.name(), search)
+    };
+    if follow {
+        title.push_str("following ");
+    }
     let block = Block::bordered()
-        .title(format!(" {} ", channel_label))
+        .title(title)
         .border_set(border_set)
         .style(if is_focused {
             Style::default()
@@ -62,46 +80,56 @@ pub(crate) fn render_logs_panel(
     let inner_area = block.inner(area);
     frame.render_widget(block, area);
 
-    let received_map = &cached_logs.received_map;
-
     let available_width = inner_area.width.saturating_sub(2);
-    let msg_width = (available_width.saturating_sub(30) as usize).max(20);
+    let msg_width = (available_width.saturating_sub(32) as usize).max(20);
 
     let header_style = Style::default()
         .fg(Color::Yellow)
         .add_modifier(Modifier::BOLD);
 
-    let header = Row::new(vec!["Index", "Message", "Delay", "Ago"])
+    let time_header = if wall_clock { "Time" } else { "Ago" };
+    let header = Row::new(vec!["Index", "Message", "Delay", time_header])
         .style(header_style)
         .height(1);
 
     let rows: Vec<Row> = cached_logs
-        .logs
-        .sent_logs
-        .iter()
-        .map(|entry| {
-            let time_ago = format_time_ago(current_elapsed_ns.saturating_sub(entry.timestamp));
+        .entries(logs_view)
+        .into_iter()
+        .map(|(direction, entry)| {
+            // Entries from servers predating wall-clock timestamps keep showing their age
+            let time = if wall_clock && entry.unix_ms > 0 {
+                format_wall_time(entry.unix_ms, false)
+            } else {
+                format_time_ago(current_elapsed_ns.saturating_sub(entry.timestamp))
+            };
 
             let msg = entry.message.as_deref().unwrap_or("");
             let truncated_msg = truncate_message(msg, msg_width);
 
-            let delay_str = if let Some(received_entry) = received_map.get(&entry.index) {
-                if received_entry.timestamp >= entry.timestamp {
-                    let delay_ns = received_entry.timestamp - entry.timestamp;
-                    format_delay(delay_ns)
-                } else {
-                    "⚠".to_string()
+            // Time between the send and the receive of the message
+            let (sent_entry, received_entry, missing) = match direction {
+                Direction::Sent => (
+                    Some(entry),
+                    cached_logs.received_map.get(&entry.index),
+                    "queued",
+                ),
+                Direction::Received => (cached_logs.sent_map.get(&entry.index), Some(entry), "-"),
+            };
+            let delay_str = match (sent_entry, received_entry) {
+                (Some(sent), Some(received)) if received.timestamp >= sent.timestamp => {
+                    format_delay(received.timestamp - sent.timestamp)
                 }
-            } else {
-                "queued".to_string()
+                (Some(_), Some(_)) => "⚠".to_string(),
+                _ => missing.to_string(),
+            };
+
+            let index = match (logs_view, direction) {
+                (LogsView::Interleaved, Direction::Sent) => format!("→ {}", entry.index),
+                (LogsView::Interleaved, Direction::Received) => format!("← {}", entry.index),
+                _ => entry.index.to_string(),
             };
 
-            let row = Row::new(vec![
-                entry.index.to_string(),
-                truncated_msg,
-                delay_str,
-                time_ago,
-            ]);
+            let row = Row::new(vec![index, truncated_msg, delay_str, time]);
 
             if !is_focused {
                 row.style(Style::default().fg(Color::DarkGray))
@@ -112,10 +140,10 @@ pub(crate) fn render_logs_panel(
         .collect();
 
     let widths = [
-        ratatui::layout::Constraint::Length(6),  // Index
+        ratatui::layout::Constraint::Length(8),  // Index
         ratatui::layout::Constraint::Min(20),    // Message
         ratatui::layout::Constraint::Length(12), // Delay
-        ratatui::layout::Constraint::Length(13), // Ago
+        ratatui::layout::Constraint::Length(13), // Ago or Time
     ];
 
     let selected_row_style = Style::default()
@@ -1,4 +1,6 @@
-use channels_console::SerializableChannelStats;
+use channels_console::labels::group_by_level;
+use channels_console::samples::ChannelHistoryJson;
+use channels_console::{SerializableChannelStats, SerializableStreamStats};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::Stylize,
@@ -7,11 +9,21 @@ use ratatui::{
     widgets::{Block, Paragraph, TableState},
     Frame,
 };
+use std::time::Duration;
 
-use crate::cmd::console::app::{CachedLogs, Focus};
+use crate::cmd::console::app::{CachedLogs, Focus, LogsView};
+use crate::cmd::console::columns::Columns;
+use crate::cmd::console::filter::Filter;
+use crate::cmd::console::grouping::Grouping;
+use crate::cmd::console::keymap::{Action, KeyMap};
+use crate::cmd::console::sort::Sort;
+use crate::cmd::console::split::{SplitDirection, SplitRatio};
+use crate::cmd::console::trend::QueueHistory;
 
-use super::channels::render_channels_panel;
-use super::inspect::render_inspect_popup;
+use super::channels::{render_channels_panel, render_grouped_panel, render_groups_panel};
+use super::chart::{render_chart_panel, CHART_HEIGHT};
+use super::columns::render_columns_popup;
+use super::inspect::{render_inspect_popup, InspectState};
 use super::logs::{render_logs_panel, render_logs_placeholder};
 
 /// Renders the main content area including channels table, logs panel, and error states
@@ -20,19 +32,39 @@ pub fn render_main_view(
     frame: &mut Frame,
     area: Rect,
     stats: &[SerializableChannelStats],
+    streams: &[SerializableStreamStats],
     error: &Option<String>,
-    metrics_port: u16,
+    location: &str,
     table_state: &mut TableState,
     logs_table_state: &mut TableState,
     focus: Focus,
     show_logs: bool,
     logs: &Option<CachedLogs>,
+    logs_view: LogsView,
+    follow_logs: bool,
+    wall_clock: bool,
     paused: bool,
     inspected_log: &Option<channels_console::LogEntry>,
+    inspected_journey: &Option<channels_console::correlation::Journey>,
+    inspect_state: &mut InspectState,
     current_elapsed_ns: u64,
+    collapse_level: usize,
+    split: SplitDirection,
+    split_ratio: SplitRatio,
+    sort: Sort,
+    filter: &Filter,
+    log_search: &Filter,
+    queue_history: &QueueHistory,
+    show_chart: bool,
+    chart_history: Option<&ChannelHistoryJson>,
+    refresh_interval: Duration,
+    grouping: &mut Grouping,
+    columns: &Columns,
+    column_chooser: Option<usize>,
+    keys: &KeyMap,
 ) {
     if let Some(ref error_msg) = error {
-        if stats.is_empty() {
+        if stats.is_empty() && streams.is_empty() {
             let error_text = vec![
                 Line::from(""),
                 Line::from("Error").red().bold().centered(),
@@ -40,8 +72,8 @@ pub fn render_main_view(
                 Line::from(error_msg.as_str()).red().centered(),
                 Line::from(""),
                 Line::from(format!(
-                    "Make sure the metrics server is running on http://127.0.0.1:{}",
-                    metrics_port
+                    "Make sure the metrics server is running on {}",
+                    location
                 ))
                 .yellow()
                 .centered(),
@@ -53,10 +85,49 @@ pub fn render_main_view(
         }
     }
 
-    if stats.is_empty() {
+    // Closed channels are hidden after the query, so all matching channels are closed
+    if stats.is_empty() && streams.is_empty() && filter.closed_hidden() > 0 {
         let empty_text = vec![
             Line::from(""),
-            Line::from("No channel statistics found")
+            Line::from(format!(
+                "All {} {}channels are closed",
+                filter.closed_hidden(),
+                if filter.is_active() { "matching " } else { "" }
+            ))
+            .yellow()
+            .centered(),
+            Line::from(""),
+            Line::from(format!(
+                "Press <{}> to show closed channels",
+                keys.label(Action::HideClosed)
+            ))
+            .centered(),
+        ];
+
+        let block = Block::bordered().border_set(border::THICK);
+        frame.render_widget(Paragraph::new(empty_text).block(block), area);
+        return;
+    }
+
+    if stats.is_empty() && streams.is_empty() && filter.hidden() > 0 {
+        let empty_text = vec![
+            Line::from(""),
+            Line::from(format!("No channels match /{}", filter.query()))
+                .yellow()
+                .centered(),
+            Line::from(""),
+            Line::from("Press <Esc> to clear the filter").centered(),
+        ];
+
+        let block = Block::bordered().border_set(border::THICK);
+        frame.render_widget(Paragraph::new(empty_text).block(block), area);
+        return;
+    }
+
+    if stats.is_empty() && streams.is_empty() {
+        let empty_text = vec![
+            Line::from(""),
+            Line::from("No channel or stream statistics found")
                 .yellow()
                 .centered(),
             Line::from(""),
@@ -68,23 +139,53 @@ pub fn render_main_view(
         return;
     }
 
+    if collapse_level > 0 {
+        let groups = group_by_level(stats, collapse_level);
+        render_groups_panel(&groups, collapse_level, area, frame);
+        return;
+    }
+
+    if let Some(by) = grouping.by() {
+        let rows = grouping.rows(stats);
+        render_grouped_panel(&rows, by, area, frame, &mut grouping.table_state);
+        return;
+    }
+
     // Split the area if logs are being shown
     let (table_area, logs_area) = if show_logs {
-        let chunks = Layout::default()
-            .direction(ratatui::layout::Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(area);
-        (chunks[0], Some(chunks[1]))
+        let (table_area, logs_area) = split.split(area, split_ratio);
+        (table_area, Some(logs_area))
     } else {
         (area, None)
     };
 
+    // The chart pane of the selected channel goes below the table
+    let selected_stat = table_state.selected().and_then(|i| stats.get(i));
+    let table_area = match selected_stat {
+        Some(stat) if show_chart => {
+            let [table_area, chart_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(CHART_HEIGHT)])
+                    .areas(table_area);
+            render_chart_panel(
+                stat,
+                chart_history,
+                queue_history,
+                refresh_interval,
+                chart_area,
+                frame,
+            );
+            table_area
+        }
+        _ => table_area,
+    };
+
     let selected_index = table_state.selected().unwrap_or(0);
     let channel_position = selected_index + 1; // 1-indexed
     let total_channels = stats.len();
 
     render_channels_panel(
         stats,
+        streams,
         table_area,
         frame,
         table_state,
@@ -92,6 +193,11 @@ pub fn render_main_view(
         focus,
         channel_position,
         total_channels,
+        sort,
+        filter,
+        queue_history,
+        current_elapsed_ns,
+        columns,
     );
 
     // Render logs panel if visible
@@ -110,10 +216,9 @@ pub fn render_main_view(
 
         if let Some(ref cached_logs) = logs {
             let has_missing_log = cached_logs
-                .logs
-                .sent_logs
+                .entries(logs_view)
                 .iter()
-                .any(|entry| entry.message.is_none());
+                .any(|(_, entry)| entry.message.is_none());
             let display_label = if has_missing_log {
                 format!("{} (missing \"log = true\")", channel_label)
             } else {
@@ -121,6 +226,10 @@ pub fn render_main_view(
             };
             render_logs_panel(
                 cached_logs,
+                logs_view,
+                follow_logs,
+                wall_clock,
+                log_search.query(),
                 &display_label,
                 logs_area,
                 frame,
@@ -142,7 +251,17 @@ pub fn render_main_view(
 
     if focus == Focus::Inspect {
         if let Some(ref inspected_log) = inspected_log {
-            render_inspect_popup(inspected_log, area, frame);
+            render_inspect_popup(
+                inspected_log,
+                inspected_journey.as_ref(),
+                inspect_state,
+                area,
+                frame,
+            );
         }
     }
+
+    if let Some(selected) = column_chooser {
+        render_columns_popup(columns, selected, area, frame);
+    }
 }
@@ -0,0 +1,38 @@
+use channels_console::SerializableStreamStats;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::cmd::console::widgets::formatters::format_delay;
+
+/// Renders a highlighted row listing streams that stopped yielding items
+pub fn render_stalled_bar(frame: &mut Frame, area: Rect, streams: &[SerializableStreamStats]) {
+    let mut spans = vec![Span::from(format!(
+        " ⚠ {} stalled stream{}: ",
+        streams.len(),
+        if streams.len() == 1 { "" } else { "s" }
+    ))
+    .bold()];
+
+    for (idx, stream) in streams.iter().enumerate() {
+        if idx > 0 {
+            spans.push(" | ".into());
+        }
+        spans.push(stream.label.clone().bold());
+        // A consumer waiting on a pending poll points at a slow producer
+        let waiting = if stream.polling {
+            "consumer waiting"
+        } else {
+            "not polled"
+        };
+        spans.push(format!(" idle for {}, {}", format_delay(stream.idle_ns), waiting).into());
+    }
+
+    let paragraph =
+        Paragraph::new(Line::from(spans)).style(Style::default().fg(Color::White).bg(Color::Red));
+    frame.render_widget(paragraph, area);
+}
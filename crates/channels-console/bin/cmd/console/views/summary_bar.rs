@@ -0,0 +1,56 @@
+use channels_console::format_bytes;
+use channels_console::summary::Summary;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::cmd::console::widgets::formatters::format_rate;
+
+/// Renders a one-line strip with totals across all channels
+pub fn render_summary_bar(frame: &mut Frame, area: Rect, summary: &Summary) {
+    let states = &summary.states;
+    let mut state_spans: Vec<Span> = vec![format!("{} active", states.active).into()];
+    if states.full > 0 {
+        state_spans.push(", ".into());
+        state_spans.push(format!("{} full", states.full).red().bold());
+    }
+    if states.notified > 0 {
+        state_spans.push(format!(", {} notified", states.notified).into());
+    }
+    if states.closed > 0 {
+        state_spans.push(format!(", {} closed", states.closed).into());
+    }
+
+    let mut spans = vec![
+        " Channels ".fg(Color::Yellow),
+        format!("{} (", summary.channels).into(),
+    ];
+    spans.extend(state_spans);
+    spans.extend([
+        ")".into(),
+        " | Queued ".fg(Color::Yellow),
+        format!(
+            "{} ({})",
+            summary.queued,
+            format_bytes(summary.queued_bytes)
+        )
+        .into(),
+        " | Sent ".fg(Color::Yellow),
+        summary.sent_count.to_string().into(),
+        " | Received ".fg(Color::Yellow),
+        summary.received_count.to_string().into(),
+        " | Busiest ".fg(Color::Yellow),
+    ]);
+    match &summary.busiest {
+        Some(busiest) => {
+            spans.push(format!("{} {}", busiest.label, format_rate(busiest.send_rate)).into())
+        }
+        None => spans.push("-".into()),
+    }
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
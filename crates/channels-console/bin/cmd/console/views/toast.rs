@@ -0,0 +1,31 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    symbols::border,
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Renders a short-lived notification in the bottom right corner of `area`
+pub(crate) fn render_toast(message: &str, is_error: bool, area: Rect, frame: &mut Frame) {
+    let width = (message.width() as u16 + 4).min(area.width);
+    let height = 3.min(area.height);
+    let toast_area = Rect {
+        x: area.x + area.width - width,
+        y: area.y + area.height - height,
+        width,
+        height,
+    };
+
+    let color = if is_error { Color::Red } else { Color::Green };
+    let block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(color));
+
+    frame.render_widget(Clear, toast_area);
+    frame.render_widget(
+        Paragraph::new(format!(" {}", message)).block(block),
+        toast_area,
+    );
+}
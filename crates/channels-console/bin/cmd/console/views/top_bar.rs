@@ -8,16 +8,25 @@ use ratatui::{
 };
 use std::time::Instant;
 
-/// Renders the top status bar showing connection status and refresh timer
+use channels_console::info::InfoJson;
+
+/// Renders the top status bar showing connection status and refresh timer, or the replay position
+#[allow(clippy::too_many_arguments)]
 pub fn render_top_bar(
     frame: &mut Frame,
     area: Rect,
     is_paused: bool,
+    replay_status: Option<&str>,
     last_successful_fetch: Option<Instant>,
     has_error: bool,
     has_data: bool,
+    schema_notice: Option<&str>,
+    target: Option<&str>,
+    server_info: Option<&InfoJson>,
 ) {
-    let status_text = if is_paused {
+    let mut status_text = if let Some(status) = replay_status {
+        Line::from(vec!["Replay ".cyan().bold(), status.into()])
+    } else if is_paused {
         Line::from(vec!["⏸ ".yellow(), "PAUSED".yellow().bold()])
     } else if let Some(last_fetch) = last_successful_fetch {
         let elapsed = Instant::now().duration_since(last_fetch);
@@ -42,9 +51,33 @@ pub fn render_top_bar(
         Line::from(vec!["⋯ ".into(), "Connecting...".into()])
     };
 
-    let block = Block::bordered()
+    if let Some(target) = target {
+        status_text.push_span(" | Process ");
+        status_text.push_span(target.to_string().bold());
+        status_text.push_span(" ");
+        status_text.push_span("<Tab> ".blue().bold());
+    }
+
+    if let Some(notice) = schema_notice {
+        status_text.push_span(" | ");
+        status_text.push_span(format!("⚠ {}", notice).yellow());
+    }
+
+    let mut block = Block::bordered()
         .title(" Status ")
         .border_set(border::PLAIN);
+    if let Some(info) = server_info {
+        let mut title = String::from(" ");
+        if let Some(service) = &info.service_name {
+            title.push_str(&format!("{} · ", service));
+        }
+        title.push_str(&format!("{} · pid {}", info.process, info.pid));
+        if !info.hostname.is_empty() {
+            title.push_str(&format!(" @ {}", info.hostname));
+        }
+        title.push_str(&format!(" · v{} ", info.version));
+        block = block.title(Line::from(title).right_aligned());
+    }
 
     let paragraph = Paragraph::new(status_text).block(block).left_aligned();
 
@@ -0,0 +1,224 @@
+use channels_console::{parse_duration, CombinedJson};
+use clap::Parser;
+use colored::{Color, Colorize};
+use eyre::Result;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+
+use super::http::{check_compatibility, fetch_combined, ServerArgs};
+
+#[derive(Debug, Parser)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub server: ServerArgs,
+
+    /// Time between samples, e.g. `500ms` or `5s`
+    #[arg(long, default_value = "1s", value_parser = parse_interval)]
+    pub interval: Duration,
+}
+
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    parse_duration(s)
+        .filter(|interval| !interval.is_zero())
+        .ok_or_else(|| format!("invalid interval '{}', expected e.g. 500ms or 5s", s))
+}
+
+impl WatchArgs {
+    /// Prints the statistics every interval until interrupted. Failed fetches are reported on
+    /// stderr and retried, so a restarted process is picked up again.
+    pub fn run(&self) -> Result<()> {
+        // Escape codes would end up in files and pipes
+        if !std::io::stdout().is_terminal() {
+            colored::control::set_override(false);
+        }
+
+        let client = self.server.client()?;
+        let mut previous: Option<Sample> = None;
+        let mut compatible = false;
+        loop {
+            if !compatible {
+                if let Err(e) = check_compatibility(&client) {
+                    eprintln!("Failed to fetch from {}: {}", client.location(), e);
+                    std::thread::sleep(self.interval);
+                    continue;
+                }
+                compatible = true;
+            }
+            match fetch_combined(&client, self.server.prefix.as_deref()) {
+                Ok(combined) => {
+                    let sample = Sample::new(&combined);
+                    print!("{}", render(&combined, previous.as_ref()));
+                    previous = Some(sample);
+                }
+                Err(e) => eprintln!("Failed to fetch from {}: {}", client.location(), e),
+            }
+            std::io::stdout().flush()?;
+            std::thread::sleep(self.interval);
+        }
+    }
+}
+
+/// Counters of the previous sample, by channel and stream id
+struct Sample {
+    /// Sent, received and queued messages
+    channels: HashMap<u64, (u64, u64, u64)>,
+    /// Yielded items
+    streams: HashMap<u64, u64>,
+}
+
+impl Sample {
+    fn new(combined: &CombinedJson) -> Self {
+        Self {
+            channels: combined
+                .channels
+                .iter()
+                .map(|c| (c.id, (c.sent_count, c.received_count, c.queued)))
+                .collect(),
+            streams: combined
+                .streams
+                .iter()
+                .map(|s| (s.id, s.items_yielded))
+                .collect(),
+        }
+    }
+}
+
+/// Change of a counter since the previous sample, colored when it grew or shrank.
+fn delta(
+    value: u64,
+    previous: Option<u64>,
+    growth: Color,
+    shrink: Color,
+) -> (String, Option<Color>) {
+    match previous {
+        Some(previous) if value > previous => (format!("+{}", value - previous), Some(growth)),
+        Some(previous) if value < previous => (format!("-{}", previous - value), Some(shrink)),
+        _ => (String::new(), None),
+    }
+}
+
+/// Table with a delta column after each counter, padded before coloring so escape codes
+/// don't break the alignment.
+struct WatchTable {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<(String, Option<Color>)>>,
+}
+
+impl WatchTable {
+    fn render(&self) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .map(|row| row[i].0.chars().count())
+                    .chain([self.headers[i].chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut out = String::new();
+        let header: Vec<String> = self
+            .headers
+            .iter()
+            .zip(&widths)
+            .map(|(header, width)| format!("{:<width$}", header, width = width))
+            .collect();
+        out.push_str(&format!("{}\n", header.join("  ").trim_end().bold()));
+
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|((text, color), width)| {
+                    let padded = format!("{:<width$}", text, width = width);
+                    match color {
+                        Some(color) => padded.color(*color).to_string(),
+                        None => padded,
+                    }
+                })
+                .collect();
+            out.push_str(&format!("{}\n", cells.join("  ").trim_end()));
+        }
+        out
+    }
+}
+
+fn render(combined: &CombinedJson, previous: Option<&Sample>) -> String {
+    let mut out = format!(
+        "\n=== {} - {} channels, {} streams ===\n",
+        chrono::Local::now().format("%H:%M:%S"),
+        combined.channels.len(),
+        combined.streams.len()
+    );
+
+    if !combined.channels.is_empty() {
+        let rows = combined
+            .channels
+            .iter()
+            .map(|channel| {
+                let before = previous.and_then(|p| p.channels.get(&channel.id));
+                let (sent, sent_color) = delta(
+                    channel.sent_count,
+                    before.map(|b| b.0),
+                    Color::Green,
+                    Color::Green,
+                );
+                let (received, received_color) = delta(
+                    channel.received_count,
+                    before.map(|b| b.1),
+                    Color::Green,
+                    Color::Green,
+                );
+                // A growing queue is the one to look at
+                let (queued, queued_color) = delta(
+                    channel.queued,
+                    before.map(|b| b.2),
+                    Color::Red,
+                    Color::Green,
+                );
+                vec![
+                    (channel.label.clone(), None),
+                    (channel.state.to_string(), None),
+                    (channel.sent_count.to_string(), None),
+                    (sent, sent_color),
+                    (channel.received_count.to_string(), None),
+                    (received, received_color),
+                    (channel.queued.to_string(), None),
+                    (queued, queued_color),
+                ]
+            })
+            .collect();
+        let table = WatchTable {
+            headers: vec!["Channel", "State", "Sent", "", "Received", "", "Queued", ""],
+            rows,
+        };
+        out.push_str(&table.render());
+    }
+
+    if !combined.streams.is_empty() {
+        let rows = combined
+            .streams
+            .iter()
+            .map(|stream| {
+                let before = previous.and_then(|p| p.streams.get(&stream.id)).copied();
+                let (yielded, yielded_color) =
+                    delta(stream.items_yielded, before, Color::Green, Color::Green);
+                vec![
+                    (stream.label.clone(), None),
+                    (stream.state.to_string(), None),
+                    (stream.items_yielded.to_string(), None),
+                    (yielded, yielded_color),
+                ]
+            })
+            .collect();
+        let table = WatchTable {
+            headers: vec!["Stream", "State", "Yielded", ""],
+            rows,
+        };
+        out.push('\n');
+        out.push_str(&table.render());
+    }
+    out
+}
@@ -1 +1,2 @@
 pub(crate) mod formatters;
+pub(crate) mod json;
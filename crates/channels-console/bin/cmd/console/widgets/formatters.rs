@@ -1,17 +1,36 @@
-use channels_console::ChannelType;
+use channels_console::{format_bytes, ChannelType};
 use ratatui::{
     style::{Color, Style},
     widgets::Cell,
 };
+use std::collections::VecDeque;
 
-pub(crate) fn truncate_left(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        let truncated_len = max_len.saturating_sub(3);
-        let start_idx = s.len().saturating_sub(truncated_len);
-        format!("...{}", &s[start_idx..])
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Truncates from the left to at most `max_width` terminal columns, keeping the end of the string
+pub(crate) fn truncate_left(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width < 3 {
+        return ".".repeat(max_width);
     }
+
+    let budget = max_width - 3;
+    let mut width = 0;
+    let start_idx = s
+        .grapheme_indices(true)
+        .rev()
+        .take_while(|(_, grapheme)| {
+            width += grapheme.width();
+            width <= budget
+        })
+        .last()
+        .map(|(idx, _)| idx)
+        .unwrap_or(s.len());
+
+    format!("...{}", &s[start_idx..])
 }
 
 pub(crate) fn queue_status(
@@ -57,15 +76,67 @@ pub(crate) fn format_delay(delay_ns: u64) -> String {
     }
 }
 
-pub(crate) fn truncate_message(msg: &str, max_len: usize) -> String {
-    if msg.len() <= max_len {
-        format!("{:<width$}", msg, width = max_len)
+/// Truncates from the right to exactly `max_width` terminal columns, padding shorter messages
+pub(crate) fn truncate_message(msg: &str, max_width: usize) -> String {
+    let msg_width = msg.width();
+    if msg_width <= max_width {
+        return format!("{}{}", msg, " ".repeat(max_width - msg_width));
+    }
+    if max_width <= 3 {
+        return ".".repeat(max_width);
+    }
+
+    // Unlike `split_at_width`, a wide grapheme that doesn't fit is left out
+    let mut width = 0;
+    let end_idx = msg
+        .grapheme_indices(true)
+        .find(|(_, grapheme)| {
+            width += grapheme.width();
+            width > max_width - 3
+        })
+        .map_or(msg.len(), |(idx, _)| idx);
+    let truncated = &msg[..end_idx];
+    format!(
+        "{}...{}",
+        truncated,
+        " ".repeat(max_width - 3 - truncated.width())
+    )
+}
+
+/// Splits a string after the last grapheme that fits in `max_width` terminal columns.
+/// At least one grapheme is kept in the head, so wrapping loops always make progress.
+pub(crate) fn split_at_width(s: &str, max_width: usize) -> (&str, &str) {
+    let mut width = 0;
+    let mut split_idx = 0;
+    for (idx, grapheme) in s.grapheme_indices(true) {
+        width += grapheme.width();
+        if width > max_width && idx > 0 {
+            break;
+        }
+        split_idx = idx + grapheme.len();
+        if width >= max_width {
+            break;
+        }
+    }
+    s.split_at(split_idx)
+}
+
+/// Formats messages per second, switching to per minute for slow rates
+pub(crate) fn format_rate(per_sec: f64) -> String {
+    if per_sec >= 10.0 {
+        format!("{:.0}/s", per_sec)
+    } else if per_sec >= 1.0 {
+        format!("{:.1}/s", per_sec)
     } else {
-        let truncated = &msg[..max_len.saturating_sub(3)];
-        format!("{}...", truncated)
+        format!("{:.1}/m", per_sec * 60.0)
     }
 }
 
+/// Formats bytes per second
+pub(crate) fn format_byte_rate(per_sec: f64) -> String {
+    format!("{}/s", format_bytes(per_sec.round() as u64))
+}
+
 /// Formats a timestamp in nanoseconds as MM:SS.mmm
 pub(crate) fn format_timestamp(timestamp_ns: u64) -> String {
     let total_secs = timestamp_ns / 1_000_000_000;
@@ -75,6 +146,19 @@ pub(crate) fn format_timestamp(timestamp_ns: u64) -> String {
     format!("{:02}:{:02}.{:03}", minutes, seconds, millis)
 }
 
+/// Formats milliseconds since the Unix epoch as local HH:MM:SS.mmm, or RFC3339 with `full`
+pub(crate) fn format_wall_time(unix_ms: u64, full: bool) -> String {
+    let Some(time) = chrono::DateTime::from_timestamp_millis(unix_ms as i64) else {
+        return "-".to_string();
+    };
+    let local = time.with_timezone(&chrono::Local);
+    if full {
+        local.to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+    } else {
+        local.format("%H:%M:%S%.3f").to_string()
+    }
+}
+
 /// Formats a time difference in nanoseconds as "now", "1s ago", "1m ago", "1h ago", etc.
 pub(crate) fn format_time_ago(nanos_ago: u64) -> String {
     const NANOS_PER_SEC: u64 = 1_000_000_000;
@@ -106,3 +190,64 @@ pub(crate) fn format_time_ago(nanos_ago: u64) -> String {
         }
     }
 }
+
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders the last `width` samples as a text sparkline, scaled so that `max` is a full bar
+pub(crate) fn sparkline(samples: &VecDeque<u64>, max: u64, width: usize) -> String {
+    let max = max.max(1);
+    samples
+        .iter()
+        .skip(samples.len().saturating_sub(width))
+        .map(|&sample| {
+            let level = (sample.min(max) * (SPARK_BARS.len() as u64 - 1)).div_ceil(max);
+            SPARK_BARS[level as usize]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_left() {
+        assert_eq!(truncate_left("src/main.rs:10", 20), "src/main.rs:10");
+        assert_eq!(truncate_left("src/main.rs:10", 8), "...rs:10");
+        assert_eq!(truncate_left("src/main.rs:10", 3), "...");
+        assert_eq!(truncate_left("src/main.rs:10", 2), "..");
+        assert_eq!(truncate_left("src/main.rs:10", 1), ".");
+        assert_eq!(truncate_left("src/main.rs:10", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_left_wide_and_combining() {
+        // Each CJK character takes two columns, one that doesn't fit is left out
+        assert_eq!(truncate_left("通道/日本語", 8), "...本語");
+        assert_eq!(truncate_left("通道/日本語", 7), "...本語");
+        assert_eq!(truncate_left("通道/日本語", 6), "...語");
+        // Combining marks stay with their base character
+        assert_eq!(truncate_left("cafe\u{301}/cafe\u{301}", 6), "...afe\u{301}");
+        assert_eq!(truncate_left("cafe\u{301}", 4), "cafe\u{301}");
+    }
+
+    #[test]
+    fn test_truncate_message() {
+        assert_eq!(truncate_message("hello", 7), "hello  ");
+        assert_eq!(truncate_message("hello world", 8), "hello...");
+        assert_eq!(truncate_message("hello", 3), "...");
+        assert_eq!(truncate_message("hello", 2), "..");
+        assert_eq!(truncate_message("hello", 1), ".");
+        assert_eq!(truncate_message("hello", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_message_wide_and_combining() {
+        // A wide character that doesn't fit is padded over, so columns stay aligned
+        assert_eq!(truncate_message("日本語テキスト", 8), "日本... ");
+        assert_eq!(truncate_message("日本語テキスト", 9), "日本語...");
+        assert_eq!(truncate_message("日本語テキスト", 4), "... ");
+        assert_eq!(truncate_message("cafe\u{301} au lait", 7), "cafe\u{301}...");
+        assert_eq!(truncate_message("cafe\u{301}", 4), "cafe\u{301}");
+    }
+}
@@ -0,0 +1,104 @@
+use channels_console::LogEntry;
+use ratatui::{
+    style::{Color, Style, Stylize},
+    text::Span,
+};
+use serde_json::Value;
+
+/// JSON payload of a log entry: the value logged with `log = json`, or a message that parses
+/// as a JSON object or array
+pub(crate) fn message_json(entry: &LogEntry) -> Option<Value> {
+    if let Some(json) = &entry.json {
+        return Some(json.clone());
+    }
+    serde_json::from_str(entry.message.as_deref()?)
+        .ok()
+        .filter(|value: &Value| value.is_object() || value.is_array())
+}
+
+/// Number of nested object and array levels, 0 for scalars
+pub(crate) fn json_depth(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => 1 + map.values().map(json_depth).max().unwrap_or(0),
+        Value::Array(items) => 1 + items.iter().map(json_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Pretty-printed, syntax-highlighted lines of `value`. Objects and arrays nested
+/// `fold_depth` levels deep or more are folded into a one-line summary.
+pub(crate) fn json_lines(value: &Value, fold_depth: Option<usize>) -> Vec<Vec<Span<'static>>> {
+    let mut lines = Vec::new();
+    push_value(&mut lines, Vec::new(), value, 0, fold_depth, false);
+    lines
+}
+
+fn push_value(
+    lines: &mut Vec<Vec<Span<'static>>>,
+    mut line: Vec<Span<'static>>,
+    value: &Value,
+    level: usize,
+    fold_depth: Option<usize>,
+    comma: bool,
+) {
+    let comma = if comma { "," } else { "" };
+    let (open, close, len, unit) = match value {
+        Value::Object(map) => ("{", "}", map.len(), "key"),
+        Value::Array(items) => ("[", "]", items.len(), "item"),
+        scalar => {
+            line.push(scalar_span(scalar));
+            line.push(comma.into());
+            lines.push(line);
+            return;
+        }
+    };
+
+    if len == 0 {
+        line.push(format!("{}{}{}", open, close, comma).into());
+        lines.push(line);
+        return;
+    }
+    if fold_depth.is_some_and(|depth| level >= depth) {
+        line.push(format!("{}…{}{}", open, close, comma).into());
+        let plural = if len == 1 { "" } else { "s" };
+        line.push(format!(" {} {}{}", len, unit, plural).dark_gray());
+        lines.push(line);
+        return;
+    }
+
+    line.push(open.into());
+    lines.push(line);
+    let indent = "  ".repeat(level + 1);
+    match value {
+        Value::Object(map) => {
+            for (i, (key, item)) in map.iter().enumerate() {
+                let key = serde_json::to_string(key).unwrap_or_default();
+                let line = vec![
+                    indent.clone().into(),
+                    Span::styled(key, Style::default().fg(Color::Cyan)),
+                    ": ".into(),
+                ];
+                push_value(lines, line, item, level + 1, fold_depth, i + 1 < len);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                let line = vec![indent.clone().into()];
+                push_value(lines, line, item, level + 1, fold_depth, i + 1 < len);
+            }
+        }
+        _ => unreachable!("scalars are handled above"),
+    }
+    lines.push(vec![
+        format!("{}{}{}", "  ".repeat(level), close, comma).into()
+    ]);
+}
+
+fn scalar_span(value: &Value) -> Span<'static> {
+    let color = match value {
+        Value::String(_) => Color::Green,
+        Value::Number(_) => Color::Yellow,
+        _ => Color::Magenta,
+    };
+    Span::styled(value.to_string(), Style::default().fg(color))
+}
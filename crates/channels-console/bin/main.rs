@@ -1,12 +1,24 @@
 mod cmd;
 use clap::{Parser, Subcommand};
-use cmd::console::ConsoleArgs;
+use cmd::console::{ConsoleArgs, DiffArgs, ReplayArgs, SnapshotArgs, Target, TopArgs, WatchArgs};
 use eyre::Result;
 
 #[derive(Subcommand, Debug)]
 pub enum TCSubcommand {
     #[command(about = "Start the console TUI")]
     Console(ConsoleArgs),
+    #[command(about = "Start the console TUI ranked by backlog")]
+    Top(TopArgs),
+    #[command(about = "Play back a recording in the console TUI")]
+    Replay(ReplayArgs),
+    #[command(about = "Print the current statistics once, without starting the TUI")]
+    Snapshot(SnapshotArgs),
+    #[command(
+        about = "Print the statistics repeatedly, highlighting changes since the previous sample"
+    )]
+    Watch(WatchArgs),
+    #[command(about = "Compare two saved JSON snapshots")]
+    Diff(DiffArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -19,9 +31,37 @@ pub struct TCArgs {
     #[command(subcommand)]
     pub cmd: Option<TCSubcommand>,
 
-    /// Port for the metrics server (used when no subcommand is provided)
-    #[arg(long, default_value = "6770", global = true)]
-    pub metrics_port: u16,
+    /// Port for the metrics server (used when no subcommand is provided) [default: 6770]
+    #[arg(long, global = true)]
+    pub metrics_port: Option<u16>,
+
+    /// Host of the metrics server (used when no subcommand is provided) [default: 127.0.0.1]
+    #[arg(long, global = true)]
+    pub host: Option<String>,
+
+    /// Base URL of the metrics server, e.g. `http://10.0.0.5:6770` (used when no subcommand is provided)
+    #[arg(long, global = true)]
+    pub url: Option<String>,
+
+    /// Metrics server of a monitored process as `name=url`, repeat to monitor several (used when no subcommand is provided)
+    #[arg(long = "target", value_name = "NAME=URL", global = true)]
+    pub targets: Vec<Target>,
+
+    /// Bearer token sent to the metrics server (used when no subcommand is provided)
+    #[arg(long, global = true)]
+    pub token: Option<String>,
+
+    /// Unix domain socket of the metrics server (used when no subcommand is provided)
+    #[arg(long, global = true)]
+    pub socket: Option<std::path::PathBuf>,
+
+    /// Only show channels with labels under this path prefix (used when no subcommand is provided)
+    #[arg(long, global = true)]
+    pub prefix: Option<String>,
+
+    /// Console config file with key remaps, theme and saved layout (used when no subcommand is provided)
+    #[arg(long, global = true)]
+    pub config: Option<std::path::PathBuf>,
 }
 
 fn main() -> Result<()> {
@@ -31,9 +71,31 @@ fn main() -> Result<()> {
         Some(TCSubcommand::Console(args)) => {
             args.run()?;
         }
+        Some(TCSubcommand::Top(args)) => {
+            args.run()?;
+        }
+        Some(TCSubcommand::Replay(args)) => {
+            args.run()?;
+        }
+        Some(TCSubcommand::Snapshot(args)) => {
+            args.run()?;
+        }
+        Some(TCSubcommand::Watch(args)) => {
+            args.run()?;
+        }
+        Some(TCSubcommand::Diff(args)) => {
+            args.run()?;
+        }
         None => {
             let args = ConsoleArgs {
                 metrics_port: root_args.metrics_port,
+                host: root_args.host,
+                url: root_args.url,
+                targets: root_args.targets,
+                token: root_args.token,
+                socket: root_args.socket,
+                prefix: root_args.prefix,
+                config: root_args.config,
             };
             args.run()?;
         }
@@ -0,0 +1,29 @@
+use std::env;
+use std::process::Command;
+
+// `std::sync::mpmc` is unstable, so the `std-mpmc` feature only takes effect on nightly
+// toolchains. Elsewhere it is ignored, keeping `--all-features` builds working on stable.
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(std_mpmc)");
+
+    if env::var_os("CARGO_FEATURE_STD_MPMC").is_none() {
+        return;
+    }
+    if is_nightly() {
+        println!("cargo::rustc-cfg=std_mpmc");
+    } else {
+        println!("cargo::warning=the std-mpmc feature requires a nightly toolchain and is ignored");
+    }
+}
+
+fn is_nightly() -> bool {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    Command::new(rustc)
+        .arg("--version")
+        .output()
+        .map(|output| {
+            let version = String::from_utf8_lossy(&output.stdout);
+            version.contains("nightly") || version.contains("-dev")
+        })
+        .unwrap_or(false)
+}
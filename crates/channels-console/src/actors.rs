@@ -0,0 +1,150 @@
+//! Tasks and threads sending and receiving the messages of a channel.
+//!
+//! Inline channels (`mode = inline`) report each message from the caller's own `send` or
+//! `recv`, so they attribute it to the Tokio task (by [`tokio::task::Id`]) or, outside of a
+//! task, the OS thread doing it. Proxied channels only see their forwarders, so they don't
+//! track actors.
+//!
+//! The top senders and receivers are served by `/channels/<id>/actors`, linking a congested
+//! channel back to the code flooding or starving it.
+
+// Only inline tokio channels record actors
+#![cfg_attr(not(feature = "tokio"), allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::thread::ThreadId;
+
+use crate::{CHANNELS_STATE, SCHEMA_VERSION};
+
+/// Max number of distinct actors tracked per direction, later ones are counted as [`OTHER`].
+const MAX_ACTORS: usize = 64;
+
+/// Number of actors served by `/channels/<id>/actors` per direction.
+const TOP_ACTORS: usize = 10;
+
+/// Actor of messages beyond [`MAX_ACTORS`].
+const OTHER: &str = "other";
+
+/// Messages sent or received by a single task or thread.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActorStats {
+    /// `task <id>` or `thread <name>`
+    pub actor: String,
+    pub count: u64,
+}
+
+/// Wrapper for the `/channels/<id>/actors` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelActorsJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    pub id: String,
+    /// Most active senders first
+    pub senders: Vec<ActorStats>,
+    /// Most active receivers first
+    pub receivers: Vec<ActorStats>,
+}
+
+/// Identity of the task or thread running the current code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ActorId {
+    #[cfg(feature = "tokio")]
+    Task(tokio::task::Id),
+    Thread(ThreadId),
+    Other,
+}
+
+impl ActorId {
+    fn current() -> Self {
+        #[cfg(feature = "tokio")]
+        if let Some(id) = tokio::task::try_id() {
+            return Self::Task(id);
+        }
+        Self::Thread(std::thread::current().id())
+    }
+
+    /// Display name, only built the first time an actor is seen.
+    fn name(self) -> String {
+        match self {
+            #[cfg(feature = "tokio")]
+            Self::Task(id) => format!("task {}", id),
+            Self::Thread(id) => {
+                let thread = std::thread::current();
+                match thread.name() {
+                    Some(name) => format!("thread {}", name),
+                    None => format!("thread {:?}", id),
+                }
+            }
+            Self::Other => OTHER.to_string(),
+        }
+    }
+}
+
+/// Message counts by actor, for one direction of a channel.
+#[derive(Debug, Default)]
+struct ActorCounts {
+    counts: HashMap<ActorId, (String, u64)>,
+}
+
+impl ActorCounts {
+    fn record(&mut self, actor: ActorId) {
+        let actor = if self.counts.len() >= MAX_ACTORS && !self.counts.contains_key(&actor) {
+            ActorId::Other
+        } else {
+            actor
+        };
+        self.counts
+            .entry(actor)
+            .or_insert_with(|| (actor.name(), 0))
+            .1 += 1;
+    }
+
+    fn top(&self) -> Vec<ActorStats> {
+        let mut actors: Vec<ActorStats> = self
+            .counts
+            .values()
+            .map(|(actor, count)| ActorStats {
+                actor: actor.clone(),
+                count: *count,
+            })
+            .collect();
+        actors.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.actor.cmp(&b.actor)));
+        actors.truncate(TOP_ACTORS);
+        actors
+    }
+}
+
+/// Senders and receivers of a channel, kept with its per-message details.
+#[derive(Debug, Default)]
+pub(crate) struct Actors {
+    senders: ActorCounts,
+    receivers: ActorCounts,
+}
+
+impl Actors {
+    /// Attributes a sent message to the current task or thread.
+    pub(crate) fn on_sent(&mut self) {
+        self.senders.record(ActorId::current());
+    }
+
+    /// Attributes a received message to the current task or thread.
+    pub(crate) fn on_received(&mut self) {
+        self.receivers.record(ActorId::current());
+    }
+}
+
+pub(crate) fn get_channel_actors(channel_id: &str) -> Option<ChannelActorsJson> {
+    let id = channel_id.parse::<u64>().ok()?;
+    let (_, stats_map) = CHANNELS_STATE.get()?;
+    stats_map.get(id, |channel_stats| {
+        let messages = channel_stats.counters.messages();
+        ChannelActorsJson {
+            schema_version: SCHEMA_VERSION,
+            id: channel_id.to_string(),
+            senders: messages.actors.senders.top(),
+            receivers: messages.actors.receivers.top(),
+        }
+    })
+}
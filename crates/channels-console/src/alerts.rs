@@ -0,0 +1,537 @@
+//! Alert rules evaluated by the statistics collector.
+//!
+//! A rule combines a [`Condition`] (e.g. "queued > 80% of capacity") with a duration the
+//! condition has to hold for, and a list of [`AlertAction`]s fired once per breach.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use channels_console::alerts::{self, AlertRule};
+//! use std::time::Duration;
+//!
+//! alerts::add_rule(
+//!     AlertRule::builder("ingest-backlog")
+//!         .channel("ingest")
+//!         .queued_percent_above(80.0)
+//!         .for_duration(Duration::from_secs(10))
+//!         .log()
+//!         .webhook("http://127.0.0.1:9000/alerts")
+//!         .build(),
+//! );
+//!
+//! // Called for alerts fired by any rule
+//! channels_console::on_alert(|alert| {
+//!     println!("{} fired for {}", alert.rule, alert.channel_label);
+//! });
+//! ```
+//!
+//! Currently active alerts, and the most recently fired ones, are served by the `/alerts`
+//! endpoint and highlighted in the TUI.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::shards::ReadAll;
+use crate::{
+    parse_duration, resolve_label, ChannelState, ChannelStats, ChannelType, SCHEMA_VERSION,
+};
+
+/// Number of fired alerts kept for the `/alerts` endpoint.
+const RECENT_ALERTS_LIMIT: usize = 100;
+
+/// How long a webhook delivery waits for the connection.
+const WEBHOOK_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a webhook delivery waits for the response once the alert is sent.
+const WEBHOOK_RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Condition evaluated against a channel's statistics.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// More than N messages queued.
+    QueuedAbove(u64),
+    /// Queue filled above the given percentage of capacity (bounded and oneshot channels only).
+    QueuedPercentAbove(f64),
+    /// Channel is in the `Full` state.
+    Full,
+    /// Channel is in the `Closed` state.
+    Closed,
+    /// Channel closed while messages were still queued, i.e. they will never be received.
+    ClosedUnexpectedly,
+}
+
+impl Condition {
+    fn is_met(&self, stats: &ChannelStats) -> bool {
+        match self {
+            Condition::QueuedAbove(limit) => stats.queued() > *limit,
+            Condition::QueuedPercentAbove(percent) => {
+                let capacity = match stats.channel_type {
+                    ChannelType::Bounded(cap) => cap as f64,
+                    ChannelType::Oneshot => 1.0,
+                    ChannelType::Unbounded => return false,
+                };
+                capacity > 0.0 && stats.queued() as f64 / capacity * 100.0 > *percent
+            }
+            Condition::Full => stats.state == ChannelState::Full,
+            Condition::Closed => stats.state == ChannelState::Closed,
+            Condition::ClosedUnexpectedly => {
+                stats.state == ChannelState::Closed && stats.queued() > 0
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Condition::QueuedAbove(limit) => write!(f, "queued > {}", limit),
+            Condition::QueuedPercentAbove(percent) => write!(f, "queued > {}%", percent),
+            Condition::Full => write!(f, "full"),
+            Condition::Closed => write!(f, "closed"),
+            Condition::ClosedUnexpectedly => write!(f, "closed unexpectedly"),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    /// Parses `queued > 100`, `queued > 80%`, `full`, `closed` or `closed unexpectedly`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "full" => return Ok(Condition::Full),
+            "closed" => return Ok(Condition::Closed),
+            "closed unexpectedly" => return Ok(Condition::ClosedUnexpectedly),
+            _ => {}
+        }
+
+        let threshold = s
+            .strip_prefix("queued")
+            .and_then(|rest| rest.trim_start().strip_prefix('>'))
+            .map(str::trim)
+            .ok_or_else(|| format!("invalid alert condition: {}", s))?;
+
+        if let Some(percent) = threshold.strip_suffix('%') {
+            percent
+                .trim()
+                .parse()
+                .map(Condition::QueuedPercentAbove)
+                .map_err(|_| format!("invalid percentage in alert condition: {}", s))
+        } else {
+            threshold
+                .parse()
+                .map(Condition::QueuedAbove)
+                .map_err(|_| format!("invalid threshold in alert condition: {}", s))
+        }
+    }
+}
+
+/// What happens when an alert fires.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertAction {
+    /// Print the alert to stderr.
+    Log,
+    /// POST the alert as JSON to a `http://` or `https://` URL.
+    Webhook(String),
+    /// Run a program with whitespace-separated arguments, without a shell. The alert JSON is
+    /// passed in the `CHANNELS_CONSOLE_ALERT` env variable.
+    Exec(String),
+}
+
+/// A single alert rule.
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub(crate) name: String,
+    pub(crate) channel: Option<String>,
+    pub(crate) condition: Condition,
+    pub(crate) duration: Duration,
+    pub(crate) actions: Vec<AlertAction>,
+}
+
+impl AlertRule {
+    /// Create a builder for a rule with the given name.
+    pub fn builder(name: impl Into<String>) -> AlertRuleBuilder {
+        AlertRuleBuilder {
+            rule: AlertRule {
+                name: name.into(),
+                channel: None,
+                condition: Condition::Full,
+                duration: Duration::ZERO,
+                actions: Vec::new(),
+            },
+        }
+    }
+
+    /// Parse a rule expression like `queued > 80% for 10s`.
+    ///
+    /// The resulting rule applies to all channels and logs to stderr; use the builder
+    /// to customize it further.
+    pub fn parse(name: impl Into<String>, expr: &str) -> Result<Self, String> {
+        let (condition, duration) = match expr.split_once(" for ") {
+            Some((condition, duration)) => (
+                condition,
+                parse_duration(duration)
+                    .ok_or_else(|| format!("invalid duration in alert rule: {}", expr))?,
+            ),
+            None => (expr, Duration::ZERO),
+        };
+
+        Ok(AlertRule {
+            name: name.into(),
+            channel: None,
+            condition: condition.parse()?,
+            duration,
+            actions: vec![AlertAction::Log],
+        })
+    }
+
+    /// Name of the rule.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, label: &str, source: &str) -> bool {
+        match &self.channel {
+            Some(channel) => channel == label || channel == source,
+            None => true,
+        }
+    }
+}
+
+/// Builder for [`AlertRule`].
+#[derive(Debug, Clone)]
+pub struct AlertRuleBuilder {
+    rule: AlertRule,
+}
+
+impl AlertRuleBuilder {
+    /// Restrict the rule to channels with the given label (or `file:line` source).
+    pub fn channel(mut self, label: impl Into<String>) -> Self {
+        self.rule.channel = Some(label.into());
+        self
+    }
+
+    /// Set the condition of the rule.
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.rule.condition = condition;
+        self
+    }
+
+    /// Fire when more than `limit` messages are queued.
+    pub fn queued_above(self, limit: u64) -> Self {
+        self.condition(Condition::QueuedAbove(limit))
+    }
+
+    /// Fire when the queue is filled above `percent` of the channel capacity.
+    pub fn queued_percent_above(self, percent: f64) -> Self {
+        self.condition(Condition::QueuedPercentAbove(percent))
+    }
+
+    /// Fire when the channel is full.
+    pub fn full(self) -> Self {
+        self.condition(Condition::Full)
+    }
+
+    /// Fire when the channel is closed.
+    pub fn closed(self) -> Self {
+        self.condition(Condition::Closed)
+    }
+
+    /// Fire when the channel is closed with messages still queued.
+    pub fn closed_unexpectedly(self) -> Self {
+        self.condition(Condition::ClosedUnexpectedly)
+    }
+
+    /// Require the condition to hold continuously for `duration` before firing.
+    pub fn for_duration(mut self, duration: Duration) -> Self {
+        self.rule.duration = duration;
+        self
+    }
+
+    /// Add an action.
+    pub fn action(mut self, action: AlertAction) -> Self {
+        self.rule.actions.push(action);
+        self
+    }
+
+    /// Print fired alerts to stderr.
+    pub fn log(self) -> Self {
+        self.action(AlertAction::Log)
+    }
+
+    /// POST fired alerts as JSON to a `http://` or `https://` URL. Responses other than 2xx
+    /// are logged to stderr.
+    pub fn webhook(self, url: impl Into<String>) -> Self {
+        self.action(AlertAction::Webhook(url.into()))
+    }
+
+    /// Run a program for fired alerts. The command is split on whitespace into the program
+    /// and its arguments, and isn't run by a shell.
+    pub fn exec(self, command: impl Into<String>) -> Self {
+        self.action(AlertAction::Exec(command.into()))
+    }
+
+    /// Build the rule. Rules without actions log to stderr.
+    pub fn build(mut self) -> AlertRule {
+        if self.rule.actions.is_empty() {
+            self.rule.actions.push(AlertAction::Log);
+        }
+        self.rule
+    }
+}
+
+/// A fired alert.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub rule: String,
+    pub channel_id: u64,
+    pub channel_label: String,
+    pub condition: String,
+    pub queued: u64,
+    pub state: ChannelState,
+    /// Wall-clock time the alert fired at, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+}
+
+/// Active and recently fired alerts, served by the `/alerts` endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertsJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Alerts whose condition still holds, oldest first
+    pub active: Vec<Alert>,
+    /// Most recently fired alerts, including resolved ones, newest first
+    pub recent: Vec<Alert>,
+}
+
+type AlertCallback = dyn Fn(&Alert) + Send + Sync;
+
+static RULES: RwLock<Vec<AlertRule>> = RwLock::new(Vec::new());
+
+static CALLBACKS: RwLock<Vec<Arc<AlertCallback>>> = RwLock::new(Vec::new());
+
+static ALERTS: RwLock<AlertsJson> = RwLock::new(AlertsJson {
+    schema_version: SCHEMA_VERSION,
+    active: Vec::new(),
+    recent: Vec::new(),
+});
+
+/// Register an alert rule. Rules are evaluated by the collector thread, together with rules
+/// from [`Config`].
+pub fn add_rule(rule: AlertRule) {
+    RULES.write().unwrap().push(rule);
+}
+
+/// Register a callback invoked for every alert fired by any rule.
+///
+/// Callbacks run on the statistics collector thread, so they should return quickly.
+pub fn on_alert(callback: impl Fn(&Alert) + Send + Sync + 'static) {
+    CALLBACKS.write().unwrap().push(Arc::new(callback));
+}
+
+/// Returns alerts whose condition still holds, oldest first.
+pub fn active_alerts() -> Vec<Alert> {
+    ALERTS.read().unwrap().active.clone()
+}
+
+pub(crate) fn get_alerts_json() -> AlertsJson {
+    ALERTS.read().unwrap().clone()
+}
+
+/// Tracks when each (rule, channel) pair started breaching and whether it already fired.
+#[derive(Debug, Default)]
+pub(crate) struct AlertEngine {
+    breaches: HashMap<(usize, u64), Breach>,
+    recent: VecDeque<Alert>,
+}
+
+/// An alert that fired during [`AlertEngine::evaluate`], with the actions of its rule.
+#[derive(Debug)]
+pub(crate) struct FiredAlert {
+    alert: Alert,
+    actions: Vec<AlertAction>,
+}
+
+impl FiredAlert {
+    /// Runs the `on_alert` callbacks and the rule's actions. Callbacks may read the stats, so
+    /// this must not be called while holding the shard guards the alert was evaluated with.
+    pub(crate) fn fire(&self) {
+        fire(&self.alert, &self.actions);
+    }
+}
+
+#[derive(Debug)]
+struct Breach {
+    since: Instant,
+    /// The alert fired for this breach, if it held long enough
+    fired: Option<Alert>,
+}
+
+impl AlertEngine {
+    /// Evaluate all rules against the current statistics, returning the alerts of new breaches.
+    /// They are fired by the caller once the stats are released.
+    pub(crate) fn evaluate(&mut self, stats: &ReadAll<'_, ChannelStats>) -> Vec<FiredAlert> {
+        let config_rules = &Config::current().alerts;
+        let rules = RULES.read().unwrap();
+        if config_rules.is_empty() && rules.is_empty() && self.breaches.is_empty() {
+            return Vec::new();
+        }
+
+        let mut fired = Vec::new();
+        let now = crate::now();
+        for (rule_idx, rule) in config_rules.iter().chain(rules.iter()).enumerate() {
+            for channel_stats in stats.values() {
+                let label = resolve_label(
+                    channel_stats.source,
+                    channel_stats.label.as_deref(),
+                    channel_stats.iter,
+                );
+                let key = (rule_idx, channel_stats.id);
+
+                if !rule.matches(&label, channel_stats.source)
+                    || !rule.condition.is_met(channel_stats)
+                {
+                    self.breaches.remove(&key);
+                    continue;
+                }
+
+                let breach = self.breaches.entry(key).or_insert(Breach {
+                    since: now,
+                    fired: None,
+                });
+                if breach.fired.is_some() || now.duration_since(breach.since) < rule.duration {
+                    continue;
+                }
+
+                let alert = Alert {
+                    rule: rule.name.clone(),
+                    channel_id: channel_stats.id,
+                    channel_label: label,
+                    condition: rule.condition.to_string(),
+                    queued: channel_stats.queued(),
+                    state: channel_stats.state,
+                    timestamp_ms: unix_millis(),
+                };
+                if self.recent.len() >= RECENT_ALERTS_LIMIT {
+                    self.recent.pop_back();
+                }
+                self.recent.push_front(alert.clone());
+                breach.fired = Some(alert.clone());
+                fired.push(FiredAlert {
+                    alert,
+                    actions: rule.actions.clone(),
+                });
+            }
+        }
+
+        // Forget breaches of channels that no longer exist
+        self.breaches.retain(|(_, id), _| stats.contains_key(*id));
+        self.publish();
+        fired
+    }
+
+    /// Exposes active and recent alerts to the HTTP API.
+    fn publish(&self) {
+        let mut active: Vec<Alert> = self
+            .breaches
+            .values()
+            .filter_map(|breach| breach.fired.clone())
+            .collect();
+        active.sort_by_key(|alert| alert.timestamp_ms);
+
+        let mut alerts = ALERTS.write().unwrap();
+        alerts.active = active;
+        alerts.recent = self.recent.iter().cloned().collect();
+    }
+}
+
+pub(crate) fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn fire(alert: &Alert, actions: &[AlertAction]) {
+    // Cloned, so callbacks can register further callbacks
+    let callbacks: Vec<Arc<AlertCallback>> = CALLBACKS.read().unwrap().clone();
+    for callback in callbacks {
+        callback(alert);
+    }
+
+    for action in actions {
+        match action {
+            AlertAction::Log => {
+                eprintln!(
+                    "[channels-console] ALERT {}: channel '{}' {} (queued: {}, state: {})",
+                    alert.rule, alert.channel_label, alert.condition, alert.queued, alert.state
+                );
+            }
+            AlertAction::Webhook(url) => {
+                let url = url.clone();
+                let body = serde_json::to_string(alert).unwrap_or_default();
+                // Never block the collector thread on network I/O
+                std::thread::spawn(move || {
+                    if let Err(e) = post_json(&url, &body) {
+                        eprintln!(
+                            "[channels-console] Failed to deliver alert to {}: {}",
+                            url, e
+                        );
+                    }
+                });
+            }
+            AlertAction::Exec(command) => {
+                let mut args = command.split_whitespace();
+                let Some(program) = args.next() else {
+                    continue;
+                };
+                let body = serde_json::to_string(alert).unwrap_or_default();
+                // Run without a shell, so the alert only reaches the command through its env
+                let child = std::process::Command::new(program)
+                    .args(args)
+                    .env("CHANNELS_CONSOLE_ALERT", body)
+                    .env("CHANNELS_CONSOLE_ALERT_RULE", &alert.rule)
+                    .env("CHANNELS_CONSOLE_ALERT_CHANNEL", &alert.channel_label)
+                    .spawn();
+                match child {
+                    // Reap the child in the background
+                    Ok(mut child) => {
+                        std::thread::spawn(move || child.wait());
+                    }
+                    Err(e) => eprintln!(
+                        "[channels-console] Failed to run alert command '{}': {}",
+                        command, e
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// POST an alert to a webhook, over `http://` or `https://`.
+fn post_json(url: &str, body: &str) -> Result<(), ureq::Error> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_connect(Some(WEBHOOK_CONNECT_TIMEOUT))
+        .timeout_recv_response(Some(WEBHOOK_RESPONSE_TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let response = agent
+        .post(url)
+        .header("Content-Type", "application/json")
+        .send(body)?;
+    let status = response.status();
+    if !status.is_success() {
+        eprintln!(
+            "[channels-console] Webhook {} rejected alert with status {}",
+            url, status
+        );
+    }
+    Ok(())
+}
@@ -1,11 +1,78 @@
-use std::time::Instant;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
+use crossbeam_channel::{bounded, RecvTimeoutError, Sender as CbSender};
 use prettytable::{Cell, Row, Table};
 
-use crate::{
-    format_bytes, get_combined_json, get_sorted_channel_stats, get_sorted_stream_stats,
-    resolve_label, Format,
-};
+use crate::info::set_service_name;
+use crate::labels::{group_by_level, matches_prefix};
+use crate::recording::{record_now, start_recording};
+use crate::report::{render_csv, render_html, render_json_lines, render_markdown, render_table};
+use crate::sinks::get_sorted_sink_stats;
+use crate::testing::{AssertionError, Assertions};
+use crate::{format_bytes, get_combined_json, resolve_label, Format, HTTP_SERVER_DISABLED};
+
+/// Destination of the statistics written by ChannelsGuard on drop.
+#[derive(Default)]
+pub enum Output {
+    #[default]
+    Stdout,
+    /// Created or truncated when the guard is dropped
+    File(PathBuf),
+    Writer(Box<dyn Write + Send>),
+}
+
+impl Output {
+    /// Write the statistics to any writer, e.g. an in-memory buffer.
+    pub fn writer(writer: impl Write + Send + 'static) -> Self {
+        Self::Writer(Box::new(writer))
+    }
+
+    fn write(&mut self, report: &str) -> std::io::Result<()> {
+        match self {
+            Self::Stdout => {
+                print!("{}", report);
+                Ok(())
+            }
+            Self::File(path) => std::fs::write(&*path, report),
+            Self::Writer(writer) => writer
+                .write_all(report.as_bytes())
+                .and_then(|_| writer.flush()),
+        }
+    }
+}
+
+impl From<PathBuf> for Output {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+impl From<&Path> for Output {
+    fn from(path: &Path) -> Self {
+        Self::File(path.to_path_buf())
+    }
+}
+
+impl From<&str> for Output {
+    fn from(path: &str) -> Self {
+        Self::File(PathBuf::from(path))
+    }
+}
+
+impl std::fmt::Debug for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stdout => write!(f, "Stdout"),
+            Self::File(path) => f.debug_tuple("File").field(path).finish(),
+            Self::Writer(_) => write!(f, "Writer(..)"),
+        }
+    }
+}
 
 /// Builder for creating a ChannelsGuard with custom configuration.
 ///
@@ -21,6 +88,14 @@ use crate::{
 /// ```
 pub struct ChannelsGuardBuilder {
     format: Format,
+    prefix: Option<String>,
+    collapse_level: usize,
+    record_path: Option<PathBuf>,
+    http: bool,
+    output: Output,
+    assertions: Option<Assertions>,
+    periodic: Option<Duration>,
+    service_name: Option<String>,
 }
 
 impl ChannelsGuardBuilder {
@@ -28,9 +103,31 @@ impl ChannelsGuardBuilder {
     pub fn new() -> Self {
         Self {
             format: Format::default(),
+            prefix: None,
+            collapse_level: 0,
+            record_path: None,
+            http: true,
+            output: Output::Stdout,
+            assertions: None,
+            periodic: None,
+            service_name: None,
         }
     }
 
+    /// Only print channels and streams with labels under the given path prefix,
+    /// e.g. `ingest` for `ingest/parser` and `ingest/writer`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Print channels collapsed into groups of labels truncated to `level` path segments
+    /// (table format only, `0` disables collapsing).
+    pub fn collapse_level(mut self, level: usize) -> Self {
+        self.collapse_level = level;
+        self
+    }
+
     /// Set the output format for statistics.
     ///
     /// # Examples
@@ -47,12 +144,124 @@ impl ChannelsGuardBuilder {
         self
     }
 
+    /// Where to write the statistics (default: stdout), a file path or an [`Output::writer`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::{ChannelsGuardBuilder, Format};
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .format(Format::Csv)
+    ///     .output("target/channels.csv")
+    ///     .build();
+    /// ```
+    pub fn output(mut self, output: impl Into<Output>) -> Self {
+        self.output = output.into();
+        self
+    }
+
+    /// Also print the statistics every `interval` while the guard is alive, for long-running
+    /// programs that never drop it. A file output is overwritten with the latest statistics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .periodic(Duration::from_secs(10))
+    ///     .build();
+    /// ```
+    pub fn periodic(mut self, interval: Duration) -> Self {
+        self.periodic = Some(interval);
+        self
+    }
+
+    /// Check the given [`Assertions`] after printing the statistics. The guard panics on drop
+    /// when they are violated, unless it is consumed with [`ChannelsGuard::finish`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::{Assertions, ChannelsGuardBuilder};
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .assert(Assertions::new().no_full_channels().all_closed().max_queued(0))
+    ///     .build();
+    /// ```
+    pub fn assert(mut self, assertions: Assertions) -> Self {
+        self.assertions = Some(assertions);
+        self
+    }
+
+    /// Record statistics and log entries to a JSONL file for `channels-console replay`,
+    /// see [`recording`](crate::recording). The final state is recorded when the guard is dropped.
+    pub fn record_to(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_path = Some(path.into());
+        self
+    }
+
+    /// Whether to start the metrics HTTP server (default: `true`). The server is started with
+    /// the first instrumented channel or stream, so the guard must be built before that.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    ///
+    /// // Print statistics on exit without serving them over HTTP
+    /// let _guard = ChannelsGuardBuilder::new().http(false).build();
+    /// ```
+    pub fn http(mut self, enabled: bool) -> Self {
+        self.http = enabled;
+        self
+    }
+
+    /// Name of the service, shown in `/info`, in snapshots and in the TUI title bar to tell
+    /// apart several instrumented processes running on one host.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    ///
+    /// let _guard = ChannelsGuardBuilder::new().service_name("ingest").build();
+    /// ```
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = Some(name.into());
+        self
+    }
+
     /// Build and return the ChannelsGuard.
     /// Statistics will be printed when the guard is dropped.
     pub fn build(self) -> ChannelsGuard {
-        ChannelsGuard {
+        if !self.http {
+            HTTP_SERVER_DISABLED.store(true, Ordering::Relaxed);
+        }
+        if let Some(name) = self.service_name {
+            set_service_name(name);
+        }
+        if let Some(path) = self.record_path {
+            start_recording(path);
+        }
+        let renderer = Renderer {
             start_time: Instant::now(),
             format: self.format,
+            prefix: self.prefix,
+            collapse_level: self.collapse_level,
+        };
+        let output = Arc::new(Mutex::new(self.output));
+        let periodic = self
+            .periodic
+            .map(|interval| spawn_periodic(renderer.clone(), Arc::clone(&output), interval));
+        ChannelsGuard {
+            renderer,
+            output,
+            assertions: self.assertions,
+            periodic,
+            finished: false,
         }
     }
 }
@@ -78,8 +287,42 @@ impl Default for ChannelsGuardBuilder {
 /// // Statistics will be printed when _guard is dropped
 /// ```
 pub struct ChannelsGuard {
+    renderer: Renderer,
+    output: Arc<Mutex<Output>>,
+    assertions: Option<Assertions>,
+    periodic: Option<Periodic>,
+    finished: bool,
+}
+
+/// Settings of the printed statistics, shared with the periodic printing thread.
+#[derive(Clone)]
+struct Renderer {
     start_time: Instant,
     format: Format,
+    prefix: Option<String>,
+    collapse_level: usize,
+}
+
+/// Thread printing statistics every interval, stopped when `stop` is dropped.
+struct Periodic {
+    stop: CbSender<()>,
+    handle: JoinHandle<()>,
+}
+
+fn spawn_periodic(renderer: Renderer, output: Arc<Mutex<Output>>, interval: Duration) -> Periodic {
+    let (stop, stop_rx) = bounded::<()>(0);
+    let handle = std::thread::Builder::new()
+        .name("channels-console-periodic".into())
+        .spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                let report = renderer.render();
+                if let Err(e) = output.lock().unwrap().write(&report) {
+                    eprintln!("Failed to write channel statistics: {}", e);
+                }
+            }
+        })
+        .expect("Failed to spawn channels-console-periodic thread");
+    Periodic { stop, handle }
 }
 
 impl ChannelsGuard {
@@ -89,8 +332,16 @@ impl ChannelsGuard {
     /// For custom configuration, use `ChannelsGuardBuilder::new()` instead.
     pub fn new() -> Self {
         Self {
-            start_time: Instant::now(),
-            format: Format::default(),
+            renderer: Renderer {
+                start_time: Instant::now(),
+                format: Format::default(),
+                prefix: None,
+                collapse_level: 0,
+            },
+            output: Arc::new(Mutex::new(Output::Stdout)),
+            assertions: None,
+            periodic: None,
+            finished: false,
         }
     }
 
@@ -105,9 +356,16 @@ impl ChannelsGuard {
     /// let _guard = ChannelsGuard::new().format(Format::Json);
     /// ```
     pub fn format(mut self, format: Format) -> Self {
-        self.format = format;
+        self.renderer.format = format;
         self
     }
+
+    /// Print the statistics and check the configured [`Assertions`], returning violations
+    /// instead of panicking on drop.
+    pub fn finish(mut self) -> Result<(), AssertionError> {
+        self.finished = true;
+        self.report()
+    }
 }
 
 impl Default for ChannelsGuard {
@@ -118,98 +376,165 @@ impl Default for ChannelsGuard {
 
 impl Drop for ChannelsGuard {
     fn drop(&mut self) {
-        let elapsed = self.start_time.elapsed();
-        let channels = get_sorted_channel_stats();
-        let streams = get_sorted_stream_stats();
-
-        if channels.is_empty() && streams.is_empty() {
-            println!("\nNo instrumented channels or streams found.");
+        if self.finished {
             return;
         }
+        if let Err(e) = self.report() {
+            // Don't turn a failing test into a double panic
+            if !std::thread::panicking() {
+                panic!("{}", e);
+            }
+        }
+    }
+}
+
+impl ChannelsGuard {
+    /// Writes the statistics to the output, then checks the assertions.
+    fn report(&mut self) -> Result<(), AssertionError> {
+        if let Some(periodic) = self.periodic.take() {
+            drop(periodic.stop);
+            let _ = periodic.handle.join();
+        }
+        record_now();
+
+        let report = self.renderer.render();
+        if let Err(e) = self.output.lock().unwrap().write(&report) {
+            eprintln!("Failed to write channel statistics: {}", e);
+        }
+
+        match &self.assertions {
+            Some(assertions) => assertions.check(self.renderer.prefix.as_deref().unwrap_or("")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Renderer {
+    /// Renders the statistics in the configured format.
+    fn render(&self) -> String {
+        let elapsed = self.start_time.elapsed();
+        let prefix = self.prefix.as_deref().unwrap_or("");
+        let mut out = String::new();
 
         match self.format {
             Format::Table => {
-                println!(
-                    "\n=== Statistics (runtime: {:.2}s) ===",
-                    elapsed.as_secs_f64()
-                );
+                let mut combined = get_combined_json();
+                combined.retain_prefix(prefix);
+                let mut sinks = get_sorted_sink_stats();
+                sinks.retain(|s| {
+                    matches_prefix(&resolve_label(s.source, s.label.as_deref(), s.iter), prefix)
+                });
 
-                // Display channels table if there are any
-                if !channels.is_empty() {
+                if combined.channels.is_empty() && combined.streams.is_empty() && sinks.is_empty() {
+                    out.push_str("\nNo instrumented channels, streams or sinks found.\n");
+                    return out;
+                }
+
+                out.push_str(&format!(
+                    "\n=== Statistics (runtime: {:.2}s) ===\n",
+                    elapsed.as_secs_f64()
+                ));
+                // Display collapsed channel groups instead of individual channels
+                if !combined.channels.is_empty() && self.collapse_level > 0 {
                     let mut table = Table::new();
 
                     table.add_row(Row::new(vec![
-                        Cell::new("Channel"),
-                        Cell::new("Type"),
-                        Cell::new("State"),
+                        Cell::new("Group"),
+                        Cell::new("Channels"),
                         Cell::new("Sent"),
                         Cell::new("Received"),
                         Cell::new("Queued"),
                         Cell::new("Mem"),
                     ]));
 
-                    for channel_stats in channels {
-                        let label = resolve_label(
-                            channel_stats.source,
-                            channel_stats.label.as_deref(),
-                            channel_stats.iter,
-                        );
+                    for group in group_by_level(&combined.channels, self.collapse_level) {
                         table.add_row(Row::new(vec![
-                            Cell::new(&label),
-                            Cell::new(&channel_stats.channel_type.to_string()),
-                            Cell::new(channel_stats.state.as_str()),
-                            Cell::new(&channel_stats.sent_count.to_string()),
-                            Cell::new(&channel_stats.received_count.to_string()),
-                            Cell::new(&channel_stats.queued().to_string()),
-                            Cell::new(&format_bytes(channel_stats.queued_bytes())),
+                            Cell::new(&group.label),
+                            Cell::new(&group.channels.to_string()),
+                            Cell::new(&group.sent_count.to_string()),
+                            Cell::new(&group.received_count.to_string()),
+                            Cell::new(&group.queued.to_string()),
+                            Cell::new(&format_bytes(group.queued_bytes)),
                         ]));
                     }
 
-                    println!("\nChannels:");
-                    table.printstd();
+                    out.push_str("\nChannel groups:\n");
+                    out.push_str(&table.to_string());
+                    combined.channels.clear();
                 }
+                out.push_str(&render_table(&combined));
 
-                // Display streams table if there are any
-                if !streams.is_empty() {
+                // Display sinks table if there are any
+                if !sinks.is_empty() {
                     let mut table = Table::new();
 
                     table.add_row(Row::new(vec![
-                        Cell::new("Stream"),
+                        Cell::new("Sink"),
                         Cell::new("State"),
-                        Cell::new("Yielded"),
+                        Cell::new("Accepted"),
+                        Cell::new("Flushes"),
+                        Cell::new("Errors"),
                     ]));
 
-                    for stream_stats in streams {
+                    for sink_stats in sinks {
                         let label = resolve_label(
-                            stream_stats.source,
-                            stream_stats.label.as_deref(),
-                            stream_stats.iter,
+                            sink_stats.source,
+                            sink_stats.label.as_deref(),
+                            sink_stats.iter,
                         );
                         table.add_row(Row::new(vec![
                             Cell::new(&label),
-                            Cell::new(stream_stats.state.as_str()),
-                            Cell::new(&stream_stats.items_yielded.to_string()),
+                            Cell::new(sink_stats.state.as_str()),
+                            Cell::new(&sink_stats.items_accepted.to_string()),
+                            Cell::new(&sink_stats.flushes.to_string()),
+                            Cell::new(&sink_stats.errors.to_string()),
                         ]));
                     }
 
-                    println!("\nStreams:");
-                    table.printstd();
+                    out.push_str("\nSinks:\n");
+                    out.push_str(&table.to_string());
                 }
             }
             Format::Json => {
-                let combined = get_combined_json();
+                let mut combined = get_combined_json();
+                combined.retain_prefix(prefix);
                 match serde_json::to_string(&combined) {
-                    Ok(json) => println!("{}", json),
+                    Ok(json) => out.push_str(&format!("{}\n", json)),
                     Err(e) => eprintln!("Failed to serialize statistics to JSON: {}", e),
                 }
             }
             Format::JsonPretty => {
-                let combined = get_combined_json();
+                let mut combined = get_combined_json();
+                combined.retain_prefix(prefix);
                 match serde_json::to_string_pretty(&combined) {
-                    Ok(json) => println!("{}", json),
+                    Ok(json) => out.push_str(&format!("{}\n", json)),
                     Err(e) => eprintln!("Failed to serialize statistics to pretty JSON: {}", e),
                 }
             }
+            Format::JsonLines => {
+                let mut combined = get_combined_json();
+                combined.retain_prefix(prefix);
+                match render_json_lines(&combined) {
+                    Ok(lines) => out.push_str(&lines),
+                    Err(e) => eprintln!("Failed to serialize statistics to JSON Lines: {}", e),
+                }
+            }
+            Format::Csv => {
+                let mut combined = get_combined_json();
+                combined.retain_prefix(prefix);
+                out.push_str(&render_csv(&combined));
+            }
+            Format::Markdown => {
+                let mut combined = get_combined_json();
+                combined.retain_prefix(prefix);
+                out.push_str(&render_markdown(&combined, elapsed));
+            }
+            Format::Html => {
+                let mut combined = get_combined_json();
+                combined.retain_prefix(prefix);
+                out.push_str(&render_html(&combined, elapsed));
+            }
         }
+        out
     }
 }
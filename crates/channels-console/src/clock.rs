@@ -0,0 +1,78 @@
+//! Time source used for message timestamps.
+//!
+//! Tests can install a [`ManualClock`] with [`ConfigBuilder::clock`](crate::ConfigBuilder::clock)
+//! and advance it explicitly, so latency and ordering assertions don't depend on scheduling.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use channels_console::clock::ManualClock;
+//! use std::time::Duration;
+//!
+//! let clock = ManualClock::new();
+//! channels_console::Config::builder()
+//!     .clock(clock.clone())
+//!     .synchronous()
+//!     .install()
+//!     .unwrap();
+//!
+//! // ... send a message ...
+//! clock.advance(Duration::from_millis(5));
+//! // ... receive it, the logged latency is exactly 5ms ...
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A source of the current time.
+pub trait Clock: Send + Sync + std::fmt::Debug + 'static {
+    fn now(&self) -> Instant;
+}
+
+/// The real monotonic clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when advanced. Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    base: Instant,
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            base: Instant::now(),
+            offset: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Move the clock forward.
+    pub fn advance(&self, by: Duration) {
+        *self.offset.lock().unwrap() += by;
+    }
+
+    /// Time elapsed since the clock was created, in clock time.
+    pub fn elapsed(&self) -> Duration {
+        *self.offset.lock().unwrap()
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.base + *self.offset.lock().unwrap()
+    }
+}
@@ -0,0 +1,794 @@
+//! Programmatic configuration of the instrumentation.
+//!
+//! All settings can be overridden with environment variables, so a deployed binary can be
+//! reconfigured without a rebuild:
+//!
+//! | Setting | Environment variable |
+//! |---|---|
+//! | [`metrics_port`](ConfigBuilder::metrics_port) | `CHANNELS_CONSOLE_METRICS_PORT` |
+//! | [`bind_addr`](ConfigBuilder::bind_addr) | `CHANNELS_CONSOLE_METRICS_BIND` |
+//! | [`metrics_socket`](ConfigBuilder::metrics_socket) | `CHANNELS_CONSOLE_METRICS_SOCKET` |
+//! | [`log_limit`](ConfigBuilder::log_limit) | `CHANNELS_CONSOLE_LOG_LIMIT` |
+//! | [`disable_server`](ConfigBuilder::disable_server) | `CHANNELS_CONSOLE_DISABLE_SERVER` |
+//! | [`disable`](ConfigBuilder::disable) | `CHANNELS_CONSOLE_DISABLED` |
+//! | [`token`](ConfigBuilder::token) | `CHANNELS_CONSOLE_TOKEN` |
+//! | [`cors_origin`](ConfigBuilder::cors_origin) | `CHANNELS_CONSOLE_CORS_ORIGIN` |
+//! | [`history_path`](ConfigBuilder::history_path) | `CHANNELS_CONSOLE_HISTORY_PATH` |
+//! | [`history_interval`](ConfigBuilder::history_interval) | `CHANNELS_CONSOLE_HISTORY_INTERVAL_MS` |
+//! | [`history_max_entries`](ConfigBuilder::history_max_entries) | `CHANNELS_CONSOLE_HISTORY_MAX_ENTRIES` |
+//! | [`history_logs`](ConfigBuilder::history_logs) | `CHANNELS_CONSOLE_HISTORY_LOGS` |
+//! | [`record_to`](ConfigBuilder::record_to) | `CHANNELS_CONSOLE_RECORD` |
+//! | [`closed_retention`](ConfigBuilder::closed_retention) | `CHANNELS_CONSOLE_CLOSED_RETENTION` |
+//! | [`max_channels`](ConfigBuilder::max_channels) | `CHANNELS_CONSOLE_MAX_CHANNELS` |
+//! | [`stream_stall_timeout`](ConfigBuilder::stream_stall_timeout) | `CHANNELS_CONSOLE_STREAM_STALL_TIMEOUT` |
+//! | [`channel_samples`](ConfigBuilder::channel_samples) | `CHANNELS_CONSOLE_CHANNEL_SAMPLES` |
+//! | [`forwarder_workers`](ConfigBuilder::forwarder_workers) | `CHANNELS_CONSOLE_FORWARDER_WORKERS` |
+//! | [`host_runtime`](ConfigBuilder::host_runtime) | `CHANNELS_CONSOLE_HOST_RUNTIME` |
+//! | [`trace_messages`](ConfigBuilder::trace_messages) | `CHANNELS_CONSOLE_TRACE_MESSAGES` |
+//! | [`disable_latency`](ConfigBuilder::disable_latency) | `CHANNELS_CONSOLE_DISABLE_LATENCY` |
+//!
+//! Settings can also be loaded from a TOML file pointed to by `CHANNELS_CONSOLE_CONFIG`,
+//! see [`FileConfig`]. Precedence, from lowest to highest: defaults, builder, config file,
+//! environment variables.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! channels_console::Config::builder()
+//!     .metrics_port(8080)
+//!     .log_limit(200)
+//!     .install()
+//!     .expect("channels-console was already configured");
+//! ```
+
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use crate::alerts::{AlertAction, AlertRule};
+use crate::clock::{Clock, SystemClock};
+use crate::parse_duration;
+
+const DEFAULT_METRICS_PORT: u16 = 6770;
+const DEFAULT_BIND_ADDR: &str = "127.0.0.1";
+const DEFAULT_LOG_LIMIT: usize = 50;
+const DEFAULT_HISTORY_INTERVAL: Duration = Duration::from_millis(1000);
+const DEFAULT_HISTORY_MAX_ENTRIES: usize = 3600;
+const DEFAULT_STREAM_STALL_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_CHANNEL_SAMPLES: usize = 300;
+
+/// Settings of the channels-console instrumentation.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub(crate) metrics_port: u16,
+    pub(crate) bind_addr: String,
+    pub(crate) metrics_socket: Option<PathBuf>,
+    pub(crate) log_limit: usize,
+    pub(crate) server_enabled: bool,
+    pub(crate) disabled: bool,
+    pub(crate) token: Option<String>,
+    pub(crate) cors_origin: Option<String>,
+    pub(crate) history_path: Option<PathBuf>,
+    pub(crate) history_interval: Duration,
+    pub(crate) history_max_entries: usize,
+    pub(crate) history_logs: bool,
+    pub(crate) record_path: Option<PathBuf>,
+    pub(crate) closed_retention: Option<Duration>,
+    pub(crate) max_channels: Option<usize>,
+    pub(crate) stream_stall_timeout: Duration,
+    pub(crate) channel_samples: usize,
+    pub(crate) forwarder_workers: usize,
+    pub(crate) host_runtime: bool,
+    pub(crate) trace_messages: bool,
+    pub(crate) latency: bool,
+    pub(crate) redact: Vec<String>,
+    pub(crate) alerts: Vec<AlertRule>,
+    pub(crate) clock: Arc<dyn Clock>,
+    pub(crate) synchronous: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            metrics_port: DEFAULT_METRICS_PORT,
+            bind_addr: DEFAULT_BIND_ADDR.to_string(),
+            metrics_socket: None,
+            log_limit: DEFAULT_LOG_LIMIT,
+            server_enabled: true,
+            disabled: false,
+            token: None,
+            cors_origin: None,
+            history_path: None,
+            history_interval: DEFAULT_HISTORY_INTERVAL,
+            history_max_entries: DEFAULT_HISTORY_MAX_ENTRIES,
+            history_logs: false,
+            record_path: None,
+            closed_retention: None,
+            max_channels: None,
+            stream_stall_timeout: DEFAULT_STREAM_STALL_TIMEOUT,
+            channel_samples: DEFAULT_CHANNEL_SAMPLES,
+            forwarder_workers: 0,
+            host_runtime: false,
+            trace_messages: false,
+            latency: true,
+            redact: Vec::new(),
+            alerts: Vec::new(),
+            clock: Arc::new(SystemClock),
+            synchronous: false,
+        }
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+impl Config {
+    /// Create a builder initialized with default settings.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder {
+            config: Config::default(),
+        }
+    }
+
+    /// Returns the active configuration, or the defaults (with env overrides) if none was installed.
+    pub fn current() -> &'static Config {
+        CONFIG.get_or_init(|| Config::default().with_file_overrides().with_env_overrides())
+    }
+
+    /// Port of the metrics HTTP server.
+    pub fn metrics_port(&self) -> u16 {
+        self.metrics_port
+    }
+
+    /// Address the metrics HTTP server binds to, e.g. `127.0.0.1:6770`.
+    pub fn metrics_addr(&self) -> String {
+        format!("{}:{}", self.bind_addr, self.metrics_port)
+    }
+
+    /// Unix domain socket the metrics HTTP server listens on instead of a TCP port.
+    pub fn metrics_socket(&self) -> Option<&Path> {
+        self.metrics_socket.as_deref()
+    }
+
+    /// Max number of log entries kept per channel or stream.
+    pub fn log_limit(&self) -> usize {
+        self.log_limit
+    }
+
+    /// Whether the metrics HTTP server is started.
+    pub fn server_enabled(&self) -> bool {
+        self.server_enabled
+    }
+
+    /// Whether instrumentation is turned off, making instrumented channels and streams
+    /// pass-through.
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// Apply settings from the file pointed to by `CHANNELS_CONSOLE_CONFIG`, if set.
+    fn with_file_overrides(self) -> Self {
+        let Ok(path) = std::env::var(CONFIG_PATH_ENV) else {
+            return self;
+        };
+        match FileConfig::load(&path) {
+            Ok(file) => file.apply(self),
+            Err(e) => {
+                eprintln!(
+                    "[channels-console] Failed to load config file {}: {}",
+                    path, e
+                );
+                self
+            }
+        }
+    }
+
+    /// Apply environment variable overrides on top of these settings.
+    fn with_env_overrides(mut self) -> Self {
+        if let Some(port) = env_parse("CHANNELS_CONSOLE_METRICS_PORT") {
+            self.metrics_port = port;
+        }
+        if let Ok(bind_addr) = std::env::var("CHANNELS_CONSOLE_METRICS_BIND") {
+            self.bind_addr = bind_addr;
+        }
+        if let Ok(path) = std::env::var("CHANNELS_CONSOLE_METRICS_SOCKET") {
+            self.metrics_socket = Some(PathBuf::from(path));
+        }
+        if let Some(log_limit) = env_parse("CHANNELS_CONSOLE_LOG_LIMIT") {
+            self.log_limit = log_limit;
+        }
+        if let Some(disabled) = env_flag("CHANNELS_CONSOLE_DISABLE_SERVER") {
+            self.server_enabled = !disabled;
+        }
+        if let Some(disabled) = env_flag("CHANNELS_CONSOLE_DISABLED") {
+            self.disabled = disabled;
+        }
+        // Instrumentation compiled with the `noop` feature can't be turned back on
+        if cfg!(feature = "noop") {
+            self.disabled = true;
+        }
+        if let Ok(token) = std::env::var("CHANNELS_CONSOLE_TOKEN") {
+            self.token = Some(token);
+        }
+        if let Ok(origin) = std::env::var("CHANNELS_CONSOLE_CORS_ORIGIN") {
+            self.cors_origin = Some(origin);
+        }
+        if let Ok(path) = std::env::var("CHANNELS_CONSOLE_HISTORY_PATH") {
+            self.history_path = Some(PathBuf::from(path));
+        }
+        if let Some(interval_ms) = env_parse::<u64>("CHANNELS_CONSOLE_HISTORY_INTERVAL_MS") {
+            self.history_interval = Duration::from_millis(interval_ms);
+        }
+        if let Some(max_entries) = env_parse("CHANNELS_CONSOLE_HISTORY_MAX_ENTRIES") {
+            self.history_max_entries = max_entries;
+        }
+        if let Some(logs) = env_flag("CHANNELS_CONSOLE_HISTORY_LOGS") {
+            self.history_logs = logs;
+        }
+        if let Ok(path) = std::env::var("CHANNELS_CONSOLE_RECORD") {
+            self.record_path = Some(PathBuf::from(path));
+        }
+        if let Some(retention) = std::env::var("CHANNELS_CONSOLE_CLOSED_RETENTION")
+            .ok()
+            .and_then(|v| parse_duration(&v))
+        {
+            self.closed_retention = Some(retention);
+        }
+        // Accept digit separators, e.g. `10_000`
+        if let Some(max_channels) = std::env::var("CHANNELS_CONSOLE_MAX_CHANNELS")
+            .ok()
+            .and_then(|v| v.replace('_', "").parse().ok())
+        {
+            self.max_channels = Some(max_channels);
+        }
+        if let Some(timeout) = std::env::var("CHANNELS_CONSOLE_STREAM_STALL_TIMEOUT")
+            .ok()
+            .and_then(|v| parse_duration(&v))
+        {
+            self.stream_stall_timeout = timeout;
+        }
+        if let Some(samples) = env_parse("CHANNELS_CONSOLE_CHANNEL_SAMPLES") {
+            self.channel_samples = samples;
+        }
+        if let Some(workers) = env_parse("CHANNELS_CONSOLE_FORWARDER_WORKERS") {
+            self.forwarder_workers = workers;
+        }
+        if let Some(host_runtime) = env_flag("CHANNELS_CONSOLE_HOST_RUNTIME") {
+            self.host_runtime = host_runtime;
+        }
+        if let Some(trace_messages) = env_flag("CHANNELS_CONSOLE_TRACE_MESSAGES") {
+            self.trace_messages = trace_messages;
+        }
+        if let Some(disabled) = env_flag("CHANNELS_CONSOLE_DISABLE_LATENCY") {
+            self.latency = !disabled;
+        }
+        self
+    }
+}
+
+fn env_parse<T: FromStr>(key: &str) -> Option<T> {
+    std::env::var(key).ok().and_then(|v| v.parse().ok())
+}
+
+fn env_flag(key: &str) -> Option<bool> {
+    std::env::var(key).ok().map(|v| v == "1" || v == "true")
+}
+
+/// Builder for [`Config`].
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Port of the metrics HTTP server (default: `6770`). `0` binds a free port, see
+    /// [`testing::metrics_addr`](crate::testing::metrics_addr).
+    pub fn metrics_port(mut self, port: u16) -> Self {
+        self.config.metrics_port = port;
+        self
+    }
+
+    /// Address the metrics HTTP server binds to (default: `127.0.0.1`).
+    pub fn bind_addr(mut self, addr: impl Into<String>) -> Self {
+        self.config.bind_addr = addr.into();
+        self
+    }
+
+    /// Serve the metrics HTTP API on a Unix domain socket at `path` instead of a TCP port.
+    /// Unix only.
+    pub fn metrics_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.metrics_socket = Some(path.into());
+        self
+    }
+
+    /// Max number of log entries kept per channel or stream (default: `50`).
+    pub fn log_limit(mut self, limit: usize) -> Self {
+        self.config.log_limit = limit;
+        self
+    }
+
+    /// Don't start the metrics HTTP server. Statistics are still collected and printed by
+    /// [`ChannelsGuard`](crate::ChannelsGuard).
+    pub fn disable_server(mut self) -> Self {
+        self.config.server_enabled = false;
+        self
+    }
+
+    /// Turn off all instrumentation. Proxied channels are returned unchanged, inline channels
+    /// and streams report nothing, and no collector threads or metrics server are started.
+    /// Always on with the `noop` feature.
+    pub fn disable(mut self) -> Self {
+        self.config.disabled = true;
+        self
+    }
+
+    /// Require an `Authorization: Bearer <token>` header on all metrics server requests.
+    /// The server is unauthenticated by default.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.config.token = Some(token.into());
+        self
+    }
+
+    /// Allow browser dashboards served from `origin`, e.g. `https://dash.example.com` or `*`,
+    /// to query the metrics server directly. Cross-origin requests are blocked by default.
+    pub fn cors_origin(mut self, origin: impl Into<String>) -> Self {
+        self.config.cors_origin = Some(origin.into());
+        self
+    }
+
+    /// Append periodic snapshots to a JSONL file, see [`history`](crate::history).
+    pub fn history_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.history_path = Some(path.into());
+        self
+    }
+
+    /// Interval between history snapshots (default: 1s).
+    pub fn history_interval(mut self, interval: Duration) -> Self {
+        self.config.history_interval = interval;
+        self
+    }
+
+    /// Number of newest history snapshots retained in the file (default: `3600`).
+    pub fn history_max_entries(mut self, max_entries: usize) -> Self {
+        self.config.history_max_entries = max_entries;
+        self
+    }
+
+    /// Also persist log entries in history snapshots (default: `false`).
+    pub fn history_logs(mut self, enabled: bool) -> Self {
+        self.config.history_logs = enabled;
+        self
+    }
+
+    /// Record statistics and log entries to a JSONL file for `channels-console replay`,
+    /// see [`recording`](crate::recording).
+    pub fn record_to(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.record_path = Some(path.into());
+        self
+    }
+
+    /// Evict channels closed for longer than `retention` from the statistics, see
+    /// [`retention`](crate::retention). Closed channels are kept forever by default.
+    pub fn closed_retention(mut self, retention: Duration) -> Self {
+        self.config.closed_retention = Some(retention);
+        self
+    }
+
+    /// Evict the oldest closed channels while more than `max` channels are tracked, see
+    /// [`retention`](crate::retention). Unlimited by default.
+    pub fn max_channels(mut self, max: usize) -> Self {
+        self.config.max_channels = Some(max);
+        self
+    }
+
+    /// Report open streams that yielded no item for `timeout` as stalled (default: 10s).
+    pub fn stream_stall_timeout(mut self, timeout: Duration) -> Self {
+        self.config.stream_stall_timeout = timeout;
+        self
+    }
+
+    /// Number of per-second samples kept for each channel, see [`samples`](crate::samples)
+    /// (default: 300, `0` disables sampling).
+    pub fn channel_samples(mut self, count: usize) -> Self {
+        self.config.channel_samples = count;
+        self
+    }
+
+    /// Forward messages of all proxied Tokio and futures channels on `workers` shared tasks
+    /// instead of a dedicated pair per channel, and crossbeam channels on `workers` shared
+    /// threads (default: 0, dedicated tasks and 2 threads). `std::sync::mpsc` channels always
+    /// get dedicated threads.
+    pub fn forwarder_workers(mut self, workers: usize) -> Self {
+        self.config.forwarder_workers = workers;
+        self
+    }
+
+    /// Spawn forwarder tasks of Tokio and futures channels on the Tokio runtime the channel is
+    /// instrumented in, instead of a private runtime. Channels instrumented outside of a
+    /// runtime still use the private one. Forwarders stop when the runtime shuts down.
+    pub fn host_runtime(mut self) -> Self {
+        self.config.host_runtime = true;
+        self
+    }
+
+    /// With the `tracing` feature, also emit a `TRACE` span for every sent and received
+    /// message, besides the events for created, full and closed channels.
+    pub fn trace_messages(mut self) -> Self {
+        self.config.trace_messages = true;
+        self
+    }
+
+    /// Don't track send to receive latencies. Together with a `log_limit` of 0, messages are
+    /// then only counted with atomics, without taking the per-channel lock.
+    pub fn disable_latency(mut self) -> Self {
+        self.config.latency = false;
+        self
+    }
+
+    /// Mask substrings of logged messages matching the regex `pattern` with `[REDACTED]`.
+    pub fn redact(mut self, pattern: impl Into<String>) -> Self {
+        self.config.redact.push(pattern.into());
+        self
+    }
+
+    /// Register an alert rule, see [`alerts`](crate::alerts).
+    pub fn alert(mut self, rule: AlertRule) -> Self {
+        self.config.alerts.push(rule);
+        self
+    }
+
+    /// Time source for message timestamps (default: [`SystemClock`]).
+    pub fn clock(mut self, clock: impl Clock) -> Self {
+        self.config.clock = Arc::new(clock);
+        self
+    }
+
+    /// Apply statistics events on the thread emitting them instead of a background collector
+    /// thread, so snapshots reflect every event emitted before them. Meant for tests.
+    pub fn synchronous(mut self) -> Self {
+        self.config.synchronous = true;
+        self
+    }
+
+    /// Build the configuration with config file and environment variable overrides applied.
+    pub fn build(self) -> Config {
+        self.config.with_file_overrides().with_env_overrides()
+    }
+
+    /// Build and activate the configuration.
+    ///
+    /// Must be called before the first channel or stream is instrumented, fails if a
+    /// configuration is already active.
+    pub fn install(self) -> Result<(), AlreadyInstalled> {
+        CONFIG.set(self.build()).map_err(|_| AlreadyInstalled)
+    }
+}
+
+/// Error returned by [`ConfigBuilder::install`] when a configuration is already active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyInstalled;
+
+impl std::fmt::Display for AlreadyInstalled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "channels-console configuration is already active, install it before instrumenting channels"
+        )
+    }
+}
+
+impl std::error::Error for AlreadyInstalled {}
+
+/// Environment variable pointing to a `channels-console.toml` file.
+pub const CONFIG_PATH_ENV: &str = "CHANNELS_CONSOLE_CONFIG";
+
+/// Contents of a `channels-console.toml` file, shared by the library and the console binary.
+///
+/// ```toml
+/// [metrics]
+/// port = 6770
+/// bind = "127.0.0.1"
+/// cors_origin = "https://dash.example.com"
+///
+/// [logs]
+/// limit = 100
+/// redact = ["password=\\S+"]
+///
+/// [history]
+/// path = "/tmp/channels-history.jsonl"
+///
+/// [retention]
+/// closed = "60s"
+/// max_channels = 10_000
+///
+/// [streams]
+/// stall_timeout = "30s"
+///
+/// [channels]
+/// samples = 600
+/// forwarder_workers = 4
+/// host_runtime = true
+/// trace_messages = true
+/// latency = false
+///
+/// [[alerts]]
+/// name = "ingest-backlog"
+/// channel = "ingest"
+/// condition = "queued > 80%"
+/// for = "10s"
+/// webhook = "http://127.0.0.1:9000/alerts"
+///
+/// [console]
+/// host = "127.0.0.1"
+/// refresh_ms = 500
+/// ```
+///
+/// All sections and keys are optional. Unknown keys are ignored.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FileConfig {
+    pub metrics: MetricsSection,
+    pub logs: LogsSection,
+    pub history: HistorySection,
+    pub retention: RetentionSection,
+    pub streams: StreamsSection,
+    pub channels: ChannelsSection,
+    pub alerts: Vec<AlertSection>,
+    pub console: ConsoleSection,
+}
+
+/// `[metrics]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MetricsSection {
+    pub port: Option<u16>,
+    pub bind: Option<String>,
+    /// Unix domain socket path, replaces the TCP port
+    pub socket: Option<PathBuf>,
+    pub disable_server: Option<bool>,
+    /// Bearer token required by the metrics server
+    pub token: Option<String>,
+    /// Origin allowed to query the metrics server from a browser, or `*`
+    pub cors_origin: Option<String>,
+}
+
+/// `[logs]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LogsSection {
+    pub limit: Option<usize>,
+    /// Regex patterns masked in logged messages
+    pub redact: Vec<String>,
+}
+
+/// `[history]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistorySection {
+    pub path: Option<PathBuf>,
+    pub interval_ms: Option<u64>,
+    pub max_entries: Option<usize>,
+    pub logs: Option<bool>,
+}
+
+/// `[retention]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RetentionSection {
+    /// How long closed channels are kept, e.g. `60s`
+    pub closed: Option<String>,
+    pub max_channels: Option<usize>,
+}
+
+/// `[streams]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct StreamsSection {
+    /// How long an open stream may yield nothing before it's reported as stalled, e.g. `30s`
+    pub stall_timeout: Option<String>,
+}
+
+/// `[channels]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ChannelsSection {
+    /// Number of per-second samples kept for each channel
+    pub samples: Option<usize>,
+    /// Number of shared forwarder tasks and threads, `0` for dedicated tasks and 2 threads
+    pub forwarder_workers: Option<usize>,
+    /// Spawn forwarder tasks on the Tokio runtime channels are instrumented in
+    pub host_runtime: Option<bool>,
+    /// Emit a `tracing` span for every message, with the `tracing` feature
+    pub trace_messages: Option<bool>,
+    /// Track send to receive latencies
+    pub latency: Option<bool>,
+}
+
+/// `[[alerts]]` entry.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AlertSection {
+    pub name: String,
+    /// Channel label or `file:line` source, all channels when missing
+    pub channel: Option<String>,
+    /// Condition expression, e.g. `queued > 80%`
+    pub condition: String,
+    /// How long the condition has to hold, e.g. `10s`
+    #[serde(rename = "for")]
+    pub for_duration: Option<String>,
+    pub log: Option<bool>,
+    pub webhook: Option<String>,
+    pub exec: Option<String>,
+}
+
+/// `[console]` section, used by the `channels-console` binary.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ConsoleSection {
+    /// Host of the metrics server
+    pub host: Option<String>,
+    /// Port of the metrics server
+    pub metrics_port: Option<u16>,
+    /// Base URL of the metrics server, takes precedence over `host` and `metrics_port`
+    pub url: Option<String>,
+    /// Bearer token sent to the metrics server
+    pub token: Option<String>,
+    /// TUI refresh interval
+    pub refresh_ms: Option<u64>,
+}
+
+impl FileConfig {
+    /// Parse a config file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        contents.parse()
+    }
+
+    /// Load the file pointed to by `CHANNELS_CONSOLE_CONFIG`, or `None` if the variable is unset.
+    pub fn from_env() -> Option<Result<Self, String>> {
+        std::env::var(CONFIG_PATH_ENV).ok().map(Self::load)
+    }
+
+    fn apply(self, mut config: Config) -> Config {
+        if let Some(port) = self.metrics.port {
+            config.metrics_port = port;
+        }
+        if let Some(bind) = self.metrics.bind {
+            config.bind_addr = bind;
+        }
+        if let Some(socket) = self.metrics.socket {
+            config.metrics_socket = Some(socket);
+        }
+        if let Some(disabled) = self.metrics.disable_server {
+            config.server_enabled = !disabled;
+        }
+        if let Some(token) = self.metrics.token {
+            config.token = Some(token);
+        }
+        if let Some(origin) = self.metrics.cors_origin {
+            config.cors_origin = Some(origin);
+        }
+        if let Some(limit) = self.logs.limit {
+            config.log_limit = limit;
+        }
+        config.redact.extend(self.logs.redact);
+        if let Some(path) = self.history.path {
+            config.history_path = Some(path);
+        }
+        if let Some(interval_ms) = self.history.interval_ms {
+            config.history_interval = Duration::from_millis(interval_ms);
+        }
+        if let Some(max_entries) = self.history.max_entries {
+            config.history_max_entries = max_entries;
+        }
+        if let Some(logs) = self.history.logs {
+            config.history_logs = logs;
+        }
+        if let Some(closed) = self.retention.closed {
+            match parse_duration(&closed) {
+                Some(retention) => config.closed_retention = Some(retention),
+                None => eprintln!(
+                    "[channels-console] Ignoring invalid closed channels retention '{}'",
+                    closed
+                ),
+            }
+        }
+        if let Some(max_channels) = self.retention.max_channels {
+            config.max_channels = Some(max_channels);
+        }
+        if let Some(stall_timeout) = self.streams.stall_timeout {
+            match parse_duration(&stall_timeout) {
+                Some(timeout) => config.stream_stall_timeout = timeout,
+                None => eprintln!(
+                    "[channels-console] Ignoring invalid stream stall timeout '{}'",
+                    stall_timeout
+                ),
+            }
+        }
+        if let Some(samples) = self.channels.samples {
+            config.channel_samples = samples;
+        }
+        if let Some(workers) = self.channels.forwarder_workers {
+            config.forwarder_workers = workers;
+        }
+        if let Some(host_runtime) = self.channels.host_runtime {
+            config.host_runtime = host_runtime;
+        }
+        if let Some(trace_messages) = self.channels.trace_messages {
+            config.trace_messages = trace_messages;
+        }
+        if let Some(latency) = self.channels.latency {
+            config.latency = latency;
+        }
+        for alert in self.alerts {
+            match alert.into_rule() {
+                Ok(rule) => config.alerts.push(rule),
+                Err(e) => eprintln!("[channels-console] Ignoring alert from config file: {}", e),
+            }
+        }
+        config
+    }
+}
+
+impl FromStr for FileConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(|e| e.to_string())
+    }
+}
+
+impl AlertSection {
+    fn into_rule(self) -> Result<AlertRule, String> {
+        let mut builder = AlertRule::builder(&self.name).condition(self.condition.parse()?);
+        if let Some(channel) = self.channel {
+            builder = builder.channel(channel);
+        }
+        if let Some(duration) = self.for_duration {
+            let duration = parse_duration(&duration).ok_or_else(|| {
+                format!("invalid duration '{}' in alert '{}'", duration, self.name)
+            })?;
+            builder = builder.for_duration(duration);
+        }
+        if self.log.unwrap_or(false) {
+            builder = builder.log();
+        }
+        if let Some(url) = self.webhook {
+            builder = builder.action(AlertAction::Webhook(url));
+        }
+        if let Some(command) = self.exec {
+            builder = builder.action(AlertAction::Exec(command));
+        }
+        Ok(builder.build())
+    }
+}
+
+/// Returns compiled redaction patterns of the active configuration.
+pub(crate) fn redaction_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        Config::current()
+            .redact
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    eprintln!(
+                        "[channels-console] Ignoring invalid redaction pattern '{}': {}",
+                        pattern, e
+                    );
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+/// Mask all substrings matching the configured redaction patterns.
+pub(crate) fn redact(log: String) -> String {
+    redaction_patterns().iter().fold(log, |log, re| {
+        re.replace_all(&log, "[REDACTED]").into_owned()
+    })
+}
@@ -0,0 +1,65 @@
+//! Per-consumer receive counters of fan-out channels.
+//!
+//! Receivers of crossbeam and `std::sync::mpmc` channels can be cloned, with each message
+//! taken by one of the clones. A receiver clone wrapped with the `receiver!` macro is
+//! attributed to a named consumer of the instrumented channel it receives from. Messages are
+//! handed over to it one at a time by a forwarder, which counts them, so the channel's
+//! `consumers` list shows how work is spread across consumers. The channel's received count
+//! still covers all of them.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! let (tx, rx) = crossbeam_channel::bounded::<Job>(100);
+//! let (tx, rx) = channels_console::channel!((tx, rx), label = "jobs");
+//!
+//! for i in 0..4 {
+//!     let rx = channels_console::receiver!(rx.clone(), channel = "jobs", label = format!("worker-{}", i));
+//!     std::thread::spawn(move || for job in rx { job.run() });
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::producers::channel_counters;
+use crate::resolve_label;
+
+/// Messages received by a single consumer of a channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsumerStats {
+    pub label: String,
+    pub received_count: u64,
+}
+
+/// Receiver clone attributed to a consumer of an instrumented channel.
+#[cfg_attr(not(any(feature = "crossbeam", std_mpmc)), allow(dead_code))]
+pub(crate) struct Consumer {
+    /// Shared with the channel's counters, see [`counters`](crate::counters)
+    received: Arc<AtomicU64>,
+}
+
+#[cfg_attr(not(any(feature = "crossbeam", std_mpmc)), allow(dead_code))]
+impl Consumer {
+    /// Registers a consumer of the most recent channel with the given label (or `file:line`
+    /// source). Returns `None` if no such channel is instrumented.
+    pub(crate) fn register(
+        source: &'static str,
+        channel: &str,
+        label: Option<String>,
+    ) -> Option<Self> {
+        let counters = channel_counters(source, channel, "receiver")?;
+        let label: Arc<str> = label
+            .unwrap_or_else(|| resolve_label(source, None, 0))
+            .into();
+        Some(Self {
+            received: counters.consumer(&label),
+        })
+    }
+
+    /// Counts a message handed over to the consumer.
+    pub(crate) fn received(&self) {
+        self.received.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,116 @@
+//! Runtime control of the instrumentation.
+//!
+//! [`pause`] sheds most of the per-message overhead during load spikes: message logs, latency
+//! samples, task attribution and message spans stop being collected, while sent and received
+//! counts, blocked time and rates keep working since they're plain atomics. [`resume`] turns
+//! full collection back on. The same is available over HTTP with `POST /control/pause` and
+//! `POST /control/resume`, and the current mode is reported by `/summary`.
+//!
+//! Latencies of messages sent before and received after a pause are approximate: their send
+//! timestamps are re-synchronized with the queue when collection resumes.
+//!
+//! [`reset`], or `POST /control/reset`, zeroes all statistics while keeping channels, streams
+//! and sinks registered, to get a clean baseline right before reproducing a bug.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::SCHEMA_VERSION;
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Incremented on every resume, so recorders notice they missed messages while paused.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Incremented on every [`reset`], so log cursors notice that log indexes started over.
+static RESETS: AtomicU64 = AtomicU64::new(0);
+
+/// What the instrumentation collects for every message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CollectionMode {
+    /// Counters, logs, latencies and task attribution
+    #[default]
+    Full,
+    /// Counters only, see [`pause`]
+    Paused,
+}
+
+impl CollectionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Paused => "paused",
+        }
+    }
+}
+
+impl std::fmt::Display for CollectionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Wrapper for the `/control/pause`, `/control/resume` and `/control/reset` JSON responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Mode after the request was applied
+    pub collection_mode: CollectionMode,
+}
+
+/// Stops collecting per-message details, keeping only the counters.
+pub fn pause() {
+    PAUSED.store(true, Ordering::Relaxed);
+}
+
+/// Resumes collecting per-message details after [`pause`].
+pub fn resume() {
+    if PAUSED.swap(false, Ordering::Relaxed) {
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Zeroes counters, rates, latencies and state histories, and drops logged messages.
+///
+/// Channels, streams and sinks stay registered. Messages still queued in a channel count as
+/// sent after the reset, so its queue depth is kept.
+pub fn reset() {
+    crate::flush();
+    RESETS.fetch_add(1, Ordering::Relaxed);
+    crate::reset_all_stats();
+    crate::sinks::reset_sink_stats();
+    crate::retention::reset_closed_total();
+}
+
+/// Current collection mode.
+pub fn collection_mode() -> CollectionMode {
+    if is_paused() {
+        CollectionMode::Paused
+    } else {
+        CollectionMode::Full
+    }
+}
+
+pub(crate) fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+/// Number of times collection resumed after a pause.
+pub(crate) fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// Number of times statistics were reset.
+pub(crate) fn resets() -> u64 {
+    RESETS.load(Ordering::Relaxed)
+}
+
+pub(crate) fn get_control_json() -> ControlJson {
+    ControlJson {
+        schema_version: SCHEMA_VERSION,
+        collection_mode: collection_mode(),
+    }
+}
@@ -0,0 +1,191 @@
+//! End-to-end message correlation across channels.
+//!
+//! Messages implementing [`Correlated`] carry a correlation id. Channels instrumented with
+//! `correlate = true` record it on every send and receive, so a message forwarded through a
+//! pipeline of channels can be followed stage by stage with [`journey`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use channels_console::correlation::{journey, Traced};
+//!
+//! let (tx, rx) = std::sync::mpsc::channel::<Traced<String>>();
+//! let (tx, rx) = channels_console::channel!((tx, rx), label = "parse", correlate = true);
+//! let (tx2, rx2) = std::sync::mpsc::channel::<Traced<usize>>();
+//! let (tx2, rx2) = channels_console::channel!((tx2, rx2), label = "count", correlate = true);
+//!
+//! let msg = Traced::new("hello".to_string());
+//! let correlation_id = msg.correlation_id();
+//! tx.send(msg).unwrap();
+//!
+//! // Transforming the payload keeps the correlation id
+//! let parsed = rx.recv().unwrap().map(|s| s.len());
+//! tx2.send(parsed).unwrap();
+//! rx2.recv().unwrap();
+//!
+//! for hop in journey(correlation_id).unwrap().hops {
+//!     println!("{}: {:?}ns", hop.label, hop.latency_ns);
+//! }
+//! ```
+
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{resolve_label, with_sorted_channel_stats, LogEntry, SCHEMA_VERSION};
+
+/// Extracts the correlation id of a message.
+///
+/// This type is not intended for direct use. Use the `channel!` macro with `correlate = true` instead.
+#[doc(hidden)]
+pub type CorrelationFn<T> = fn(&T) -> Option<u64>;
+
+static CORRELATION_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// Messages that carry a correlation id.
+pub trait Correlated {
+    fn correlation_id(&self) -> Option<u64>;
+}
+
+#[doc(hidden)]
+pub fn correlation_id_of<M: Correlated>(msg: &M) -> Option<u64> {
+    msg.correlation_id()
+}
+
+/// Envelope attaching a correlation id to a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Traced<T> {
+    correlation_id: u64,
+    inner: T,
+}
+
+impl<T> Traced<T> {
+    /// Wraps a message with a new, process-unique correlation id.
+    pub fn new(inner: T) -> Self {
+        Self::with_id(
+            CORRELATION_ID_COUNTER.fetch_add(1, Ordering::Relaxed),
+            inner,
+        )
+    }
+
+    /// Wraps a message with an existing correlation id, e.g. a request id.
+    pub fn with_id(correlation_id: u64, inner: T) -> Self {
+        Self {
+            correlation_id,
+            inner,
+        }
+    }
+
+    pub fn correlation_id(&self) -> u64 {
+        self.correlation_id
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Transforms the payload, keeping the correlation id for the next pipeline stage.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Traced<U> {
+        Traced::with_id(self.correlation_id, f(self.inner))
+    }
+}
+
+impl<T> Correlated for Traced<T> {
+    fn correlation_id(&self) -> Option<u64> {
+        Some(self.correlation_id)
+    }
+}
+
+impl<T> Deref for Traced<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for Traced<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+/// A single channel a correlated message passed through.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JourneyHop {
+    pub channel_id: u64,
+    pub label: String,
+    /// Nanoseconds since start when the message entered the channel
+    pub sent_at: Option<u64>,
+    /// Nanoseconds since start when the message left the channel
+    pub received_at: Option<u64>,
+    /// Time the message spent in this channel
+    pub latency_ns: Option<u64>,
+}
+
+/// Path of a correlated message across channels, ordered by time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journey {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    pub correlation_id: u64,
+    pub hops: Vec<JourneyHop>,
+    /// Time from entering the first channel to leaving the last one
+    pub total_ns: Option<u64>,
+}
+
+/// Returns the journey of a correlated message, or `None` if no channel logged it.
+///
+/// Journeys are rebuilt from channel logs, so only messages within the log limit of each
+/// channel are found.
+pub fn journey(correlation_id: u64) -> Option<Journey> {
+    let mut hops: Vec<JourneyHop> = with_sorted_channel_stats(|channels| {
+        channels
+            .iter()
+            .filter_map(|stats| {
+                let find = |logs: &std::collections::VecDeque<LogEntry>| {
+                    logs.iter()
+                        .rev()
+                        .find(|entry| entry.correlation_id == Some(correlation_id))
+                        .map(|entry| entry.timestamp)
+                };
+                let messages = stats.counters.messages();
+                let sent_at = find(&messages.sent_logs);
+                let received_at = find(&messages.received_logs);
+                drop(messages);
+                if sent_at.is_none() && received_at.is_none() {
+                    return None;
+                }
+
+                Some(JourneyHop {
+                    channel_id: stats.id,
+                    label: resolve_label(stats.source, stats.label.as_deref(), stats.iter),
+                    sent_at,
+                    received_at,
+                    latency_ns: sent_at
+                        .zip(received_at)
+                        .map(|(sent, received)| received.saturating_sub(sent)),
+                })
+            })
+            .collect()
+    });
+
+    if hops.is_empty() {
+        return None;
+    }
+    hops.sort_by_key(|hop| hop.sent_at.or(hop.received_at));
+
+    let first_sent = hops.first().and_then(|hop| hop.sent_at);
+    let last_received = hops.last().and_then(|hop| hop.received_at);
+
+    Some(Journey {
+        schema_version: SCHEMA_VERSION,
+        correlation_id,
+        hops,
+        total_ns: first_sent
+            .zip(last_received)
+            .map(|(sent, received)| received.saturating_sub(sent)),
+    })
+}
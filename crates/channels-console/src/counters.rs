@@ -0,0 +1,441 @@
+//! Per-message bookkeeping of instrumented channels, done in place by the wrappers.
+//!
+//! Sending an event to the collector for every message made its thread, and the unbounded
+//! event queue in front of it, the bottleneck of high-throughput channels. Instead, each channel
+//! shares a [`ChannelCounters`] with its wrappers: message counts and blocked time are atomics,
+//! send timestamps and log entries are kept behind a per-channel lock. The collector folds the
+//! counters into the channel's statistics every tick and on [`flush`](crate::flush), so
+//! events are only sent for created and closed channels.
+//!
+//! While collection is [paused](crate::control::pause), only the atomics are updated. The same
+//! goes for channels keeping no log entries, with latencies
+//! [disabled](crate::ConfigBuilder::disable_latency) and without actors, so their wrappers
+//! never take the lock. Latency tracking is on by default, so with the default config every
+//! send and receive still takes it.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::actors::Actors;
+use crate::config::redact;
+use crate::consumers::ConsumerStats;
+use crate::control;
+use crate::latency::LatencyTracker;
+use crate::producers::ProducerStats;
+use crate::sizes::{self, ErasedSizeFn, SizeFn};
+use crate::{get_log_limit, push_log, ChannelEvent, ChannelEventSender, ChannelOptions, LogEntry};
+
+/// Counters of a channel, updated by its wrappers and read by the collector.
+#[derive(Debug, Default)]
+pub(crate) struct ChannelCounters {
+    sent: AtomicU64,
+    received: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    blocked_ns: AtomicU64,
+    max_blocked_ns: AtomicU64,
+    send_failures_full: AtomicU64,
+    send_failures_closed: AtomicU64,
+    /// Number of log entries kept, declared with `log_limit` (defaults to [`Config::log_limit`](crate::Config))
+    log_limit: Option<usize>,
+    /// Whether logged messages are JSON, declared with `log = json`
+    json_logs: bool,
+    /// Measures messages, declared with `size = true` or `size_with`, see [`sizes`]
+    size_fn: Option<ErasedSizeFn>,
+    /// Whether messages are attributed to the task or thread handling them, see [`actors`](crate::actors)
+    track_actors: bool,
+    /// Whether send to receive latencies are tracked, see [`latency`](crate::latency)
+    latency: bool,
+    /// Resolved label, set by the collector once the channel is registered
+    #[cfg(feature = "tracing")]
+    pub(crate) label: std::sync::OnceLock<String>,
+    /// Whether every message is emitted as a span, see [`trace`](crate::trace)
+    #[cfg(feature = "tracing")]
+    trace_messages: bool,
+    messages: Mutex<Messages>,
+    /// Sender clones wrapped with `sender!`, in order of registration
+    producers: Mutex<Vec<(Arc<str>, Arc<AtomicU64>)>>,
+    /// Receiver clones wrapped with `receiver!`, in order of registration
+    consumers: Mutex<Vec<(Arc<str>, Arc<AtomicU64>)>>,
+}
+
+/// Per-message details that can't be reduced to counters.
+#[derive(Debug, Default)]
+pub(crate) struct Messages {
+    pub(crate) latency: LatencyTracker,
+    pub(crate) sent_logs: VecDeque<LogEntry>,
+    pub(crate) received_logs: VecDeque<LogEntry>,
+    pub(crate) actors: Actors,
+    /// [`control::generation`] when details were last collected
+    generation: u64,
+}
+
+/// Counter values read at one point in time.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CountersSnapshot {
+    pub(crate) sent: u64,
+    pub(crate) received: u64,
+    pub(crate) bytes_sent: u64,
+    pub(crate) bytes_received: u64,
+    pub(crate) blocked: Duration,
+    pub(crate) max_blocked: Duration,
+    pub(crate) send_failures_full: u64,
+    pub(crate) send_failures_closed: u64,
+}
+
+impl ChannelCounters {
+    /// Counters of a channel, with the log and size options declared in its `channel!` macro.
+    pub(crate) fn new(options: &ChannelOptions) -> Self {
+        Self {
+            log_limit: options.log_limit,
+            json_logs: options.json_logs,
+            size_fn: options.size_fn.clone(),
+            latency: crate::config::Config::current().latency,
+            #[cfg(feature = "tracing")]
+            trace_messages: crate::config::Config::current().trace_messages,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> CountersSnapshot {
+        // Messages are received after being sent, so reading `received` first never reports
+        // more received than sent messages
+        let received = self.received.load(Ordering::Acquire);
+        let bytes_received = self.bytes_received.load(Ordering::Acquire);
+        CountersSnapshot {
+            sent: self.sent.load(Ordering::Acquire),
+            received,
+            bytes_sent: self.bytes_sent.load(Ordering::Acquire),
+            bytes_received,
+            blocked: Duration::from_nanos(self.blocked_ns.load(Ordering::Relaxed)),
+            max_blocked: Duration::from_nanos(self.max_blocked_ns.load(Ordering::Relaxed)),
+            send_failures_full: self.send_failures_full.load(Ordering::Relaxed),
+            send_failures_closed: self.send_failures_closed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Whether messages are measured by a size function rather than by their type size
+    pub(crate) fn measures_size(&self) -> bool {
+        self.size_fn.is_some()
+    }
+
+    pub(crate) fn messages(&self) -> MutexGuard<'_, Messages> {
+        self.messages.lock().unwrap()
+    }
+
+    /// Zeroes the counters and drops logged messages, see [`reset`](crate::control::reset).
+    ///
+    /// Messages still queued count as sent after the reset, so the queue depth is kept. The
+    /// `received` swap and `sent` subtraction keep it exact even with concurrent wrappers.
+    pub(crate) fn reset(&self) {
+        let mut messages = self.messages();
+        let received = self.received.swap(0, Ordering::AcqRel);
+        self.sent.fetch_sub(received, Ordering::AcqRel);
+        let bytes_received = self.bytes_received.swap(0, Ordering::AcqRel);
+        self.bytes_sent.fetch_sub(bytes_received, Ordering::AcqRel);
+        self.blocked_ns.store(0, Ordering::Relaxed);
+        self.max_blocked_ns.store(0, Ordering::Relaxed);
+        self.send_failures_full.store(0, Ordering::Relaxed);
+        self.send_failures_closed.store(0, Ordering::Relaxed);
+        // Send timestamps of the queued messages are kept for their latencies
+        messages.latency.histogram = Default::default();
+        messages.sent_logs.clear();
+        messages.received_logs.clear();
+        messages.actors = Actors::default();
+        for (_, sent) in self.producers.lock().unwrap().iter() {
+            sent.store(0, Ordering::Relaxed);
+        }
+        for (_, received) in self.consumers.lock().unwrap().iter() {
+            received.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Re-synchronizes pending send timestamps with the messages in flight after a pause,
+    /// in which sends and receives were counted without being timestamped.
+    fn resync(&self, messages: &mut Messages, at: Instant) {
+        let generation = control::generation();
+        if messages.generation == generation {
+            return;
+        }
+        messages.generation = generation;
+        let received = self.received.load(Ordering::Acquire);
+        let in_flight = self.sent.load(Ordering::Acquire).saturating_sub(received);
+        messages.latency.resync(in_flight as usize, at);
+    }
+
+    /// Counter of the producer with the given label, registering it on first use.
+    pub(crate) fn producer(&self, label: &Arc<str>) -> Arc<AtomicU64> {
+        let mut producers = self.producers.lock().unwrap();
+        match producers.iter().find(|(l, _)| l == label) {
+            Some((_, sent)) => Arc::clone(sent),
+            None => {
+                let sent = Arc::new(AtomicU64::new(0));
+                producers.push((Arc::clone(label), Arc::clone(&sent)));
+                sent
+            }
+        }
+    }
+
+    pub(crate) fn producers(&self) -> Vec<ProducerStats> {
+        self.producers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(label, sent)| ProducerStats {
+                label: label.to_string(),
+                sent_count: sent.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Counter of the consumer with the given label, registering it on first use.
+    #[cfg_attr(not(any(feature = "crossbeam", std_mpmc)), allow(dead_code))]
+    pub(crate) fn consumer(&self, label: &Arc<str>) -> Arc<AtomicU64> {
+        let mut consumers = self.consumers.lock().unwrap();
+        match consumers.iter().find(|(l, _)| l == label) {
+            Some((_, received)) => Arc::clone(received),
+            None => {
+                let received = Arc::new(AtomicU64::new(0));
+                consumers.push((Arc::clone(label), Arc::clone(&received)));
+                received
+            }
+        }
+    }
+
+    pub(crate) fn consumers(&self) -> Vec<ConsumerStats> {
+        self.consumers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(label, received)| ConsumerStats {
+                label: label.to_string(),
+                received_count: received.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    fn log_limit(&self) -> usize {
+        self.log_limit.unwrap_or_else(get_log_limit)
+    }
+
+    /// Whether messages need more than the atomics: log entries, latencies or actors.
+    fn keeps_details(&self) -> bool {
+        self.latency || self.track_actors || self.log_limit() > 0
+    }
+
+    /// Redacted log of a message, with its parsed value for `log = json` channels. Redaction
+    /// patterns that break the JSON leave only the string.
+    fn prepare_log(&self, log: Option<String>) -> (Option<String>, Option<serde_json::Value>) {
+        let message = log.map(redact);
+        let json = match &message {
+            Some(message) if self.json_logs => serde_json::from_str(message).ok(),
+            _ => None,
+        };
+        (message, json)
+    }
+}
+
+/// Records the messages of one channel, held by each of its wrappers.
+#[derive(Clone)]
+pub(crate) struct ChannelRecorder {
+    id: u64,
+    counters: Arc<ChannelCounters>,
+    stats_tx: ChannelEventSender,
+}
+
+impl ChannelRecorder {
+    pub(crate) fn new(id: u64, options: &ChannelOptions, stats_tx: &ChannelEventSender) -> Self {
+        Self {
+            id,
+            counters: Arc::new(ChannelCounters::new(options)),
+            stats_tx: stats_tx.clone(),
+        }
+    }
+
+    /// Recorder of a channel whose wrappers run in the sending and receiving code, also
+    /// attributing messages to the task or thread handling them.
+    #[allow(dead_code)]
+    pub(crate) fn with_actors(
+        id: u64,
+        options: &ChannelOptions,
+        stats_tx: &ChannelEventSender,
+    ) -> Self {
+        Self {
+            id,
+            counters: Arc::new(ChannelCounters {
+                track_actors: true,
+                ..ChannelCounters::new(options)
+            }),
+            stats_tx: stats_tx.clone(),
+        }
+    }
+
+    pub(crate) fn is_disabled(&self) -> bool {
+        self.stats_tx.is_disabled()
+    }
+
+    /// Shared with the collector through [`ChannelEvent::Created`].
+    pub(crate) fn counters(&self) -> Arc<ChannelCounters> {
+        Arc::clone(&self.counters)
+    }
+
+    /// Size function of the channel, for wrappers measuring messages themselves.
+    pub(crate) fn size_fn<T: 'static>(&self) -> Option<SizeFn<T>> {
+        sizes::downcast(self.counters.size_fn.as_ref())
+    }
+
+    /// Size of a message in bytes, see [`sizes`].
+    pub(crate) fn message_size<T: 'static>(&self, msg: &T) -> u64 {
+        sizes::measure(self.size_fn(), msg)
+    }
+
+    pub(crate) fn sent(
+        &self,
+        log: Option<String>,
+        correlation_id: Option<u64>,
+        bytes: u64,
+        blocked: Duration,
+        timestamp: Instant,
+    ) {
+        if self.is_disabled() {
+            return;
+        }
+        let blocked_ns = blocked.as_nanos() as u64;
+        self.counters
+            .blocked_ns
+            .fetch_add(blocked_ns, Ordering::Relaxed);
+        self.counters
+            .max_blocked_ns
+            .fetch_max(blocked_ns, Ordering::Relaxed);
+        // Counted before the message, so a snapshot never sees its receive without its bytes
+        self.counters.bytes_sent.fetch_add(bytes, Ordering::Release);
+        if control::is_paused() {
+            self.counters.sent.fetch_add(1, Ordering::Release);
+            self.counted();
+            return;
+        }
+        if !self.counters.keeps_details() {
+            #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+            let index = self.counters.sent.fetch_add(1, Ordering::Release) + 1;
+            #[cfg(feature = "tracing")]
+            self.traced("sent", index);
+            self.counted();
+            return;
+        }
+
+        let (message, json) = self.counters.prepare_log(log);
+        let limit = self.counters.log_limit();
+        #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+        let index = {
+            // Counted under the lock, so log entries are ordered by index
+            let mut messages = self.counters.messages();
+            self.counters.resync(&mut messages, timestamp);
+            let index = self.counters.sent.fetch_add(1, Ordering::Release) + 1;
+            messages.latency.on_sent(timestamp);
+            if self.counters.track_actors {
+                messages.actors.on_sent();
+            }
+            let entry = LogEntry::new(index, timestamp, message, correlation_id).with_json(json);
+            push_log(&mut messages.sent_logs, entry, limit);
+            index
+        };
+        #[cfg(feature = "tracing")]
+        self.traced("sent", index);
+        self.counted();
+    }
+
+    pub(crate) fn received(
+        &self,
+        log: Option<String>,
+        correlation_id: Option<u64>,
+        bytes: u64,
+        timestamp: Instant,
+    ) {
+        if self.is_disabled() {
+            return;
+        }
+        self.counters
+            .bytes_received
+            .fetch_add(bytes, Ordering::Release);
+        if control::is_paused() {
+            self.counters.received.fetch_add(1, Ordering::Release);
+            self.counted();
+            return;
+        }
+        if !self.counters.keeps_details() {
+            #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+            let index = self.counters.received.fetch_add(1, Ordering::Release) + 1;
+            #[cfg(feature = "tracing")]
+            self.traced("received", index);
+            self.counted();
+            return;
+        }
+        let (message, json) = self.counters.prepare_log(log);
+        let limit = self.counters.log_limit();
+        #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+        let index = {
+            let mut messages = self.counters.messages();
+            self.counters.resync(&mut messages, timestamp);
+            let index = self.counters.received.fetch_add(1, Ordering::Release) + 1;
+            messages.latency.on_received(timestamp);
+            if self.counters.track_actors {
+                messages.actors.on_received();
+            }
+            let entry = LogEntry::new(index, timestamp, message, correlation_id).with_json(json);
+            push_log(&mut messages.received_logs, entry, limit);
+            index
+        };
+        #[cfg(feature = "tracing")]
+        self.traced("received", index);
+        self.counted();
+    }
+
+    /// A message was rejected because the channel was full, e.g. by `try_send`.
+    #[allow(dead_code)]
+    pub(crate) fn send_rejected_full(&self) {
+        if self.is_disabled() {
+            return;
+        }
+        self.counters
+            .send_failures_full
+            .fetch_add(1, Ordering::Relaxed);
+        self.counted();
+    }
+
+    /// A message was rejected, or dropped by a forwarder, because the channel was closed.
+    pub(crate) fn send_rejected_closed(&self) {
+        if self.is_disabled() {
+            return;
+        }
+        self.counters
+            .send_failures_closed
+            .fetch_add(1, Ordering::Relaxed);
+        self.counted();
+    }
+
+    pub(crate) fn closed(&self) {
+        let _ = self.stats_tx.send(ChannelEvent::Closed { id: self.id });
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn notified(&self) {
+        let _ = self.stats_tx.send(ChannelEvent::Notified { id: self.id });
+    }
+
+    #[cfg(feature = "tracing")]
+    fn traced(&self, direction: &'static str, index: u64) {
+        if self.counters.trace_messages {
+            let label = self.counters.label.get().map(String::as_str);
+            crate::trace::message(label, self.id, direction, index);
+        }
+    }
+
+    /// In synchronous mode counters are folded right away, instead of on the collector tick.
+    fn counted(&self) {
+        if self.stats_tx.is_synchronous() {
+            let _ = self.stats_tx.send(ChannelEvent::Counted { id: self.id });
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Incremental channel statistics for pollers.
+//!
+//! `/channels/delta?since_ns=<current_elapsed_ns of the previous response>` returns only the
+//! channels whose counters or state changed since then, with their full statistics and how
+//! much their counters moved. Channels that had traffic within the longest rate window are
+//! included too, as their rates keep decaying. Pollers like the console merge the response
+//! into the channels they already have with [`ChannelsDeltaJson::apply`], instead of
+//! re-transferring every channel on each refresh.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::labels::matches_prefix;
+use crate::rates::MAX_WINDOW;
+use crate::retention::{closed_total, ClosedTotal};
+use crate::samples::ChannelSample;
+use crate::tags::matches_tag;
+use crate::{
+    elapsed_ns, resolve_label, with_sorted_channel_stats, ChannelStats, ChannelsJson,
+    SerializableChannelStats, SCHEMA_VERSION,
+};
+
+/// How long counter changes are kept per channel to compute deltas from. The newest change
+/// older than that is kept too, as the baseline of a `since_ns` at the start of the window.
+const CHECKPOINTS_WINDOW: Duration = Duration::from_secs(30);
+
+/// Max number of counter changes kept per channel. Counters are synced every collector tick,
+/// so this only cuts the window short while statistics are flushed more often.
+const CHECKPOINTS_LIMIT: usize = 256;
+
+/// How much the counters of a channel changed since `since_ns`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CounterDeltas {
+    pub sent_delta: i64,
+    pub received_delta: i64,
+    pub queued_delta: i64,
+    pub queued_bytes_delta: i64,
+}
+
+/// Current statistics of a changed channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelDelta {
+    #[serde(flatten)]
+    pub stats: SerializableChannelStats,
+    /// `None` if the counters at `since_ns` are unknown, as it's more than 30 seconds ago or the
+    /// statistics were reset since then. Channels created since then count from zero.
+    pub deltas: Option<CounterDeltas>,
+}
+
+/// Wrapper for the `/channels/delta` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelsDeltaJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Current elapsed time since program start in nanoseconds, to pass as `since_ns` next
+    pub current_elapsed_ns: u64,
+    pub since_ns: u64,
+    /// Channels changed since `since_ns`
+    pub channels: Vec<ChannelDelta>,
+    /// Ids of all tracked channels, so pollers can drop evicted ones
+    pub channel_ids: Vec<u64>,
+    /// Counters of closed channels evicted from the statistics, see [`retention`](crate::retention)
+    #[serde(default)]
+    pub closed_total: ClosedTotal,
+}
+
+impl ChannelsDeltaJson {
+    /// Updates channels fetched earlier, e.g. from `/channels`, to the state of this response.
+    /// New channels are appended.
+    pub fn apply(self, channels: &mut ChannelsJson) {
+        let ids: HashSet<u64> = self.channel_ids.into_iter().collect();
+        channels
+            .channels
+            .retain(|channel| ids.contains(&channel.id));
+        for ChannelDelta { stats, .. } in self.channels {
+            match channels
+                .channels
+                .iter_mut()
+                .find(|channel| channel.id == stats.id)
+            {
+                Some(channel) => *channel = stats,
+                None => channels.channels.push(stats),
+            }
+        }
+        channels.schema_version = self.schema_version;
+        channels.current_elapsed_ns = self.current_elapsed_ns;
+        channels.closed_total = self.closed_total;
+    }
+}
+
+/// Records the counters of a channel after they changed, dropping the ones no longer needed.
+pub(crate) fn record_checkpoint(stats: &mut ChannelStats, elapsed_ns: u64) {
+    let window_start = elapsed_ns.saturating_sub(CHECKPOINTS_WINDOW.as_nanos() as u64);
+    while stats.checkpoints.len() >= CHECKPOINTS_LIMIT
+        || stats
+            .checkpoints
+            .get(1)
+            .is_some_and(|next| next.elapsed_ns <= window_start)
+    {
+        stats.checkpoints.pop_front();
+    }
+    stats.checkpoints.push_back(ChannelSample {
+        elapsed_ns,
+        queued: stats.queued(),
+        queued_bytes: stats.queued_bytes(),
+        sent_count: stats.sent_count,
+        received_count: stats.received_count,
+    });
+}
+
+/// Counter changes of a channel since `since_ns`, if its counters back then are still known.
+fn deltas(stats: &ChannelStats, since_ns: u64) -> Option<CounterDeltas> {
+    let baseline = stats
+        .checkpoints
+        .iter()
+        .rev()
+        .find(|checkpoint| checkpoint.elapsed_ns <= since_ns)
+        // Channels created since then had nothing to count before
+        .or_else(|| {
+            stats
+                .checkpoints
+                .front()
+                .filter(|checkpoint| checkpoint.elapsed_ns == stats.created_ns)
+        })?;
+    let queued = stats.queued();
+    Some(CounterDeltas {
+        sent_delta: stats.sent_count as i64 - baseline.sent_count as i64,
+        received_delta: stats.received_count as i64 - baseline.received_count as i64,
+        queued_delta: queued as i64 - baseline.queued as i64,
+        queued_bytes_delta: stats.queued_bytes() as i64 - baseline.queued_bytes as i64,
+    })
+}
+
+/// Channels changed since `since_ns`, limited to labels under `prefix` and channels tagged
+/// with `tag` if given.
+pub(crate) fn get_channels_delta_json(
+    since_ns: u64,
+    prefix: Option<&str>,
+    tag: Option<&str>,
+) -> ChannelsDeltaJson {
+    // Taken before reading the statistics, so changes applied meanwhile are served again next
+    // time rather than missed
+    let current_elapsed_ns = elapsed_ns();
+    let settled_ns = since_ns.saturating_sub(MAX_WINDOW.as_nanos() as u64);
+
+    let (channels, channel_ids) = with_sorted_channel_stats(|stats| {
+        let mut channels = Vec::new();
+        let mut channel_ids = Vec::new();
+        for channel_stats in stats {
+            let label = || {
+                resolve_label(
+                    channel_stats.source,
+                    channel_stats.label.as_deref(),
+                    channel_stats.iter,
+                )
+            };
+            if prefix.is_some_and(|prefix| !matches_prefix(&label(), prefix))
+                || tag.is_some_and(|tag| !matches_tag(&channel_stats.tags, tag))
+            {
+                continue;
+            }
+            channel_ids.push(channel_stats.id);
+            let changed = channel_stats.changed_at_ns > since_ns;
+            let rates_moving = channel_stats.changed_at_ns > settled_ns
+                && (channel_stats.sent_count > 0 || channel_stats.received_count > 0);
+            if changed || rates_moving {
+                channels.push(ChannelDelta {
+                    stats: SerializableChannelStats::from(*channel_stats),
+                    deltas: deltas(channel_stats, since_ns),
+                });
+            }
+        }
+        (channels, channel_ids)
+    });
+
+    ChannelsDeltaJson {
+        schema_version: SCHEMA_VERSION,
+        current_elapsed_ns,
+        since_ns,
+        channels,
+        channel_ids,
+        closed_total: closed_total(),
+    }
+}
@@ -0,0 +1,146 @@
+//! Differences between two statistics snapshots, e.g. before and after a load test.
+//!
+//! Channels and streams are matched by label, so snapshots of different runs of the same
+//! program can be compared. Used by the `channels-console diff` command.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use channels_console::diff::SnapshotDiff;
+//!
+//! let before = channels_console::snapshot();
+//! // Run the load test
+//! let after = channels_console::snapshot();
+//!
+//! for channel in SnapshotDiff::between(&before, &after).changed_channels {
+//!     println!("{}: {:+} sent", channel.label, channel.sent_delta);
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{ChannelState, CombinedJson};
+
+/// Changes of a channel present in both snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelDiff {
+    pub label: String,
+    pub sent_delta: i64,
+    pub received_delta: i64,
+    pub queued_delta: i64,
+    pub queued_bytes_delta: i64,
+    /// States before and after, if the state changed
+    pub state_change: Option<(ChannelState, ChannelState)>,
+}
+
+/// Changes of a stream present in both snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamDiff {
+    pub label: String,
+    pub yielded_delta: i64,
+    /// States before and after, if the state changed
+    pub state_change: Option<(ChannelState, ChannelState)>,
+}
+
+/// Channels and streams that appeared, disappeared or changed between two snapshots.
+/// Entries without any change are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub added_channels: Vec<String>,
+    pub removed_channels: Vec<String>,
+    pub changed_channels: Vec<ChannelDiff>,
+    pub added_streams: Vec<String>,
+    pub removed_streams: Vec<String>,
+    pub changed_streams: Vec<StreamDiff>,
+}
+
+impl SnapshotDiff {
+    /// Compares `after` to `before`. Entries keep the order of the snapshot they're taken from.
+    pub fn between(before: &CombinedJson, after: &CombinedJson) -> Self {
+        let mut diff = Self::default();
+
+        let old_channels: HashMap<&str, _> = before
+            .channels
+            .iter()
+            .map(|channel| (channel.label.as_str(), channel))
+            .collect();
+        let new_channels: HashMap<&str, _> = after
+            .channels
+            .iter()
+            .map(|channel| (channel.label.as_str(), channel))
+            .collect();
+
+        for channel in &after.channels {
+            let Some(old) = old_channels.get(channel.label.as_str()) else {
+                diff.added_channels.push(channel.label.clone());
+                continue;
+            };
+            let change = ChannelDiff {
+                label: channel.label.clone(),
+                sent_delta: delta(old.sent_count, channel.sent_count),
+                received_delta: delta(old.received_count, channel.received_count),
+                queued_delta: delta(old.queued, channel.queued),
+                queued_bytes_delta: delta(old.queued_bytes, channel.queued_bytes),
+                state_change: (old.state != channel.state).then_some((old.state, channel.state)),
+            };
+            if change.sent_delta != 0
+                || change.received_delta != 0
+                || change.queued_delta != 0
+                || change.queued_bytes_delta != 0
+                || change.state_change.is_some()
+            {
+                diff.changed_channels.push(change);
+            }
+        }
+        diff.removed_channels = before
+            .channels
+            .iter()
+            .filter(|channel| !new_channels.contains_key(channel.label.as_str()))
+            .map(|channel| channel.label.clone())
+            .collect();
+
+        let old_streams: HashMap<&str, _> = before
+            .streams
+            .iter()
+            .map(|stream| (stream.label.as_str(), stream))
+            .collect();
+        let new_streams: HashMap<&str, _> = after
+            .streams
+            .iter()
+            .map(|stream| (stream.label.as_str(), stream))
+            .collect();
+
+        for stream in &after.streams {
+            let Some(old) = old_streams.get(stream.label.as_str()) else {
+                diff.added_streams.push(stream.label.clone());
+                continue;
+            };
+            let change = StreamDiff {
+                label: stream.label.clone(),
+                yielded_delta: delta(old.items_yielded, stream.items_yielded),
+                state_change: (old.state != stream.state).then_some((old.state, stream.state)),
+            };
+            if change.yielded_delta != 0 || change.state_change.is_some() {
+                diff.changed_streams.push(change);
+            }
+        }
+        diff.removed_streams = before
+            .streams
+            .iter()
+            .filter(|stream| !new_streams.contains_key(stream.label.as_str()))
+            .map(|stream| stream.label.clone())
+            .collect();
+
+        diff
+    }
+
+    /// Whether both snapshots have the same channels and streams, with the same statistics.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn delta(before: u64, after: u64) -> i64 {
+    after as i64 - before as i64
+}
@@ -0,0 +1,295 @@
+//! Forwarders of proxied channels, shared by many channels.
+//!
+//! Crossbeam channels are always forwarded by shared threads, each waiting on the pending
+//! operations of many channels with a crossbeam [`Select`](crossbeam_channel::Select), so
+//! instrumenting hundreds of them doesn't cost hundreds of threads. There are
+//! [`DEFAULT_THREAD_WORKERS`] of them, or as many as
+//! [`forwarder_workers`](crate::ConfigBuilder::forwarder_workers) if set. A busy channel can't
+//! starve the others on its thread, as each turn forwards at most `FORWARD_BATCH` messages,
+//! and a forwarder panicking in a user closure only closes its own channel.
+//!
+//! Tokio and futures channels get their own pair of forwarder tasks by default. With
+//! [`forwarder_workers`](crate::ConfigBuilder::forwarder_workers) set, they are instead driven by
+//! that many Tokio tasks, each polling the forwarder futures of many channels.
+//!
+//! Workers are assigned round-robin. `std::sync::mpsc` channels can't be waited on together,
+//! so they keep two dedicated threads per channel, blocked until a message arrives.
+//!
+//! Forwarder tasks run on a private multi-threaded Tokio runtime, or with
+//! [`host_runtime`](crate::ConfigBuilder::host_runtime) on the runtime the channel is
+//! instrumented in.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::LazyLock;
+
+use crate::config::Config;
+
+static NEXT_WORKER: AtomicUsize = AtomicUsize::new(0);
+
+fn next_worker<W>(workers: &[W]) -> &W {
+    &workers[NEXT_WORKER.fetch_add(1, Ordering::Relaxed) % workers.len()]
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(feature = "tokio", feature = "futures"))] {
+        use futures_util::future::BoxFuture;
+        use futures_util::stream::{FuturesUnordered, StreamExt};
+        use std::future::Future;
+        use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+        use crate::RT;
+
+        static TASK_WORKERS: LazyLock<Vec<UnboundedSender<BoxFuture<'static, ()>>>> =
+            LazyLock::new(|| {
+                (0..Config::current().forwarder_workers)
+                    .map(|_| {
+                        let (tx, rx) = unbounded_channel();
+                        spawn_on_runtime(run_task_worker(rx));
+                        tx
+                    })
+                    .collect()
+            });
+
+        /// Runs a forwarder on a shared worker task, or on its own task if no workers are
+        /// configured.
+        pub(crate) fn spawn_task(forwarder: impl Future<Output = ()> + Send + 'static) {
+            if TASK_WORKERS.is_empty() {
+                spawn_on_runtime(forwarder);
+            } else {
+                let _ = next_worker(&TASK_WORKERS).send(Box::pin(forwarder));
+            }
+        }
+
+        /// Spawns on the caller's runtime with
+        /// [`host_runtime`](crate::ConfigBuilder::host_runtime), on the private one otherwise.
+        fn spawn_on_runtime(future: impl Future<Output = ()> + Send + 'static) {
+            if Config::current().host_runtime {
+                if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                    handle.spawn(future);
+                    return;
+                }
+            }
+            RT.spawn(future);
+        }
+
+        async fn run_task_worker(mut new_forwarders: UnboundedReceiver<BoxFuture<'static, ()>>) {
+            let mut forwarders = FuturesUnordered::new();
+            loop {
+                tokio::select! {
+                    forwarder = new_forwarders.recv() => match forwarder {
+                        Some(forwarder) => forwarders.push(forwarder),
+                        None => break,
+                    },
+                    Some(()) = forwarders.next(), if !forwarders.is_empty() => {}
+                }
+            }
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "crossbeam")] {
+        use crossbeam_channel::{Receiver, Select, Sender, TryRecvError, TrySendError};
+        use std::time::Instant;
+
+        /// Forwarders of a channel driven by a shared worker thread.
+        pub(crate) trait PooledForwarder: Send {
+            /// Forwards up to [`FORWARD_BATCH`] messages per direction, or until it would
+            /// block. Returns `false` once done.
+            fn forward(&mut self) -> bool;
+
+            /// Channel ends [`forward`](Self::forward) waits on, in their current state.
+            fn endpoints(&self) -> Vec<Box<dyn Endpoint>>;
+
+            /// Changes whenever [`endpoints`](Self::endpoints) would return different ones.
+            fn interest(&self) -> u8;
+
+            /// Called instead of [`forward`](Self::forward) returning `false` when it panicked.
+            fn failed(&mut self) {}
+        }
+
+        /// Channel end a worker thread waits on, owned so the worker can keep its
+        /// [`Select`] while forwarding.
+        pub(crate) trait Endpoint {
+            fn register<'a>(&'a self, select: &mut Select<'a>);
+        }
+
+        impl<T> Endpoint for Receiver<T> {
+            fn register<'a>(&'a self, select: &mut Select<'a>) {
+                select.recv(self);
+            }
+        }
+
+        impl<T> Endpoint for Sender<T> {
+            fn register<'a>(&'a self, select: &mut Select<'a>) {
+                select.send(self);
+            }
+        }
+
+        /// Messages a forwarder moves per direction before the worker turns to other
+        /// channels, so a busy channel doesn't starve the others on its worker.
+        pub(crate) const FORWARD_BATCH: usize = 64;
+
+        /// Worker threads forwarding crossbeam channels unless
+        /// [`forwarder_workers`](crate::ConfigBuilder::forwarder_workers) is set.
+        pub(crate) const DEFAULT_THREAD_WORKERS: usize = 2;
+
+        static THREAD_WORKERS: LazyLock<Vec<Sender<Box<dyn PooledForwarder>>>> =
+            LazyLock::new(|| {
+                let workers = match Config::current().forwarder_workers {
+                    0 => DEFAULT_THREAD_WORKERS,
+                    workers => workers,
+                };
+                (0..workers)
+                    .map(|idx| {
+                        let (tx, rx) = crossbeam_channel::unbounded();
+                        std::thread::Builder::new()
+                            .name(format!("channels-console-forwarder-{}", idx))
+                            .spawn(move || run_thread_worker(rx))
+                            .expect("Failed to spawn forwarder thread");
+                        tx
+                    })
+                    .collect()
+            });
+
+        /// Hands a forwarder to a shared worker thread.
+        pub(crate) fn spawn_pooled(forwarder: impl PooledForwarder + 'static) {
+            let _ = next_worker(&THREAD_WORKERS).send(Box::new(forwarder));
+        }
+
+        fn run_thread_worker(new_forwarders: Receiver<Box<dyn PooledForwarder>>) {
+            let mut forwarders: Vec<Box<dyn PooledForwarder>> = Vec::new();
+            loop {
+                forwarders.extend(new_forwarders.try_iter());
+                forwarders.retain_mut(|forwarder| forward_guarded(forwarder.as_mut()));
+
+                // The select is only rebuilt when the set of forwarders or what they wait
+                // on changes, and only the forwarder owning the ready operation runs
+                let endpoints: Vec<(usize, Box<dyn Endpoint>)> = forwarders
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(idx, forwarder)| {
+                        forwarder.endpoints().into_iter().map(move |e| (idx, e))
+                    })
+                    .collect();
+                let mut select = Select::new();
+                // Operation 0 waits for new forwarders, the others belong to a forwarder
+                select.recv(&new_forwarders);
+                let mut owners = vec![None];
+                for (idx, endpoint) in &endpoints {
+                    endpoint.register(&mut select);
+                    owners.push(Some(*idx));
+                }
+
+                while let Some(idx) = owners[select.ready()] {
+                    let forwarder = &mut forwarders[idx];
+                    let interest = forwarder.interest();
+                    if !forward_guarded(forwarder.as_mut()) || forwarder.interest() != interest {
+                        break;
+                    }
+                }
+            }
+        }
+
+        /// Runs [`PooledForwarder::forward`], treating a panic, e.g. in a `log_with` closure,
+        /// as the end of that forwarder only, so the other channels on the worker keep flowing.
+        fn forward_guarded(forwarder: &mut dyn PooledForwarder) -> bool {
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| forwarder.forward())) {
+                Ok(active) => active,
+                Err(_) => {
+                    eprintln!("[channels-console] Forwarder panicked, closing its channel");
+                    forwarder.failed();
+                    false
+                }
+            }
+        }
+
+        /// Outcome of [`ForwardPath::forward_one`].
+        pub(crate) enum Forwarded {
+            Message {
+                log: Option<String>,
+                correlation_id: Option<u64>,
+                /// Size of the message, see [`sizes`](crate::sizes)
+                bytes: u64,
+                /// When the message started waiting for a full destination
+                blocked_since: Option<Instant>,
+            },
+            WouldBlock,
+            SourceClosed,
+            DestinationClosed,
+        }
+
+        /// Message waiting for a full destination, with its log, correlation id, size and the
+        /// time it started waiting
+        type Pending<T> = (T, Option<String>, Option<u64>, u64, Instant);
+
+        /// One direction of a pooled proxy, moving messages without blocking.
+        pub(crate) struct ForwardPath<T> {
+            from: Receiver<T>,
+            to: Sender<T>,
+            pending: Option<Pending<T>>,
+        }
+
+        impl<T> ForwardPath<T> {
+            pub(crate) fn new(from: Receiver<T>, to: Sender<T>) -> Self {
+                Self {
+                    from,
+                    to,
+                    pending: None,
+                }
+            }
+
+            /// Forwards a single message, describing it with its log, correlation id and size
+            /// once received.
+            pub(crate) fn forward_one(
+                &mut self,
+                describe: impl FnOnce(&T) -> (Option<String>, Option<u64>, u64),
+            ) -> Forwarded {
+                let (msg, log, correlation_id, bytes, blocked_since) = match self.pending.take() {
+                    Some((msg, log, correlation_id, bytes, since)) => {
+                        (msg, log, correlation_id, bytes, Some(since))
+                    }
+                    None => match self.from.try_recv() {
+                        Ok(msg) => {
+                            let (log, correlation_id, bytes) = describe(&msg);
+                            (msg, log, correlation_id, bytes, None)
+                        }
+                        Err(TryRecvError::Empty) => return Forwarded::WouldBlock,
+                        Err(TryRecvError::Disconnected) => return Forwarded::SourceClosed,
+                    },
+                };
+                match self.to.try_send(msg) {
+                    Ok(()) => Forwarded::Message {
+                        log,
+                        correlation_id,
+                        bytes,
+                        blocked_since,
+                    },
+                    Err(TrySendError::Full(msg)) => {
+                        let since = blocked_since.unwrap_or_else(crate::now);
+                        self.pending = Some((msg, log, correlation_id, bytes, since));
+                        Forwarded::WouldBlock
+                    }
+                    Err(TrySendError::Disconnected(_)) => Forwarded::DestinationClosed,
+                }
+            }
+
+            /// Channel end the path waits on: the destination while a message is pending,
+            /// the source otherwise.
+            pub(crate) fn endpoint(&self) -> Box<dyn Endpoint>
+            where
+                T: 'static,
+            {
+                match self.pending {
+                    Some(_) => Box::new(self.to.clone()),
+                    None => Box::new(self.from.clone()),
+                }
+            }
+
+            /// Changes whenever [`endpoint`](Self::endpoint) would.
+            pub(crate) fn interest(&self) -> u8 {
+                1 + self.pending.is_some() as u8
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Grouping of channels created at the same call site.
+//!
+//! Services spawning a channel per connection or task create many channels from a single
+//! `channel!` call, told apart only by their `iter`. Grouping them by `source` sums their
+//! counters into one entry. The `/channels?group_by=source` endpoint serves the groups.
+//!
+//! Channels can also be grouped by their [`tags`](crate::tags) with `/channels?group_by=tag`.
+//! A channel with several tags counts towards each of their groups.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ChannelsJson, SerializableChannelStats};
+
+/// Aggregated statistics of channels created at the same source location.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceGroup {
+    pub source: String,
+    /// Label of the channels, without the `-N` suffix of channels created in a loop
+    pub label: String,
+    pub channels: usize,
+    pub sent_count: u64,
+    pub received_count: u64,
+    pub queued: u64,
+    /// Largest number of messages queued in a single channel of the group
+    pub max_queued: u64,
+    pub queued_bytes: u64,
+    pub channel_ids: Vec<u64>,
+}
+
+/// Wrapper for the `/channels?group_by=source` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceGroupsJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`](crate::SCHEMA_VERSION)
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Current elapsed time since program start in nanoseconds
+    pub current_elapsed_ns: u64,
+    pub groups: Vec<SourceGroup>,
+}
+
+impl From<&ChannelsJson> for SourceGroupsJson {
+    fn from(channels: &ChannelsJson) -> Self {
+        Self {
+            schema_version: channels.schema_version,
+            current_elapsed_ns: channels.current_elapsed_ns,
+            groups: group_by_source(&channels.channels),
+        }
+    }
+}
+
+/// Groups channels by their source location, sorted by label.
+pub fn group_by_source(stats: &[SerializableChannelStats]) -> Vec<SourceGroup> {
+    let mut groups: Vec<SourceGroup> = Vec::new();
+    for channel in stats {
+        let group = match groups
+            .iter()
+            .position(|group| group.source == channel.source)
+        {
+            Some(idx) => &mut groups[idx],
+            None => {
+                groups.push(SourceGroup {
+                    source: channel.source.clone(),
+                    label: base_label(channel).to_string(),
+                    ..Default::default()
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+        group.channels += 1;
+        group.sent_count += channel.sent_count;
+        group.received_count += channel.received_count;
+        group.queued += channel.queued;
+        group.max_queued = group.max_queued.max(channel.queued);
+        group.queued_bytes += channel.queued_bytes;
+        group.channel_ids.push(channel.id);
+    }
+    groups.sort_by(|a, b| a.label.cmp(&b.label));
+    groups
+}
+
+/// Aggregated statistics of channels sharing a tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagGroup {
+    pub tag: String,
+    pub channels: usize,
+    pub sent_count: u64,
+    pub received_count: u64,
+    pub queued: u64,
+    /// Largest number of messages queued in a single channel of the group
+    pub max_queued: u64,
+    pub queued_bytes: u64,
+    pub channel_ids: Vec<u64>,
+}
+
+/// Wrapper for the `/channels?group_by=tag` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagGroupsJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`](crate::SCHEMA_VERSION)
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Current elapsed time since program start in nanoseconds
+    pub current_elapsed_ns: u64,
+    pub groups: Vec<TagGroup>,
+}
+
+impl From<&ChannelsJson> for TagGroupsJson {
+    fn from(channels: &ChannelsJson) -> Self {
+        Self {
+            schema_version: channels.schema_version,
+            current_elapsed_ns: channels.current_elapsed_ns,
+            groups: group_by_tag(&channels.channels),
+        }
+    }
+}
+
+/// Groups channels by their tags, sorted by tag. Untagged channels aren't in any group.
+pub fn group_by_tag(stats: &[SerializableChannelStats]) -> Vec<TagGroup> {
+    let mut groups: Vec<TagGroup> = Vec::new();
+    for channel in stats {
+        for tag in &channel.tags {
+            let group = match groups.iter().position(|group| group.tag == *tag) {
+                Some(idx) => &mut groups[idx],
+                None => {
+                    groups.push(TagGroup {
+                        tag: tag.clone(),
+                        ..Default::default()
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+            group.channels += 1;
+            group.sent_count += channel.sent_count;
+            group.received_count += channel.received_count;
+            group.queued += channel.queued;
+            group.max_queued = group.max_queued.max(channel.queued);
+            group.queued_bytes += channel.queued_bytes;
+            group.channel_ids.push(channel.id);
+        }
+    }
+    groups.sort_by(|a, b| a.tag.cmp(&b.tag));
+    groups
+}
+
+/// Strips the `-N` suffix added to labels of channels created more than once.
+fn base_label(channel: &SerializableChannelStats) -> &str {
+    if channel.iter == 0 {
+        return &channel.label;
+    }
+    channel
+        .label
+        .strip_suffix(&format!("-{}", channel.iter + 1))
+        .unwrap_or(&channel.label)
+}
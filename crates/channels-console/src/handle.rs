@@ -0,0 +1,94 @@
+//! In-process access to channel and stream statistics.
+//!
+//! [`ChannelsHandle`] returns the same typed stats as the HTTP API, without a server, e.g. to
+//! assert on backlogs in integration tests or to export them into your own metrics pipeline.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use channels_console::ChannelsHandle;
+//!
+//! let ingest = ChannelsHandle::new().prefix("ingest");
+//!
+//! for channel in ingest.channels() {
+//!     println!("{} queued={} sent={}", channel.label, channel.queued, channel.sent_count);
+//! }
+//! assert!(ingest.total_queued() < 1_000);
+//! ```
+
+use crate::{
+    flush, get_channel_logs, get_combined_json, get_stream_logs, ChannelLogs, CombinedJson,
+    LogQuery, SerializableChannelStats, SerializableStreamStats, StreamLogs,
+};
+
+/// Handle for reading statistics of the instrumented channels and streams.
+///
+/// Handles are cheap to clone and can be passed to the components that report metrics.
+/// Every read first waits for already emitted events to be applied, see [`flush`].
+#[derive(Debug, Clone, Default)]
+pub struct ChannelsHandle {
+    prefix: Option<String>,
+}
+
+impl ChannelsHandle {
+    /// Create a handle covering all channels and streams.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only cover channels and streams with labels under the given path prefix,
+    /// e.g. `ingest` for `ingest/parser` and `ingest/writer`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Returns current statistics of all covered channels and streams.
+    pub fn snapshot(&self) -> CombinedJson {
+        flush();
+        let mut combined = get_combined_json();
+        if let Some(prefix) = &self.prefix {
+            combined.retain_prefix(prefix);
+        }
+        combined
+    }
+
+    /// Returns current statistics of all covered channels, sorted like in the console.
+    pub fn channels(&self) -> Vec<SerializableChannelStats> {
+        self.snapshot().channels
+    }
+
+    /// Returns current statistics of all covered streams, sorted like in the console.
+    pub fn streams(&self) -> Vec<SerializableStreamStats> {
+        self.snapshot().streams
+    }
+
+    /// Returns current statistics of the channel with the given label (or `file:line` source).
+    pub fn channel(&self, label: &str) -> Option<SerializableChannelStats> {
+        self.channels()
+            .into_iter()
+            .find(|channel| channel.label == label || channel.source == label)
+    }
+
+    /// Returns current statistics of the stream with the given label (or `file:line` source).
+    pub fn stream(&self, label: &str) -> Option<SerializableStreamStats> {
+        self.streams()
+            .into_iter()
+            .find(|stream| stream.label == label || stream.source == label)
+    }
+
+    /// Returns logs of the channel with the given label (or `file:line` source), most recent first.
+    pub fn channel_logs(&self, label: &str) -> Option<ChannelLogs> {
+        get_channel_logs(&self.channel(label)?.id.to_string(), &LogQuery::default())
+    }
+
+    /// Returns logs of the stream with the given label (or `file:line` source), most recent first.
+    pub fn stream_logs(&self, label: &str) -> Option<StreamLogs> {
+        get_stream_logs(&self.stream(label)?.id.to_string())
+    }
+
+    /// Total number of messages queued across the covered channels.
+    pub fn total_queued(&self) -> u64 {
+        self.channels().iter().map(|channel| channel.queued).sum()
+    }
+}
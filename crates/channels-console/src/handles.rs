@@ -0,0 +1,81 @@
+//! Sender and receiver handles still alive.
+//!
+//! "Why is this channel never closing?" is usually answered by a forgotten sender clone.
+//! Tokio `mpsc` channels report the number of live senders as `senders_alive`, and whether
+//! the receiver still exists as `receivers_alive`. Other channel types don't expose their
+//! handle counts, so both are `None` for them.
+
+// Only tokio channels report their handles
+#![cfg_attr(not(feature = "tokio"), allow(dead_code))]
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Number of sender clones still alive.
+enum Senders {
+    /// Read from the channel
+    Channel(Box<dyn Fn() -> usize + Send + Sync>),
+    /// Counted by the clones of an inline instrumented sender
+    Clones(AtomicUsize),
+}
+
+/// Live handles of an instrumented channel, read when its statistics are served.
+pub(crate) struct Handles {
+    senders: Senders,
+    receiver_alive: AtomicBool,
+}
+
+impl Handles {
+    /// Handles of a channel reporting its own sender count.
+    pub(crate) fn new(senders: impl Fn() -> usize + Send + Sync + 'static) -> Arc<Self> {
+        Arc::new(Self {
+            senders: Senders::Channel(Box::new(senders)),
+            receiver_alive: AtomicBool::new(true),
+        })
+    }
+
+    /// Handles of a channel with a single sender, counted with [`sender_cloned`](Self::sender_cloned)
+    /// and [`sender_dropped`](Self::sender_dropped).
+    pub(crate) fn counted() -> Arc<Self> {
+        Arc::new(Self {
+            senders: Senders::Clones(AtomicUsize::new(1)),
+            receiver_alive: AtomicBool::new(true),
+        })
+    }
+
+    pub(crate) fn sender_cloned(&self) {
+        if let Senders::Clones(count) = &self.senders {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn sender_dropped(&self) {
+        if let Senders::Clones(count) = &self.senders {
+            count.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn senders_alive(&self) -> u64 {
+        match &self.senders {
+            Senders::Channel(senders) => senders() as u64,
+            Senders::Clones(count) => count.load(Ordering::Relaxed) as u64,
+        }
+    }
+
+    pub(crate) fn receivers_alive(&self) -> u64 {
+        self.receiver_alive.load(Ordering::Relaxed) as u64
+    }
+
+    pub(crate) fn receiver_dropped(&self) {
+        self.receiver_alive.store(false, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Debug for Handles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handles")
+            .field("senders", &self.senders_alive())
+            .field("receivers", &self.receivers_alive())
+            .finish()
+    }
+}
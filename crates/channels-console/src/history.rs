@@ -0,0 +1,500 @@
+//! Optional persistent history of channel and stream statistics.
+//!
+//! When `CHANNELS_CONSOLE_HISTORY_PATH` (or [`ConfigBuilder::history_path`](crate::ConfigBuilder::history_path))
+//! is set, a background thread periodically appends
+//! snapshots to a JSONL file (one [`HistoryRecord`] per line). The file is reopened in append
+//! mode, so data survives process restarts, and it's compacted to the newest
+//! `CHANNELS_CONSOLE_HISTORY_MAX_ENTRIES` records.
+//!
+//! History files share their format and writer with [recordings](crate::recording), so both
+//! are served by `/history`, played back by `channels-console replay` and loaded with
+//! [`read_history`]. Within a run, the persisted snapshots also extend the in-memory
+//! [`samples`](crate::samples) of `/channels/:id/history` when a longer `window` is requested.
+//!
+//! The history writer keeps the offset and timestamps of each record in memory, so serving
+//! and compacting the file only reads the records needed, instead of parsing all of it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::control;
+use crate::info::ProcessJson;
+use crate::samples::ChannelSample;
+use crate::{
+    get_combined_json, start_unix_ms, with_sorted_channel_stats, ChannelLogs, LogEntry,
+    SerializableChannelStats, SerializableStreamStats, SCHEMA_VERSION,
+};
+
+/// A single persisted snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Wall-clock time of the snapshot in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// Elapsed time since program start in nanoseconds
+    pub elapsed_ns: u64,
+    /// Channel statistics
+    pub channels: Vec<SerializableChannelStats>,
+    /// Stream statistics
+    pub streams: Vec<SerializableStreamStats>,
+    /// Process the statistics come from
+    #[serde(default)]
+    pub process: ProcessJson,
+    /// Log entries recorded since the previous snapshot (only with `CHANNELS_CONSOLE_HISTORY_LOGS=1`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<ChannelLogs>,
+}
+
+/// Response of the `/history` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Persisted snapshots, oldest first
+    pub records: Vec<HistoryRecord>,
+}
+
+/// What a [`SnapshotWriter`] persists snapshots for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WriterKind {
+    /// Periodic history, compacted to its newest records
+    History,
+    /// Recording for replay, see [`recording`](crate::recording)
+    Recording,
+}
+
+impl std::fmt::Display for WriterKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::History => write!(f, "history"),
+            Self::Recording => write!(f, "recording"),
+        }
+    }
+}
+
+/// Appends a [`HistoryRecord`] to a JSONL file every `interval`.
+pub(crate) struct SnapshotWriter {
+    kind: WriterKind,
+    path: PathBuf,
+    interval: Duration,
+    /// Number of newest records kept when compacting, `None` to never compact
+    max_entries: Option<usize>,
+    include_logs: bool,
+    /// Records in the file, oldest first, kept only when compacting
+    index: VecDeque<IndexEntry>,
+    next_due: Instant,
+    cursors: LogCursors,
+}
+
+impl SnapshotWriter {
+    /// Writer of the persistent history, if a history path is configured.
+    pub(crate) fn history(config: &Config) -> Option<Self> {
+        let max_entries = config.history_max_entries.max(1);
+        let path = config.history_path.clone()?;
+        Some(Self {
+            kind: WriterKind::History,
+            index: index_records(&path),
+            path,
+            interval: config.history_interval.max(Duration::from_millis(1)),
+            max_entries: Some(max_entries),
+            include_logs: config.history_logs,
+            next_due: Instant::now() + config.history_interval,
+            cursors: LogCursors::new(),
+        })
+    }
+
+    /// Writer of a recording, which keeps every record and all log entries.
+    pub(crate) fn recording(path: PathBuf, interval: Duration) -> Self {
+        Self {
+            kind: WriterKind::Recording,
+            path,
+            interval,
+            max_entries: None,
+            include_logs: true,
+            index: VecDeque::new(),
+            next_due: Instant::now() + interval,
+            cursors: LogCursors::new(),
+        }
+    }
+
+    /// Appends a snapshot, compacting the file once it outgrows `max_entries`.
+    fn write(&mut self) {
+        self.next_due = Instant::now() + self.interval;
+        let record = take_snapshot(self.include_logs, &mut self.cursors);
+        let offset = match append_record(&self.path, &record) {
+            Ok(offset) => offset,
+            Err(e) => {
+                eprintln!(
+                    "Failed to write channels-console {} to {}: {}",
+                    self.kind,
+                    self.path.display(),
+                    e
+                );
+                return;
+            }
+        };
+
+        let Some(max_entries) = self.max_entries else {
+            return;
+        };
+        self.index.push_back(IndexEntry::new(offset, &record));
+        // Allow some slack before compacting, so the file isn't rewritten on every snapshot
+        if self.index.len() > max_entries + max_entries / 10 {
+            let first_kept = self.index.len() - max_entries;
+            match compact(&self.path, self.index[first_kept].offset) {
+                Ok(removed) => {
+                    self.index.drain(..first_kept);
+                    for entry in self.index.iter_mut() {
+                        entry.offset -= removed;
+                    }
+                }
+                Err(e) => eprintln!(
+                    "Failed to compact channels-console {} {}: {}",
+                    self.kind,
+                    self.path.display(),
+                    e
+                ),
+            }
+        }
+    }
+}
+
+static WRITERS: Mutex<Vec<SnapshotWriter>> = Mutex::new(Vec::new());
+static WRITERS_THREAD: Once = Once::new();
+static HISTORY_RECORDER: Once = Once::new();
+
+/// Starts `writer`. Only one writer of each kind runs at a time, so a writer to another file
+/// than the running one of its kind is rejected. All writers are driven by a single background
+/// thread.
+pub(crate) fn add_writer(writer: SnapshotWriter) {
+    let mut writers = WRITERS.lock().unwrap();
+    if let Some(running) = writers.iter().find(|w| w.kind == writer.kind) {
+        if running.path != writer.path {
+            eprintln!(
+                "Not writing channels-console {} to {}, already writing it to {}",
+                writer.kind,
+                writer.path.display(),
+                running.path.display()
+            );
+        }
+        return;
+    }
+    writers.push(writer);
+    drop(writers);
+
+    WRITERS_THREAD.call_once(|| {
+        std::thread::Builder::new()
+            .name("channels-console-history".into())
+            .spawn(run_writers)
+            .expect("Failed to spawn channels-console-history thread");
+    });
+}
+
+fn run_writers() {
+    loop {
+        let next_due = {
+            let mut writers = WRITERS.lock().unwrap();
+            let now = Instant::now();
+            for writer in writers.iter_mut().filter(|w| w.next_due <= now) {
+                writer.write();
+            }
+            writers.iter().map(|w| w.next_due).min()
+        };
+        let wait = next_due.map_or(Duration::from_millis(100), |due| {
+            due.saturating_duration_since(Instant::now())
+        });
+        std::thread::sleep(wait);
+    }
+}
+
+/// Appends a snapshot to the running writer of `kind` right away, if any.
+pub(crate) fn write_now(kind: WriterKind) {
+    let mut writers = WRITERS.lock().unwrap();
+    for writer in writers.iter_mut().filter(|w| w.kind == kind) {
+        writer.write();
+    }
+}
+
+/// Reads the records of the running history writer selected by `filter`, if history is enabled.
+/// The writers are locked meanwhile, so the file isn't compacted under the reader.
+fn read_history_where(filter: impl Fn(&IndexEntry) -> bool) -> Option<Vec<HistoryRecord>> {
+    let writers = WRITERS.lock().unwrap();
+    let writer = writers.iter().find(|w| w.kind == WriterKind::History)?;
+    let entries: Vec<IndexEntry> = writer.index.iter().copied().filter(filter).collect();
+    Some(read_records(&writer.path, &entries).unwrap_or_default())
+}
+
+/// Start the history writer if history is enabled (only once per process).
+pub(crate) fn spawn_history_recorder() {
+    HISTORY_RECORDER.call_once(|| {
+        if let Some(writer) = SnapshotWriter::history(Config::current()) {
+            add_writer(writer);
+        }
+    });
+}
+
+/// Highest sent and received log indexes already persisted for a channel.
+#[derive(Debug, Clone, Copy, Default)]
+struct LogCursor {
+    sent: u64,
+    received: u64,
+    /// [`control::resets`] when the entries were persisted, as a reset starts indexes over
+    resets: u64,
+}
+
+type LogCursors = HashMap<u64, LogCursor>;
+
+/// Where a record starts in the file, with the fields it's looked up by.
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    offset: u64,
+    timestamp_ms: u64,
+    elapsed_ns: u64,
+    pid: u32,
+}
+
+impl IndexEntry {
+    fn new(offset: u64, record: &HistoryRecord) -> Self {
+        Self {
+            offset,
+            timestamp_ms: record.timestamp_ms,
+            elapsed_ns: record.elapsed_ns,
+            pid: record.process.pid,
+        }
+    }
+}
+
+/// Fields of a [`HistoryRecord`] needed for its [`IndexEntry`], parsed without the statistics.
+#[derive(Deserialize)]
+struct IndexedFields {
+    timestamp_ms: u64,
+    elapsed_ns: u64,
+    #[serde(default)]
+    process: IndexedProcess,
+}
+
+#[derive(Default, Deserialize)]
+struct IndexedProcess {
+    #[serde(default)]
+    pid: u32,
+}
+
+fn take_snapshot(include_logs: bool, cursors: &mut LogCursors) -> HistoryRecord {
+    let combined = get_combined_json();
+
+    let logs = if include_logs {
+        let resets = control::resets();
+        with_sorted_channel_stats(|channels| {
+            // Forget evicted channels
+            cursors.retain(|id, _| channels.iter().any(|stats| stats.id == *id));
+
+            channels
+                .iter()
+                .filter_map(|stats| {
+                    let cursor = cursors
+                        .get(&stats.id)
+                        .copied()
+                        .filter(|cursor| cursor.resets == resets)
+                        .unwrap_or_default();
+                    let messages = stats.counters.messages();
+                    let sent_logs: Vec<LogEntry> = messages
+                        .sent_logs
+                        .iter()
+                        .filter(|entry| entry.index > cursor.sent)
+                        .cloned()
+                        .collect();
+                    let received_logs: Vec<LogEntry> = messages
+                        .received_logs
+                        .iter()
+                        .filter(|entry| entry.index > cursor.received)
+                        .cloned()
+                        .collect();
+                    drop(messages);
+
+                    if sent_logs.is_empty() && received_logs.is_empty() {
+                        return None;
+                    }
+                    // Logs are appended by the wrappers, so they may be ahead of the counts
+                    cursors.insert(
+                        stats.id,
+                        LogCursor {
+                            sent: sent_logs.last().map_or(cursor.sent, |entry| entry.index),
+                            received: received_logs
+                                .last()
+                                .map_or(cursor.received, |entry| entry.index),
+                            resets,
+                        },
+                    );
+
+                    Some(ChannelLogs {
+                        schema_version: SCHEMA_VERSION,
+                        id: stats.id.to_string(),
+                        sent_logs,
+                        received_logs,
+                        next_cursor: None,
+                    })
+                })
+                .collect()
+        })
+    } else {
+        Vec::new()
+    };
+
+    HistoryRecord {
+        schema_version: SCHEMA_VERSION,
+        timestamp_ms: unix_millis(),
+        elapsed_ns: combined.current_elapsed_ns,
+        channels: combined.channels,
+        streams: combined.streams,
+        process: combined.process,
+        logs,
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Appends `record` as a line, returning the offset it starts at.
+fn append_record(path: &Path, record: &HistoryRecord) -> std::io::Result<u64> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let offset = file.metadata()?.len();
+    let line = serde_json::to_string(record)?;
+    writeln!(file, "{}", line)?;
+    Ok(offset)
+}
+
+/// Indexes the records already in a history file. Lines that fail to parse are left out, like
+/// in [`read_history`].
+fn index_records(path: &Path) -> VecDeque<IndexEntry> {
+    let Ok(file) = File::open(path) else {
+        return VecDeque::new();
+    };
+    let mut reader = BufReader::new(file);
+    let mut index = VecDeque::new();
+    let mut offset = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                if let Ok(fields) = serde_json::from_str::<IndexedFields>(&line) {
+                    index.push_back(IndexEntry {
+                        offset,
+                        timestamp_ms: fields.timestamp_ms,
+                        elapsed_ns: fields.elapsed_ns,
+                        pid: fields.process.pid,
+                    });
+                }
+                offset += read as u64;
+            }
+        }
+    }
+    index
+}
+
+/// Reads the records at `entries`, which are sorted by offset.
+fn read_records(path: &Path, entries: &[IndexEntry]) -> std::io::Result<Vec<HistoryRecord>> {
+    let Some(first) = entries.first() else {
+        return Ok(Vec::new());
+    };
+    let mut reader = BufReader::new(File::open(path)?);
+    reader.seek(SeekFrom::Start(first.offset))?;
+    let mut records = Vec::with_capacity(entries.len());
+    let mut offset = first.offset;
+    let mut line = String::new();
+    let mut entries = entries.iter().peekable();
+    while let Some(entry) = entries.peek() {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            break;
+        }
+        if offset == entry.offset {
+            entries.next();
+            if let Ok(record) = serde_json::from_str(&line) {
+                records.push(record);
+            }
+        }
+        offset += read as u64;
+    }
+    Ok(records)
+}
+
+/// Rewrites the history file without its bytes before `first_kept`, streaming the kept ones
+/// through a temporary file. Returns the number of bytes removed.
+fn compact(path: &Path, first_kept: u64) -> std::io::Result<u64> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(first_kept))?;
+
+    let tmp_path = path.with_extension("jsonl.tmp");
+    {
+        let mut tmp = File::create(&tmp_path)?;
+        std::io::copy(&mut file, &mut tmp)?;
+    }
+    std::fs::rename(&tmp_path, path)?;
+
+    Ok(first_kept)
+}
+
+/// Read persisted history records from a JSONL file, oldest first.
+/// Lines that fail to parse are skipped.
+pub fn read_history(path: impl AsRef<Path>) -> std::io::Result<Vec<HistoryRecord>> {
+    let file = File::open(path)?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect())
+}
+
+/// Returns persisted history for the `/history` endpoint, or `None` when history is disabled.
+pub(crate) fn get_history_json(limit: Option<usize>, since_ms: Option<u64>) -> Option<HistoryJson> {
+    let mut records =
+        read_history_where(|entry| since_ms.is_none_or(|since_ms| entry.timestamp_ms > since_ms))?;
+    if let Some(limit) = limit {
+        records.drain(..records.len().saturating_sub(limit));
+    }
+
+    Some(HistoryJson {
+        schema_version: SCHEMA_VERSION,
+        records,
+    })
+}
+
+/// Samples of a channel older than `before_ns`, taken from the persisted history of this
+/// process. Extends the samples kept in memory, see [`samples`](crate::samples).
+pub(crate) fn persisted_samples(channel_id: u64, before_ns: u64) -> Vec<ChannelSample> {
+    // Channel ids are only meaningful within the process run that assigned them
+    let pid = std::process::id();
+    let started_ms = start_unix_ms();
+    read_history_where(|entry| {
+        entry.pid == pid && entry.timestamp_ms >= started_ms && entry.elapsed_ns < before_ns
+    })
+    .unwrap_or_default()
+    .into_iter()
+    .filter_map(|record| {
+        let channel = record.channels.iter().find(|c| c.id == channel_id)?;
+        Some(ChannelSample {
+            elapsed_ns: record.elapsed_ns,
+            queued: channel.queued,
+            queued_bytes: channel.queued_bytes,
+            sent_count: channel.sent_count,
+            received_count: channel.received_count,
+        })
+    })
+    .collect()
+}
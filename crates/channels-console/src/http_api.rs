@@ -1,51 +1,338 @@
-use crate::{get_channel_logs, get_channels_json, get_stream_logs, get_streams_json};
+use crate::actors::get_channel_actors;
+use crate::alerts::get_alerts_json;
+use crate::config::Config;
+use crate::control;
+use crate::correlation::journey;
+use crate::delta::get_channels_delta_json;
+use crate::groups::{SourceGroupsJson, TagGroupsJson};
+use crate::history::get_history_json;
+use crate::info::{get_health_json, get_info_json, get_version_json};
+use crate::latency::get_latency_histogram;
+use crate::registry::get_object_json;
+use crate::samples::get_channel_history;
+use crate::sinks::get_sinks_json;
+use crate::summary::SummaryJson;
+use crate::topology::get_topology_json;
+use crate::{
+    get_channel_logs, get_channel_states, get_channels_json, get_combined_json, get_stream_logs,
+    get_streams_json, CombinedJson, LogOrder, LogQuery,
+};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use regex::Regex;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fmt::Display;
-use std::sync::LazyLock;
-use tiny_http::{Header, Request, Response, Server};
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, OnceLock};
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+mod grafana;
+#[cfg(feature = "ws")]
+mod ws;
 
 static RE_CHANNEL_LOGS: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^/channels/(\d+)/logs$").unwrap());
+static RE_CHANNEL_STATES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^/channels/(\d+)/states$").unwrap());
+static RE_CHANNEL_ACTORS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^/channels/(\d+)/actors$").unwrap());
+static RE_CHANNEL_HISTORY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^/channels/(\d+)/history$").unwrap());
+static RE_LATENCY_HISTOGRAM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^/channels/(\d+)/latency_histogram$").unwrap());
 static RE_STREAM_LOGS: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^/streams/(\d+)/logs$").unwrap());
+static RE_OBJECT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^/objects/(\d+)$").unwrap());
+static RE_JOURNEY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^/journeys/(\d+)$").unwrap());
+
+/// Push interval of `/events` and `/ws` when no `interval_ms` is given.
+const DEFAULT_EVENTS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Shortest push interval accepted by `/events` and `/ws`.
+const MIN_EVENTS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Max number of clients subscribed to `/events` and `/ws` at once, each holding a thread.
+const MAX_SUBSCRIBERS: usize = 32;
+
+static SUBSCRIBERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Slot of a connected subscriber, released when it disconnects.
+struct Subscriber;
+
+impl Subscriber {
+    /// Takes a slot, unless [`MAX_SUBSCRIBERS`] clients are already subscribed.
+    fn acquire() -> Option<Self> {
+        SUBSCRIBERS
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < MAX_SUBSCRIBERS).then_some(count + 1)
+            })
+            .ok()
+            .map(|_| Self)
+    }
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        SUBSCRIBERS.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Address the TCP metrics server is listening on, once it's bound.
+pub(crate) static METRICS_ADDR: OnceLock<SocketAddr> = OnceLock::new();
 
 pub(crate) fn start_metrics_server(addr: &str) {
     let server = match Server::http(addr) {
         Ok(s) => s,
         Err(e) => {
-            panic!("Failed to bind metrics server to {}: {}. Customize the port using the CHANNELS_CONSOLE_METRICS_PORT environment variable.", addr, e);
+            panic!("Failed to bind metrics server to {}: {}. Customize the port using the CHANNELS_CONSOLE_METRICS_PORT environment variable or Config::builder().metrics_port(..).", addr, e);
         }
     };
 
-    println!("Channel metrics server listening on http://{}", addr);
+    // Port 0 binds a free port, so report the one actually used
+    match server.server_addr().to_ip() {
+        Some(bound) => {
+            let _ = METRICS_ADDR.set(bound);
+            println!("Channel metrics server listening on http://{}", bound);
+        }
+        None => println!("Channel metrics server listening on http://{}", addr),
+    }
+    serve(server);
+}
+
+/// Serves the metrics HTTP API on a Unix domain socket instead of a TCP port.
+#[cfg(unix)]
+pub(crate) fn start_unix_metrics_server(path: &std::path::Path) {
+    use std::os::unix::fs::FileTypeExt;
+
+    // A socket left behind by a previous run would make binding fail
+    if std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        let _ = std::fs::remove_file(path);
+    }
 
+    let server = match Server::http_unix(path) {
+        Ok(s) => s,
+        Err(e) => {
+            panic!("Failed to bind metrics server to socket {}: {}. Customize the path using the CHANNELS_CONSOLE_METRICS_SOCKET environment variable or Config::builder().metrics_socket(..).", path.display(), e);
+        }
+    };
+
+    println!(
+        "Channel metrics server listening on unix:{}",
+        path.display()
+    );
+    serve(server);
+}
+
+fn serve(server: Server) {
     for request in server.incoming_requests() {
         handle_request(request);
     }
 }
 
 fn handle_request(request: Request) {
+    // Browsers send CORS preflight requests without credentials
+    if request.method() == &Method::Options {
+        return respond(request, Response::empty(204));
+    }
     let path = request.url().split('?').next().unwrap_or("/");
 
+    // Liveness probes reveal nothing about the channels, so they don't need the token
+    if path != "/healthz" && !is_authorized(&request) {
+        return respond_unauthorized(request);
+    }
+
     match path {
         "/channels" => {
-            let channels = get_channels_json();
-            respond_json(request, &channels);
+            let mut channels = get_channels_json();
+            if let Some(prefix) = query_param(request.url(), "prefix") {
+                channels.retain_prefix(&percent_decode(prefix));
+            }
+            if let Some(tag) = query_param(request.url(), "tag") {
+                channels.retain_tag(&percent_decode(tag));
+            }
+            let after_index =
+                query_param(request.url(), "after_index").and_then(|v| v.parse().ok());
+            let limit = query_param(request.url(), "limit").and_then(|v| v.parse().ok());
+            if after_index.is_some() || limit.is_some() {
+                channels.paginate(after_index, limit);
+            }
+            match query_param(request.url(), "group_by") {
+                None => respond_json_fields(request, &channels, "channels"),
+                Some("source") => respond_json(request, &SourceGroupsJson::from(&channels)),
+                Some("tag") => respond_json(request, &TagGroupsJson::from(&channels)),
+                Some(_) => respond_error(
+                    request,
+                    400,
+                    "Unsupported group_by value, expected `source` or `tag`",
+                ),
+            }
+        }
+        "/channels/delta" => {
+            let Some(since_ns) =
+                query_param(request.url(), "since_ns").and_then(|v| v.parse().ok())
+            else {
+                return respond_error(
+                    request,
+                    400,
+                    "Missing since_ns, pass current_elapsed_ns of the previous response",
+                );
+            };
+            let prefix = query_param(request.url(), "prefix").map(percent_decode);
+            let tag = query_param(request.url(), "tag").map(percent_decode);
+            respond_json(
+                request,
+                &get_channels_delta_json(since_ns, prefix.as_deref(), tag.as_deref()),
+            );
+        }
+        "/summary" => {
+            let mut channels = get_channels_json();
+            if let Some(prefix) = query_param(request.url(), "prefix") {
+                channels.retain_prefix(&percent_decode(prefix));
+            }
+            if let Some(tag) = query_param(request.url(), "tag") {
+                channels.retain_tag(&percent_decode(tag));
+            }
+            respond_json(request, &SummaryJson::from(&channels));
         }
         "/streams" => {
-            let streams = get_streams_json();
-            respond_json(request, &streams);
+            let mut streams = get_streams_json();
+            if let Some(prefix) = query_param(request.url(), "prefix") {
+                streams.retain_prefix(&percent_decode(prefix));
+            }
+            respond_json_fields(request, &streams, "streams");
+        }
+        "/all" => {
+            let mut combined = get_combined_json();
+            if let Some(prefix) = query_param(request.url(), "prefix") {
+                combined.retain_prefix(&percent_decode(prefix));
+            }
+            respond_json(request, &combined);
+        }
+        "/sinks" => {
+            let mut sinks = get_sinks_json();
+            if let Some(prefix) = query_param(request.url(), "prefix") {
+                sinks.retain_prefix(&percent_decode(prefix));
+            }
+            respond_json(request, &sinks);
+        }
+        "/history" => {
+            let limit = query_param(request.url(), "limit").and_then(|v| v.parse().ok());
+            let since_ms = query_param(request.url(), "since_ms").and_then(|v| v.parse().ok());
+            match get_history_json(limit, since_ms) {
+                Some(history) => respond_json(request, &history),
+                None => respond_error(
+                    request,
+                    404,
+                    "History is disabled, set CHANNELS_CONSOLE_HISTORY_PATH to enable it",
+                ),
+            }
+        }
+        "/topology" => {
+            let topology = get_topology_json();
+            match query_param(request.url(), "format") {
+                Some("dot") => respond_text(request, &topology.to_dot(), "text/vnd.graphviz"),
+                _ => respond_json(request, &topology),
+            }
+        }
+        "/alerts" => respond_json(request, &get_alerts_json()),
+        "/healthz" => respond_json(request, &get_health_json()),
+        "/info" => respond_json(request, &get_info_json()),
+        "/version" => respond_json(request, &get_version_json()),
+        "/control/pause" | "/control/resume" | "/control/reset"
+            if request.method() != &Method::Post =>
+        {
+            respond_error(request, 405, "Control endpoints only accept POST")
+        }
+        "/control/pause" => {
+            control::pause();
+            respond_json(request, &control::get_control_json());
+        }
+        "/control/resume" => {
+            control::resume();
+            respond_json(request, &control::get_control_json());
+        }
+        "/control/reset" => {
+            control::reset();
+            respond_json(request, &control::get_control_json());
+        }
+        #[cfg(feature = "tokio")]
+        "/sync" => respond_json(request, &crate::primitives::get_primitives_json()),
+        "/events" => {
+            let Some(subscriber) = Subscriber::acquire() else {
+                return respond_error(request, 503, "Too many subscribers");
+            };
+            let (interval, prefix) = live_params(request.url());
+
+            // Subscribers keep their connection open, so each one is served from its own thread
+            let _ = std::thread::Builder::new()
+                .name("channels-console-events".into())
+                .spawn(move || {
+                    let _subscriber = subscriber;
+                    stream_events(request, interval, prefix)
+                });
+        }
+        #[cfg(feature = "ws")]
+        "/ws" => {
+            let Some(subscriber) = Subscriber::acquire() else {
+                return respond_error(request, 503, "Too many subscribers");
+            };
+            let (interval, prefix) = live_params(request.url());
+            let _ = std::thread::Builder::new()
+                .name("channels-console-ws".into())
+                .spawn(move || {
+                    let _subscriber = subscriber;
+                    ws::serve(request, interval, prefix)
+                });
         }
+        _ if path == "/grafana" || path.starts_with("/grafana/") => grafana::handle(request),
         _ => {
             // Handle /channels/<id>/logs
             if let Some(caps) = RE_CHANNEL_LOGS.captures(path) {
-                return match get_channel_logs(&caps[1]) {
+                return match get_channel_logs(&caps[1], &log_query(request.url())) {
                     Some(logs) => respond_json(request, &logs),
                     None => respond_error(request, 404, "Channel not found"),
                 };
             }
 
+            // Handle /channels/<id>/states
+            if let Some(caps) = RE_CHANNEL_STATES.captures(path) {
+                return match get_channel_states(&caps[1]) {
+                    Some(states) => respond_json(request, &states),
+                    None => respond_error(request, 404, "Channel not found"),
+                };
+            }
+
+            // Handle /channels/<id>/actors
+            if let Some(caps) = RE_CHANNEL_ACTORS.captures(path) {
+                return match get_channel_actors(&caps[1]) {
+                    Some(actors) => respond_json(request, &actors),
+                    None => respond_error(request, 404, "Channel not found"),
+                };
+            }
+
+            // Handle /channels/<id>/history
+            if let Some(caps) = RE_CHANNEL_HISTORY.captures(path) {
+                let window = query_param(request.url(), "window")
+                    .and_then(|v| v.parse().ok())
+                    .map(Duration::from_secs);
+                return match get_channel_history(&caps[1], window) {
+                    Some(history) => respond_json(request, &history),
+                    None => respond_error(request, 404, "Channel not found"),
+                };
+            }
+
+            // Handle /channels/<id>/latency_histogram
+            if let Some(caps) = RE_LATENCY_HISTOGRAM.captures(path) {
+                return match get_latency_histogram(&caps[1]) {
+                    Some(histogram) => respond_json(request, &histogram),
+                    None => respond_error(request, 404, "Channel not found"),
+                };
+            }
+
             // Handle /streams/<id>/logs
             if let Some(caps) = RE_STREAM_LOGS.captures(path) {
                 return match get_stream_logs(&caps[1]) {
@@ -54,32 +341,335 @@ fn handle_request(request: Request) {
                 };
             }
 
+            // Handle /objects/<id>, a channel, stream or sink
+            if let Some(caps) = RE_OBJECT.captures(path) {
+                return match get_object_json(&caps[1]) {
+                    Some(object) => respond_json(request, &object),
+                    None => respond_error(request, 404, "Object not found"),
+                };
+            }
+
+            // Handle /journeys/<correlation_id>
+            if let Some(caps) = RE_JOURNEY.captures(path) {
+                return match caps[1].parse().ok().and_then(journey) {
+                    Some(journey) => respond_json(request, &journey),
+                    None => respond_error(request, 404, "Journey not found"),
+                };
+            }
+
             respond_error(request, 404, "Not found");
         }
     }
 }
 
+/// Streams stats as Server-Sent Events until the client disconnects.
+///
+/// The first `snapshot` event carries all channels and streams, following `update` events
+/// only the ones that changed since the previous push. Both use the `CombinedJson` format.
+fn stream_events(request: Request, interval: Duration, prefix: Option<String>) {
+    let mut writer = request.into_writer();
+    let mut headers = String::from(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n",
+    );
+    for header in cors_headers() {
+        headers.push_str(&format!("{}: {}\r\n", header.field, header.value));
+    }
+    headers.push_str("\r\n");
+    if writer
+        .write_all(headers.as_bytes())
+        .and_then(|_| writer.flush())
+        .is_err()
+    {
+        return;
+    }
+
+    let mut diff = StatsDiff::default();
+    let mut event = "snapshot";
+
+    loop {
+        let combined = diff.next(prefix.as_deref());
+        let frame =
+            if event == "snapshot" || !combined.channels.is_empty() || !combined.streams.is_empty()
+            {
+                match serde_json::to_string(&combined) {
+                    Ok(data) => format!("event: {}\ndata: {}\n\n", event, data),
+                    Err(e) => {
+                        eprintln!("Internal server error: {}", e);
+                        return;
+                    }
+                }
+            } else {
+                // Comment line, keeps the connection alive and detects disconnected clients
+                ":\n\n".to_string()
+            };
+
+        if writer
+            .write_all(frame.as_bytes())
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            return;
+        }
+
+        event = "update";
+        std::thread::sleep(interval);
+    }
+}
+
+/// Push interval and label prefix of the live endpoints, from the `interval_ms` and `prefix` params.
+fn live_params(url: &str) -> (Duration, Option<String>) {
+    let interval = query_param(url, "interval_ms")
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_EVENTS_INTERVAL)
+        .max(MIN_EVENTS_INTERVAL);
+    (interval, query_param(url, "prefix").map(percent_decode))
+}
+
+/// Tracks which channels and streams changed between pushes of the live endpoints.
+#[derive(Default)]
+struct StatsDiff {
+    channels: HashMap<u64, String>,
+    streams: HashMap<u64, String>,
+}
+
+impl StatsDiff {
+    /// Current stats, keeping only the entries that changed since the previous call.
+    fn next(&mut self, prefix: Option<&str>) -> CombinedJson {
+        let mut combined = get_combined_json();
+        if let Some(prefix) = prefix {
+            combined.retain_prefix(prefix);
+        }
+        combined
+            .channels
+            .retain(|channel| changed(&mut self.channels, channel.id, channel));
+        combined
+            .streams
+            .retain(|stream| changed(&mut self.streams, stream.id, stream));
+        combined
+    }
+}
+
+/// Records the JSON of an entry, returning whether it differs from the previously recorded one.
+fn changed<T: Serialize>(seen: &mut HashMap<u64, String>, id: u64, value: &T) -> bool {
+    let Ok(json) = serde_json::to_string(value) else {
+        return true;
+    };
+    seen.insert(id, json.clone()).as_ref() != Some(&json)
+}
+
+/// Returns the value of a query string parameter, e.g. `limit` in `/history?limit=10`.
+fn query_param<'a>(url: &'a str, key: &str) -> Option<&'a str> {
+    let (_, query) = url.split_once('?')?;
+    query.split('&').find_map(|pair| {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        (k == key).then_some(v)
+    })
+}
+
+/// Search and filter of the logs endpoint, e.g. `?contains=foo&since_ns=1000&limit=10&order=asc`.
+/// Pages requested with `after_index` are oldest first unless `order` says otherwise. Invalid
+/// values are ignored.
+fn log_query(url: &str) -> LogQuery {
+    // Filter `log = json` messages by a field, e.g. ?field=user.id&value=42
+    let field = match (query_param(url, "field"), query_param(url, "value")) {
+        (Some(field), Some(value)) => {
+            let value = percent_decode(value);
+            // Values that aren't valid JSON are matched as strings
+            let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+            Some((percent_decode(field), value))
+        }
+        _ => None,
+    };
+    let after_index = query_param(url, "after_index").and_then(|v| v.parse().ok());
+    LogQuery {
+        contains: query_param(url, "contains").map(percent_decode),
+        since_ns: query_param(url, "since_ns").and_then(|v| v.parse().ok()),
+        field,
+        after_index,
+        limit: query_param(url, "limit").and_then(|v| v.parse().ok()),
+        order: match query_param(url, "order") {
+            Some("asc") => LogOrder::Asc,
+            Some("desc") => LogOrder::Desc,
+            _ if after_index.is_some() => LogOrder::Asc,
+            _ => LogOrder::Desc,
+        },
+    }
+}
+
+/// Decodes `%XX` escapes and `+` in a query string value.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let byte = std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = byte {
+                    decoded.push(byte);
+                    i += 3;
+                    continue;
+                }
+                decoded.push(b'%');
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Sends `response` with the CORS headers of the configured origin, if any.
+fn respond<R: Read>(request: Request, mut response: Response<R>) {
+    for header in cors_headers() {
+        response.add_header(header);
+    }
+    let _ = request.respond(response);
+}
+
+/// Headers letting browser dashboards on [`cors_origin`](crate::ConfigBuilder::cors_origin)
+/// read responses and send the `Authorization` header. `Vary: Origin` keeps caches from
+/// serving them to other origins.
+fn cors_headers() -> Vec<Header> {
+    let Some(origin) = Config::current().cors_origin.as_deref() else {
+        return Vec::new();
+    };
+    [
+        ("Access-Control-Allow-Origin", origin),
+        ("Vary", "Origin"),
+        ("Access-Control-Allow-Methods", "GET, POST, OPTIONS"),
+        (
+            "Access-Control-Allow-Headers",
+            "Authorization, Content-Type",
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, value)| Header::from_bytes(field.as_bytes(), value.as_bytes()).ok())
+    .collect()
+}
+
 fn respond_json<T: Serialize>(request: Request, value: &T) {
     match serde_json::to_vec(value) {
         Ok(body) => {
+            let gzip = body.len() >= MIN_GZIP_SIZE && accepts_gzip(&request);
+            let body = if gzip { gzip_encode(&body) } else { body };
             let mut response = Response::from_data(body);
             response.add_header(
                 Header::from_bytes(b"Content-Type".as_slice(), b"application/json".as_slice())
                     .unwrap(),
             );
-            let _ = request.respond(response);
+            if gzip {
+                response.add_header(
+                    Header::from_bytes(b"Content-Encoding".as_slice(), b"gzip".as_slice()).unwrap(),
+                );
+            }
+            respond(request, response);
+        }
+        Err(e) => respond_internal_error(request, e),
+    }
+}
+
+/// Smaller responses aren't worth compressing.
+const MIN_GZIP_SIZE: usize = 1024;
+
+fn accepts_gzip(request: &Request) -> bool {
+    request.headers().iter().any(|header| {
+        header.field.equiv("Accept-Encoding")
+            && header
+                .value
+                .as_str()
+                .split(',')
+                .any(|encoding| encoding.split(';').next().unwrap_or("").trim() == "gzip")
+    })
+}
+
+fn gzip_encode(body: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 4), Compression::fast());
+    // Writing to a Vec can't fail
+    let _ = encoder.write_all(body);
+    encoder.finish().unwrap_or_default()
+}
+
+/// Keeps only the comma-separated `fields` of each item in the `key` array of `value`, e.g.
+/// `?fields=id,label,queued`. Other top-level fields are left as they are.
+fn select_fields(value: &mut serde_json::Value, key: &str, fields: &str) {
+    let fields: Vec<&str> = fields.split(',').map(str::trim).collect();
+    if let Some(items) = value.get_mut(key).and_then(|items| items.as_array_mut()) {
+        for item in items {
+            if let Some(item) = item.as_object_mut() {
+                item.retain(|field, _| fields.contains(&field.as_str()));
+            }
+        }
+    }
+}
+
+/// Responds with the `key` items of `value` narrowed down to the `?fields=` param, if given.
+fn respond_json_fields<T: Serialize>(request: Request, value: &T, key: &str) {
+    let Some(fields) = query_param(request.url(), "fields").map(percent_decode) else {
+        return respond_json(request, value);
+    };
+    match serde_json::to_value(value) {
+        Ok(mut value) => {
+            select_fields(&mut value, key, &fields);
+            respond_json(request, &value)
         }
         Err(e) => respond_internal_error(request, e),
     }
 }
 
+fn respond_text(request: Request, body: &str, content_type: &str) {
+    let mut response = Response::from_string(body);
+    response.add_header(
+        Header::from_bytes(b"Content-Type".as_slice(), content_type.as_bytes()).unwrap(),
+    );
+    respond(request, response);
+}
+
 fn respond_error(request: Request, code: u16, msg: &str) {
-    let _ = request.respond(Response::from_string(msg).with_status_code(code));
+    respond(request, Response::from_string(msg).with_status_code(code));
+}
+
+/// Checks the `Authorization: Bearer` header against the configured token, if any.
+fn is_authorized(request: &Request) -> bool {
+    let Some(token) = Config::current().token.as_deref() else {
+        return true;
+    };
+    request.headers().iter().any(|header| {
+        header.field.equiv("Authorization")
+            && header
+                .value
+                .as_str()
+                .strip_prefix("Bearer ")
+                .is_some_and(|provided| constant_time_eq(provided.trim(), token))
+    })
+}
+
+/// Compares secrets in time independent of the position of the first mismatch.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn respond_unauthorized(request: Request) {
+    let mut response =
+        Response::from_string("Missing or invalid bearer token").with_status_code(401);
+    response.add_header(
+        Header::from_bytes(b"WWW-Authenticate".as_slice(), b"Bearer".as_slice()).unwrap(),
+    );
+    respond(request, response);
 }
 
 fn respond_internal_error(request: Request, e: impl Display) {
     eprintln!("Internal server error: {}", e);
-    let _ = request.respond(
+    respond(
+        request,
         Response::from_string(format!("Internal server error: {}", e)).with_status_code(500),
     );
 }
@@ -0,0 +1,255 @@
+//! Endpoints of the Grafana JSON datasource plugin, serving the channel [`samples`] as time
+//! series and fired alerts as annotations.
+//!
+//! The datasource URL is `http://<host>:<port>/grafana`, the plugin appends `/search`, `/query`
+//! and `/annotations` to it. Targets are named `<label>.<metric>`, with the metrics `queued`,
+//! `sent` and `received`.
+//!
+//! [`samples`]: crate::samples
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use tiny_http::Request;
+
+use super::{respond_error, respond_json, respond_text};
+use crate::alerts::{get_alerts_json, unix_millis};
+use crate::samples::ChannelSample;
+use crate::{elapsed_ns, resolve_label, CHANNELS_STATE};
+
+const METRICS: [&str; 3] = ["queued", "sent", "received"];
+
+/// Largest accepted request body.
+const MAX_BODY_LEN: u64 = 1024 * 1024;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SearchRequest {
+    /// Text typed into the metric picker
+    target: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryRequest {
+    range: TimeRange,
+    targets: Vec<QueryTarget>,
+    #[serde(default)]
+    max_data_points: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeRange {
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryTarget {
+    #[serde(default)]
+    target: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TimeSeries {
+    target: String,
+    /// `[value, timestamp_ms]` pairs, oldest first
+    datapoints: Vec<(u64, u64)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnnotationsRequest {
+    range: TimeRange,
+    /// Echoed back in each annotation, as expected by older plugin versions
+    #[serde(default)]
+    annotation: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Annotation {
+    annotation: serde_json::Value,
+    time: u64,
+    title: String,
+    text: String,
+    tags: Vec<String>,
+}
+
+pub(super) fn handle(mut request: Request) {
+    let path = request.url().split('?').next().unwrap_or("/").to_string();
+    match path.as_str() {
+        // Connection test of the datasource settings
+        "/grafana" | "/grafana/" => respond_text(request, "OK", "text/plain"),
+        "/grafana/search" => {
+            let search: SearchRequest = read_body(&mut request).unwrap_or_default();
+            respond_json(request, &search_targets(&search.target));
+        }
+        "/grafana/query" => match read_body::<QueryRequest>(&mut request) {
+            Some(query) => match query_series(&query) {
+                Some(series) => respond_json(request, &series),
+                None => respond_error(request, 400, "Invalid time range"),
+            },
+            None => respond_error(request, 400, "Invalid query request"),
+        },
+        "/grafana/annotations" => match read_body::<AnnotationsRequest>(&mut request) {
+            Some(annotations) => match alert_annotations(annotations) {
+                Some(annotations) => respond_json(request, &annotations),
+                None => respond_error(request, 400, "Invalid time range"),
+            },
+            None => respond_error(request, 400, "Invalid annotations request"),
+        },
+        _ => respond_error(request, 404, "Not found"),
+    }
+}
+
+fn read_body<T: DeserializeOwned>(request: &mut Request) -> Option<T> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_LEN)
+        .read_to_end(&mut body)
+        .ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+/// Names of all targets containing `query`, ordered by channel id.
+fn search_targets(query: &str) -> Vec<String> {
+    let Some((_, stats_map)) = CHANNELS_STATE.get() else {
+        return Vec::new();
+    };
+    let stats = stats_map.read_all();
+    let mut channels: Vec<_> = stats.values().collect();
+    channels.sort_by_key(|channel_stats| channel_stats.id);
+
+    channels
+        .into_iter()
+        .flat_map(|channel_stats| {
+            let label = resolve_label(
+                channel_stats.source,
+                channel_stats.label.as_deref(),
+                channel_stats.iter,
+            );
+            METRICS.map(|metric| format!("{}.{}", label, metric))
+        })
+        .filter(|target| target.contains(query))
+        .collect()
+}
+
+/// One series per target and matching channel, `None` if the range can't be parsed.
+fn query_series(query: &QueryRequest) -> Option<Vec<TimeSeries>> {
+    let from = parse_timestamp(&query.range.from)?;
+    let to = parse_timestamp(&query.range.to)?;
+
+    let Some((_, stats_map)) = CHANNELS_STATE.get() else {
+        return Some(Vec::new());
+    };
+    let stats = stats_map.read_all();
+    // Samples are timestamped relative to program start
+    let start_ms = unix_millis().saturating_sub(elapsed_ns() / 1_000_000);
+
+    let mut series = Vec::new();
+    for target in &query.targets {
+        let Some((label, metric)) = target.target.rsplit_once('.') else {
+            continue;
+        };
+        let value: fn(&ChannelSample) -> u64 = match metric {
+            "queued" => |sample| sample.queued,
+            "sent" => |sample| sample.sent_count,
+            "received" => |sample| sample.received_count,
+            _ => continue,
+        };
+
+        for channel_stats in stats.values() {
+            let channel_label = resolve_label(
+                channel_stats.source,
+                channel_stats.label.as_deref(),
+                channel_stats.iter,
+            );
+            if channel_label != label {
+                continue;
+            }
+            let mut datapoints: Vec<(u64, u64)> = channel_stats
+                .samples
+                .iter()
+                .map(|sample| (value(sample), start_ms + sample.elapsed_ns / 1_000_000))
+                .filter(|(_, time)| (from..=to).contains(time))
+                .collect();
+            if let Some(max) = query.max_data_points.filter(|max| *max > 0) {
+                let step = datapoints.len().div_ceil(max);
+                if step > 1 {
+                    datapoints = datapoints.into_iter().step_by(step).collect();
+                }
+            }
+            series.push(TimeSeries {
+                target: target.target.clone(),
+                datapoints,
+            });
+        }
+    }
+    Some(series)
+}
+
+/// Alerts fired within the range, filtered by the annotation's `query` if given.
+fn alert_annotations(request: AnnotationsRequest) -> Option<Vec<Annotation>> {
+    let from = parse_timestamp(&request.range.from)?;
+    let to = parse_timestamp(&request.range.to)?;
+    let query = request
+        .annotation
+        .get("query")
+        .and_then(|query| query.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    Some(
+        get_alerts_json()
+            .recent
+            .into_iter()
+            .filter(|alert| (from..=to).contains(&alert.timestamp_ms))
+            .filter(|alert| alert.rule.contains(&query) || alert.channel_label.contains(&query))
+            .map(|alert| Annotation {
+                annotation: request.annotation.clone(),
+                time: alert.timestamp_ms,
+                title: alert.rule.clone(),
+                text: format!(
+                    "{} {} ({} queued)",
+                    alert.channel_label, alert.condition, alert.queued
+                ),
+                tags: vec![alert.channel_label, alert.state.to_string()],
+            })
+            .collect(),
+    )
+}
+
+/// Parses UTC timestamps like `2024-01-31T06:33:44.866Z`, as sent by Grafana, into Unix
+/// milliseconds.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':').map(|part| part.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+    if !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let millis: i64 = format!("{:0<3}", &fraction[..fraction.len().min(3)])
+        .parse()
+        .ok()?;
+
+    let secs = days_from_civil(year, month, day) * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    u64::try_from(secs * 1_000 + millis).ok()
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
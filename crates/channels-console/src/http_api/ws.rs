@@ -0,0 +1,158 @@
+//! `/ws` endpoint, pushing stats and new log entries over a WebSocket.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tiny_http::{Header, Request, Response};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use super::{respond_error, StatsDiff};
+use crate::{
+    ChannelLogs, LogEntry, StreamLogs, WsMessage, CHANNELS_STATE, SCHEMA_VERSION, STREAMS_STATE,
+};
+
+/// Upgrades the request and pushes [`WsMessage`]s until the client disconnects.
+///
+/// The socket is push-only, messages sent by the client are ignored.
+pub(super) fn serve(request: Request, interval: Duration, prefix: Option<String>) {
+    let key = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Sec-WebSocket-Key"))
+        .map(|header| derive_accept_key(header.value.as_bytes()));
+    let Some(accept) = key else {
+        return respond_error(request, 400, "Expected a WebSocket upgrade request");
+    };
+
+    let response = Response::empty(101).with_header(
+        Header::from_bytes(b"Sec-WebSocket-Accept".as_slice(), accept.as_bytes()).unwrap(),
+    );
+    let stream = request.upgrade("websocket", response);
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+
+    let mut diff = StatsDiff::default();
+    let mut cursors = LogCursors::default();
+    let mut first = true;
+
+    loop {
+        let combined = diff.next(prefix.as_deref());
+        let mut messages = Vec::new();
+        if first || !combined.channels.is_empty() || !combined.streams.is_empty() {
+            let channel_ids: Vec<u64> = combined.channels.iter().map(|c| c.id).collect();
+            let stream_ids: Vec<u64> = combined.streams.iter().map(|s| s.id).collect();
+
+            messages.push(if first {
+                WsMessage::Snapshot(combined)
+            } else {
+                WsMessage::Update(combined)
+            });
+            messages.extend(
+                cursors
+                    .new_channel_logs(&channel_ids)
+                    .into_iter()
+                    .map(WsMessage::ChannelLogs),
+            );
+            messages.extend(
+                cursors
+                    .new_stream_logs(&stream_ids)
+                    .into_iter()
+                    .map(WsMessage::StreamLogs),
+            );
+        }
+
+        let sent = if messages.is_empty() {
+            // Detects disconnected clients
+            socket.send(Message::Ping(Default::default()))
+        } else {
+            messages.iter().try_for_each(|message| {
+                let json = serde_json::to_string(message).expect("WsMessage is serializable");
+                socket.send(Message::text(json))
+            })
+        };
+        if sent.is_err() {
+            return;
+        }
+
+        first = false;
+        std::thread::sleep(interval);
+    }
+}
+
+/// Index of the last log entry pushed, per channel and stream.
+#[derive(Default)]
+struct LogCursors {
+    /// (sent, received) log index per channel
+    channels: HashMap<u64, (u64, u64)>,
+    streams: HashMap<u64, u64>,
+}
+
+impl LogCursors {
+    fn new_channel_logs(&mut self, ids: &[u64]) -> Vec<ChannelLogs> {
+        let Some((_, stats_map)) = CHANNELS_STATE.get() else {
+            return Vec::new();
+        };
+
+        ids.iter()
+            .filter_map(|id| {
+                let (sent_cursor, received_cursor) = self.channels.entry(*id).or_default();
+                let (sent_logs, received_logs) = stats_map.get(*id, |stats| {
+                    let messages = stats.counters.messages();
+                    (
+                        entries_after(messages.sent_logs.iter(), sent_cursor),
+                        entries_after(messages.received_logs.iter(), received_cursor),
+                    )
+                })?;
+                if sent_logs.is_empty() && received_logs.is_empty() {
+                    return None;
+                }
+
+                Some(ChannelLogs {
+                    schema_version: SCHEMA_VERSION,
+                    id: id.to_string(),
+                    sent_logs,
+                    received_logs,
+                    next_cursor: None,
+                })
+            })
+            .collect()
+    }
+
+    fn new_stream_logs(&mut self, ids: &[u64]) -> Vec<StreamLogs> {
+        let Some((_, stats_map)) = STREAMS_STATE.get() else {
+            return Vec::new();
+        };
+
+        ids.iter()
+            .filter_map(|id| {
+                let cursor = self.streams.entry(*id).or_default();
+                let logs = stats_map.get(*id, |stats| entries_after(stats.logs.iter(), cursor))?;
+                if logs.is_empty() {
+                    return None;
+                }
+
+                Some(StreamLogs {
+                    schema_version: SCHEMA_VERSION,
+                    id: id.to_string(),
+                    logs,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Log entries with an index above the cursor, advancing the cursor past them.
+fn entries_after<'a>(
+    entries: impl Iterator<Item = &'a LogEntry>,
+    cursor: &mut u64,
+) -> Vec<LogEntry> {
+    let new: Vec<LogEntry> = entries
+        .filter(|entry| entry.index > *cursor)
+        .cloned()
+        .collect();
+    if let Some(last) = new.last() {
+        *cursor = last.index;
+    }
+    new
+}
@@ -0,0 +1,187 @@
+//! Health and build information of the instrumented process.
+//!
+//! `/healthz` answers liveness probes of orchestrators, and `/info` tells the TUI which process
+//! and library version it's connected to, so a CLI and library version mismatch is reported
+//! instead of surfacing as missing data. `/version` lets clients check they can read the
+//! payloads before fetching them.
+//!
+//! `/info` and every snapshot also carry a [`ProcessJson`], telling apart several instrumented
+//! services running on one host. Name them with
+//! [`ChannelsGuardBuilder::service_name`](crate::ChannelsGuardBuilder::service_name).
+
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+
+use crate::{
+    collector_backlog, now, start_unix_ms, MIN_CLIENT_SCHEMA_VERSION, SCHEMA_VERSION, START_TIME,
+};
+
+/// Cargo features the library was built with.
+const FEATURES: &[(&str, bool)] = &[
+    ("tokio", cfg!(feature = "tokio")),
+    ("futures", cfg!(feature = "futures")),
+    ("crossbeam", cfg!(feature = "crossbeam")),
+    ("ws", cfg!(feature = "ws")),
+    ("tracing", cfg!(feature = "tracing")),
+];
+
+/// Wrapper for the `/healthz` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Always `ok`, the server only responds while the process is alive
+    pub status: String,
+    /// Time since the first instrumented channel or stream was created
+    pub uptime_ns: u64,
+    /// Events waiting for the collector threads, growing when they can't keep up
+    pub collector_backlog: usize,
+}
+
+/// Wrapper for the `/info` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfoJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Version of the channels-console library
+    pub version: String,
+    /// Enabled Cargo features of the library
+    pub features: Vec<String>,
+    /// Executable name of the process
+    pub process: String,
+    pub pid: u32,
+    /// Wall-clock time the first instrumented channel or stream was created, in Unix
+    /// milliseconds
+    pub start_time_ms: u64,
+    /// Host name of the machine, empty if it can't be determined
+    #[serde(default)]
+    pub hostname: String,
+    /// Name given with [`ChannelsGuardBuilder::service_name`](crate::ChannelsGuardBuilder::service_name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_name: Option<String>,
+}
+
+/// Process the statistics come from, included in `/info` and in every snapshot
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessJson {
+    /// Executable name of the process
+    pub process: String,
+    pub pid: u32,
+    /// Host name of the machine, empty if it can't be determined
+    pub hostname: String,
+    /// Name given with [`ChannelsGuardBuilder::service_name`](crate::ChannelsGuardBuilder::service_name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_name: Option<String>,
+}
+
+impl InfoJson {
+    /// Process metadata of the response, as included in snapshots
+    pub fn process_json(&self) -> ProcessJson {
+        ProcessJson {
+            process: self.process.clone(),
+            pid: self.pid,
+            hostname: self.hostname.clone(),
+            service_name: self.service_name.clone(),
+        }
+    }
+}
+
+/// Wrapper for the `/version` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Oldest schema version clients have to understand, see [`MIN_CLIENT_SCHEMA_VERSION`]
+    pub min_client_schema_version: u32,
+    /// Version of the channels-console library
+    pub version: String,
+}
+
+static SERVICE_NAME: RwLock<Option<String>> = RwLock::new(None);
+
+/// Names the service in `/info` and in snapshots, see
+/// [`ChannelsGuardBuilder::service_name`](crate::ChannelsGuardBuilder::service_name).
+pub(crate) fn set_service_name(name: String) {
+    *SERVICE_NAME.write().unwrap() = Some(name);
+}
+
+/// Executable name and host name, which don't change while the process runs
+fn process_names() -> &'static (String, String) {
+    static NAMES: OnceLock<(String, String)> = OnceLock::new();
+    NAMES.get_or_init(|| {
+        let process = std::env::current_exe()
+            .ok()
+            .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        (process, hostname())
+    })
+}
+
+/// Host name from the environment or the system files, as std has no portable API for it
+fn hostname() -> String {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .chain(
+            ["/proc/sys/kernel/hostname", "/etc/hostname"]
+                .iter()
+                .filter_map(|path| std::fs::read_to_string(path).ok()),
+        )
+        .map(|name| name.trim().to_string())
+        .find(|name| !name.is_empty())
+        .unwrap_or_default()
+}
+
+pub(crate) fn get_process_json() -> ProcessJson {
+    let (process, hostname) = process_names();
+    ProcessJson {
+        process: process.clone(),
+        pid: std::process::id(),
+        hostname: hostname.clone(),
+        service_name: SERVICE_NAME.read().unwrap().clone(),
+    }
+}
+
+fn uptime_ns() -> u64 {
+    now()
+        .duration_since(*START_TIME.get_or_init(now))
+        .as_nanos() as u64
+}
+
+pub(crate) fn get_health_json() -> HealthJson {
+    HealthJson {
+        schema_version: SCHEMA_VERSION,
+        status: "ok".to_string(),
+        uptime_ns: uptime_ns(),
+        collector_backlog: collector_backlog(),
+    }
+}
+
+pub(crate) fn get_version_json() -> VersionJson {
+    VersionJson {
+        schema_version: SCHEMA_VERSION,
+        min_client_schema_version: MIN_CLIENT_SCHEMA_VERSION,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
+pub(crate) fn get_info_json() -> InfoJson {
+    let process = get_process_json();
+    InfoJson {
+        schema_version: SCHEMA_VERSION,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(feature, _)| feature.to_string())
+            .collect(),
+        process: process.process,
+        pid: process.pid,
+        start_time_ms: start_unix_ms(),
+        hostname: process.hostname,
+        service_name: process.service_name,
+    }
+}
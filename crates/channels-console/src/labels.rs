@@ -0,0 +1,78 @@
+//! Hierarchical labels.
+//!
+//! Labels can be structured as `/`-separated paths, e.g. `ingest/parser/retries`. Channels can
+//! then be filtered by a path prefix, or collapsed into groups at a given depth.
+
+use serde::{Deserialize, Serialize};
+
+use crate::SerializableChannelStats;
+
+/// Separator of label path segments.
+pub const LABEL_SEPARATOR: char = '/';
+
+/// Returns true if `label` is `prefix` or nested under it.
+///
+/// Matching is segment-aware: `ingest` matches `ingest/parser`, but not `ingestion`.
+pub fn matches_prefix(label: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches(LABEL_SEPARATOR);
+    if prefix.is_empty() {
+        return true;
+    }
+    match label.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with(LABEL_SEPARATOR),
+        None => false,
+    }
+}
+
+/// Number of path segments in a label.
+pub fn label_depth(label: &str) -> usize {
+    label.split(LABEL_SEPARATOR).count()
+}
+
+/// Returns the first `level` segments of a label, e.g. `ingest/parser` for
+/// `ingest/parser/retries` at level 2. Labels with fewer segments are returned unchanged.
+pub fn collapse_label(label: &str, level: usize) -> &str {
+    match label
+        .match_indices(LABEL_SEPARATOR)
+        .nth(level.saturating_sub(1))
+    {
+        Some((idx, _)) if level > 0 => &label[..idx],
+        _ => label,
+    }
+}
+
+/// Aggregated statistics of channels sharing a label prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelGroup {
+    pub label: String,
+    pub channels: usize,
+    pub sent_count: u64,
+    pub received_count: u64,
+    pub queued: u64,
+    pub queued_bytes: u64,
+}
+
+/// Collapses channels into groups of labels truncated to `level` segments, sorted by label.
+pub fn group_by_level(stats: &[SerializableChannelStats], level: usize) -> Vec<LabelGroup> {
+    let mut groups: Vec<LabelGroup> = Vec::new();
+    for channel in stats {
+        let label = collapse_label(&channel.label, level);
+        let group = match groups.iter().position(|group| group.label == label) {
+            Some(idx) => &mut groups[idx],
+            None => {
+                groups.push(LabelGroup {
+                    label: label.to_string(),
+                    ..Default::default()
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+        group.channels += 1;
+        group.sent_count += channel.sent_count;
+        group.received_count += channel.received_count;
+        group.queued += channel.queued;
+        group.queued_bytes += channel.queued_bytes;
+    }
+    groups.sort_by(|a, b| a.label.cmp(&b.label));
+    groups
+}
@@ -0,0 +1,254 @@
+//! Send to receive latency percentiles.
+//!
+//! Messages leave a channel in the order they entered it, so the n-th received message is
+//! paired with the n-th sent one. At most 4096 send timestamps are kept per channel. Messages
+//! sent beyond that share the timestamp of the newest kept one, so a deep queue doesn't grow
+//! memory, at the cost of overestimating their latency. Latencies are recorded in a log-linear
+//! histogram (HDR-style, 16 linear sub-buckets per power of two), so percentiles have a
+//! relative error of at most 1/16.
+//!
+//! Timestamps are paired behind a per-channel lock, so with latency tracking on (the default)
+//! every send and receive takes it. Use [`disable_latency`](crate::ConfigBuilder::disable_latency)
+//! to keep the hot path atomic only.
+//!
+//! The full distribution is served by `/channels/<id>/latency_histogram`, merged into
+//! power-of-two buckets.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::{CHANNELS_STATE, SCHEMA_VERSION};
+
+const SUB_BUCKET_BITS: u32 = 4;
+const SUB_BUCKETS: usize = 1 << SUB_BUCKET_BITS;
+const BUCKETS: usize = (64 - SUB_BUCKET_BITS as usize + 1) * SUB_BUCKETS;
+
+/// Max number of distinct send timestamps kept for the messages in flight of a channel.
+pub(crate) const MAX_PENDING: usize = 4096;
+
+/// Send to receive latency percentiles, in nanoseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+    /// Number of latency samples
+    pub count: u64,
+}
+
+/// Number of latency samples between `lower_ns` and `upper_ns`, inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    pub lower_ns: u64,
+    pub upper_ns: u64,
+    pub count: u64,
+}
+
+/// Wrapper for the `/channels/<id>/latency_histogram` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyHistogramJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    pub id: String,
+    /// Number of latency samples
+    pub count: u64,
+    /// Power-of-two buckets from the lowest to the highest non-empty one, empty ones in
+    /// between included
+    pub buckets: Vec<LatencyBucket>,
+}
+
+/// Streaming log-linear histogram of nanosecond values.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatencyHistogram {
+    /// Allocated on the first sample
+    counts: Vec<u64>,
+    total: u64,
+    max: u64,
+}
+
+impl LatencyHistogram {
+    pub(crate) fn record(&mut self, value_ns: u64) {
+        if self.counts.is_empty() {
+            self.counts = vec![0; BUCKETS];
+        }
+        self.counts[bucket_index(value_ns)] += 1;
+        self.total += 1;
+        self.max = self.max.max(value_ns);
+    }
+
+    /// Smallest recorded value that `quantile` of samples are at or below, within bucket precision.
+    pub(crate) fn value_at_quantile(&self, quantile: f64) -> u64 {
+        if self.total == 0 {
+            return 0;
+        }
+        let target = ((quantile * self.total as f64).ceil() as u64).clamp(1, self.total);
+        let mut seen = 0;
+        for (idx, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return bucket_upper_bound(idx).min(self.max);
+            }
+        }
+        self.max
+    }
+
+    pub(crate) fn percentiles(&self) -> Option<LatencyPercentiles> {
+        (self.total > 0).then(|| LatencyPercentiles {
+            p50: self.value_at_quantile(0.50),
+            p95: self.value_at_quantile(0.95),
+            p99: self.value_at_quantile(0.99),
+            max: self.max,
+            count: self.total,
+        })
+    }
+
+    /// Counts merged into power-of-two buckets, see [`LatencyHistogramJson::buckets`].
+    pub(crate) fn buckets(&self) -> Vec<LatencyBucket> {
+        let mut buckets: Vec<LatencyBucket> = self
+            .counts
+            .chunks(SUB_BUCKETS)
+            .enumerate()
+            .map(|(group, counts)| LatencyBucket {
+                lower_ns: bucket_lower_bound(group * SUB_BUCKETS),
+                upper_ns: bucket_upper_bound((group + 1) * SUB_BUCKETS - 1),
+                count: counts.iter().sum(),
+            })
+            .collect();
+
+        let Some(last) = buckets.iter().rposition(|bucket| bucket.count > 0) else {
+            return Vec::new();
+        };
+        let first = buckets
+            .iter()
+            .position(|bucket| bucket.count > 0)
+            .unwrap_or(0);
+        buckets.truncate(last + 1);
+        buckets.drain(..first);
+        buckets
+    }
+}
+
+fn bucket_index(value: u64) -> usize {
+    if value < SUB_BUCKETS as u64 {
+        return value as usize;
+    }
+    let exponent = 63 - value.leading_zeros();
+    let shift = exponent - SUB_BUCKET_BITS;
+    let sub_bucket = ((value >> shift) as usize) & (SUB_BUCKETS - 1);
+    (shift as usize + 1) * SUB_BUCKETS + sub_bucket
+}
+
+fn bucket_lower_bound(idx: usize) -> u64 {
+    if idx < SUB_BUCKETS {
+        return idx as u64;
+    }
+    let shift = (idx / SUB_BUCKETS - 1) as u32;
+    let sub_bucket = (idx % SUB_BUCKETS) as u64;
+    (SUB_BUCKETS as u64 + sub_bucket) << shift
+}
+
+fn bucket_upper_bound(idx: usize) -> u64 {
+    if idx < SUB_BUCKETS {
+        return idx as u64;
+    }
+    let shift = (idx / SUB_BUCKETS - 1) as u32;
+    let sub_bucket = (idx % SUB_BUCKETS) as u64;
+    let lower = (SUB_BUCKETS as u64 + sub_bucket) << shift;
+    lower.saturating_add((1u64 << shift) - 1)
+}
+
+/// Pairs send and receive timestamps of a channel and records their latencies.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatencyTracker {
+    /// Send timestamps of messages not received yet, oldest first, with the number of
+    /// messages sharing each of them
+    pending: VecDeque<(Instant, u64)>,
+    /// Number of messages in `pending`
+    in_flight: u64,
+    /// Receive events applied before their matching send event
+    early_receives: u64,
+    pub(crate) histogram: LatencyHistogram,
+}
+
+impl LatencyTracker {
+    pub(crate) fn on_sent(&mut self, at: Instant) {
+        // The forwarders report independently, so a receive may be applied first
+        if self.early_receives > 0 {
+            self.early_receives -= 1;
+        } else {
+            self.push_pending(at, 1);
+        }
+    }
+
+    fn push_pending(&mut self, at: Instant, count: u64) {
+        self.in_flight += count;
+        let full = self.pending.len() >= MAX_PENDING;
+        match self.pending.back_mut() {
+            Some((_, shared)) if full => *shared += count,
+            _ => self.pending.push_back((at, count)),
+        }
+    }
+
+    /// Removes up to `count` of the oldest messages in flight, returning the send timestamp of
+    /// the last one removed.
+    fn pop_pending(&mut self, mut count: u64) -> Option<Instant> {
+        let mut sent_at = None;
+        while count > 0 {
+            let Some((at, shared)) = self.pending.front_mut() else {
+                break;
+            };
+            let removed = count.min(*shared);
+            sent_at = Some(*at);
+            *shared -= removed;
+            if *shared == 0 {
+                self.pending.pop_front();
+            }
+            self.in_flight -= removed;
+            count -= removed;
+        }
+        sent_at
+    }
+
+    /// Matches the pending send timestamps to `in_flight` messages after some were sent or
+    /// received without being recorded. The oldest timestamps belong to messages received in
+    /// the meantime, while messages sent in the meantime are assumed to be sent `at`.
+    pub(crate) fn resync(&mut self, in_flight: usize, at: Instant) {
+        self.early_receives = 0;
+        let in_flight = in_flight as u64;
+        if self.in_flight > in_flight {
+            self.pop_pending(self.in_flight - in_flight);
+        } else if self.in_flight < in_flight {
+            self.push_pending(at, in_flight - self.in_flight);
+        }
+    }
+
+    pub(crate) fn on_received(&mut self, at: Instant) {
+        match self.pop_pending(1) {
+            Some(sent_at) => self
+                .histogram
+                .record(at.saturating_duration_since(sent_at).as_nanos() as u64),
+            None => {
+                self.early_receives += 1;
+                self.histogram.record(0);
+            }
+        }
+    }
+}
+
+pub(crate) fn get_latency_histogram(channel_id: &str) -> Option<LatencyHistogramJson> {
+    let id = channel_id.parse::<u64>().ok()?;
+    let (_, stats_map) = CHANNELS_STATE.get()?;
+    stats_map.get(id, |channel_stats| {
+        let messages = channel_stats.counters.messages();
+        let histogram = &messages.latency.histogram;
+        LatencyHistogramJson {
+            schema_version: SCHEMA_VERSION,
+            id: channel_id.to_string(),
+            count: histogram.total,
+            buckets: histogram.buckets(),
+        }
+    })
+}
@@ -1,38 +1,168 @@
-use crossbeam_channel::{unbounded, Sender as CbSender};
+#![cfg_attr(std_mpmc, feature(mpmc_channel))]
+
+use crossbeam_channel::{unbounded, RecvTimeoutError, Sender as CbSender};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
-use std::sync::atomic::AtomicU64;
-use std::sync::{Arc, OnceLock, RwLock};
-use std::time::Instant;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, LazyLock, Mutex, Once, OnceLock};
+use std::time::{Duration, Instant};
 
+pub mod alerts;
+pub use alerts::on_alert;
 pub mod channels_guard;
-pub use channels_guard::{ChannelsGuard, ChannelsGuardBuilder};
-
+pub mod clock;
+pub use channels_guard::{ChannelsGuard, ChannelsGuardBuilder, Output};
+pub mod config;
+pub mod consumers;
+pub mod control;
+pub use config::{Config, ConfigBuilder};
+pub use control::{pause, reset, resume};
+pub mod actors;
+pub mod correlation;
+mod counters;
+pub mod delta;
+pub mod diff;
+#[cfg(any(feature = "tokio", feature = "futures", feature = "crossbeam"))]
+mod forwarders;
+pub mod groups;
+pub mod handle;
+mod handles;
+pub use handle::ChannelsHandle;
+pub use registry::register_label;
+
+use crate::alerts::AlertEngine;
+use crate::config::redact;
+use crate::consumers::ConsumerStats;
+use crate::correlation::CorrelationFn;
+use crate::counters::ChannelCounters;
+use crate::handles::Handles;
+use crate::history::spawn_history_recorder;
 use crate::http_api::start_metrics_server;
+use crate::info::{get_process_json, ProcessJson};
+use crate::latency::LatencyPercentiles;
+use crate::producers::ProducerStats;
+use crate::rates::{RateStatus, RateTracker, RateWindows, EXPECTED_RATE_WINDOW};
+use crate::recording::spawn_configured_recorder;
+use crate::retention::{closed_total, evict_closed_channels, ClosedTotal};
+use crate::samples::{record_samples, ChannelSample, SAMPLE_INTERVAL};
+use crate::shards::Shards;
+pub mod history;
 mod http_api;
+pub mod info;
+pub mod labels;
+pub mod latency;
+#[cfg(feature = "tokio")]
+pub mod primitives;
+pub mod producers;
+pub mod rates;
+pub mod recording;
+pub mod registry;
+pub mod report;
+pub mod retention;
+pub mod samples;
+mod shards;
+mod sink_wrappers;
+pub mod sinks;
+pub mod sizes;
 mod stream_wrappers;
+pub mod summary;
+pub mod tags;
+pub mod testing;
+pub use testing::{AssertionError, Assertions};
+pub mod topology;
+#[cfg(feature = "tracing")]
+mod trace;
 mod wrappers;
+#[cfg(feature = "tokio")]
+pub use wrappers::tokio_inline::{
+    InstrumentedOwnedPermit, InstrumentedPermit, InstrumentedPermitIterator, InstrumentedReceiver,
+    InstrumentedSender, InstrumentedUnboundedReceiver, InstrumentedUnboundedSender,
+    InstrumentedWeakSender, InstrumentedWeakUnboundedSender,
+};
 
 /// A single log entry for a message sent or received.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogEntry {
     pub index: u64,
+    /// Nanoseconds since the first instrumented channel or stream was created
     pub timestamp: u64,
+    /// Wall-clock time in Unix milliseconds, `0` for entries of servers predating it
+    #[serde(default)]
+    pub unix_ms: u64,
     pub message: Option<String>,
+    /// Correlation id of the message, for channels instrumented with `correlate = true`
+    #[serde(default)]
+    pub correlation_id: Option<u64>,
+    /// Id of the `tracing` span the message was sent or received in, with the `tracing` feature
+    #[serde(default)]
+    pub span_id: Option<u64>,
+    /// Message as a JSON value, for channels instrumented with `log = json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json: Option<serde_json::Value>,
 }
 
 impl LogEntry {
-    pub(crate) fn new(index: u64, timestamp: Instant, message: Option<String>) -> Self {
+    pub(crate) fn new(
+        index: u64,
+        timestamp: Instant,
+        message: Option<String>,
+        correlation_id: Option<u64>,
+    ) -> Self {
         let start_time = START_TIME.get().copied().unwrap_or(timestamp);
         let timestamp_nanos = timestamp.duration_since(start_time).as_nanos() as u64;
         Self {
             index,
             timestamp: timestamp_nanos,
+            unix_ms: start_unix_ms() + timestamp_nanos / 1_000_000,
             message,
+            correlation_id,
+            #[cfg(feature = "tracing")]
+            span_id: trace::current_span_id(),
+            #[cfg(not(feature = "tracing"))]
+            span_id: None,
+            json: None,
+        }
+    }
+
+    pub(crate) fn with_json(mut self, json: Option<serde_json::Value>) -> Self {
+        self.json = json;
+        self
+    }
+
+    /// Value of the JSON message at a dot-separated `path` (e.g. `user.id`, array elements by
+    /// index), `None` for messages not logged with `log = json`.
+    pub fn json_field(&self, path: &str) -> Option<&serde_json::Value> {
+        // Keys may contain the `~` and `/` of JSON pointers, escaped as in RFC 6901
+        let pointer: String = path
+            .split('.')
+            .map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+            .collect();
+        self.json.as_ref()?.pointer(&pointer)
+    }
+}
+
+/// A change of the state of a channel.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StateTransition {
+    /// State the channel entered
+    pub state: ChannelState,
+    /// Nanoseconds since the first instrumented channel was created, like [`LogEntry::timestamp`]
+    pub timestamp: u64,
+}
+
+impl StateTransition {
+    pub(crate) fn new(state: ChannelState, timestamp: Instant) -> Self {
+        let start_time = START_TIME.get().copied().unwrap_or(timestamp);
+        Self {
+            state,
+            timestamp: timestamp.duration_since(start_time).as_nanos() as u64,
         }
     }
 }
 
+/// Number of state transitions kept per channel.
+const STATE_HISTORY_LIMIT: usize = 32;
+
 /// Type of a channel.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChannelType {
@@ -92,6 +222,14 @@ pub enum Format {
     Table,
     Json,
     JsonPretty,
+    /// One JSON object per line for each channel and stream, tagged with its `kind`
+    JsonLines,
+    /// One row per channel and stream, with a header row
+    Csv,
+    /// Report with summary, channels and streams tables, e.g. for a PR description
+    Markdown,
+    /// Self-contained HTML page of the `Markdown` report, e.g. for a CI artifact
+    Html,
 }
 
 /// State of a instrumented channel.
@@ -102,6 +240,11 @@ pub enum ChannelState {
     Closed,
     Full,
     Notified,
+    /// Stream that yielded no item for the [stall timeout](ConfigBuilder::stream_stall_timeout)
+    Stalled,
+    /// State sent by a newer server that this version doesn't know, so adding states doesn't
+    /// break older clients
+    Unknown,
 }
 
 impl std::fmt::Display for ChannelState {
@@ -117,6 +260,8 @@ impl ChannelState {
             ChannelState::Closed => "closed",
             ChannelState::Full => "full",
             ChannelState::Notified => "notified",
+            ChannelState::Stalled => "stalled",
+            ChannelState::Unknown => "unknown",
         }
     }
 }
@@ -141,7 +286,8 @@ impl<'de> Deserialize<'de> for ChannelState {
             "closed" => Ok(ChannelState::Closed),
             "full" => Ok(ChannelState::Full),
             "notified" => Ok(ChannelState::Notified),
-            _ => Err(serde::de::Error::custom("invalid channel state")),
+            "stalled" => Ok(ChannelState::Stalled),
+            _ => Ok(ChannelState::Unknown),
         }
     }
 }
@@ -156,22 +302,81 @@ pub(crate) struct ChannelStats {
     pub(crate) state: ChannelState,
     pub(crate) sent_count: u64,
     pub(crate) received_count: u64,
+    /// Cumulative size of sent messages, see [`sizes`]
+    pub(crate) bytes_sent: u64,
+    /// Cumulative size of received messages
+    pub(crate) bytes_received: u64,
     pub(crate) type_name: &'static str,
     pub(crate) type_size: usize,
-    pub(crate) sent_logs: VecDeque<LogEntry>,
-    pub(crate) received_logs: VecDeque<LogEntry>,
     pub(crate) iter: u32,
+    /// Updated in place by the wrappers, see [`counters`]
+    pub(crate) counters: Arc<ChannelCounters>,
+    pub(crate) sends: RateTracker,
+    pub(crate) receives: RateTracker,
+    /// Bytes of sent messages, see [`sizes`]
+    pub(crate) bytes_sends: RateTracker,
+    /// Bytes of received messages
+    pub(crate) bytes_receives: RateTracker,
+    /// Expected messages per second, see [`rates`]
+    pub(crate) expected_rate: Option<f64>,
+    /// Whether messages pass through a proxy channel, which holds one extra message in flight
+    pub(crate) proxied: bool,
+    /// Tags declared with `tags = [...]`, see [`tags`]
+    pub(crate) tags: Vec<String>,
+    /// Cumulative time sends spent waiting for capacity
+    pub(crate) blocked_total: Duration,
+    /// Longest time a single send spent waiting for capacity
+    pub(crate) blocked_max: Duration,
+    /// Messages rejected because the channel was full
+    pub(crate) send_failures_full: u64,
+    /// Messages rejected because the channel was closed
+    pub(crate) send_failures_closed: u64,
+    /// Most recent state changes, oldest first, starting with the initial `Active` state
+    pub(crate) state_history: VecDeque<StateTransition>,
+    /// Sender clones wrapped with `sender!`, in order of registration
+    pub(crate) producers: Vec<ProducerStats>,
+    /// Receiver clones wrapped with `receiver!`, in order of registration
+    pub(crate) consumers: Vec<ConsumerStats>,
+    /// `None` if the channel type doesn't expose its handle counts
+    pub(crate) handles: Option<Arc<Handles>>,
+    /// Periodic samples, oldest first, see [`samples`]
+    pub(crate) samples: VecDeque<ChannelSample>,
+    /// Time since program start of the last change of the counters or state, see [`delta`]
+    pub(crate) changed_at_ns: u64,
+    /// Counters after each of their most recent changes, oldest first, see [`delta`]
+    pub(crate) checkpoints: VecDeque<ChannelSample>,
+    /// Time since program start the channel was created at
+    pub(crate) created_ns: u64,
+    /// Time since program start of the last send, as of the collector tick that counted it
+    pub(crate) last_send_ns: Option<u64>,
+    /// Time since program start of the last receive, as of the collector tick that counted it
+    pub(crate) last_recv_ns: Option<u64>,
 }
 
 impl ChannelStats {
     pub fn queued(&self) -> u64 {
-        self.sent_count
-            .saturating_sub(self.received_count)
-            .saturating_sub(1)
+        let queued = self.sent_count.saturating_sub(self.received_count);
+        if self.proxied {
+            queued.saturating_sub(1)
+        } else {
+            queued
+        }
     }
 
+    /// Memory held by queued messages. Channels declared with `size = true` or `size_with`
+    /// report their measured sizes, others `size_of::<T>()` per message.
     pub fn queued_bytes(&self) -> u64 {
-        self.queued() * self.type_size as u64
+        if !self.counters.measures_size() {
+            return self.queued() * self.type_size as u64;
+        }
+        let in_flight = self.sent_count.saturating_sub(self.received_count);
+        let bytes = self.bytes_sent.saturating_sub(self.bytes_received);
+        if self.proxied && in_flight > 0 {
+            // The message held by the proxy is assumed to be of average size
+            bytes - bytes / in_flight
+        } else {
+            bytes
+        }
     }
 }
 
@@ -181,28 +386,79 @@ pub(crate) struct StreamStats {
     pub(crate) id: u64,
     pub(crate) source: &'static str,
     pub(crate) label: Option<String>,
-    pub(crate) state: ChannelState, // Only Active or Closed
+    pub(crate) state: ChannelState, // Only Active or Closed, stalls are derived
     pub(crate) items_yielded: u64,
     pub(crate) type_name: &'static str,
     pub(crate) type_size: usize,
     pub(crate) logs: VecDeque<LogEntry>,
     pub(crate) iter: u32,
+    pub(crate) yields: RateTracker,
+    pub(crate) created_at: Instant,
+    pub(crate) last_yield_at: Option<Instant>,
+    /// Longest time between two consecutive items
+    pub(crate) max_gap: Duration,
+    pub(crate) polls: u64,
+    pub(crate) pending_polls: u64,
+    /// Total time from the first poll for an item until it was yielded
+    pub(crate) poll_wait: Duration,
+    pub(crate) max_poll_wait: Duration,
+    /// Whether the last poll returned `Pending`, so the consumer is waiting for an item
+    pub(crate) polling: bool,
 }
 
+/// Version of the JSON schema served by the metrics HTTP API.
+///
+/// Bumped when a payload gains an enum value, or a field is removed or retyped. Added
+/// optional fields and new endpoints don't bump it. Payloads produced by servers that predate
+/// versioning deserialize with `schema_version == 0`.
+///
+/// - 1: first versioned schema
+/// - 2: `stalled` channel state, unknown states decode as [`ChannelState::Unknown`]
+/// - 3: `semaphore` and `notify` objects in `/objects/:id`
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Oldest [`SCHEMA_VERSION`] a client has to understand to read the current payloads.
+///
+/// Bumped along with [`SCHEMA_VERSION`] when older clients can't decode the change, like a
+/// removed or retyped field, or an enum value they reject. Added fields keep it, as clients
+/// ignore fields they don't know, and so do new channel states since version 2.
+pub const MIN_CLIENT_SCHEMA_VERSION: u32 = 3;
+
 /// Wrapper for channels-only JSON response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelsJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
     /// Current elapsed time since program start in nanoseconds
     pub current_elapsed_ns: u64,
+    /// Wall-clock time of program start in Unix milliseconds, to convert the relative
+    /// timestamps. `0` for servers predating it.
+    #[serde(default)]
+    pub start_unix_ms: u64,
     /// Channel statistics
     pub channels: Vec<SerializableChannelStats>,
+    /// Counters of closed channels evicted from `channels`, see [`retention`]
+    #[serde(default)]
+    pub closed_total: ClosedTotal,
+    /// Id of the last listed channel, to pass as `after_index` for the next page. Only set for
+    /// paginated responses, see [`ChannelsJson::paginate`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<u64>,
 }
 
 /// Wrapper for streams-only JSON response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamsJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
     /// Current elapsed time since program start in nanoseconds
     pub current_elapsed_ns: u64,
+    /// Wall-clock time of program start in Unix milliseconds, to convert the relative
+    /// timestamps. `0` for servers predating it.
+    #[serde(default)]
+    pub start_unix_ms: u64,
     /// Stream statistics
     pub streams: Vec<SerializableStreamStats>,
 }
@@ -210,12 +466,69 @@ pub struct StreamsJson {
 /// Combined wrapper for both channels and streams JSON response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CombinedJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
     /// Current elapsed time since program start in nanoseconds
     pub current_elapsed_ns: u64,
+    /// Wall-clock time of program start in Unix milliseconds, to convert the relative
+    /// timestamps. `0` for servers predating it.
+    #[serde(default)]
+    pub start_unix_ms: u64,
     /// Channel statistics
     pub channels: Vec<SerializableChannelStats>,
     /// Stream statistics
     pub streams: Vec<SerializableStreamStats>,
+    /// Counters of closed channels evicted from `channels`, see [`retention`]
+    #[serde(default)]
+    pub closed_total: ClosedTotal,
+    /// Process the statistics come from
+    #[serde(default)]
+    pub process: ProcessJson,
+}
+
+impl ChannelsJson {
+    /// Keep only channels with labels under the given path prefix, see [`labels`].
+    pub fn retain_prefix(&mut self, prefix: &str) {
+        self.channels
+            .retain(|channel| labels::matches_prefix(&channel.label, prefix));
+    }
+
+    /// Keeps at most `limit` channels created after the one with id `after_index`, ordered by
+    /// id, and sets [`next_cursor`](Self::next_cursor) to continue from. Channel ids are
+    /// assigned in creation order, so pollers can fetch only channels they haven't seen yet.
+    pub fn paginate(&mut self, after_index: Option<u64>, limit: Option<usize>) {
+        if let Some(after) = after_index {
+            self.channels.retain(|channel| channel.id > after);
+        }
+        self.channels.sort_by_key(|channel| channel.id);
+        if let Some(limit) = limit {
+            self.channels.truncate(limit);
+        }
+        self.next_cursor = self
+            .channels
+            .last()
+            .map(|channel| channel.id)
+            .or(after_index);
+    }
+}
+
+impl StreamsJson {
+    /// Keep only streams with labels under the given path prefix, see [`labels`].
+    pub fn retain_prefix(&mut self, prefix: &str) {
+        self.streams
+            .retain(|stream| labels::matches_prefix(&stream.label, prefix));
+    }
+}
+
+impl CombinedJson {
+    /// Keep only channels and streams with labels under the given path prefix, see [`labels`].
+    pub fn retain_prefix(&mut self, prefix: &str) {
+        self.channels
+            .retain(|channel| labels::matches_prefix(&channel.label, prefix));
+        self.streams
+            .retain(|stream| labels::matches_prefix(&stream.label, prefix));
+    }
 }
 
 /// Serializable version of channel statistics for JSON responses.
@@ -233,7 +546,86 @@ pub struct SerializableChannelStats {
     pub type_name: String,
     pub type_size: usize,
     pub queued_bytes: u64,
+    /// Cumulative size of sent messages in bytes
+    #[serde(default)]
+    pub bytes_sent_total: u64,
+    /// Cumulative size of received messages in bytes
+    #[serde(default)]
+    pub bytes_recv_total: u64,
+    /// Whether byte counts are measured per message, declared with `size = true` or
+    /// `size_with`, rather than estimated from `type_size`
+    #[serde(default)]
+    pub measured_sizes: bool,
     pub iter: u32,
+    /// Expected messages per second, declared with `expected_rate`
+    #[serde(default)]
+    pub expected_rate: Option<f64>,
+    /// How the send rate compares to `expected_rate`
+    #[serde(default)]
+    pub rate_status: Option<RateStatus>,
+    /// Messages sent per second
+    #[serde(default)]
+    pub send_rate: RateWindows,
+    /// Messages received per second
+    #[serde(default)]
+    pub recv_rate: RateWindows,
+    /// Bytes sent per second
+    #[serde(default)]
+    pub send_byte_rate: RateWindows,
+    /// Bytes received per second
+    #[serde(default)]
+    pub recv_byte_rate: RateWindows,
+    /// Send to receive latency percentiles, `None` until a message is received
+    #[serde(default)]
+    pub latency: Option<LatencyPercentiles>,
+    /// Cumulative time senders spent blocked waiting for capacity, in nanoseconds
+    #[serde(default)]
+    pub blocked_ns: u64,
+    /// Longest time a single send spent blocked waiting for capacity, in nanoseconds
+    #[serde(default)]
+    pub max_blocked_ns: u64,
+    /// Messages rejected because the channel was full, e.g. by `try_send`. Only inline
+    /// channels see these, proxied channels reject them before the instrumentation.
+    #[serde(default)]
+    pub send_failures_full: u64,
+    /// Messages rejected, or dropped by the proxy, because the channel was closed
+    #[serde(default)]
+    pub send_failures_closed: u64,
+    /// Messages sent by each producer wrapped with `sender!`
+    #[serde(default)]
+    pub producers: Vec<ProducerStats>,
+    /// Messages received by each consumer wrapped with `receiver!`
+    #[serde(default)]
+    pub consumers: Vec<ConsumerStats>,
+    /// Number of sender clones still alive, `None` if the channel type doesn't expose it
+    #[serde(default)]
+    pub senders_alive: Option<u64>,
+    /// Number of receivers still alive, `None` if the channel type doesn't expose it
+    #[serde(default)]
+    pub receivers_alive: Option<u64>,
+    /// Description registered with [`register_label`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the custom label is also used by a channel instrumented elsewhere
+    #[serde(default)]
+    pub label_conflict: bool,
+    /// Tags declared with `tags = [...]`, see [`tags`]
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Time since program start the channel was created at, in nanoseconds
+    #[serde(default)]
+    pub created_ns: u64,
+    /// Time since program start of the last send, `None` if nothing was sent yet. Accurate to
+    /// the collector tick.
+    #[serde(default)]
+    pub last_send_ns: Option<u64>,
+    /// Time since program start of the last receive, `None` if nothing was received yet
+    #[serde(default)]
+    pub last_recv_ns: Option<u64>,
+    /// Time since the last send or receive, or since creation if there was none. Open channels
+    /// idle for long are a common symptom of leaked handles.
+    #[serde(default)]
+    pub idle_ns: u64,
 }
 
 /// Serializable version of stream statistics for JSON responses.
@@ -248,6 +640,36 @@ pub struct SerializableStreamStats {
     pub type_name: String,
     pub type_size: usize,
     pub iter: u32,
+    /// Items yielded per second over rolling windows
+    #[serde(default)]
+    pub yield_rate: RateWindows,
+    /// Time since the last item, or since creation if none was yielded yet
+    #[serde(default)]
+    pub idle_ns: u64,
+    /// Time since program start the stream was created at, in nanoseconds
+    #[serde(default)]
+    pub created_ns: u64,
+    /// Time since program start of the last item, `None` if none was yielded yet
+    #[serde(default)]
+    pub last_yield_ns: Option<u64>,
+    /// Longest time between two consecutive items
+    #[serde(default)]
+    pub max_gap_ns: u64,
+    /// Number of `poll_next` calls
+    #[serde(default)]
+    pub polls: u64,
+    /// Number of `poll_next` calls that returned `Pending`
+    #[serde(default)]
+    pub pending_polls: u64,
+    /// Total time from the first poll for an item until it was yielded. High values mean a
+    /// slow producer, while a stream idle with no pending poll isn't being polled at all.
+    #[serde(default)]
+    pub poll_wait_ns: u64,
+    #[serde(default)]
+    pub max_poll_wait_ns: u64,
+    /// Whether the consumer is waiting for an item, i.e. the last poll returned `Pending`
+    #[serde(default)]
+    pub polling: bool,
 }
 
 impl From<&ChannelStats> for SerializableChannelStats {
@@ -271,7 +693,46 @@ impl From<&ChannelStats> for SerializableChannelStats {
             type_name: channel_stats.type_name.to_string(),
             type_size: channel_stats.type_size,
             queued_bytes: channel_stats.queued_bytes(),
+            bytes_sent_total: channel_stats.bytes_sent,
+            bytes_recv_total: channel_stats.bytes_received,
+            measured_sizes: channel_stats.counters.measures_size(),
             iter: channel_stats.iter,
+            expected_rate: channel_stats.expected_rate,
+            rate_status: channel_stats.expected_rate.map(|expected| {
+                let actual = channel_stats.sends.rate(now(), EXPECTED_RATE_WINDOW);
+                RateStatus::evaluate(actual, expected)
+            }),
+            send_rate: channel_stats.sends.windows(now()),
+            recv_rate: channel_stats.receives.windows(now()),
+            send_byte_rate: channel_stats.bytes_sends.windows(now()),
+            recv_byte_rate: channel_stats.bytes_receives.windows(now()),
+            latency: channel_stats
+                .counters
+                .messages()
+                .latency
+                .histogram
+                .percentiles(),
+            blocked_ns: channel_stats.blocked_total.as_nanos() as u64,
+            max_blocked_ns: channel_stats.blocked_max.as_nanos() as u64,
+            send_failures_full: channel_stats.send_failures_full,
+            send_failures_closed: channel_stats.send_failures_closed,
+            producers: channel_stats.producers.clone(),
+            consumers: channel_stats.consumers.clone(),
+            senders_alive: channel_stats.handles.as_ref().map(|h| h.senders_alive()),
+            receivers_alive: channel_stats.handles.as_ref().map(|h| h.receivers_alive()),
+            description: channel_stats
+                .label
+                .as_deref()
+                .and_then(registry::label_description),
+            label_conflict: channel_stats
+                .label
+                .as_deref()
+                .is_some_and(registry::has_label_conflict),
+            tags: channel_stats.tags.clone(),
+            created_ns: channel_stats.created_ns,
+            last_send_ns: channel_stats.last_send_ns,
+            last_recv_ns: channel_stats.last_recv_ns,
+            idle_ns: channel_stats.idle_ns(elapsed_ns()),
         }
     }
 }
@@ -284,47 +745,169 @@ impl From<&StreamStats> for SerializableStreamStats {
             stream_stats.iter,
         );
 
+        let now = now();
+
         Self {
             id: stream_stats.id,
             source: stream_stats.source.to_string(),
             label,
             has_custom_label: stream_stats.label.is_some(),
-            state: stream_stats.state,
+            state: stream_stats.state_at(now),
             items_yielded: stream_stats.items_yielded,
             type_name: stream_stats.type_name.to_string(),
             type_size: stream_stats.type_size,
             iter: stream_stats.iter,
+            yield_rate: stream_stats.yields.windows(now),
+            idle_ns: stream_stats.idle(now).as_nanos() as u64,
+            created_ns: elapsed_ns_at(stream_stats.created_at),
+            last_yield_ns: stream_stats.last_yield_at.map(elapsed_ns_at),
+            max_gap_ns: stream_stats.max_gap.as_nanos() as u64,
+            polls: stream_stats.polls,
+            pending_polls: stream_stats.pending_polls,
+            poll_wait_ns: stream_stats.poll_wait.as_nanos() as u64,
+            max_poll_wait_ns: stream_stats.max_poll_wait.as_nanos() as u64,
+            polling: stream_stats.polling,
         }
     }
 }
 
 impl ChannelStats {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         id: u64,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         channel_type: ChannelType,
         type_name: &'static str,
         type_size: usize,
         iter: u32,
+        counters: Arc<ChannelCounters>,
     ) -> Self {
+        let created_ns = elapsed_ns();
         Self {
             id,
             source,
-            label,
+            label: options.label,
             channel_type,
             state: ChannelState::default(),
             sent_count: 0,
             received_count: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
             type_name,
             type_size,
-            sent_logs: VecDeque::new(),
-            received_logs: VecDeque::new(),
             iter,
+            counters,
+            sends: RateTracker::new(now()),
+            receives: RateTracker::new(now()),
+            bytes_sends: RateTracker::new(now()),
+            bytes_receives: RateTracker::new(now()),
+            expected_rate: options.expected_rate,
+            proxied: true,
+            tags: options.tags,
+            blocked_total: Duration::ZERO,
+            blocked_max: Duration::ZERO,
+            send_failures_full: 0,
+            send_failures_closed: 0,
+            state_history: VecDeque::from([StateTransition::new(ChannelState::default(), now())]),
+            producers: Vec::new(),
+            consumers: Vec::new(),
+            handles: None,
+            samples: VecDeque::new(),
+            changed_at_ns: created_ns,
+            checkpoints: VecDeque::from([ChannelSample {
+                elapsed_ns: created_ns,
+                queued: 0,
+                queued_bytes: 0,
+                sent_count: 0,
+                received_count: 0,
+            }]),
+            created_ns,
+            last_send_ns: None,
+            last_recv_ns: None,
         }
     }
 
-    fn update_state(&mut self) {
+    /// Folds the counters updated by the wrappers since the previous call into the statistics.
+    fn sync_counters(&mut self, timestamp: Instant) {
+        let counters = self.counters.snapshot();
+        let sent = counters.sent.saturating_sub(self.sent_count);
+        let received = counters.received.saturating_sub(self.received_count);
+        let bytes_sent = counters.bytes_sent.saturating_sub(self.bytes_sent);
+        let bytes_received = counters.bytes_received.saturating_sub(self.bytes_received);
+        let changed = sent != 0
+            || received != 0
+            || counters.blocked != self.blocked_total
+            || counters.send_failures_full != self.send_failures_full
+            || counters.send_failures_closed != self.send_failures_closed;
+        self.sent_count = counters.sent;
+        self.received_count = counters.received;
+        self.bytes_sent = counters.bytes_sent;
+        self.bytes_received = counters.bytes_received;
+        self.blocked_total = counters.blocked;
+        self.blocked_max = counters.max_blocked;
+        self.send_failures_full = counters.send_failures_full;
+        self.send_failures_closed = counters.send_failures_closed;
+        self.producers = self.counters.producers();
+        self.consumers = self.counters.consumers();
+        if changed {
+            // Stamped while holding the shard lock, so `/channels/delta` readers can't miss it
+            self.changed_at_ns = elapsed_ns();
+        }
+        // Sizes are counted just before their messages, so they may arrive a tick earlier
+        self.bytes_sends.record_many(timestamp, bytes_sent);
+        self.bytes_receives.record_many(timestamp, bytes_received);
+        if sent == 0 && received == 0 {
+            return;
+        }
+        delta::record_checkpoint(self, self.changed_at_ns);
+        if sent != 0 {
+            self.last_send_ns = Some(elapsed_ns_at(timestamp));
+        }
+        if received != 0 {
+            self.last_recv_ns = Some(elapsed_ns_at(timestamp));
+        }
+        self.sends.record_many(timestamp, sent);
+        self.receives.record_many(timestamp, received);
+        self.update_state(timestamp);
+    }
+
+    /// Zeroes the statistics, keeping the channel registered in its current state.
+    fn reset(&mut self, timestamp: Instant) {
+        self.counters.reset();
+        let counters = self.counters.snapshot();
+        self.sent_count = counters.sent;
+        self.received_count = counters.received;
+        self.bytes_sent = counters.bytes_sent;
+        self.bytes_received = counters.bytes_received;
+        self.blocked_total = counters.blocked;
+        self.blocked_max = counters.max_blocked;
+        self.send_failures_full = counters.send_failures_full;
+        self.send_failures_closed = counters.send_failures_closed;
+        self.producers = self.counters.producers();
+        self.consumers = self.counters.consumers();
+        self.sends = RateTracker::new(timestamp);
+        self.receives = RateTracker::new(timestamp);
+        self.bytes_sends = RateTracker::new(timestamp);
+        self.bytes_receives = RateTracker::new(timestamp);
+        self.state_history = VecDeque::from([StateTransition::new(self.state, timestamp)]);
+        self.samples.clear();
+        // Counters before the reset can't be compared with the new ones
+        self.changed_at_ns = elapsed_ns();
+        self.checkpoints.clear();
+        delta::record_checkpoint(self, self.changed_at_ns);
+    }
+
+    /// Time since the last send or receive, or since creation if there was none.
+    fn idle_ns(&self, elapsed_ns: u64) -> u64 {
+        let last_activity = self
+            .last_send_ns
+            .max(self.last_recv_ns)
+            .unwrap_or(self.created_ns);
+        elapsed_ns.saturating_sub(last_activity)
+    }
+
+    fn update_state(&mut self, timestamp: Instant) {
         if self.state == ChannelState::Closed || self.state == ChannelState::Notified {
             return;
         }
@@ -337,10 +920,26 @@ impl ChannelStats {
         };
 
         if is_full {
-            self.state = ChannelState::Full;
+            self.set_state(ChannelState::Full, timestamp);
         } else {
-            self.state = ChannelState::Active;
+            self.set_state(ChannelState::Active, timestamp);
+        }
+    }
+
+    /// Changes the state, recording a transition if it differs from the current one.
+    fn set_state(&mut self, state: ChannelState, timestamp: Instant) {
+        if self.state == state {
+            return;
         }
+        self.state = state;
+        self.changed_at_ns = elapsed_ns();
+        if self.state_history.len() >= STATE_HISTORY_LIMIT {
+            self.state_history.pop_front();
+        }
+        self.state_history
+            .push_back(StateTransition::new(state, timestamp));
+        #[cfg(feature = "tracing")]
+        trace::state_changed(self, state);
     }
 }
 
@@ -353,6 +952,7 @@ impl StreamStats {
         type_size: usize,
         iter: u32,
     ) -> Self {
+        let created_at = now();
         Self {
             id,
             source,
@@ -363,6 +963,51 @@ impl StreamStats {
             type_size,
             logs: VecDeque::new(),
             iter,
+            yields: RateTracker::new(created_at),
+            created_at,
+            last_yield_at: None,
+            max_gap: Duration::ZERO,
+            polls: 0,
+            pending_polls: 0,
+            poll_wait: Duration::ZERO,
+            max_poll_wait: Duration::ZERO,
+            polling: false,
+        }
+    }
+
+    /// Zeroes the statistics, keeping the stream registered in its current state.
+    fn reset(&mut self, timestamp: Instant) {
+        self.items_yielded = 0;
+        self.logs.clear();
+        self.yields = RateTracker::new(timestamp);
+        self.max_gap = Duration::ZERO;
+        self.polls = 0;
+        self.pending_polls = 0;
+        self.poll_wait = Duration::ZERO;
+        self.max_poll_wait = Duration::ZERO;
+    }
+
+    fn record_yield(&mut self, timestamp: Instant) {
+        if let Some(last) = self.last_yield_at {
+            self.max_gap = self.max_gap.max(timestamp.saturating_duration_since(last));
+        }
+        self.last_yield_at = Some(timestamp);
+        self.yields.record(timestamp);
+    }
+
+    /// Time since the last item, or since creation if none was yielded yet.
+    fn idle(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_yield_at.unwrap_or(self.created_at))
+    }
+
+    /// Open streams idle for longer than the stall timeout are reported as stalled.
+    fn state_at(&self, now: Instant) -> ChannelState {
+        if self.state == ChannelState::Active
+            && self.idle(now) >= Config::current().stream_stall_timeout
+        {
+            ChannelState::Stalled
+        } else {
+            self.state
         }
     }
 }
@@ -373,19 +1018,17 @@ pub(crate) enum ChannelEvent {
     Created {
         id: u64,
         source: &'static str,
-        display_label: Option<String>,
+        options: ChannelOptions,
         channel_type: ChannelType,
         type_name: &'static str,
         type_size: usize,
+        /// `false` for channels instrumented with `mode = inline`
+        proxied: bool,
+        counters: Arc<ChannelCounters>,
     },
-    MessageSent {
-        id: u64,
-        log: Option<String>,
-        timestamp: Instant,
-    },
-    MessageReceived {
+    /// Counters of the channel changed, only sent in synchronous mode, see [`counters`]
+    Counted {
         id: u64,
-        timestamp: Instant,
     },
     Closed {
         id: u64,
@@ -394,6 +1037,16 @@ pub(crate) enum ChannelEvent {
     Notified {
         id: u64,
     },
+    /// Live handles of a channel type that exposes them
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    Handles {
+        id: u64,
+        handles: Arc<Handles>,
+    },
+    /// Acknowledged once all previously sent events are applied, see [`flush`].
+    Flush {
+        ack: CbSender<()>,
+    },
 }
 
 /// Events sent to the background stream statistics collection thread.
@@ -410,207 +1063,467 @@ pub(crate) enum StreamEvent {
         id: u64,
         log: Option<String>,
         timestamp: Instant,
+        /// Time from the first poll for this item until it was yielded
+        waited: Duration,
+    },
+    /// `poll_next` returned `Pending`
+    Pending {
+        id: u64,
     },
     Completed {
         id: u64,
     },
+    /// Acknowledged once all previously sent events are applied, see [`flush`].
+    Flush {
+        ack: CbSender<()>,
+    },
+}
+
+/// Sends events to a collector thread, or applies them in place in synchronous mode.
+pub(crate) struct EventSender<E, S> {
+    tx: CbSender<E>,
+    stats: Arc<Shards<S>>,
+    apply: fn(&Shards<S>, E),
+    synchronous: bool,
+    /// Drops all events, see [`ConfigBuilder::disable`](config::ConfigBuilder::disable)
+    disabled: bool,
+}
+
+impl<E, S> Clone for EventSender<E, S> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            stats: Arc::clone(&self.stats),
+            apply: self.apply,
+            synchronous: self.synchronous,
+            disabled: self.disabled,
+        }
+    }
 }
 
-type ChannelStatsState = (
-    CbSender<ChannelEvent>,
-    Arc<RwLock<HashMap<u64, ChannelStats>>>,
-);
-type StreamStatsState = (
-    CbSender<StreamEvent>,
-    Arc<RwLock<HashMap<u64, StreamStats>>>,
-);
+impl<E, S> EventSender<E, S> {
+    pub(crate) fn send(&self, event: E) -> Result<(), crossbeam_channel::SendError<E>> {
+        if self.disabled {
+            Ok(())
+        } else if self.synchronous {
+            (self.apply)(&self.stats, event);
+            Ok(())
+        } else {
+            self.tx.send(event)
+        }
+    }
+
+    pub(crate) fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// Number of events waiting for the collector thread.
+    pub(crate) fn backlog(&self) -> usize {
+        self.tx.len()
+    }
+
+    pub(crate) fn is_synchronous(&self) -> bool {
+        self.synchronous
+    }
+}
+
+pub(crate) type ChannelEventSender = EventSender<ChannelEvent, ChannelStats>;
+pub(crate) type StreamEventSender = EventSender<StreamEvent, StreamStats>;
+
+type ChannelStatsState = (ChannelEventSender, Arc<Shards<ChannelStats>>);
+type StreamStatsState = (StreamEventSender, Arc<Shards<StreamStats>>);
 
 static CHANNELS_STATE: OnceLock<ChannelStatsState> = OnceLock::new();
 
 static STREAMS_STATE: OnceLock<StreamStatsState> = OnceLock::new();
 
-static START_TIME: OnceLock<Instant> = OnceLock::new();
+pub(crate) static START_TIME: OnceLock<Instant> = OnceLock::new();
+
+/// Wall-clock time of [`START_TIME`] in Unix milliseconds
+static START_UNIX_MS: OnceLock<u64> = OnceLock::new();
+
+fn get_log_limit() -> usize {
+    Config::current().log_limit
+}
 
-pub(crate) static CHANNEL_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+/// Options declared in the `channel!` macro, carried to the channel's statistics by
+/// [`ChannelEvent::Created`].
+///
+/// This type is not intended for direct use. Use the `channel!` macro options instead.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct ChannelOptions {
+    pub label: Option<String>,
+    /// Number of log entries kept, declared with `log_limit` (defaults to [`Config::log_limit`])
+    pub log_limit: Option<usize>,
+    /// Whether logged messages are JSON, declared with `log = json`
+    pub json_logs: bool,
+    /// Declared with `tags = [..]`, see [`tags`]
+    pub tags: Vec<String>,
+    /// Expected messages per second, declared with `expected_rate`, see [`rates`]
+    pub expected_rate: Option<f64>,
+    /// Measures messages, declared with `size = true` or `size_with`, see [`sizes`]
+    pub size_fn: Option<sizes::ErasedSizeFn>,
+}
 
-pub(crate) static STREAM_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+/// Serializes a logged message for `log = json`.
+///
+/// This function is not intended for direct use. Use the `channel!` macro with `log = json` instead.
+#[doc(hidden)]
+pub fn json_log<T: Serialize>(msg: &T) -> String {
+    serde_json::to_string(msg).unwrap_or_else(|e| format!("<unserializable: {}>", e))
+}
 
-const DEFAULT_LOG_LIMIT: usize = 50;
+/// Number of channels and primitives created at each source location so far. Unlike counting
+/// the tracked ones, this keeps labels unique after closed ones are evicted.
+static CHANNEL_ITERS: LazyLock<Mutex<HashMap<&'static str, u32>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the `iter` of a new channel or primitive created at `source`.
+pub(crate) fn next_iter(source: &'static str) -> u32 {
+    let mut iters = CHANNEL_ITERS.lock().unwrap();
+    let count = iters.entry(source).or_insert(0);
+    let iter = *count;
+    *count += 1;
+    iter
+}
 
-fn get_log_limit() -> usize {
-    std::env::var("CHANNELS_CONSOLE_LOG_LIMIT")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(DEFAULT_LOG_LIMIT)
+/// Appends a log entry, dropping the oldest ones to keep at most `limit` entries.
+fn push_log(logs: &mut VecDeque<LogEntry>, entry: LogEntry, limit: usize) {
+    if limit == 0 {
+        return;
+    }
+    while logs.len() >= limit {
+        logs.pop_front();
+    }
+    logs.push_back(entry);
+}
+
+/// Interval of periodic work done by the collector thread (e.g. alert rules evaluation).
+const COLLECTOR_TICK: Duration = Duration::from_millis(250);
+
+/// Apply a single event to the channel statistics map.
+fn apply_channel_event(stats: &Shards<ChannelStats>, event: ChannelEvent) {
+    match event {
+        ChannelEvent::Created {
+            id,
+            source,
+            options,
+            channel_type,
+            type_name,
+            type_size,
+            proxied,
+            counters,
+        } => {
+            let iter = next_iter(source);
+
+            let mut channel_stats = ChannelStats::new(
+                id,
+                source,
+                options,
+                channel_type,
+                type_name,
+                type_size,
+                iter,
+                counters,
+            );
+            channel_stats.proxied = proxied;
+            if let Some(label) = &channel_stats.label {
+                registry::claim_label(label, source);
+            }
+            #[cfg(feature = "tracing")]
+            {
+                let label = resolve_label(source, channel_stats.label.as_deref(), iter);
+                let _ = channel_stats.counters.label.set(label);
+                trace::channel_created(&channel_stats);
+            }
+            stats.insert(id, channel_stats);
+        }
+        ChannelEvent::Counted { id } => {
+            stats.update(id, |channel_stats| channel_stats.sync_counters(now()));
+        }
+        ChannelEvent::Closed { id } => {
+            stats.update(id, |channel_stats| {
+                // Messages counted right before closing are included in the final statistics
+                channel_stats.sync_counters(now());
+                channel_stats.set_state(ChannelState::Closed, now());
+            });
+        }
+        ChannelEvent::Notified { id } => {
+            stats.update(id, |channel_stats| {
+                channel_stats.set_state(ChannelState::Notified, now());
+            });
+        }
+        ChannelEvent::Handles { id, handles } => {
+            stats.update(id, |channel_stats| channel_stats.handles = Some(handles));
+        }
+        ChannelEvent::Flush { ack } => {
+            sync_all_counters(stats);
+            let _ = ack.send(());
+        }
+    }
+}
+
+/// Zeroes the statistics of all channels and streams, see [`control::reset`].
+pub(crate) fn reset_all_stats() {
+    let timestamp = now();
+    if let Some((_, stats)) = CHANNELS_STATE.get() {
+        stats.update_all(|channel_stats| channel_stats.reset(timestamp));
+    }
+    if let Some((_, stats)) = STREAMS_STATE.get() {
+        stats.update_all(|stream_stats| stream_stats.reset(timestamp));
+    }
+}
+
+fn sync_all_counters(stats: &Shards<ChannelStats>) {
+    let timestamp = now();
+    stats.update_all(|channel_stats| channel_stats.sync_counters(timestamp));
 }
 
 /// Initialize the channel statistics collection system (called on first instrumented channel).
 /// Returns a reference to the global state.
 pub(crate) fn init_channels_state() -> &'static ChannelStatsState {
     CHANNELS_STATE.get_or_init(|| {
-        START_TIME.get_or_init(Instant::now);
+        init_start_time();
 
         let (tx, rx) = unbounded::<ChannelEvent>();
-        let stats_map = Arc::new(RwLock::new(HashMap::<u64, ChannelStats>::new()));
+        let stats_map = Arc::new(Shards::<ChannelStats>::default());
         let stats_map_clone = Arc::clone(&stats_map);
 
+        let disabled = Config::current().disabled;
+        if disabled {
+            let sender = EventSender {
+                tx,
+                stats: Arc::clone(&stats_map),
+                apply: apply_channel_event,
+                synchronous: false,
+                disabled,
+            };
+            return (sender, stats_map);
+        }
+
         std::thread::Builder::new()
             .name("channel-stats-collector".into())
             .spawn(move || {
-                while let Ok(event) = rx.recv() {
-                    let mut stats = stats_map_clone.write().unwrap();
-                    match event {
-                        ChannelEvent::Created {
-                            id,
-                            source,
-                            display_label,
-                            channel_type,
-                            type_name,
-                            type_size,
-                        } => {
-                            // Count existing items with the same source location
-                            let iter = stats.values().filter(|s| s.source == source).count() as u32;
-
-                            stats.insert(
-                                id,
-                                ChannelStats::new(
-                                    id,
-                                    source,
-                                    display_label,
-                                    channel_type,
-                                    type_name,
-                                    type_size,
-                                    iter,
-                                ),
-                            );
-                        }
-                        ChannelEvent::MessageSent { id, log, timestamp } => {
-                            if let Some(channel_stats) = stats.get_mut(&id) {
-                                channel_stats.sent_count += 1;
-                                channel_stats.update_state();
-
-                                let limit = get_log_limit();
-                                if channel_stats.sent_logs.len() >= limit {
-                                    channel_stats.sent_logs.pop_front();
-                                }
-                                channel_stats.sent_logs.push_back(LogEntry::new(
-                                    channel_stats.sent_count,
-                                    timestamp,
-                                    log,
-                                ));
-                            }
-                        }
-                        ChannelEvent::MessageReceived { id, timestamp } => {
-                            if let Some(channel_stats) = stats.get_mut(&id) {
-                                channel_stats.received_count += 1;
-                                channel_stats.update_state();
-
-                                let limit = get_log_limit();
-                                if channel_stats.received_logs.len() >= limit {
-                                    channel_stats.received_logs.pop_front();
-                                }
-                                channel_stats.received_logs.push_back(LogEntry::new(
-                                    channel_stats.received_count,
-                                    timestamp,
-                                    None,
-                                ));
-                            }
-                        }
-                        ChannelEvent::Closed { id } => {
-                            if let Some(channel_stats) = stats.get_mut(&id) {
-                                channel_stats.state = ChannelState::Closed;
-                            }
+                let mut alert_engine = AlertEngine::default();
+                let mut last_tick = Instant::now();
+                let mut last_sample = Instant::now();
+
+                loop {
+                    match rx.recv_timeout(COLLECTOR_TICK) {
+                        Ok(event) => apply_channel_event(&stats_map_clone, event),
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+
+                    if last_tick.elapsed() >= COLLECTOR_TICK {
+                        last_tick = Instant::now();
+                        let stats = &stats_map_clone;
+                        sync_all_counters(stats);
+                        evict_closed_channels(stats, Config::current(), elapsed_ns());
+                        let fired = alert_engine.evaluate(&stats.read_all());
+                        // Callbacks may read the stats, so they run after the guards are dropped
+                        for alert in fired {
+                            alert.fire();
                         }
-                        ChannelEvent::Notified { id } => {
-                            if let Some(channel_stats) = stats.get_mut(&id) {
-                                channel_stats.state = ChannelState::Notified;
-                            }
+                        if last_sample.elapsed() >= SAMPLE_INTERVAL {
+                            last_sample = Instant::now();
+                            record_samples(stats, elapsed_ns(), Config::current().channel_samples);
                         }
                     }
                 }
             })
             .expect("Failed to spawn channel-stats-collector thread");
 
-        // Spawn the metrics HTTP server in the background
-        // Check environment variable for custom port, default to 6770
-        let port = std::env::var("CHANNELS_CONSOLE_METRICS_PORT")
-            .ok()
-            .and_then(|p| p.parse::<u16>().ok())
-            .unwrap_or(6770);
-        let addr = format!("127.0.0.1:{}", port);
+        spawn_background_services();
+
+        let sender = EventSender {
+            tx,
+            stats: Arc::clone(&stats_map),
+            apply: apply_channel_event,
+            synchronous: Config::current().synchronous,
+            disabled,
+        };
+        (sender, stats_map)
+    })
+}
+
+/// Anchors the relative timestamps, shared by the channel, stream and sink registries.
+pub(crate) fn init_start_time() {
+    START_TIME.get_or_init(now);
+    start_unix_ms();
+}
+
+/// Starts the metrics server and the recorders, once the first channel, stream or sink is
+/// instrumented.
+pub(crate) fn spawn_background_services() {
+    spawn_metrics_server();
+    spawn_history_recorder();
+    spawn_configured_recorder();
+}
+
+static METRICS_SERVER: Once = Once::new();
+
+/// Set by [`ChannelsGuardBuilder::http`] to keep the metrics server from starting.
+pub(crate) static HTTP_SERVER_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Spawn the metrics HTTP server in the background (only once per process).
+pub(crate) fn spawn_metrics_server() {
+    METRICS_SERVER.call_once(|| {
+        let config = Config::current();
+        if !config.server_enabled || HTTP_SERVER_DISABLED.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+        if let Some(path) = config.metrics_socket.clone() {
+            #[cfg(unix)]
+            {
+                std::thread::spawn(move || {
+                    http_api::start_unix_metrics_server(&path);
+                });
+                return;
+            }
+            #[cfg(not(unix))]
+            eprintln!(
+                "[channels-console] Unix sockets are not supported on this platform, ignoring metrics socket {}",
+                path.display()
+            );
+        }
+        let addr = config.metrics_addr();
 
         std::thread::spawn(move || {
             start_metrics_server(&addr);
         });
-
-        (tx, stats_map)
-    })
+    });
 }
 
 /// Initialize the stream statistics collection system (called on first instrumented stream).
 /// Returns a reference to the global state.
 pub(crate) fn init_streams_state() -> &'static StreamStatsState {
     STREAMS_STATE.get_or_init(|| {
-        START_TIME.get_or_init(Instant::now);
+        init_start_time();
 
         let (tx, rx) = unbounded::<StreamEvent>();
-        let stats_map = Arc::new(RwLock::new(HashMap::<u64, StreamStats>::new()));
+        let stats_map = Arc::new(Shards::<StreamStats>::default());
         let stats_map_clone = Arc::clone(&stats_map);
 
+        let disabled = Config::current().disabled;
+        if disabled {
+            let sender = EventSender {
+                tx,
+                stats: Arc::clone(&stats_map),
+                apply: apply_stream_event,
+                synchronous: false,
+                disabled,
+            };
+            return (sender, stats_map);
+        }
+
         std::thread::Builder::new()
             .name("stream-stats-collector".into())
             .spawn(move || {
                 while let Ok(event) = rx.recv() {
-                    let mut stats = stats_map_clone.write().unwrap();
-                    match event {
-                        StreamEvent::Created {
-                            id,
-                            source,
-                            display_label,
-                            type_name,
-                            type_size,
-                        } => {
-                            // Count existing items with the same source location
-                            let iter = stats.values().filter(|s| s.source == source).count() as u32;
-
-                            stats.insert(
-                                id,
-                                StreamStats::new(
-                                    id,
-                                    source,
-                                    display_label,
-                                    type_name,
-                                    type_size,
-                                    iter,
-                                ),
-                            );
-                        }
-                        StreamEvent::Yielded { id, log, timestamp } => {
-                            if let Some(stream_stats) = stats.get_mut(&id) {
-                                stream_stats.items_yielded += 1;
-
-                                let limit = get_log_limit();
-                                if stream_stats.logs.len() >= limit {
-                                    stream_stats.logs.pop_front();
-                                }
-                                stream_stats.logs.push_back(LogEntry::new(
-                                    stream_stats.items_yielded,
-                                    timestamp,
-                                    log,
-                                ));
-                            }
-                        }
-                        StreamEvent::Completed { id } => {
-                            if let Some(stream_stats) = stats.get_mut(&id) {
-                                stream_stats.state = ChannelState::Closed;
-                            }
-                        }
-                    }
+                    apply_stream_event(&stats_map_clone, event);
                 }
             })
             .expect("Failed to spawn stream-stats-collector thread");
 
-        (tx, stats_map)
+        spawn_background_services();
+
+        let sender = EventSender {
+            tx,
+            stats: Arc::clone(&stats_map),
+            apply: apply_stream_event,
+            synchronous: Config::current().synchronous,
+            disabled,
+        };
+        (sender, stats_map)
+    })
+}
+
+/// Apply a single event to the stream statistics map.
+fn apply_stream_event(stats: &Shards<StreamStats>, event: StreamEvent) {
+    match event {
+        StreamEvent::Created {
+            id,
+            source,
+            display_label,
+            type_name,
+            type_size,
+        } => {
+            // Count existing items with the same source location
+            let iter = stats
+                .read_all()
+                .values()
+                .filter(|s| s.source == source)
+                .count() as u32;
+
+            stats.insert(
+                id,
+                StreamStats::new(id, source, display_label, type_name, type_size, iter),
+            );
+        }
+        StreamEvent::Yielded {
+            id,
+            log,
+            timestamp,
+            waited,
+        } => {
+            stats.update(id, |stream_stats| {
+                stream_stats.items_yielded += 1;
+                stream_stats.record_yield(timestamp);
+                stream_stats.polls += 1;
+                stream_stats.polling = false;
+                stream_stats.poll_wait += waited;
+                stream_stats.max_poll_wait = stream_stats.max_poll_wait.max(waited);
+
+                let entry =
+                    LogEntry::new(stream_stats.items_yielded, timestamp, log.map(redact), None);
+                push_log(&mut stream_stats.logs, entry, get_log_limit());
+            });
+        }
+        StreamEvent::Pending { id } => {
+            stats.update(id, |stream_stats| {
+                stream_stats.polls += 1;
+                stream_stats.pending_polls += 1;
+                stream_stats.polling = true;
+            });
+        }
+        StreamEvent::Completed { id } => {
+            stats.update(id, |stream_stats| {
+                stream_stats.state = ChannelState::Closed;
+                stream_stats.polls += 1;
+                stream_stats.polling = false;
+            });
+        }
+        StreamEvent::Flush { ack } => {
+            let _ = ack.send(());
+        }
+    }
+}
+
+/// Current time according to the configured [`Clock`](clock::Clock).
+pub(crate) fn now() -> Instant {
+    Config::current().clock.now()
+}
+
+/// Wall-clock time of program start in Unix milliseconds, anchoring the relative timestamps.
+pub(crate) fn start_unix_ms() -> u64 {
+    *START_UNIX_MS.get_or_init(|| {
+        let start = *START_TIME.get_or_init(now);
+        let elapsed_ms = now().saturating_duration_since(start).as_millis() as u64;
+        alerts::unix_millis().saturating_sub(elapsed_ms)
+    })
+}
+
+/// Nanoseconds elapsed since program start, as used by log and state timestamps.
+pub(crate) fn elapsed_ns() -> u64 {
+    elapsed_ns_at(now())
+}
+
+/// Nanoseconds from program start until `timestamp`.
+fn elapsed_ns_at(timestamp: Instant) -> u64 {
+    START_TIME.get().map_or(0, |start| {
+        timestamp.saturating_duration_since(*start).as_nanos() as u64
     })
 }
 
@@ -667,17 +1580,59 @@ pub fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Parse a human-readable rate like `100/s`, `5/m` or `3/500ms` into messages per second.
+/// A bare number is interpreted as messages per second.
+pub fn parse_rate(s: &str) -> Option<f64> {
+    let (count, per) = s.trim().split_once('/').unwrap_or((s.trim(), "s"));
+    let count: f64 = count.trim().parse().ok()?;
+    let per = per.trim();
+    let per = if per.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        parse_duration(per)?
+    } else {
+        parse_duration(&format!("1{}", per))?
+    };
+    if count < 0.0 || per.is_zero() {
+        return None;
+    }
+    Some(count / per.as_secs_f64())
+}
+
+/// Parse a human-readable duration like `500ms`, `10s`, `5m` or `1h`.
+/// A bare number is interpreted as seconds.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let split_at = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(split_at);
+    let value: f64 = value.parse().ok()?;
+    if value < 0.0 {
+        return None;
+    }
+
+    let secs = match unit.trim() {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => return None,
+    };
+    Some(Duration::from_secs_f64(secs))
+}
+
 /// Trait for instrumenting channels.
 ///
 /// This trait is not intended for direct use. Use the `channel!` macro instead.
 #[doc(hidden)]
 pub trait Instrument {
     type Output;
+    type Message;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         capacity: Option<usize>,
+        correlate: Option<CorrelationFn<Self::Message>>,
     ) -> Self::Output;
 }
 
@@ -687,14 +1642,104 @@ pub trait Instrument {
 #[doc(hidden)]
 pub trait InstrumentLog {
     type Output;
+    type Message;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
+        capacity: Option<usize>,
+        correlate: Option<CorrelationFn<Self::Message>>,
+    ) -> Self::Output;
+}
+
+/// Trait for instrumenting channels with a custom message formatter.
+///
+/// This trait is not intended for direct use. Use the `channel!` macro with `log_with = ..` instead.
+#[doc(hidden)]
+pub trait InstrumentLogWith {
+    type Output;
+    type Message;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
         capacity: Option<usize>,
+        correlate: Option<CorrelationFn<Self::Message>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&Self::Message) -> String + Send + Sync + 'static;
+}
+
+/// Trait for instrumenting channels in place, without proxy channels or forwarding tasks.
+///
+/// This trait is not intended for direct use. Use the `channel!` macro with `mode = inline` instead.
+#[doc(hidden)]
+pub trait InstrumentInline {
+    type Output;
+    type Message;
+    fn instrument_inline(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<Self::Message>>,
     ) -> Self::Output;
 }
 
+/// Trait for instrumenting channels in place with message logging.
+///
+/// This trait is not intended for direct use. Use the `channel!` macro with `mode = inline, log = true` instead.
+#[doc(hidden)]
+pub trait InstrumentInlineLog {
+    type Output;
+    type Message;
+    fn instrument_inline_log(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<Self::Message>>,
+    ) -> Self::Output;
+}
+
+/// Trait for instrumenting channels in place with a custom message formatter.
+///
+/// This trait is not intended for direct use. Use the `channel!` macro with `mode = inline, log_with = ..` instead.
+#[doc(hidden)]
+pub trait InstrumentInlineLogWith {
+    type Output;
+    type Message;
+    fn instrument_inline_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<Self::Message>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&Self::Message) -> String + Send + Sync + 'static;
+}
+
+/// Trait for attributing a sender clone to a producer of an instrumented channel.
+///
+/// This trait is not intended for direct use. Use the `sender!` macro instead.
+#[doc(hidden)]
+pub trait InstrumentSender: Sized {
+    fn instrument_sender(self, source: &'static str, channel: &str, label: Option<String>) -> Self;
+}
+
+/// Trait for attributing a receiver clone to a consumer of an instrumented channel.
+///
+/// This trait is not intended for direct use. Use the `receiver!` macro instead.
+#[doc(hidden)]
+pub trait InstrumentReceiver: Sized {
+    fn instrument_receiver(
+        self,
+        source: &'static str,
+        channel: &str,
+        label: Option<String>,
+    ) -> Self;
+}
+
 /// Trait for instrumenting streams.
 ///
 /// This trait is not intended for direct use. Use the `stream!` macro instead.
@@ -738,9 +1783,26 @@ where
     }
 }
 
+/// Trait for instrumenting sinks.
+///
+/// This trait is not intended for direct use. Use the `sink!` macro instead.
+#[doc(hidden)]
+pub trait InstrumentSink {
+    type Output;
+    fn instrument_sink(self, source: &'static str, label: Option<String>) -> Self::Output;
+}
+
+// Implement InstrumentSink for all types, the wrapper is a Sink whenever they are
+impl<S> InstrumentSink for S {
+    type Output = sink_wrappers::InstrumentedSink<S>;
+
+    fn instrument_sink(self, source: &'static str, label: Option<String>) -> Self::Output {
+        sink_wrappers::InstrumentedSink::new(self, source, label)
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(any(feature = "tokio", feature = "futures"))] {
-        use std::sync::LazyLock;
         pub static RT: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
             tokio::runtime::Builder::new_multi_thread()
                 .enable_time()
@@ -776,126 +1838,132 @@ cfg_if::cfg_if! {
 /// See the `channel!` macro documentation for full usage details.
 #[macro_export]
 macro_rules! channel {
-    ($expr:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::Instrument::instrument($expr, CHANNEL_ID, None, None)
-    }};
+    // Options are parsed one `key = value` pair at a time, in any order.
+    // State: [label] [capacity] [log] [correlate] [mode] [links]. Options
+    // carried to the statistics are set on `$options` instead.
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; label = $new_label:expr $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr, $id, $options, [Some($new_label.to_string())] [$capacity] [$log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
+    };
 
-    ($expr:expr, label = $label:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::Instrument::instrument($expr, CHANNEL_ID, Some($label.to_string()), None)
-    }};
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; capacity = $new_capacity:expr $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr, $id, $options, [$label] [{ const _: usize = $new_capacity; Some($new_capacity) }] [$log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
+    };
 
-    ($expr:expr, capacity = $capacity:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::Instrument::instrument($expr, CHANNEL_ID, None, Some($capacity))
-    }};
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; log = $new_log:tt $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [$new_log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
+    };
 
-    ($expr:expr, label = $label:expr, capacity = $capacity:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::Instrument::instrument($expr, CHANNEL_ID, Some($label.to_string()), Some($capacity))
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; log_with = $log_with:expr $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [{ $log_with }] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; correlate = $new_correlate:tt $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [$log] [$new_correlate] [$mode] [$($link),*]; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; expected_rate = $new_rate:expr $(, $($rest:tt)*)?) => {{
+        $options.expected_rate = Some($crate::rates::parse_expected_rate($id, ::std::convert::AsRef::<str>::as_ref(&$new_rate)));
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [$log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
     }};
 
-    ($expr:expr, capacity = $capacity:expr, label = $label:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::Instrument::instrument($expr, CHANNEL_ID, Some($label.to_string()), Some($capacity))
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; log_limit = $new_limit:expr $(, $($rest:tt)*)?) => {{
+        $options.log_limit = Some($new_limit);
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [$log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
     }};
 
-    // Variants with log = true
-    ($expr:expr, log = true) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, None, None)
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; links = [$($new_link:expr),* $(,)?] $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [$log] [$correlate] [$mode] [$($link,)* $($new_link),*]; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; mode = $new_mode:tt $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [$log] [$correlate] [$new_mode] [$($link),*]; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; tags = [$($tag:expr),* $(,)?] $(, $($rest:tt)*)?) => {{
+        $options.tags = vec![$(::std::convert::AsRef::<str>::as_ref(&$tag).to_string()),*];
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [$log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
     }};
 
-    ($expr:expr, label = $label:expr, log = true) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, Some($label.to_string()), None)
+    // The channel is bound first, so its message type is known when erasing its size function
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; size = true $(, $($rest:tt)*)?) => {{
+        let channel = $expr;
+        $options.size_fn = Some($crate::sizes::erase_message_size(&channel, $crate::sizes::message_size_of));
+        $crate::channel!(@opts channel, $id, $options, [$label] [$capacity] [$log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
     }};
 
-    ($expr:expr, log = true, label = $label:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, Some($label.to_string()), None)
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; size = false $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [$log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [$log:tt] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?]; size_with = $size:expr $(, $($rest:tt)*)?) => {{
+        let channel = $expr;
+        $options.size_fn = Some($crate::sizes::erase_message_size(&channel, $size));
+        $crate::channel!(@opts channel, $id, $options, [$label] [$capacity] [$log] [$correlate] [$mode] [$($link),*]; $($($rest)*)?)
     }};
 
-    ($expr:expr, capacity = $capacity:expr, log = true) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, None, Some($capacity))
+    (@correlate true) => {
+        Some($crate::correlation::correlation_id_of)
+    };
+
+    (@correlate false) => {
+        None
+    };
+
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [false] [$correlate:tt] [proxy] [$($link:expr),* $(,)?];) => {{
+        let label: Option<String> = $label;
+        $crate::topology::register_links($id, label.as_deref(), &[$(::std::convert::AsRef::<str>::as_ref(&$link)),*]);
+        $options.label = label;
+        $crate::Instrument::instrument($expr, $id, $options, $capacity, $crate::channel!(@correlate $correlate))
     }};
 
-    ($expr:expr, log = true, capacity = $capacity:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, None, Some($capacity))
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [true] [$correlate:tt] [proxy] [$($link:expr),* $(,)?];) => {{
+        let label: Option<String> = $label;
+        $crate::topology::register_links($id, label.as_deref(), &[$(::std::convert::AsRef::<str>::as_ref(&$link)),*]);
+        $options.label = label;
+        $crate::InstrumentLog::instrument_log($expr, $id, $options, $capacity, $crate::channel!(@correlate $correlate))
     }};
 
-    ($expr:expr, label = $label:expr, capacity = $capacity:expr, log = true) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
-            $expr,
-            CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
-        )
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [false] [$correlate:tt] [inline] [$($link:expr),* $(,)?];) => {{
+        let label: Option<String> = $label;
+        $crate::topology::register_links($id, label.as_deref(), &[$(::std::convert::AsRef::<str>::as_ref(&$link)),*]);
+        $options.label = label;
+        $crate::InstrumentInline::instrument_inline($expr, $id, $options, $crate::channel!(@correlate $correlate))
     }};
 
-    ($expr:expr, label = $label:expr, log = true, capacity = $capacity:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
-            $expr,
-            CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
-        )
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [true] [$correlate:tt] [inline] [$($link:expr),* $(,)?];) => {{
+        let label: Option<String> = $label;
+        $crate::topology::register_links($id, label.as_deref(), &[$(::std::convert::AsRef::<str>::as_ref(&$link)),*]);
+        $options.label = label;
+        $crate::InstrumentInlineLog::instrument_inline_log($expr, $id, $options, $crate::channel!(@correlate $correlate))
     }};
 
-    ($expr:expr, capacity = $capacity:expr, label = $label:expr, log = true) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
-            $expr,
-            CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
-        )
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [{ $log_with:expr }] [$correlate:tt] [proxy] [$($link:expr),* $(,)?];) => {{
+        let label: Option<String> = $label;
+        $crate::topology::register_links($id, label.as_deref(), &[$(::std::convert::AsRef::<str>::as_ref(&$link)),*]);
+        $options.label = label;
+        $crate::InstrumentLogWith::instrument_log_with($expr, $id, $options, $capacity, $crate::channel!(@correlate $correlate), $log_with)
     }};
 
-    ($expr:expr, capacity = $capacity:expr, log = true, label = $label:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
-            $expr,
-            CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
-        )
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [{ $log_with:expr }] [$correlate:tt] [inline] [$($link:expr),* $(,)?];) => {{
+        let label: Option<String> = $label;
+        $crate::topology::register_links($id, label.as_deref(), &[$(::std::convert::AsRef::<str>::as_ref(&$link)),*]);
+        $options.label = label;
+        $crate::InstrumentInlineLogWith::instrument_inline_log_with($expr, $id, $options, $crate::channel!(@correlate $correlate), $log_with)
     }};
 
-    ($expr:expr, log = true, label = $label:expr, capacity = $capacity:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
-            $expr,
-            CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
-        )
+    (@opts $expr:expr, $id:ident, $options:ident, [$label:expr] [$capacity:expr] [json] [$correlate:tt] [$mode:tt] [$($link:expr),* $(,)?];) => {{
+        $options.json_logs = true;
+        $crate::channel!(@opts $expr, $id, $options, [$label] [$capacity] [{ $crate::json_log }] [$correlate] [$mode] [$($link),*];)
     }};
 
-    ($expr:expr, log = true, capacity = $capacity:expr, label = $label:expr) => {{
+    (@opts $($unknown:tt)*) => {
+        compile_error!("channel! accepts `label = ..`, `capacity = ..`, `log = true|false|json`, `log_with = |msg| ..`, `correlate = true|false`, `mode = proxy|inline`, `expected_rate = \"..\"`, `log_limit = ..`, `links = [..]`, `tags = [..]`, `size = true|false` and `size_with = |msg| ..` options")
+    };
+
+    ($expr:expr $(, $($opts:tt)*)?) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
-            $expr,
-            CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
-        )
+        let mut options = $crate::ChannelOptions::default();
+        $crate::channel!(@opts $expr, CHANNEL_ID, options, [None] [None] [false] [false] [proxy] []; $($($opts)*)?)
     }};
 }
 
@@ -957,20 +2025,118 @@ macro_rules! stream {
     }};
 }
 
-fn get_all_channel_stats() -> HashMap<u64, ChannelStats> {
-    if let Some((_, stats_map)) = CHANNELS_STATE.get() {
-        stats_map.read().unwrap().clone()
-    } else {
-        HashMap::new()
-    }
+/// Instrument a sink to track accepted items, flushes and errors.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use futures::SinkExt;
+/// use channels_console::sink;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, _rx) = futures::channel::mpsc::channel::<u32>(10);
+///
+///     // Instrument it
+///     let mut tx = sink!(tx, label = "writer");
+///
+///     // Use it normally
+///     tx.send(1).await.unwrap();
+/// }
+/// ```
+#[macro_export]
+macro_rules! sink {
+    ($expr:expr) => {{
+        const SINK_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentSink::instrument_sink($expr, SINK_ID, None)
+    }};
+
+    ($expr:expr, label = $label:expr) => {{
+        const SINK_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentSink::instrument_sink($expr, SINK_ID, Some($label.to_string()))
+    }};
 }
 
-fn get_all_stream_stats() -> HashMap<u64, StreamStats> {
-    if let Some((_, stats_map)) = STREAMS_STATE.get() {
-        stats_map.read().unwrap().clone()
-    } else {
-        HashMap::new()
-    }
+/// Attribute sends of a sender clone to a named producer of an instrumented channel.
+///
+/// The channel is looked up by its label (or `file:line` source). The producer label defaults
+/// to the `file:line` of the macro call. Returns a sender of the same type, see [`producers`].
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let (tx, rx) = tokio::sync::mpsc::channel::<u64>(100);
+/// let (tx, rx) = channels_console::channel!((tx, rx), label = "events");
+///
+/// let ingest_tx = channels_console::sender!(tx.clone(), channel = "events", label = "ingest");
+/// ```
+#[macro_export]
+macro_rules! sender {
+    ($expr:expr, channel = $channel:expr $(,)?) => {{
+        const SENDER_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentSender::instrument_sender(
+            $expr,
+            SENDER_ID,
+            ::std::convert::AsRef::<str>::as_ref(&$channel),
+            None,
+        )
+    }};
+
+    ($expr:expr, channel = $channel:expr, label = $label:expr $(,)?) => {{
+        const SENDER_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentSender::instrument_sender(
+            $expr,
+            SENDER_ID,
+            ::std::convert::AsRef::<str>::as_ref(&$channel),
+            Some($label.to_string()),
+        )
+    }};
+
+    ($expr:expr, label = $label:expr, channel = $channel:expr $(,)?) => {{
+        $crate::sender!($expr, channel = $channel, label = $label)
+    }};
+}
+
+/// Attribute receives of a receiver clone to a named consumer of an instrumented channel.
+///
+/// Only channels with cloneable receivers have several consumers: crossbeam channels, and
+/// `std::sync::mpmc` channels with the `std-mpmc` feature. The channel is looked up by its
+/// label (or `file:line` source). The consumer label defaults to the `file:line` of the macro
+/// call. Returns a receiver of the same type, see [`consumers`].
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let (tx, rx) = crossbeam_channel::bounded::<u64>(100);
+/// let (tx, rx) = channels_console::channel!((tx, rx), label = "jobs");
+///
+/// let worker_rx = channels_console::receiver!(rx.clone(), channel = "jobs", label = "worker-1");
+/// ```
+#[macro_export]
+macro_rules! receiver {
+    ($expr:expr, channel = $channel:expr $(,)?) => {{
+        const RECEIVER_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentReceiver::instrument_receiver(
+            $expr,
+            RECEIVER_ID,
+            ::std::convert::AsRef::<str>::as_ref(&$channel),
+            None,
+        )
+    }};
+
+    ($expr:expr, channel = $channel:expr, label = $label:expr $(,)?) => {{
+        const RECEIVER_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentReceiver::instrument_receiver(
+            $expr,
+            RECEIVER_ID,
+            ::std::convert::AsRef::<str>::as_ref(&$channel),
+            Some($label.to_string()),
+        )
+    }};
+
+    ($expr:expr, label = $label:expr, channel = $channel:expr $(,)?) => {{
+        $crate::receiver!($expr, channel = $channel, label = $label)
+    }};
 }
 
 /// Compare two channel stats for sorting.
@@ -1011,125 +2177,383 @@ fn compare_stream_stats(a: &StreamStats, b: &StreamStats) -> std::cmp::Ordering
     }
 }
 
-pub(crate) fn get_sorted_channel_stats() -> Vec<ChannelStats> {
-    let mut stats: Vec<ChannelStats> = get_all_channel_stats().into_values().collect();
-    stats.sort_by(compare_channel_stats);
-    stats
+/// Calls `f` with all channel statistics, sorted like in the console. Statistics are read in
+/// place, so `f` should not block.
+pub(crate) fn with_sorted_channel_stats<R>(f: impl FnOnce(&[&ChannelStats]) -> R) -> R {
+    let Some((_, stats_map)) = CHANNELS_STATE.get() else {
+        return f(&[]);
+    };
+    let stats = stats_map.read_all();
+    let mut sorted: Vec<&ChannelStats> = stats.values().collect();
+    sorted.sort_by(|a, b| compare_channel_stats(a, b));
+    f(&sorted)
+}
+
+/// Calls `f` with all stream statistics, sorted like in the console. Statistics are read in
+/// place, so `f` should not block.
+pub(crate) fn with_sorted_stream_stats<R>(f: impl FnOnce(&[&StreamStats]) -> R) -> R {
+    let Some((_, stats_map)) = STREAMS_STATE.get() else {
+        return f(&[]);
+    };
+    let stats = stats_map.read_all();
+    let mut sorted: Vec<&StreamStats> = stats.values().collect();
+    sorted.sort_by(|a, b| compare_stream_stats(a, b));
+    f(&sorted)
+}
+
+fn serializable_channels() -> Vec<SerializableChannelStats> {
+    with_sorted_channel_stats(|stats| {
+        stats
+            .iter()
+            .map(|channel_stats| SerializableChannelStats::from(*channel_stats))
+            .collect()
+    })
+}
+
+fn serializable_streams() -> Vec<SerializableStreamStats> {
+    with_sorted_stream_stats(|stats| {
+        stats
+            .iter()
+            .map(|stream_stats| SerializableStreamStats::from(*stream_stats))
+            .collect()
+    })
+}
+
+/// Number of channel and stream events waiting for the collector threads.
+pub(crate) fn collector_backlog() -> usize {
+    let channels = CHANNELS_STATE.get().map_or(0, |(tx, _)| tx.backlog());
+    let streams = STREAMS_STATE.get().map_or(0, |(tx, _)| tx.backlog());
+    channels + streams
 }
 
-pub(crate) fn get_sorted_stream_stats() -> Vec<StreamStats> {
-    let mut stats: Vec<StreamStats> = get_all_stream_stats().into_values().collect();
-    stats.sort_by(compare_stream_stats);
-    stats
+/// Max time [`flush`] waits for a collector thread.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Blocks until the collector threads have applied all events emitted before this call.
+///
+/// Messages still in flight inside the proxy forwarders are not accounted for yet,
+/// so statistics may keep changing shortly after a send or receive.
+pub fn flush() {
+    if let Some((tx, _)) = CHANNELS_STATE.get() {
+        let (ack, done) = crossbeam_channel::bounded(1);
+        if tx.send(ChannelEvent::Flush { ack }).is_ok() {
+            let _ = done.recv_timeout(FLUSH_TIMEOUT);
+        }
+    }
+    if let Some((tx, _)) = STREAMS_STATE.get() {
+        let (ack, done) = crossbeam_channel::bounded(1);
+        if tx.send(StreamEvent::Flush { ack }).is_ok() {
+            let _ = done.recv_timeout(FLUSH_TIMEOUT);
+        }
+    }
+    if let Some((tx, _)) = sinks::SINKS_STATE.get() {
+        let (ack, done) = crossbeam_channel::bounded(1);
+        if tx.send(sinks::SinkEvent::Flush { ack }).is_ok() {
+            let _ = done.recv_timeout(FLUSH_TIMEOUT);
+        }
+    }
+}
+
+/// Returns current statistics of all instrumented channels and streams.
+///
+/// Call [`flush`] first to include events emitted right before the snapshot.
+pub fn snapshot() -> CombinedJson {
+    get_combined_json()
 }
 
 pub(crate) fn get_channels_json() -> ChannelsJson {
-    let channels = get_sorted_channel_stats()
-        .iter()
-        .map(SerializableChannelStats::from)
-        .collect();
-
-    let current_elapsed_ns = START_TIME
-        .get()
-        .expect("START_TIME must be initialized")
-        .elapsed()
+    let channels = serializable_channels();
+
+    let current_elapsed_ns = now()
+        .duration_since(*START_TIME.get_or_init(now))
         .as_nanos() as u64;
 
     ChannelsJson {
+        schema_version: SCHEMA_VERSION,
         current_elapsed_ns,
+        start_unix_ms: start_unix_ms(),
         channels,
+        closed_total: closed_total(),
+        next_cursor: None,
     }
 }
 
 pub(crate) fn get_streams_json() -> StreamsJson {
-    let streams = get_sorted_stream_stats()
-        .iter()
-        .map(SerializableStreamStats::from)
-        .collect();
-
-    let current_elapsed_ns = START_TIME
-        .get()
-        .expect("START_TIME must be initialized")
-        .elapsed()
+    let streams = serializable_streams();
+
+    let current_elapsed_ns = now()
+        .duration_since(*START_TIME.get_or_init(now))
         .as_nanos() as u64;
 
     StreamsJson {
+        schema_version: SCHEMA_VERSION,
         current_elapsed_ns,
+        start_unix_ms: start_unix_ms(),
         streams,
     }
 }
 
 pub(crate) fn get_combined_json() -> CombinedJson {
-    let channels = get_sorted_channel_stats()
-        .iter()
-        .map(SerializableChannelStats::from)
-        .collect();
-
-    let streams = get_sorted_stream_stats()
-        .iter()
-        .map(SerializableStreamStats::from)
-        .collect();
-
-    let current_elapsed_ns = START_TIME
-        .get()
-        .expect("START_TIME must be initialized")
-        .elapsed()
+    let channels = serializable_channels();
+
+    let streams = serializable_streams();
+
+    let current_elapsed_ns = now()
+        .duration_since(*START_TIME.get_or_init(now))
         .as_nanos() as u64;
 
     CombinedJson {
+        schema_version: SCHEMA_VERSION,
         current_elapsed_ns,
+        start_unix_ms: start_unix_ms(),
         channels,
         streams,
+        closed_total: closed_total(),
+        process: get_process_json(),
     }
 }
 
 /// Serializable log response containing sent and received logs for channels.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelLogs {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
     pub id: String,
     pub sent_logs: Vec<LogEntry>,
     pub received_logs: Vec<LogEntry>,
+    /// Index up to which both sent and received entries are listed, to pass as `after_index`
+    /// for the next page. Only set for paginated responses, see [`LogQuery::after_index`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<u64>,
+}
+
+impl ChannelLogs {
+    /// Keeps only the entries whose JSON message has `value` at the dot-separated `path`, see
+    /// [`LogEntry::json_field`].
+    pub fn retain_field(&mut self, path: &str, value: &serde_json::Value) {
+        let matches = |entry: &LogEntry| entry.json_field(path) == Some(value);
+        self.sent_logs.retain(matches);
+        self.received_logs.retain(matches);
+    }
+
+    /// Keeps only the entries selected by `query`, in the order it asks for.
+    pub fn apply(&mut self, query: &LogQuery) {
+        query.select(&mut self.sent_logs);
+        query.select(&mut self.received_logs);
+
+        if query.after_index.is_some() || query.limit.is_some() {
+            // Messages are received after they're sent, so the cursor follows the side lagging
+            // behind. Sent entries past it are listed again on the next page.
+            let last = |entries: &[LogEntry]| {
+                entries
+                    .iter()
+                    .map(|entry| entry.index)
+                    .max()
+                    .or(query.after_index)
+            };
+            self.next_cursor = last(&self.sent_logs)
+                .zip(last(&self.received_logs))
+                .map(|(sent, received)| sent.min(received));
+        }
+    }
+}
+
+/// Order of the entries in a [`ChannelLogs`] response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogOrder {
+    /// Most recent first
+    #[default]
+    Desc,
+    /// Oldest first
+    Asc,
+}
+
+/// Search and filter applied to the logs of a channel, e.g. with the
+/// `?contains=foo&since_ns=1000&limit=10&order=asc` query of `/channels/:id/logs`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogQuery {
+    /// Only entries whose message contains this text
+    pub contains: Option<String>,
+    /// Only entries recorded at or after this many nanoseconds since the start
+    pub since_ns: Option<u64>,
+    /// Only entries with a greater index, e.g. the `next_cursor` of the previous page
+    pub after_index: Option<u64>,
+    /// Only entries whose JSON message has this value at the dot-separated path, see
+    /// [`LogEntry::json_field`]
+    pub field: Option<(String, serde_json::Value)>,
+    /// At most this many sent and this many received entries, taken in `order`
+    pub limit: Option<usize>,
+    pub order: LogOrder,
+}
+
+impl LogQuery {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        let contains = self.contains.as_deref().is_none_or(|text| {
+            entry
+                .message
+                .as_deref()
+                .is_some_and(|message| message.contains(text))
+        });
+        contains
+            && self.since_ns.is_none_or(|since| entry.timestamp >= since)
+            && self.after_index.is_none_or(|after| entry.index > after)
+            && self
+                .field
+                .as_ref()
+                .is_none_or(|(path, value)| entry.json_field(path) == Some(value))
+    }
+
+    fn select(&self, entries: &mut Vec<LogEntry>) {
+        entries.retain(|entry| self.matches(entry));
+        match self.order {
+            LogOrder::Desc => entries.sort_by_key(|entry| std::cmp::Reverse(entry.index)),
+            LogOrder::Asc => entries.sort_by_key(|entry| entry.index),
+        }
+        if let Some(limit) = self.limit {
+            entries.truncate(limit);
+        }
+    }
+}
+
+/// Serializable response with the state transitions of a channel, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelStates {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    pub id: String,
+    pub transitions: Vec<StateTransition>,
+}
+
+/// Message pushed by the `/ws` WebSocket endpoint, available with the `ws` feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsMessage {
+    /// All channels and streams, sent once after connecting
+    Snapshot(CombinedJson),
+    /// Channels and streams that changed since the previous push
+    Update(CombinedJson),
+    /// Log entries of a channel added since the previous push, oldest first
+    ChannelLogs(ChannelLogs),
+    /// Log entries of a stream added since the previous push, oldest first
+    StreamLogs(StreamLogs),
 }
 
 /// Serializable log response containing yielded logs for streams.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamLogs {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
     pub id: String,
     pub logs: Vec<LogEntry>,
 }
 
-pub(crate) fn get_channel_logs(channel_id: &str) -> Option<ChannelLogs> {
+pub(crate) fn get_channel_logs(channel_id: &str, query: &LogQuery) -> Option<ChannelLogs> {
     let id = channel_id.parse::<u64>().ok()?;
-    let stats = get_all_channel_stats();
-    stats.get(&id).map(|channel_stats| {
-        let mut sent_logs: Vec<LogEntry> = channel_stats.sent_logs.iter().cloned().collect();
-        let mut received_logs: Vec<LogEntry> =
-            channel_stats.received_logs.iter().cloned().collect();
-
-        // Sort by index descending (most recent first)
-        sent_logs.sort_by(|a, b| b.index.cmp(&a.index));
-        received_logs.sort_by(|a, b| b.index.cmp(&a.index));
-
-        ChannelLogs {
+    let (_, stats_map) = CHANNELS_STATE.get()?;
+    stats_map.get(id, |channel_stats| {
+        let messages = channel_stats.counters.messages();
+        let sent_logs: Vec<LogEntry> = messages.sent_logs.iter().cloned().collect();
+        let received_logs: Vec<LogEntry> = messages.received_logs.iter().cloned().collect();
+        drop(messages);
+
+        let mut logs = ChannelLogs {
+            schema_version: SCHEMA_VERSION,
             id: channel_id.to_string(),
             sent_logs,
             received_logs,
-        }
+            next_cursor: None,
+        };
+        logs.apply(query);
+        logs
+    })
+}
+
+pub(crate) fn get_channel_states(channel_id: &str) -> Option<ChannelStates> {
+    let id = channel_id.parse::<u64>().ok()?;
+    let (_, stats_map) = CHANNELS_STATE.get()?;
+    stats_map.get(id, |channel_stats| ChannelStates {
+        schema_version: SCHEMA_VERSION,
+        id: channel_id.to_string(),
+        transitions: channel_stats.state_history.iter().copied().collect(),
     })
 }
 
 pub(crate) fn get_stream_logs(stream_id: &str) -> Option<StreamLogs> {
     let id = stream_id.parse::<u64>().ok()?;
-    let stats = get_all_stream_stats();
-    stats.get(&id).map(|stream_stats| {
+    let (_, stats_map) = STREAMS_STATE.get()?;
+    stats_map.get(id, |stream_stats| {
         let mut yielded_logs: Vec<LogEntry> = stream_stats.logs.iter().cloned().collect();
 
         // Sort by index descending (most recent first)
-        yielded_logs.sort_by(|a, b| b.index.cmp(&a.index));
+        yielded_logs.sort_by_key(|entry| std::cmp::Reverse(entry.index));
 
         StreamLogs {
+            schema_version: SCHEMA_VERSION,
             id: stream_id.to_string(),
             logs: yielded_logs,
         }
     })
 }
+
+/// Instrument a [`tokio::sync::Semaphore`] to track acquired and available permits.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use tokio::sync::Semaphore;
+///
+/// let pool = channels_console::semaphore!(Semaphore::new(10), label = "db-pool");
+/// let permit = pool.acquire().await.unwrap();
+/// ```
+///
+/// See the [`primitives`] module for the reported statistics.
+#[cfg(feature = "tokio")]
+#[macro_export]
+macro_rules! semaphore {
+    ($expr:expr) => {{
+        const SEMAPHORE_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::primitives::InstrumentedSemaphore::instrument($expr, SEMAPHORE_ID, None)
+    }};
+
+    ($expr:expr, label = $label:expr $(,)?) => {{
+        const SEMAPHORE_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::primitives::InstrumentedSemaphore::instrument(
+            $expr,
+            SEMAPHORE_ID,
+            Some($label.to_string()),
+        )
+    }};
+}
+
+/// Instrument a [`tokio::sync::Notify`] to track notifications and waiting tasks.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use tokio::sync::Notify;
+///
+/// let shutdown = channels_console::notify!(Notify::new(), label = "shutdown");
+/// shutdown.notify_waiters();
+/// ```
+///
+/// See the [`primitives`] module for the reported statistics.
+#[cfg(feature = "tokio")]
+#[macro_export]
+macro_rules! notify {
+    ($expr:expr) => {{
+        const NOTIFY_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::primitives::InstrumentedNotify::instrument($expr, NOTIFY_ID, None)
+    }};
+
+    ($expr:expr, label = $label:expr $(,)?) => {{
+        const NOTIFY_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::primitives::InstrumentedNotify::instrument(
+            $expr,
+            NOTIFY_ID,
+            Some($label.to_string()),
+        )
+    }};
+}
@@ -0,0 +1,518 @@
+//! Instrumented Tokio synchronization primitives.
+//!
+//! The `semaphore!` and `notify!` macros wrap [`tokio::sync::Semaphore`] and
+//! [`tokio::sync::Notify`], reporting permits and notifications under the `/sync` endpoint.
+//! Unlike channels, primitives have no proxies: counters are updated directly by the wrappers.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use tokio::sync::{Notify, Semaphore};
+//!
+//! # async fn run() {
+//! let pool = channels_console::semaphore!(Semaphore::new(10), label = "db-pool");
+//! let permit = pool.acquire().await.unwrap();
+//! drop(permit);
+//!
+//! let shutdown = channels_console::notify!(Notify::new(), label = "shutdown");
+//! shutdown.notify_one();
+//! shutdown.notified().await;
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::{
+    AcquireError, Notify, OwnedSemaphorePermit, Semaphore, SemaphorePermit, TryAcquireError,
+};
+
+use crate::config::Config;
+use crate::registry;
+use crate::{
+    elapsed_ns, next_iter, now, resolve_label, spawn_metrics_server, ChannelState, SCHEMA_VERSION,
+    START_TIME,
+};
+
+/// Registered primitives by ID, so in creation order.
+static PRIMITIVES: RwLock<BTreeMap<u64, Arc<PrimitiveStats>>> = RwLock::new(BTreeMap::new());
+
+/// IDs of dropped primitives with the time they were dropped, oldest first.
+static DROPPED: Mutex<VecDeque<(u64, u64)>> = Mutex::new(VecDeque::new());
+
+/// Kind of an instrumented primitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrimitiveKind {
+    Semaphore,
+    Notify,
+}
+
+impl std::fmt::Display for PrimitiveKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrimitiveKind::Semaphore => write!(f, "semaphore"),
+            PrimitiveKind::Notify => write!(f, "notify"),
+        }
+    }
+}
+
+/// Live counters of an instrumented primitive, shared with the registry.
+#[derive(Debug)]
+struct PrimitiveStats {
+    id: u64,
+    source: &'static str,
+    label: Option<String>,
+    kind: PrimitiveKind,
+    iter: u32,
+    /// Whether the stats are in [`PRIMITIVES`], which they aren't with instrumentation disabled
+    registered: bool,
+    closed: AtomicBool,
+    /// Tasks currently waiting in `acquire` or `notified`
+    waiting: AtomicU64,
+    // Semaphore counters
+    acquired: AtomicU64,
+    held: AtomicU64,
+    available: AtomicU64,
+    // Notify counters
+    notify_one: AtomicU64,
+    notify_waiters: AtomicU64,
+    notified: AtomicU64,
+}
+
+impl PrimitiveStats {
+    /// Stats of a new primitive. With instrumentation [disabled](crate::ConfigBuilder::disable)
+    /// they're not registered, so the wrapper only updates counters nobody reads.
+    fn register(
+        source: &'static str,
+        label: Option<String>,
+        kind: PrimitiveKind,
+    ) -> Arc<PrimitiveStats> {
+        let config = Config::current();
+        if config.is_disabled() {
+            return Arc::new(PrimitiveStats::new(0, source, label, kind, 0, false));
+        }
+        START_TIME.get_or_init(now);
+        spawn_metrics_server();
+
+        let stats = Arc::new(PrimitiveStats::new(
+            registry::next_id(),
+            source,
+            label,
+            kind,
+            next_iter(source),
+            true,
+        ));
+        let mut primitives = PRIMITIVES.write().unwrap();
+        primitives.insert(stats.id, Arc::clone(&stats));
+        evict_dropped(&mut primitives, config);
+        stats
+    }
+
+    fn new(
+        id: u64,
+        source: &'static str,
+        label: Option<String>,
+        kind: PrimitiveKind,
+        iter: u32,
+        registered: bool,
+    ) -> Self {
+        PrimitiveStats {
+            id,
+            source,
+            label,
+            kind,
+            iter,
+            registered,
+            closed: AtomicBool::new(false),
+            waiting: AtomicU64::new(0),
+            acquired: AtomicU64::new(0),
+            held: AtomicU64::new(0),
+            available: AtomicU64::new(0),
+            notify_one: AtomicU64::new(0),
+            notify_waiters: AtomicU64::new(0),
+            notified: AtomicU64::new(0),
+        }
+    }
+
+    /// Marks the primitive as closed and queues it for eviction.
+    fn dropped(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        if self.registered {
+            DROPPED.lock().unwrap().push_back((elapsed_ns(), self.id));
+        }
+    }
+
+    fn to_serializable(&self) -> SerializablePrimitiveStats {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let details = match self.kind {
+            PrimitiveKind::Semaphore => PrimitiveDetails::Semaphore {
+                available: load(&self.available),
+                held: load(&self.held),
+                acquired: load(&self.acquired),
+                waiting: load(&self.waiting),
+            },
+            PrimitiveKind::Notify => PrimitiveDetails::Notify {
+                notify_one: load(&self.notify_one),
+                notify_waiters: load(&self.notify_waiters),
+                notified: load(&self.notified),
+                waiting: load(&self.waiting),
+            },
+        };
+
+        SerializablePrimitiveStats {
+            id: self.id,
+            source: self.source.to_string(),
+            label: resolve_label(self.source, self.label.as_deref(), self.iter),
+            has_custom_label: self.label.is_some(),
+            state: if self.closed.load(Ordering::Relaxed) {
+                ChannelState::Closed
+            } else {
+                ChannelState::Active
+            },
+            details,
+        }
+    }
+}
+
+/// Counters specific to the kind of primitive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PrimitiveDetails {
+    Semaphore {
+        /// Permits currently available
+        available: u64,
+        /// Permits currently held
+        held: u64,
+        /// Permits acquired in total
+        acquired: u64,
+        /// Tasks waiting to acquire permits
+        waiting: u64,
+    },
+    Notify {
+        /// `notify_one` calls
+        notify_one: u64,
+        /// `notify_waiters` calls
+        notify_waiters: u64,
+        /// Completed `notified` waits
+        notified: u64,
+        /// Tasks waiting in `notified`
+        waiting: u64,
+    },
+}
+
+/// Serializable statistics of an instrumented primitive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializablePrimitiveStats {
+    pub id: u64,
+    pub source: String,
+    pub label: String,
+    pub has_custom_label: bool,
+    /// `closed` once the primitive is dropped (or the semaphore closed)
+    pub state: ChannelState,
+    #[serde(flatten)]
+    pub details: PrimitiveDetails,
+}
+
+impl SerializablePrimitiveStats {
+    pub fn kind(&self) -> PrimitiveKind {
+        match self.details {
+            PrimitiveDetails::Semaphore { .. } => PrimitiveKind::Semaphore,
+            PrimitiveDetails::Notify { .. } => PrimitiveKind::Notify,
+        }
+    }
+}
+
+/// Wrapper for the `/sync` JSON response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrimitivesJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    pub primitives: Vec<SerializablePrimitiveStats>,
+}
+
+/// Drops primitives dropped for longer than the
+/// [`closed_retention`](crate::ConfigBuilder::closed_retention), and the oldest dropped ones
+/// while more than [`max_channels`](crate::ConfigBuilder::max_channels) primitives are
+/// tracked, like closed channels.
+fn evict_dropped(primitives: &mut BTreeMap<u64, Arc<PrimitiveStats>>, config: &Config) {
+    if config.closed_retention.is_none() && config.max_channels.is_none() {
+        return;
+    }
+    let elapsed_ns = elapsed_ns();
+    let mut dropped = DROPPED.lock().unwrap();
+    while let Some(&(dropped_at, id)) = dropped.front() {
+        let expired = config.closed_retention.is_some_and(|retention| {
+            elapsed_ns.saturating_sub(dropped_at) >= retention.as_nanos() as u64
+        });
+        let over_limit = config
+            .max_channels
+            .is_some_and(|max| primitives.len() > max);
+        if !expired && !over_limit {
+            break;
+        }
+        dropped.pop_front();
+        primitives.remove(&id);
+    }
+}
+
+/// Returns current statistics of all instrumented primitives, in creation order.
+pub fn primitives() -> Vec<SerializablePrimitiveStats> {
+    PRIMITIVES
+        .read()
+        .unwrap()
+        .values()
+        .map(|stats| stats.to_serializable())
+        .collect()
+}
+
+/// Statistics of the primitive with the given ID, see [`registry::object`].
+pub(crate) fn primitive(id: u64) -> Option<SerializablePrimitiveStats> {
+    PRIMITIVES
+        .read()
+        .unwrap()
+        .get(&id)
+        .map(|stats| stats.to_serializable())
+}
+
+pub(crate) fn get_primitives_json() -> PrimitivesJson {
+    PrimitivesJson {
+        schema_version: SCHEMA_VERSION,
+        primitives: primitives(),
+    }
+}
+
+/// Decrements the waiting counter when a wait completes or is cancelled.
+struct WaitGuard<'a>(&'a AtomicU64);
+
+impl<'a> WaitGuard<'a> {
+    fn new(waiting: &'a AtomicU64) -> Self {
+        waiting.fetch_add(1, Ordering::Relaxed);
+        Self(waiting)
+    }
+}
+
+impl Drop for WaitGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Instrumented [`Semaphore`], created with the `semaphore!` macro.
+///
+/// Permits acquired through the wrapper are counted until dropped. Methods of the inner
+/// semaphore are available through `Deref`, but permits acquired with them are not counted.
+#[derive(Debug)]
+pub struct InstrumentedSemaphore {
+    inner: Arc<Semaphore>,
+    stats: Arc<PrimitiveStats>,
+}
+
+impl InstrumentedSemaphore {
+    /// This function is not intended for direct use. Use the `semaphore!` macro instead.
+    #[doc(hidden)]
+    pub fn instrument(semaphore: Semaphore, source: &'static str, label: Option<String>) -> Self {
+        let stats = PrimitiveStats::register(source, label, PrimitiveKind::Semaphore);
+        stats
+            .available
+            .store(semaphore.available_permits() as u64, Ordering::Relaxed);
+        Self {
+            inner: Arc::new(semaphore),
+            stats,
+        }
+    }
+
+    pub async fn acquire(&self) -> Result<InstrumentedSemaphorePermit<'_>, AcquireError> {
+        self.acquire_many(1).await
+    }
+
+    pub async fn acquire_many(
+        &self,
+        n: u32,
+    ) -> Result<InstrumentedSemaphorePermit<'_>, AcquireError> {
+        let permit = {
+            let _waiting = WaitGuard::new(&self.stats.waiting);
+            self.inner.acquire_many(n).await?
+        };
+        Ok(InstrumentedSemaphorePermit {
+            permit: Some(permit),
+            tracker: self.on_acquired(n),
+        })
+    }
+
+    pub fn try_acquire(&self) -> Result<InstrumentedSemaphorePermit<'_>, TryAcquireError> {
+        self.try_acquire_many(1)
+    }
+
+    pub fn try_acquire_many(
+        &self,
+        n: u32,
+    ) -> Result<InstrumentedSemaphorePermit<'_>, TryAcquireError> {
+        let permit = self.inner.try_acquire_many(n)?;
+        Ok(InstrumentedSemaphorePermit {
+            permit: Some(permit),
+            tracker: self.on_acquired(n),
+        })
+    }
+
+    pub async fn acquire_owned(&self) -> Result<InstrumentedOwnedSemaphorePermit, AcquireError> {
+        let permit = {
+            let _waiting = WaitGuard::new(&self.stats.waiting);
+            Arc::clone(&self.inner).acquire_owned().await?
+        };
+        Ok(InstrumentedOwnedSemaphorePermit {
+            permit: Some(permit),
+            tracker: self.on_acquired(1),
+        })
+    }
+
+    pub fn add_permits(&self, n: usize) {
+        self.inner.add_permits(n);
+        self.sync_available();
+    }
+
+    pub fn close(&self) {
+        self.inner.close();
+        self.stats.closed.store(true, Ordering::Relaxed);
+    }
+
+    fn on_acquired(&self, n: u32) -> PermitTracker {
+        self.stats.acquired.fetch_add(n as u64, Ordering::Relaxed);
+        self.stats.held.fetch_add(n as u64, Ordering::Relaxed);
+        self.sync_available();
+        PermitTracker {
+            semaphore: Arc::clone(&self.inner),
+            stats: Arc::clone(&self.stats),
+            permits: n as u64,
+        }
+    }
+
+    fn sync_available(&self) {
+        self.stats
+            .available
+            .store(self.inner.available_permits() as u64, Ordering::Relaxed);
+    }
+}
+
+impl std::ops::Deref for InstrumentedSemaphore {
+    type Target = Semaphore;
+
+    fn deref(&self) -> &Semaphore {
+        &self.inner
+    }
+}
+
+impl Drop for InstrumentedSemaphore {
+    fn drop(&mut self) {
+        self.stats.dropped();
+    }
+}
+
+/// Updates the counters of a semaphore when its permits are released.
+#[derive(Debug)]
+struct PermitTracker {
+    semaphore: Arc<Semaphore>,
+    stats: Arc<PrimitiveStats>,
+    permits: u64,
+}
+
+impl PermitTracker {
+    /// Called after the inner permit is dropped or forgotten.
+    fn release(&self) {
+        self.stats.held.fetch_sub(self.permits, Ordering::Relaxed);
+        self.stats
+            .available
+            .store(self.semaphore.available_permits() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Permit acquired from an [`InstrumentedSemaphore`].
+#[derive(Debug)]
+pub struct InstrumentedSemaphorePermit<'a> {
+    permit: Option<SemaphorePermit<'a>>,
+    tracker: PermitTracker,
+}
+
+impl InstrumentedSemaphorePermit<'_> {
+    /// Forgets the permit without releasing it back to the semaphore.
+    pub fn forget(mut self) {
+        if let Some(permit) = self.permit.take() {
+            permit.forget();
+        }
+    }
+}
+
+impl Drop for InstrumentedSemaphorePermit<'_> {
+    fn drop(&mut self) {
+        drop(self.permit.take());
+        self.tracker.release();
+    }
+}
+
+/// Owned permit acquired from an [`InstrumentedSemaphore`].
+#[derive(Debug)]
+pub struct InstrumentedOwnedSemaphorePermit {
+    permit: Option<OwnedSemaphorePermit>,
+    tracker: PermitTracker,
+}
+
+impl InstrumentedOwnedSemaphorePermit {
+    /// Forgets the permit without releasing it back to the semaphore.
+    pub fn forget(mut self) {
+        if let Some(permit) = self.permit.take() {
+            permit.forget();
+        }
+    }
+}
+
+impl Drop for InstrumentedOwnedSemaphorePermit {
+    fn drop(&mut self) {
+        drop(self.permit.take());
+        self.tracker.release();
+    }
+}
+
+/// Instrumented [`Notify`], created with the `notify!` macro.
+#[derive(Debug)]
+pub struct InstrumentedNotify {
+    inner: Notify,
+    stats: Arc<PrimitiveStats>,
+}
+
+impl InstrumentedNotify {
+    /// This function is not intended for direct use. Use the `notify!` macro instead.
+    #[doc(hidden)]
+    pub fn instrument(notify: Notify, source: &'static str, label: Option<String>) -> Self {
+        Self {
+            inner: notify,
+            stats: PrimitiveStats::register(source, label, PrimitiveKind::Notify),
+        }
+    }
+
+    pub fn notify_one(&self) {
+        self.stats.notify_one.fetch_add(1, Ordering::Relaxed);
+        self.inner.notify_one();
+    }
+
+    pub fn notify_waiters(&self) {
+        self.stats.notify_waiters.fetch_add(1, Ordering::Relaxed);
+        self.inner.notify_waiters();
+    }
+
+    /// Waits for a notification, see [`Notify::notified`].
+    pub async fn notified(&self) {
+        {
+            let _waiting = WaitGuard::new(&self.stats.waiting);
+            self.inner.notified().await;
+        }
+        self.stats.notified.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for InstrumentedNotify {
+    fn drop(&mut self) {
+        self.stats.dropped();
+    }
+}
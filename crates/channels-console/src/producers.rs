@@ -0,0 +1,91 @@
+//! Per-producer send counters of fan-in channels.
+//!
+//! A sender clone wrapped with the `sender!` macro is attributed to a named producer of the
+//! instrumented channel it sends into. Its messages pass through a proxy of the same sender
+//! type, which counts them before forwarding into the channel, so the channel's
+//! `producers` list shows which producer is flooding it.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! let (tx, rx) = tokio::sync::mpsc::channel::<Job>(100);
+//! let (tx, rx) = channels_console::channel!((tx, rx), label = "jobs");
+//!
+//! let api_tx = channels_console::sender!(tx.clone(), channel = "jobs", label = "api");
+//! let cron_tx = channels_console::sender!(tx.clone(), channel = "jobs", label = "cron");
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::counters::ChannelCounters;
+use crate::{flush, init_channels_state, resolve_label};
+
+/// Messages sent by a single producer of a channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProducerStats {
+    pub label: String,
+    pub sent_count: u64,
+}
+
+/// Sender clone attributed to a producer of an instrumented channel.
+pub(crate) struct Producer {
+    /// Shared with the channel's counters, see [`counters`](crate::counters)
+    sent: Arc<AtomicU64>,
+}
+
+impl Producer {
+    /// Registers a producer of the most recent channel with the given label (or `file:line`
+    /// source). Returns `None` if no such channel is instrumented.
+    pub(crate) fn register(
+        source: &'static str,
+        channel: &str,
+        label: Option<String>,
+    ) -> Option<Self> {
+        let counters = channel_counters(source, channel, "sender")?;
+        let label: Arc<str> = label
+            .unwrap_or_else(|| resolve_label(source, None, 0))
+            .into();
+        Some(Self {
+            sent: counters.producer(&label),
+        })
+    }
+
+    /// Counts a message forwarded into the channel.
+    pub(crate) fn sent(&self) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Counters of the most recent channel with the given label (or `file:line` source), for the
+/// `handle` wrapped at `source`. Returns `None` if no such channel is instrumented.
+pub(crate) fn channel_counters(
+    source: &'static str,
+    channel: &str,
+    handle: &str,
+) -> Option<Arc<ChannelCounters>> {
+    let (stats_tx, stats) = init_channels_state();
+    if stats_tx.is_disabled() {
+        return None;
+    }
+
+    // The parent channel may have been created right before, make sure it's registered
+    flush();
+    let counters = stats
+        .read_all()
+        .values()
+        .filter(|stats| {
+            stats.source == channel
+                || resolve_label(stats.source, stats.label.as_deref(), stats.iter) == channel
+        })
+        .max_by_key(|stats| stats.id)
+        .map(|stats| Arc::clone(&stats.counters));
+    if counters.is_none() {
+        eprintln!(
+            "[channels-console] No instrumented channel '{}' found for {} at {}",
+            channel, handle, source
+        );
+    }
+    counters
+}
@@ -0,0 +1,151 @@
+//! Message rates and expected-rate annotations.
+//!
+//! The collector keeps rolling send and receive rates per channel, over 1s, 10s and 60s windows.
+//!
+//! Channels declared with `channel!(..., expected_rate = "100/s")` compare their actual send
+//! rate against the expectation, and are flagged when running significantly above or below it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::parse_rate;
+
+/// Window over which the actual send rate is compared against the expected rate.
+pub const EXPECTED_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Relative deviation from the expected rate tolerated before a channel is flagged.
+pub const EXPECTED_RATE_TOLERANCE: f64 = 0.5;
+
+/// Longest window a [`RateTracker`] keeps data for.
+const MAX_WINDOW_SECS: u64 = 60;
+
+/// Time after the last message until the rates of a channel stop changing.
+pub(crate) const MAX_WINDOW: Duration = Duration::from_secs(MAX_WINDOW_SECS);
+
+/// Parses the expected rate declared in the `channel!` macro, in messages per second.
+///
+/// This function is not intended for direct use. Use the `channel!` macro with `expected_rate = ".."` instead.
+#[doc(hidden)]
+pub fn parse_expected_rate(source: &'static str, rate: &str) -> f64 {
+    parse_rate(rate).unwrap_or_else(|| {
+        panic!(
+            "Invalid expected_rate {:?} at {}, expected a rate like \"100/s\", \"5/m\" or \"3/500ms\"",
+            rate, source
+        )
+    })
+}
+
+/// How the actual send rate of a channel compares to its expected rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateStatus {
+    Ok,
+    Above,
+    Below,
+}
+
+impl RateStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateStatus::Ok => "ok",
+            RateStatus::Above => "above",
+            RateStatus::Below => "below",
+        }
+    }
+
+    /// Compares an actual rate against the expected one, see [`EXPECTED_RATE_TOLERANCE`].
+    pub fn evaluate(actual: f64, expected: f64) -> Self {
+        if actual > expected * (1.0 + EXPECTED_RATE_TOLERANCE) {
+            RateStatus::Above
+        } else if actual < expected * (1.0 - EXPECTED_RATE_TOLERANCE) {
+            RateStatus::Below
+        } else {
+            RateStatus::Ok
+        }
+    }
+}
+
+impl std::fmt::Display for RateStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Messages per second over rolling windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateWindows {
+    pub last_1s: f64,
+    pub last_10s: f64,
+    pub last_60s: f64,
+}
+
+/// Counts events in one-second buckets to compute rolling rates.
+#[derive(Debug, Clone)]
+pub(crate) struct RateTracker {
+    created_at: Instant,
+    /// (seconds since `created_at`, events in that second), oldest first
+    buckets: VecDeque<(u64, u64)>,
+}
+
+impl RateTracker {
+    pub(crate) fn new(created_at: Instant) -> Self {
+        Self {
+            created_at,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, at: Instant) {
+        self.record_many(at, 1);
+    }
+
+    /// Records `count` events at once, e.g. counted since the previous sample.
+    pub(crate) fn record_many(&mut self, at: Instant, count: u64) {
+        if count == 0 {
+            return;
+        }
+        let second = at.saturating_duration_since(self.created_at).as_secs();
+        match self.buckets.back_mut() {
+            Some((last, total)) if *last >= second => *total += count,
+            _ => self.buckets.push_back((second, count)),
+        }
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(s, _)| *s + MAX_WINDOW_SECS < second)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Events per second over the last `window` full seconds, or since creation for
+    /// trackers younger than the window.
+    pub(crate) fn rate(&self, now: Instant, window: Duration) -> f64 {
+        let elapsed = now.saturating_duration_since(self.created_at);
+        let window_secs = window.as_secs().clamp(1, MAX_WINDOW_SECS);
+
+        if elapsed.as_secs() < window_secs {
+            let count: u64 = self.buckets.iter().map(|(_, count)| count).sum();
+            return count as f64 / elapsed.as_secs_f64().max(1.0);
+        }
+
+        // The current second is incomplete, so it's left out
+        let now_second = elapsed.as_secs();
+        let count: u64 = self
+            .buckets
+            .iter()
+            .filter(|(s, _)| *s < now_second && *s + window_secs >= now_second)
+            .map(|(_, count)| count)
+            .sum();
+        count as f64 / window_secs as f64
+    }
+
+    pub(crate) fn windows(&self, now: Instant) -> RateWindows {
+        RateWindows {
+            last_1s: self.rate(now, Duration::from_secs(1)),
+            last_10s: self.rate(now, Duration::from_secs(10)),
+            last_60s: self.rate(now, Duration::from_secs(60)),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Recording of statistics for offline replay.
+//!
+//! When `CHANNELS_CONSOLE_RECORD` (or [`ConfigBuilder::record_to`](crate::ConfigBuilder::record_to)
+//! / [`ChannelsGuardBuilder::record_to`](crate::ChannelsGuardBuilder::record_to)) is set, a
+//! background thread appends a [`HistoryRecord`](crate::history::HistoryRecord) to a JSONL
+//! file every [`RECORD_INTERVAL`]. Each record carries the log entries emitted since the
+//! previous one. Recordings are written by the same thread and in the same format as
+//! [`history`](crate::history), but they're never compacted.
+//!
+//! Play a recording back in the TUI with `channels-console replay <path>`, or load it with
+//! [`read_history`](crate::history::read_history).
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::history::{add_writer, write_now, SnapshotWriter, WriterKind};
+
+/// Interval between recorded snapshots.
+pub const RECORD_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Start recording to the configured path, if any.
+pub(crate) fn spawn_configured_recorder() {
+    if let Some(path) = &Config::current().record_path {
+        start_recording(path.clone());
+    }
+}
+
+/// Start recording, unless a recording is already running.
+pub(crate) fn start_recording(path: PathBuf) {
+    add_writer(SnapshotWriter::recording(path, RECORD_INTERVAL));
+}
+
+/// Append a snapshot right away, so a recording ends with the final state of the program.
+pub(crate) fn record_now() {
+    write_now(WriterKind::Recording);
+}
@@ -0,0 +1,302 @@
+//! Runtime registry of instrumented channels and streams.
+//!
+//! Lets application code introspect its own wiring, e.g. to print channel health from an
+//! admin command, without going through the HTTP API. Unlike [`snapshot`](crate::snapshot),
+//! registry lookups don't copy message logs.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use channels_console::registry;
+//!
+//! for channel in registry::channels() {
+//!     println!("{} {} {} queued={}", channel.label, channel.channel_type, channel.state, channel.queued);
+//! }
+//!
+//! if let Some(jobs) = registry::find_channel("jobs") {
+//!     assert!(!jobs.is_closed());
+//! }
+//! ```
+//!
+//! # Named channels
+//!
+//! Custom labels can be registered with a description, shown next to the channel in the
+//! console and in JSON responses. Independently of registration, the collector flags custom
+//! labels used by channels instrumented at different places, usually a copy-pasted
+//! `label = "worker"`, as conflicting:
+//!
+//! ```no_run
+//! channels_console::register_label("ingest", "Lines read from the upstream socket");
+//!
+//! for channel in channels_console::registry::channels() {
+//!     if channel.label_conflict {
+//!         eprintln!("{} is declared at several places", channel.label);
+//!     }
+//! }
+//! ```
+//!
+//! # Object IDs
+//!
+//! Channels, streams, sinks and primitives share one ID space, so an ID identifies a single
+//! object in combined views. [`object`] and the `/objects/:id` endpoint look one up without knowing its
+//! kind up front.
+
+#[cfg(feature = "tokio")]
+use crate::primitives::SerializablePrimitiveStats;
+use crate::sinks::{SerializableSinkStats, SINKS_STATE};
+use crate::{
+    compare_channel_stats, compare_stream_stats, resolve_label, ChannelState, ChannelStats,
+    ChannelType, SerializableChannelStats, SerializableStreamStats, StreamStats, CHANNELS_STATE,
+    SCHEMA_VERSION, STREAMS_STATE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, RwLock};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Allocates the ID of a new channel, stream, sink or primitive.
+pub(crate) fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// An instrumented channel, stream, sink or primitive, tagged with its `kind` in JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Object {
+    Channel(Box<SerializableChannelStats>),
+    Stream(SerializableStreamStats),
+    Sink(SerializableSinkStats),
+    /// Semaphore or notify, tagged with its own `semaphore` or `notify` kind
+    #[cfg(feature = "tokio")]
+    #[serde(untagged)]
+    Primitive(SerializablePrimitiveStats),
+}
+
+impl Object {
+    pub fn id(&self) -> u64 {
+        match self {
+            Self::Channel(stats) => stats.id,
+            Self::Stream(stats) => stats.id,
+            Self::Sink(stats) => stats.id,
+            #[cfg(feature = "tokio")]
+            Self::Primitive(stats) => stats.id,
+        }
+    }
+}
+
+/// Wrapper for the `/objects/:id` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub object: Object,
+}
+
+/// Looks up a channel, stream, sink or primitive by its ID.
+pub fn object(id: u64) -> Option<Object> {
+    let channel = CHANNELS_STATE
+        .get()
+        .and_then(|(_, stats)| stats.get(id, |stats| Object::Channel(Box::new(stats.into()))));
+    channel
+        .or_else(|| {
+            STREAMS_STATE
+                .get()
+                .and_then(|(_, stats)| stats.get(id, |stats| Object::Stream(stats.into())))
+        })
+        .or_else(|| {
+            SINKS_STATE
+                .get()
+                .and_then(|(_, stats)| stats.get(id, |stats| Object::Sink(stats.into())))
+        })
+        .or_else(|| primitive_object(id))
+}
+
+#[cfg(feature = "tokio")]
+fn primitive_object(id: u64) -> Option<Object> {
+    crate::primitives::primitive(id).map(Object::Primitive)
+}
+
+#[cfg(not(feature = "tokio"))]
+fn primitive_object(_id: u64) -> Option<Object> {
+    None
+}
+
+pub(crate) fn get_object_json(id: &str) -> Option<ObjectJson> {
+    let object = object(id.parse().ok()?)?;
+    Some(ObjectJson {
+        schema_version: SCHEMA_VERSION,
+        object,
+    })
+}
+
+/// Metadata and current counters of an instrumented channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelInfo {
+    pub id: u64,
+    /// `file:line` where the channel was instrumented
+    pub source: &'static str,
+    /// Custom label, or a label derived from the source
+    pub label: String,
+    pub channel_type: ChannelType,
+    pub state: ChannelState,
+    pub type_name: &'static str,
+    pub sent_count: u64,
+    pub received_count: u64,
+    pub queued: u64,
+    /// Description registered with [`register_label`]
+    pub description: Option<String>,
+    /// Whether the custom label is also used by a channel instrumented elsewhere
+    pub label_conflict: bool,
+}
+
+impl ChannelInfo {
+    pub fn is_closed(&self) -> bool {
+        self.state == ChannelState::Closed
+    }
+}
+
+impl From<&ChannelStats> for ChannelInfo {
+    fn from(stats: &ChannelStats) -> Self {
+        Self {
+            id: stats.id,
+            source: stats.source,
+            label: resolve_label(stats.source, stats.label.as_deref(), stats.iter),
+            channel_type: stats.channel_type,
+            state: stats.state,
+            type_name: stats.type_name,
+            sent_count: stats.sent_count,
+            received_count: stats.received_count,
+            queued: stats.queued(),
+            description: stats.label.as_deref().and_then(label_description),
+            label_conflict: stats.label.as_deref().is_some_and(has_label_conflict),
+        }
+    }
+}
+
+/// Metadata and current counters of an instrumented stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamInfo {
+    pub id: u64,
+    /// `file:line` where the stream was instrumented
+    pub source: &'static str,
+    /// Custom label, or a label derived from the source
+    pub label: String,
+    pub state: ChannelState,
+    pub type_name: &'static str,
+    pub items_yielded: u64,
+}
+
+impl StreamInfo {
+    pub fn is_closed(&self) -> bool {
+        self.state == ChannelState::Closed
+    }
+}
+
+impl From<&StreamStats> for StreamInfo {
+    fn from(stats: &StreamStats) -> Self {
+        Self {
+            id: stats.id,
+            source: stats.source,
+            label: resolve_label(stats.source, stats.label.as_deref(), stats.iter),
+            state: stats.state,
+            type_name: stats.type_name,
+            items_yielded: stats.items_yielded,
+        }
+    }
+}
+
+/// Returns all instrumented channels, sorted like in the console.
+pub fn channels() -> Vec<ChannelInfo> {
+    let Some((_, stats_map)) = CHANNELS_STATE.get() else {
+        return Vec::new();
+    };
+    let stats_map = stats_map.read_all();
+    let mut stats: Vec<&ChannelStats> = stats_map.values().collect();
+    stats.sort_by(|a, b| compare_channel_stats(a, b));
+    stats.into_iter().map(ChannelInfo::from).collect()
+}
+
+/// Returns all instrumented streams, sorted like in the console.
+pub fn streams() -> Vec<StreamInfo> {
+    let Some((_, stats_map)) = STREAMS_STATE.get() else {
+        return Vec::new();
+    };
+    let stats_map = stats_map.read_all();
+    let mut stats: Vec<&StreamStats> = stats_map.values().collect();
+    stats.sort_by(|a, b| compare_stream_stats(a, b));
+    stats.into_iter().map(StreamInfo::from).collect()
+}
+
+/// Looks up a channel by its label (or `file:line` source).
+pub fn find_channel(label: &str) -> Option<ChannelInfo> {
+    channels()
+        .into_iter()
+        .find(|channel| channel.label == label || channel.source == label)
+}
+
+/// Looks up a stream by its label (or `file:line` source).
+pub fn find_stream(label: &str) -> Option<StreamInfo> {
+    streams()
+        .into_iter()
+        .find(|stream| stream.label == label || stream.source == label)
+}
+
+static LABEL_DESCRIPTIONS: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+
+/// Registers a custom channel label with a description of what flows through the channel.
+///
+/// Registering the same label again replaces its description.
+pub fn register_label(label: impl Into<String>, description: impl Into<String>) {
+    let (label, description) = (label.into(), description.into());
+    let mut registered = LABEL_DESCRIPTIONS.write().unwrap();
+    match registered.iter_mut().find(|(l, _)| *l == label) {
+        Some(entry) => entry.1 = description,
+        None => registered.push((label, description)),
+    }
+}
+
+/// Description registered for `label` with [`register_label`].
+pub fn label_description(label: &str) -> Option<String> {
+    LABEL_DESCRIPTIONS
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(l, _)| l == label)
+        .map(|(_, description)| description.clone())
+}
+
+/// Sources of the channels created with each custom label.
+static LABEL_SOURCES: LazyLock<Mutex<HashMap<String, Vec<&'static str>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Records a channel created at `source` with a custom `label`, warning the first time the
+/// label shows up at another source. Channels created repeatedly at the same place, e.g. one
+/// per connection, don't conflict, their labels get an `-N` suffix instead.
+pub(crate) fn claim_label(label: &str, source: &'static str) {
+    let mut sources = LABEL_SOURCES.lock().unwrap();
+    let sources = sources.entry(label.to_string()).or_default();
+    if sources.contains(&source) {
+        return;
+    }
+    sources.push(source);
+    if sources.len() > 1 {
+        eprintln!(
+            "[channels-console] Label '{}' is used by channels at {}, their stats are hard to tell apart",
+            label,
+            sources.join(", ")
+        );
+    }
+}
+
+/// Whether the custom `label` is used by channels instrumented at more than one place.
+pub fn has_label_conflict(label: &str) -> bool {
+    LABEL_SOURCES
+        .lock()
+        .unwrap()
+        .get(label)
+        .is_some_and(|sources| sources.len() > 1)
+}
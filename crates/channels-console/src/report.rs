@@ -0,0 +1,365 @@
+//! Renderings of the statistics printed by [`ChannelsGuard`](crate::ChannelsGuard), other than
+//! JSON. Also used by the `channels-console snapshot` command.
+
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::summary::Summary;
+use crate::{
+    format_bytes, ChannelType, CombinedJson, SerializableChannelStats, SerializableStreamStats,
+};
+
+/// Channels and streams tables, as printed in the `Table` format.
+pub fn render_table(combined: &CombinedJson) -> String {
+    let mut out = String::new();
+    if !combined.channels.is_empty() {
+        out.push_str("\nChannels:\n");
+        out.push_str(&channels_text_table(&combined.channels));
+    }
+    if !combined.streams.is_empty() {
+        out.push_str("\nStreams:\n");
+        out.push_str(&streams_text_table(&combined.streams));
+    }
+    out
+}
+
+pub(crate) fn channels_text_table(channels: &[SerializableChannelStats]) -> String {
+    let mut table = Table::new();
+
+    table.add_row(Row::new(vec![
+        Cell::new("Channel"),
+        Cell::new("Type"),
+        Cell::new("State"),
+        Cell::new("Sent"),
+        Cell::new("Received"),
+        Cell::new("Queued"),
+        Cell::new("Mem"),
+    ]));
+
+    for channel in channels {
+        table.add_row(Row::new(vec![
+            Cell::new(&channel.label),
+            Cell::new(&channel.channel_type.to_string()),
+            Cell::new(channel.state.as_str()),
+            Cell::new(&channel.sent_count.to_string()),
+            Cell::new(&channel.received_count.to_string()),
+            Cell::new(&channel.queued.to_string()),
+            Cell::new(&format_bytes(channel.queued_bytes)),
+        ]));
+    }
+
+    table.to_string()
+}
+
+pub(crate) fn streams_text_table(streams: &[SerializableStreamStats]) -> String {
+    let mut table = Table::new();
+
+    table.add_row(Row::new(vec![
+        Cell::new("Stream"),
+        Cell::new("State"),
+        Cell::new("Yielded"),
+    ]));
+
+    for stream in streams {
+        table.add_row(Row::new(vec![
+            Cell::new(&stream.label),
+            Cell::new(stream.state.as_str()),
+            Cell::new(&stream.items_yielded.to_string()),
+        ]));
+    }
+
+    table.to_string()
+}
+
+const CSV_HEADER: &str = "kind,id,label,source,state,channel_type,type_name,sent_count,received_count,queued,queued_bytes,latency_p50_ns,latency_p99_ns,items_yielded";
+
+/// One row per channel and stream, channels first. Columns that don't apply are left empty.
+pub fn render_csv(combined: &CombinedJson) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+
+    for channel in &combined.channels {
+        let row = [
+            "channel".to_string(),
+            channel.id.to_string(),
+            csv_field(&channel.label),
+            csv_field(&channel.source),
+            channel.state.to_string(),
+            channel.channel_type.to_string(),
+            csv_field(&channel.type_name),
+            channel.sent_count.to_string(),
+            channel.received_count.to_string(),
+            channel.queued.to_string(),
+            channel.queued_bytes.to_string(),
+            channel
+                .latency
+                .map_or(String::new(), |latency| latency.p50.to_string()),
+            channel
+                .latency
+                .map_or(String::new(), |latency| latency.p99.to_string()),
+            String::new(),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+
+    for stream in &combined.streams {
+        let row = [
+            "stream".to_string(),
+            stream.id.to_string(),
+            csv_field(&stream.label),
+            csv_field(&stream.source),
+            stream.state.to_string(),
+            String::new(),
+            csv_field(&stream.type_name),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            stream.items_yielded.to_string(),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Quotes fields containing separators, quotes or line breaks (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A line of the JSON Lines output, tagged with its `kind`.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum JsonLine<'a> {
+    Channel(&'a SerializableChannelStats),
+    Stream(&'a SerializableStreamStats),
+}
+
+/// One JSON object per channel and stream, channels first.
+pub(crate) fn render_json_lines(combined: &CombinedJson) -> serde_json::Result<String> {
+    let lines = combined
+        .channels
+        .iter()
+        .map(JsonLine::Channel)
+        .chain(combined.streams.iter().map(JsonLine::Stream));
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&serde_json::to_string(&line)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Rows of a report table, shared by the Markdown and HTML renderings.
+struct ReportTable {
+    headers: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+    /// Queue fill ratio of each row, drawn as a bar in HTML
+    fills: Vec<Option<f64>>,
+}
+
+fn summary_table(summary: &Summary) -> ReportTable {
+    let busiest = summary.busiest.as_ref().map_or("-".to_string(), |busiest| {
+        format!("{} ({:.1}/s)", busiest.label, busiest.send_rate)
+    });
+    ReportTable {
+        headers: &[
+            "Channels", "Active", "Full", "Closed", "Sent", "Received", "Queued", "Mem", "Busiest",
+        ],
+        rows: vec![vec![
+            summary.channels.to_string(),
+            summary.states.active.to_string(),
+            summary.states.full.to_string(),
+            summary.states.closed.to_string(),
+            summary.sent_count.to_string(),
+            summary.received_count.to_string(),
+            summary.queued.to_string(),
+            format_bytes(summary.queued_bytes),
+            busiest,
+        ]],
+        fills: vec![None],
+    }
+}
+
+fn channels_table(channels: &[SerializableChannelStats]) -> ReportTable {
+    let latency = |channel: &SerializableChannelStats,
+                   pick: fn(&crate::LatencyPercentiles) -> u64| {
+        channel
+            .latency
+            .as_ref()
+            .map_or("-".to_string(), |latency| format_ns(pick(latency)))
+    };
+    let fill = |channel: &SerializableChannelStats| match channel.channel_type {
+        ChannelType::Bounded(capacity) if capacity > 0 => {
+            Some((channel.queued as f64 / capacity as f64).min(1.0))
+        }
+        _ => None,
+    };
+
+    ReportTable {
+        headers: &[
+            "Channel", "Type", "State", "Sent", "Received", "Queued", "Fill", "Mem", "p50", "p95",
+            "p99", "Max",
+        ],
+        rows: channels
+            .iter()
+            .map(|channel| {
+                vec![
+                    channel.label.clone(),
+                    channel.channel_type.to_string(),
+                    channel.state.to_string(),
+                    channel.sent_count.to_string(),
+                    channel.received_count.to_string(),
+                    channel.queued.to_string(),
+                    fill(channel).map_or("-".to_string(), |fill| format!("{:.0}%", fill * 100.0)),
+                    format_bytes(channel.queued_bytes),
+                    latency(channel, |l| l.p50),
+                    latency(channel, |l| l.p95),
+                    latency(channel, |l| l.p99),
+                    latency(channel, |l| l.max),
+                ]
+            })
+            .collect(),
+        fills: channels.iter().map(fill).collect(),
+    }
+}
+
+fn streams_table(streams: &[SerializableStreamStats]) -> ReportTable {
+    ReportTable {
+        headers: &["Stream", "State", "Yielded", "Rate (10s)", "Max gap"],
+        rows: streams
+            .iter()
+            .map(|stream| {
+                vec![
+                    stream.label.clone(),
+                    stream.state.to_string(),
+                    stream.items_yielded.to_string(),
+                    format!("{:.1}/s", stream.yield_rate.last_10s),
+                    format_ns(stream.max_gap_ns),
+                ]
+            })
+            .collect(),
+        fills: vec![None; streams.len()],
+    }
+}
+
+/// Markdown report with a summary, and channels and streams tables.
+pub(crate) fn render_markdown(combined: &CombinedJson, runtime: Duration) -> String {
+    let mut out = format!(
+        "# Channel Statistics\n\nRuntime: {:.2}s\n",
+        runtime.as_secs_f64()
+    );
+
+    out.push_str("\n## Summary\n\n");
+    out.push_str(&markdown_table(&summary_table(&Summary::new(
+        &combined.channels,
+    ))));
+    if !combined.channels.is_empty() {
+        out.push_str("\n## Channels\n\n");
+        out.push_str(&markdown_table(&channels_table(&combined.channels)));
+    }
+    if !combined.streams.is_empty() {
+        out.push_str("\n## Streams\n\n");
+        out.push_str(&markdown_table(&streams_table(&combined.streams)));
+    }
+    out
+}
+
+fn markdown_table(table: &ReportTable) -> String {
+    let mut out = format!("| {} |\n", table.headers.join(" | "));
+    out.push_str(&format!("|{}\n", "---|".repeat(table.headers.len())));
+    for row in &table.rows {
+        let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:1.5em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+th{background:#f3f3f3}\
+.fill{display:inline-block;width:60px;height:8px;margin-right:6px;background:#eee}\
+.fill span{display:block;height:100%;background:#e0a030}";
+
+/// Self-contained HTML page with a summary, and channels and streams tables.
+pub(crate) fn render_html(combined: &CombinedJson, runtime: Duration) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Channel Statistics</title>\n<style>{}</style>\n</head>\n<body>\n<h1>Channel Statistics</h1>\n<p>Runtime: {:.2}s</p>\n",
+        HTML_STYLE,
+        runtime.as_secs_f64()
+    );
+
+    out.push_str("<h2>Summary</h2>\n");
+    out.push_str(&html_table(&summary_table(&Summary::new(
+        &combined.channels,
+    ))));
+    if !combined.channels.is_empty() {
+        out.push_str("<h2>Channels</h2>\n");
+        out.push_str(&html_table(&channels_table(&combined.channels)));
+    }
+    if !combined.streams.is_empty() {
+        out.push_str("<h2>Streams</h2>\n");
+        out.push_str(&html_table(&streams_table(&combined.streams)));
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn html_table(table: &ReportTable) -> String {
+    let mut out = String::from("<table>\n<tr>");
+    for header in table.headers {
+        out.push_str(&format!("<th>{}</th>", header));
+    }
+    out.push_str("</tr>\n");
+
+    let fill_column = table.headers.iter().position(|header| *header == "Fill");
+    for (row, fill) in table.rows.iter().zip(&table.fills) {
+        out.push_str("<tr>");
+        for (idx, cell) in row.iter().enumerate() {
+            match fill {
+                Some(fill) if Some(idx) == fill_column => out.push_str(&format!(
+                    "<td><span class=\"fill\"><span style=\"width:{:.0}%\"></span></span>{}</td>",
+                    fill * 100.0,
+                    html_escape(cell)
+                )),
+                _ => out.push_str(&format!("<td>{}</td>", html_escape(cell))),
+            }
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Formats nanoseconds with a unit matching their magnitude.
+fn format_ns(ns: u64) -> String {
+    if ns < 1_000 {
+        format!("{}ns", ns)
+    } else if ns < 1_000_000 {
+        format!("{:.1}µs", ns as f64 / 1_000.0)
+    } else if ns < 1_000_000_000 {
+        format!("{:.2}ms", ns as f64 / 1_000_000.0)
+    } else {
+        format!("{:.3}s", ns as f64 / 1_000_000_000.0)
+    }
+}
@@ -0,0 +1,97 @@
+//! Eviction of closed channels.
+//!
+//! Programs creating a channel per request would otherwise grow the statistics map forever.
+//! Closed channels are dropped once they've been closed for longer than
+//! [`closed_retention`](crate::config::ConfigBuilder::closed_retention), or earlier, oldest
+//! first, while more than [`max_channels`](crate::config::ConfigBuilder::max_channels) are
+//! tracked. Open channels are never evicted. Dropped semaphores and notifies follow
+//! the same rules.
+//!
+//! Counters of evicted channels are summed into a [`ClosedTotal`], served as `closed_total`
+//! by the `/channels` endpoint.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::config::Config;
+use crate::shards::Shards;
+use crate::{ChannelState, ChannelStats};
+
+/// Aggregated counters of channels evicted from the statistics map.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClosedTotal {
+    /// Number of evicted channels
+    pub channels: u64,
+    pub sent_count: u64,
+    pub received_count: u64,
+}
+
+static CLOSED_TOTAL: Mutex<ClosedTotal> = Mutex::new(ClosedTotal {
+    channels: 0,
+    sent_count: 0,
+    received_count: 0,
+});
+
+/// Counters of all channels evicted so far.
+pub fn closed_total() -> ClosedTotal {
+    *CLOSED_TOTAL.lock().unwrap()
+}
+
+/// Zeroes the counters of evicted channels, keeping their number.
+pub(crate) fn reset_closed_total() {
+    let mut total = CLOSED_TOTAL.lock().unwrap();
+    total.sent_count = 0;
+    total.received_count = 0;
+}
+
+/// Time the channel was closed at, in nanoseconds since program start.
+fn closed_at(stats: &ChannelStats) -> Option<u64> {
+    if stats.state != ChannelState::Closed {
+        return None;
+    }
+    stats
+        .state_history
+        .back()
+        .map(|transition| transition.timestamp)
+}
+
+/// Drops closed channels past their retention, and the oldest closed ones while over the
+/// channels limit.
+pub(crate) fn evict_closed_channels(
+    stats: &Shards<ChannelStats>,
+    config: &Config,
+    elapsed_ns: u64,
+) {
+    if config.closed_retention.is_none() && config.max_channels.is_none() {
+        return;
+    }
+
+    let all = stats.read_all();
+    let mut closed: Vec<(u64, u64)> = all
+        .values()
+        .filter_map(|channel| closed_at(channel).map(|closed_at| (closed_at, channel.id)))
+        .collect();
+    closed.sort_unstable();
+
+    let over_limit = config
+        .max_channels
+        .map_or(0, |max| all.len().saturating_sub(max));
+    drop(all);
+    let expired = |closed_at: u64| {
+        config.closed_retention.is_some_and(|retention| {
+            elapsed_ns.saturating_sub(closed_at) >= retention.as_nanos() as u64
+        })
+    };
+
+    let mut total = CLOSED_TOTAL.lock().unwrap();
+    for (idx, (closed_at, id)) in closed.into_iter().enumerate() {
+        if idx >= over_limit && !expired(closed_at) {
+            break;
+        }
+        if let Some(channel) = stats.remove(id) {
+            total.channels += 1;
+            total.sent_count += channel.sent_count;
+            total.received_count += channel.received_count;
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Time series of each channel's queue depth and counters.
+//!
+//! The collector samples every channel once per [`SAMPLE_INTERVAL`] and keeps the most recent
+//! [`channel_samples`](crate::ConfigBuilder::channel_samples) samples (default: 300, five
+//! minutes). They are served by the `/channels/:id/history?window=<seconds>` endpoint, so
+//! trends can be charted without polling the process since it started. With
+//! [persistent history](crate::history) enabled, longer windows are filled from the history file.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::history::persisted_samples;
+use crate::shards::Shards;
+use crate::{ChannelStats, CHANNELS_STATE, SCHEMA_VERSION};
+
+/// Time between two samples of a channel.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Statistics of a channel at one point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelSample {
+    /// Time since program start in nanoseconds
+    pub elapsed_ns: u64,
+    pub queued: u64,
+    /// Memory held by queued messages, see [`sizes`](crate::sizes)
+    #[serde(default)]
+    pub queued_bytes: u64,
+    pub sent_count: u64,
+    pub received_count: u64,
+}
+
+/// Wrapper for the `/channels/:id/history` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelHistoryJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    pub id: String,
+    /// Time between samples in milliseconds
+    pub interval_ms: u64,
+    /// Oldest first
+    pub samples: Vec<ChannelSample>,
+}
+
+/// Appends a sample to every channel, dropping the oldest ones beyond `limit`.
+pub(crate) fn record_samples(stats: &Shards<ChannelStats>, elapsed_ns: u64, limit: usize) {
+    stats.update_all(|channel_stats| {
+        if limit == 0 {
+            channel_stats.samples.clear();
+            return;
+        }
+        while channel_stats.samples.len() >= limit {
+            channel_stats.samples.pop_front();
+        }
+        channel_stats.samples.push_back(ChannelSample {
+            elapsed_ns,
+            queued: channel_stats.queued(),
+            queued_bytes: channel_stats.queued_bytes(),
+            sent_count: channel_stats.sent_count,
+            received_count: channel_stats.received_count,
+        });
+    });
+}
+
+/// Samples of a channel, limited to the last `window` if given.
+///
+/// When the in-memory samples are full and `window` reaches further back, older samples are
+/// taken from the persisted [`history`](crate::history) of this run, if enabled.
+pub(crate) fn get_channel_history(
+    channel_id: &str,
+    window: Option<Duration>,
+) -> Option<ChannelHistoryJson> {
+    let id = channel_id.parse::<u64>().ok()?;
+    let (_, stats_map) = CHANNELS_STATE.get()?;
+    let window_ns = window.map_or(u64::MAX, |window| window.as_nanos() as u64);
+    let (newest, full, mut samples) = stats_map.get(id, |channel_stats| {
+        let newest = channel_stats.samples.back().map_or(0, |s| s.elapsed_ns);
+        let full = channel_stats.samples.len() >= Config::current().channel_samples;
+        let samples: Vec<ChannelSample> = channel_stats
+            .samples
+            .iter()
+            .filter(|sample| newest - sample.elapsed_ns < window_ns)
+            .copied()
+            .collect();
+        (newest, full, samples)
+    })?;
+
+    // Read the history file only after the shard guard is released
+    if let (Some(oldest), Some(_), true) = (samples.first(), window, full) {
+        if newest - oldest.elapsed_ns + (SAMPLE_INTERVAL.as_nanos() as u64) < window_ns {
+            let mut persisted: Vec<ChannelSample> = persisted_samples(id, oldest.elapsed_ns)
+                .into_iter()
+                .filter(|sample| newest - sample.elapsed_ns < window_ns)
+                .collect();
+            persisted.append(&mut samples);
+            samples = persisted;
+        }
+    }
+
+    Some(ChannelHistoryJson {
+        schema_version: SCHEMA_VERSION,
+        id: channel_id.to_string(),
+        interval_ms: SAMPLE_INTERVAL.as_millis() as u64,
+        samples,
+    })
+}
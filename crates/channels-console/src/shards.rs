@@ -0,0 +1,94 @@
+//! Sharded storage of channel and stream statistics.
+//!
+//! Entries are spread by id over independently locked shards, so the collector applying an
+//! event only blocks readers of one shard, and readers of a single entry (e.g. the
+//! `/channels/:id/logs` endpoint) only lock the shard holding it. Readers of all entries hold
+//! read locks while serializing in place, instead of cloning the whole map.
+
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard};
+
+const SHARD_COUNT: usize = 16;
+
+/// Statistics by id, split into [`SHARD_COUNT`] shards.
+#[derive(Debug)]
+pub(crate) struct Shards<S> {
+    shards: Vec<RwLock<HashMap<u64, S>>>,
+}
+
+impl<S> Default for Shards<S> {
+    fn default() -> Self {
+        Self {
+            shards: (0..SHARD_COUNT)
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+        }
+    }
+}
+
+impl<S> Shards<S> {
+    fn shard(&self, id: u64) -> &RwLock<HashMap<u64, S>> {
+        &self.shards[id as usize % SHARD_COUNT]
+    }
+
+    pub(crate) fn insert(&self, id: u64, stats: S) {
+        self.shard(id).write().unwrap().insert(id, stats);
+    }
+
+    pub(crate) fn remove(&self, id: u64) -> Option<S> {
+        self.shard(id).write().unwrap().remove(&id)
+    }
+
+    /// Reads a single entry, only locking its shard.
+    pub(crate) fn get<R>(&self, id: u64, f: impl FnOnce(&S) -> R) -> Option<R> {
+        self.shard(id).read().unwrap().get(&id).map(f)
+    }
+
+    /// Updates a single entry, only locking its shard.
+    pub(crate) fn update<R>(&self, id: u64, f: impl FnOnce(&mut S) -> R) -> Option<R> {
+        self.shard(id).write().unwrap().get_mut(&id).map(f)
+    }
+
+    /// Updates all entries, locking one shard at a time.
+    pub(crate) fn update_all(&self, mut f: impl FnMut(&mut S)) {
+        for shard in &self.shards {
+            shard.write().unwrap().values_mut().for_each(&mut f);
+        }
+    }
+
+    /// Read access to all entries. Holds the read locks of all shards until dropped, so it
+    /// must not be held while updating entries.
+    pub(crate) fn read_all(&self) -> ReadAll<'_, S> {
+        ReadAll {
+            guards: self
+                .shards
+                .iter()
+                .map(|shard| shard.read().unwrap())
+                .collect(),
+        }
+    }
+}
+
+/// All entries of [`Shards`], see [`Shards::read_all`].
+pub(crate) struct ReadAll<'a, S> {
+    guards: Vec<RwLockReadGuard<'a, HashMap<u64, S>>>,
+}
+
+impl<S> ReadAll<'_, S> {
+    /// Entries in no particular order.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &S> {
+        self.guards.iter().flat_map(|shard| shard.values())
+    }
+
+    pub(crate) fn get(&self, id: u64) -> Option<&S> {
+        self.guards[id as usize % SHARD_COUNT].get(&id)
+    }
+
+    pub(crate) fn contains_key(&self, id: u64) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.guards.iter().map(|shard| shard.len()).sum()
+    }
+}
@@ -0,0 +1,107 @@
+use crate::registry::next_id;
+use crate::sinks::{init_sinks_state, SinkEvent, SinkEventSender};
+use futures_util::Sink;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Wrapper around a `Sink` that instruments it with statistics collection.
+///
+/// This struct implements the `Sink` trait and forwards all calls to the inner sink
+/// while counting accepted items, completed flushes and errors.
+pub struct InstrumentedSink<S> {
+    inner: S,
+    stats_tx: SinkEventSender,
+    id: u64,
+}
+
+impl<S> InstrumentedSink<S> {
+    /// Create a new instrumented sink wrapper.
+    ///
+    /// # Parameters
+    /// - `sink`: The underlying sink to instrument
+    /// - `source`: Source location (file:line) for identification
+    /// - `label`: Optional custom label
+    pub(crate) fn new(sink: S, source: &'static str, label: Option<String>) -> Self {
+        let (stats_tx, _) = init_sinks_state();
+        let id = next_id();
+
+        // Send sink creation event
+        let _ = stats_tx.send(SinkEvent::Created {
+            id,
+            source,
+            display_label: label,
+        });
+
+        Self {
+            inner: sink,
+            stats_tx: stats_tx.clone(),
+            id,
+        }
+    }
+
+    /// Counts a completed flush or close, or an error.
+    fn record<E>(&self, result: Poll<Result<(), E>>, done: SinkEvent) -> Poll<Result<(), E>> {
+        match &result {
+            Poll::Ready(Ok(())) => {
+                let _ = self.stats_tx.send(done);
+            }
+            Poll::Ready(Err(_)) => {
+                let _ = self.stats_tx.send(SinkEvent::Failed { id: self.id });
+            }
+            Poll::Pending => {}
+        }
+        result
+    }
+}
+
+impl<S: Sink<Item>, Item> Sink<Item> for InstrumentedSink<S> {
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // SAFETY: We need to project through the Pin to access the inner sink.
+        // This is safe because we don't move the inner sink, we just get a mutable reference.
+        // The outer InstrumentedSink being pinned ensures the inner sink stays pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+        inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        // SAFETY: Same as above - we're projecting through Pin without moving
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+
+        let result = inner.start_send(item);
+        let event = match result {
+            Ok(()) => SinkEvent::Accepted { id: this.id },
+            Err(_) => SinkEvent::Failed { id: this.id },
+        };
+        let _ = this.stats_tx.send(event);
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // SAFETY: Same as above - we're projecting through Pin without moving
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+
+        let result = inner.poll_flush(cx);
+        this.record(result, SinkEvent::Flushed { id: this.id })
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // SAFETY: Same as above - we're projecting through Pin without moving
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+
+        let result = inner.poll_close(cx);
+        this.record(result, SinkEvent::Closed { id: this.id })
+    }
+}
+
+impl<S> Drop for InstrumentedSink<S> {
+    fn drop(&mut self) {
+        // A sink dropped without `close` is closed too
+        let _ = self.stats_tx.send(SinkEvent::Closed { id: self.id });
+    }
+}
@@ -0,0 +1,235 @@
+//! Statistics of sinks instrumented with the `sink!` macro.
+//!
+//! Pipelines are often a `Stream` feeding a `Sink`. Instrumented sinks count accepted items,
+//! completed flushes and errors, and are served by the `/sinks` endpoint.
+
+use crossbeam_channel::{unbounded, Sender as CbSender};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+
+use crate::config::Config;
+use crate::shards::Shards;
+use crate::{
+    init_start_time, labels, now, resolve_label, spawn_background_services, ChannelState,
+    EventSender, SCHEMA_VERSION, START_TIME,
+};
+
+/// Statistics for a single instrumented sink.
+#[derive(Debug, Clone)]
+pub(crate) struct SinkStats {
+    pub(crate) id: u64,
+    pub(crate) source: &'static str,
+    pub(crate) label: Option<String>,
+    pub(crate) state: ChannelState, // Only Active or Closed
+    pub(crate) items_accepted: u64,
+    pub(crate) flushes: u64,
+    pub(crate) errors: u64,
+    pub(crate) iter: u32,
+}
+
+/// Events sent to the background sink statistics collection thread.
+#[derive(Debug)]
+pub(crate) enum SinkEvent {
+    Created {
+        id: u64,
+        source: &'static str,
+        display_label: Option<String>,
+    },
+    Accepted {
+        id: u64,
+    },
+    Flushed {
+        id: u64,
+    },
+    /// `start_send`, `poll_flush` or `poll_close` returned an error
+    Failed {
+        id: u64,
+    },
+    Closed {
+        id: u64,
+    },
+    /// Acknowledged once all previously sent events are applied, see [`flush`](crate::flush).
+    Flush {
+        ack: CbSender<()>,
+    },
+}
+
+pub(crate) type SinkEventSender = EventSender<SinkEvent, SinkStats>;
+
+type SinkStatsState = (SinkEventSender, Arc<Shards<SinkStats>>);
+
+pub(crate) static SINKS_STATE: OnceLock<SinkStatsState> = OnceLock::new();
+
+/// Initialize the sink statistics collection system (called on first instrumented sink).
+/// Returns a reference to the global state.
+pub(crate) fn init_sinks_state() -> &'static SinkStatsState {
+    SINKS_STATE.get_or_init(|| {
+        init_start_time();
+
+        let (tx, rx) = unbounded::<SinkEvent>();
+        let stats_map = Arc::new(Shards::<SinkStats>::default());
+        let stats_map_clone = Arc::clone(&stats_map);
+
+        let disabled = Config::current().disabled;
+        if !disabled {
+            std::thread::Builder::new()
+                .name("sink-stats-collector".into())
+                .spawn(move || {
+                    while let Ok(event) = rx.recv() {
+                        apply_sink_event(&stats_map_clone, event);
+                    }
+                })
+                .expect("Failed to spawn sink-stats-collector thread");
+
+            spawn_background_services();
+        }
+
+        let sender = EventSender {
+            tx,
+            stats: Arc::clone(&stats_map),
+            apply: apply_sink_event,
+            synchronous: Config::current().synchronous,
+            disabled,
+        };
+        (sender, stats_map)
+    })
+}
+
+/// Apply a single event to the sink statistics map.
+fn apply_sink_event(stats: &Shards<SinkStats>, event: SinkEvent) {
+    match event {
+        SinkEvent::Created {
+            id,
+            source,
+            display_label,
+        } => {
+            // Count existing sinks with the same source location
+            let iter = stats
+                .read_all()
+                .values()
+                .filter(|s| s.source == source)
+                .count() as u32;
+
+            stats.insert(
+                id,
+                SinkStats {
+                    id,
+                    source,
+                    label: display_label,
+                    state: ChannelState::Active,
+                    items_accepted: 0,
+                    flushes: 0,
+                    errors: 0,
+                    iter,
+                },
+            );
+        }
+        SinkEvent::Accepted { id } => {
+            stats.update(id, |sink_stats| sink_stats.items_accepted += 1);
+        }
+        SinkEvent::Flushed { id } => {
+            stats.update(id, |sink_stats| sink_stats.flushes += 1);
+        }
+        SinkEvent::Failed { id } => {
+            stats.update(id, |sink_stats| sink_stats.errors += 1);
+        }
+        SinkEvent::Closed { id } => {
+            stats.update(id, |sink_stats| sink_stats.state = ChannelState::Closed);
+        }
+        SinkEvent::Flush { ack } => {
+            let _ = ack.send(());
+        }
+    }
+}
+
+/// Serializable version of sink statistics for JSON responses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableSinkStats {
+    pub id: u64,
+    pub source: String,
+    pub label: String,
+    pub has_custom_label: bool,
+    pub state: ChannelState,
+    pub items_accepted: u64,
+    /// Number of completed `poll_flush` calls
+    pub flushes: u64,
+    pub errors: u64,
+    pub iter: u32,
+}
+
+impl From<&SinkStats> for SerializableSinkStats {
+    fn from(sink_stats: &SinkStats) -> Self {
+        Self {
+            id: sink_stats.id,
+            source: sink_stats.source.to_string(),
+            label: resolve_label(
+                sink_stats.source,
+                sink_stats.label.as_deref(),
+                sink_stats.iter,
+            ),
+            has_custom_label: sink_stats.label.is_some(),
+            state: sink_stats.state,
+            items_accepted: sink_stats.items_accepted,
+            flushes: sink_stats.flushes,
+            errors: sink_stats.errors,
+            iter: sink_stats.iter,
+        }
+    }
+}
+
+/// Wrapper for the `/sinks` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinksJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Current elapsed time since program start in nanoseconds
+    pub current_elapsed_ns: u64,
+    pub sinks: Vec<SerializableSinkStats>,
+}
+
+impl SinksJson {
+    /// Keep only sinks with labels under the given path prefix, see [`labels`].
+    pub fn retain_prefix(&mut self, prefix: &str) {
+        self.sinks
+            .retain(|sink| labels::matches_prefix(&sink.label, prefix));
+    }
+}
+
+/// Zeroes the counters of all sinks, see [`reset`](crate::control::reset).
+pub(crate) fn reset_sink_stats() {
+    if let Some((_, stats_map)) = SINKS_STATE.get() {
+        stats_map.update_all(|sink| {
+            sink.items_accepted = 0;
+            sink.flushes = 0;
+            sink.errors = 0;
+        });
+    }
+}
+
+/// Statistics of all instrumented sinks, sorted by id.
+pub(crate) fn get_sorted_sink_stats() -> Vec<SinkStats> {
+    let Some((_, stats_map)) = SINKS_STATE.get() else {
+        return Vec::new();
+    };
+    let mut stats: Vec<SinkStats> = stats_map.read_all().values().cloned().collect();
+    stats.sort_by_key(|sink| sink.id);
+    stats
+}
+
+pub(crate) fn get_sinks_json() -> SinksJson {
+    let sinks = get_sorted_sink_stats()
+        .iter()
+        .map(SerializableSinkStats::from)
+        .collect();
+
+    let current_elapsed_ns = now()
+        .duration_since(*START_TIME.get_or_init(now))
+        .as_nanos() as u64;
+
+    SinksJson {
+        schema_version: SCHEMA_VERSION,
+        current_elapsed_ns,
+        sinks,
+    }
+}
@@ -0,0 +1,97 @@
+//! Per-message sizes.
+//!
+//! By default a channel's queued bytes are estimated as `queued × size_of::<T>()`, which misses
+//! the heap memory owned by messages like `Vec<u8>` or `String`. Channels declared with
+//! `channel!(..., size = true)` measure each message with its [`MessageSize`] implementation,
+//! or with the function given as `size_with = |msg| ..`. Measured channels report the bytes
+//! actually queued, and the cumulative bytes sent and received.
+//!
+//! ```rust,ignore
+//! let (tx, rx) = tokio::sync::mpsc::channel::<Vec<u8>>(100);
+//! let (tx, rx) = channels_console::channel!((tx, rx), label = "frames", size = true);
+//!
+//! let (tx, rx) = tokio::sync::mpsc::channel::<Job>(100);
+//! let (tx, rx) = channels_console::channel!((tx, rx), size_with = |job| job.payload.len());
+//! ```
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::Arc;
+
+use crate::Instrument;
+
+/// Size of a message in bytes, including the heap memory it owns.
+pub trait MessageSize {
+    fn message_size(&self) -> usize;
+}
+
+impl MessageSize for String {
+    fn message_size(&self) -> usize {
+        mem::size_of::<Self>() + self.capacity()
+    }
+}
+
+impl MessageSize for Box<str> {
+    fn message_size(&self) -> usize {
+        mem::size_of::<Self>() + self.len()
+    }
+}
+
+impl<T> MessageSize for Vec<T> {
+    fn message_size(&self) -> usize {
+        mem::size_of::<Self>() + self.capacity() * mem::size_of::<T>()
+    }
+}
+
+impl<T> MessageSize for Box<[T]> {
+    fn message_size(&self) -> usize {
+        mem::size_of::<Self>() + mem::size_of_val(&**self)
+    }
+}
+
+impl<T> MessageSize for VecDeque<T> {
+    fn message_size(&self) -> usize {
+        mem::size_of::<Self>() + self.capacity() * mem::size_of::<T>()
+    }
+}
+
+/// Function measuring a message, declared with `size_with`.
+pub type SizeFn<T> = fn(&T) -> usize;
+
+/// Measures a message with its [`MessageSize`] implementation.
+///
+/// This function is not intended for direct use. Use the `channel!` macro with `size = true` instead.
+#[doc(hidden)]
+pub fn message_size_of<T: MessageSize>(msg: &T) -> usize {
+    msg.message_size()
+}
+
+/// Type-erased [`SizeFn`], downcast back by the wrappers that know the message type.
+#[doc(hidden)]
+pub type ErasedSizeFn = Arc<dyn Any + Send + Sync>;
+
+/// Erases the type of the size function of a channel. The channel is only used to infer the
+/// message type.
+///
+/// This function is not intended for direct use. Use the `channel!` macro with `size = true`
+/// or `size_with = |msg| ..` instead.
+#[doc(hidden)]
+pub fn erase_message_size<C>(_channel: &C, size: SizeFn<C::Message>) -> ErasedSizeFn
+where
+    C: Instrument,
+    C::Message: 'static,
+{
+    Arc::new(size)
+}
+
+/// The size function of a channel of `T` messages, if one was declared.
+pub(crate) fn downcast<T: 'static>(size: Option<&ErasedSizeFn>) -> Option<SizeFn<T>> {
+    size.and_then(|size| size.downcast_ref::<SizeFn<T>>())
+        .copied()
+}
+
+/// Size of `msg` measured by `size`, or `size_of::<T>()` for channels without a size function.
+pub(crate) fn measure<T>(size: Option<SizeFn<T>>, msg: &T) -> u64 {
+    size.map_or(mem::size_of::<T>(), |size| size(msg)) as u64
+}
@@ -1,8 +1,7 @@
-use crate::{init_streams_state, StreamEvent, STREAM_ID_COUNTER};
-use crossbeam_channel::Sender as CbSender;
+use crate::registry::next_id;
+use crate::{init_streams_state, StreamEvent, StreamEventSender};
 use futures_util::Stream;
 use std::pin::Pin;
-use std::sync::atomic::Ordering;
 use std::task::{Context, Poll};
 use std::time::Instant;
 
@@ -12,8 +11,10 @@ use std::time::Instant;
 /// while recording statistics about yielded items.
 pub struct InstrumentedStream<S> {
     inner: S,
-    stats_tx: CbSender<StreamEvent>,
+    stats_tx: StreamEventSender,
     id: u64,
+    /// First poll since the last yielded item
+    first_poll_at: Option<Instant>,
 }
 
 impl<S> InstrumentedStream<S> {
@@ -28,7 +29,7 @@ impl<S> InstrumentedStream<S> {
         S: Stream,
     {
         let (stats_tx, _) = init_streams_state();
-        let id = STREAM_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let id = next_id();
 
         // Send stream creation event
         let _ = stats_tx.send(StreamEvent::Created {
@@ -43,6 +44,7 @@ impl<S> InstrumentedStream<S> {
             inner: stream,
             stats_tx: stats_tx.clone(),
             id,
+            first_poll_at: None,
         }
     }
 }
@@ -57,12 +59,17 @@ impl<S: Stream> Stream for InstrumentedStream<S> {
         let this = unsafe { self.get_unchecked_mut() };
         let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
 
+        let polled_at = *this.first_poll_at.get_or_insert_with(crate::now);
+
         match inner.poll_next(cx) {
             Poll::Ready(Some(item)) => {
+                let timestamp = crate::now();
+                this.first_poll_at = None;
                 let _ = this.stats_tx.send(StreamEvent::Yielded {
                     id: this.id,
                     log: None,
-                    timestamp: Instant::now(),
+                    timestamp,
+                    waited: timestamp.saturating_duration_since(polled_at),
                 });
                 Poll::Ready(Some(item))
             }
@@ -70,7 +77,10 @@ impl<S: Stream> Stream for InstrumentedStream<S> {
                 let _ = this.stats_tx.send(StreamEvent::Completed { id: this.id });
                 Poll::Ready(None)
             }
-            Poll::Pending => Poll::Pending,
+            Poll::Pending => {
+                let _ = this.stats_tx.send(StreamEvent::Pending { id: this.id });
+                Poll::Pending
+            }
         }
     }
 }
@@ -80,8 +90,10 @@ impl<S: Stream> Stream for InstrumentedStream<S> {
 /// This variant captures the Debug representation of yielded items.
 pub struct InstrumentedStreamLog<S> {
     inner: S,
-    stats_tx: CbSender<StreamEvent>,
+    stats_tx: StreamEventSender,
     id: u64,
+    /// First poll since the last yielded item
+    first_poll_at: Option<Instant>,
 }
 
 impl<S> InstrumentedStreamLog<S> {
@@ -91,7 +103,7 @@ impl<S> InstrumentedStreamLog<S> {
         S: Stream,
     {
         let (stats_tx, _) = init_streams_state();
-        let id = STREAM_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let id = next_id();
 
         // Send stream creation event
         let _ = stats_tx.send(StreamEvent::Created {
@@ -106,6 +118,7 @@ impl<S> InstrumentedStreamLog<S> {
             inner: stream,
             stats_tx: stats_tx.clone(),
             id,
+            first_poll_at: None,
         }
     }
 }
@@ -121,22 +134,32 @@ where
         let this = unsafe { self.get_unchecked_mut() };
         let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
 
+        let polled_at = *this.first_poll_at.get_or_insert_with(crate::now);
+
         match inner.poll_next(cx) {
             Poll::Ready(Some(item)) => {
-                let log_msg = format!("{:?}", item);
-                dbg!(&log_msg);
-                let _ = this.stats_tx.send(StreamEvent::Yielded {
-                    id: this.id,
-                    log: Some(log_msg),
-                    timestamp: Instant::now(),
-                });
+                this.first_poll_at = None;
+                if !this.stats_tx.is_disabled() {
+                    let log_msg = format!("{:?}", item);
+                    dbg!(&log_msg);
+                    let timestamp = crate::now();
+                    let _ = this.stats_tx.send(StreamEvent::Yielded {
+                        id: this.id,
+                        log: Some(log_msg),
+                        timestamp,
+                        waited: timestamp.saturating_duration_since(polled_at),
+                    });
+                }
                 Poll::Ready(Some(item))
             }
             Poll::Ready(None) => {
                 let _ = this.stats_tx.send(StreamEvent::Completed { id: this.id });
                 Poll::Ready(None)
             }
-            Poll::Pending => Poll::Pending,
+            Poll::Pending => {
+                let _ = this.stats_tx.send(StreamEvent::Pending { id: this.id });
+                Poll::Pending
+            }
         }
     }
 }
@@ -0,0 +1,100 @@
+//! Totals across all channels.
+//!
+//! Served by the `/summary` endpoint, which accepts the same `?prefix=` filter as `/channels`,
+//! and shown as a header strip in the TUI.
+
+use serde::{Deserialize, Serialize};
+
+use crate::control::{collection_mode, CollectionMode};
+use crate::{ChannelState, ChannelsJson, SerializableChannelStats};
+
+/// Number of channels in each state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateCounts {
+    pub active: usize,
+    pub full: usize,
+    pub notified: usize,
+    pub closed: usize,
+}
+
+/// Channel with the highest send rate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusiestChannel {
+    pub id: u64,
+    pub label: String,
+    /// Messages sent per second over the last 10s
+    pub send_rate: f64,
+}
+
+/// Aggregated statistics of a set of channels.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub channels: usize,
+    pub states: StateCounts,
+    pub queued: u64,
+    pub queued_bytes: u64,
+    pub sent_count: u64,
+    pub received_count: u64,
+    /// `None` if no channel sent a message in the last 10s
+    pub busiest: Option<BusiestChannel>,
+}
+
+impl Summary {
+    pub fn new(stats: &[SerializableChannelStats]) -> Self {
+        let mut summary = Self {
+            channels: stats.len(),
+            ..Default::default()
+        };
+        for channel in stats {
+            match channel.state {
+                // Only streams stall
+                ChannelState::Active | ChannelState::Stalled | ChannelState::Unknown => {
+                    summary.states.active += 1
+                }
+                ChannelState::Full => summary.states.full += 1,
+                ChannelState::Notified => summary.states.notified += 1,
+                ChannelState::Closed => summary.states.closed += 1,
+            }
+            summary.queued += channel.queued;
+            summary.queued_bytes += channel.queued_bytes;
+            summary.sent_count += channel.sent_count;
+            summary.received_count += channel.received_count;
+        }
+        summary.busiest = stats
+            .iter()
+            .filter(|channel| channel.send_rate.last_10s > 0.0)
+            .max_by(|a, b| a.send_rate.last_10s.total_cmp(&b.send_rate.last_10s))
+            .map(|channel| BusiestChannel {
+                id: channel.id,
+                label: channel.label.clone(),
+                send_rate: channel.send_rate.last_10s,
+            });
+        summary
+    }
+}
+
+/// Wrapper for the `/summary` JSON response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`](crate::SCHEMA_VERSION)
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Current elapsed time since program start in nanoseconds
+    pub current_elapsed_ns: u64,
+    /// Whether per-message details are collected, see [`control`](crate::control)
+    #[serde(default)]
+    pub collection_mode: CollectionMode,
+    #[serde(flatten)]
+    pub summary: Summary,
+}
+
+impl From<&ChannelsJson> for SummaryJson {
+    fn from(channels: &ChannelsJson) -> Self {
+        Self {
+            schema_version: channels.schema_version,
+            current_elapsed_ns: channels.current_elapsed_ns,
+            collection_mode: collection_mode(),
+            summary: Summary::new(&channels.channels),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Channel tags.
+//!
+//! Channels declared with `channel!(..., tags = ["pipeline:video", "tier:hot"])` carry free-form
+//! tags besides their label. Tags are usually `key:value` pairs, so channels can be sliced
+//! along several dimensions at once, e.g. `/channels?tag=tier:hot` or `/channels?tag=pipeline`
+//! for channels of any pipeline.
+
+use crate::ChannelsJson;
+
+/// Separator of the key and value of a tag.
+pub const TAG_SEPARATOR: char = ':';
+
+/// Returns true if one of `tags` is `query`, or has `query` as its key.
+///
+/// `pipeline` matches `pipeline:video`, while `pipeline:video` only matches itself.
+pub fn matches_tag(tags: &[String], query: &str) -> bool {
+    tags.iter().any(|tag| {
+        tag == query
+            || tag
+                .split_once(TAG_SEPARATOR)
+                .is_some_and(|(key, _)| key == query)
+    })
+}
+
+impl ChannelsJson {
+    /// Keep only channels with a tag matching `query`, see [`matches_tag`].
+    pub fn retain_tag(&mut self, query: &str) {
+        self.channels
+            .retain(|channel| matches_tag(&channel.tags, query));
+    }
+}
@@ -0,0 +1,288 @@
+//! Assertion helpers for tests of instrumented code.
+//!
+//! Statistics are updated asynchronously by the proxy forwarders and collector threads, so
+//! assertions retry for up to one second before failing.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use channels_console::testing::{assert_all_closed, assert_channel_drained, stats_for};
+//!
+//! let (tx, rx) = std::sync::mpsc::channel::<u32>();
+//! let (tx, rx) = channels_console::channel!((tx, rx), label = "jobs");
+//!
+//! tx.send(1).unwrap();
+//! rx.recv().unwrap();
+//! assert_channel_drained("jobs");
+//! assert_eq!(stats_for("jobs").unwrap().received_count, 1);
+//!
+//! drop((tx, rx));
+//! assert_all_closed();
+//! ```
+
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use crate::actors::{get_channel_actors, ChannelActorsJson};
+use crate::http_api::METRICS_ADDR;
+use crate::latency::{get_latency_histogram, LatencyHistogramJson};
+use crate::samples::{get_channel_history, ChannelHistoryJson};
+use crate::sinks::{get_sinks_json, SerializableSinkStats};
+use crate::{
+    flush, get_channel_logs, get_channel_states, labels, snapshot, ChannelLogs, ChannelState,
+    ChannelStates, ChannelType, CombinedJson, LogQuery, SerializableChannelStats,
+    SerializableStreamStats,
+};
+
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(1);
+const SETTLE_INTERVAL: Duration = Duration::from_millis(10);
+const SERVER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns the address the metrics server is listening on, waiting for it to bind. The server
+/// starts with the first instrumented channel or stream, so tests can serve on port `0` to run
+/// in parallel. `None` if it isn't listening on a TCP port after 10 seconds.
+pub fn metrics_addr() -> Option<SocketAddr> {
+    let start = Instant::now();
+    loop {
+        if let Some(addr) = METRICS_ADDR.get() {
+            return Some(*addr);
+        }
+        if start.elapsed() >= SERVER_TIMEOUT {
+            return None;
+        }
+        std::thread::sleep(SETTLE_INTERVAL);
+    }
+}
+
+/// Returns current statistics of the channel with the given label (or `file:line` source).
+pub fn stats_for(label: &str) -> Option<SerializableChannelStats> {
+    flush();
+    find_channel(&snapshot(), label).cloned()
+}
+
+/// Returns logs of the channel with the given label (or `file:line` source), most recent first.
+pub fn logs_for(label: &str) -> Option<ChannelLogs> {
+    let stats = stats_for(label)?;
+    get_channel_logs(&stats.id.to_string(), &LogQuery::default())
+}
+
+/// Returns state transitions of the channel with the given label (or `file:line` source),
+/// oldest first.
+pub fn states_for(label: &str) -> Option<ChannelStates> {
+    let stats = stats_for(label)?;
+    get_channel_states(&stats.id.to_string())
+}
+
+/// Returns the top senders and receivers of the channel with the given label (or `file:line`
+/// source). Only inline channels track them.
+pub fn actors_for(label: &str) -> Option<ChannelActorsJson> {
+    let stats = stats_for(label)?;
+    get_channel_actors(&stats.id.to_string())
+}
+
+/// Returns the latency distribution of the channel with the given label (or `file:line` source).
+pub fn latency_histogram_for(label: &str) -> Option<LatencyHistogramJson> {
+    let stats = stats_for(label)?;
+    get_latency_histogram(&stats.id.to_string())
+}
+
+/// Returns periodic samples of the channel with the given label (or `file:line` source),
+/// limited to the last `window` if given.
+pub fn history_for(label: &str, window: Option<Duration>) -> Option<ChannelHistoryJson> {
+    let stats = stats_for(label)?;
+    get_channel_history(&stats.id.to_string(), window)
+}
+
+/// Returns current statistics of the stream with the given label (or `file:line` source).
+pub fn stream_stats_for(label: &str) -> Option<SerializableStreamStats> {
+    flush();
+    snapshot()
+        .streams
+        .into_iter()
+        .find(|stream| stream.label == label || stream.source == label)
+}
+
+/// Returns current statistics of the sink with the given label (or `file:line` source).
+pub fn sink_stats_for(label: &str) -> Option<SerializableSinkStats> {
+    flush();
+    get_sinks_json()
+        .sinks
+        .into_iter()
+        .find(|sink| sink.label == label || sink.source == label)
+}
+
+/// Asserts that no messages are queued in the channel.
+///
+/// Like all metrics, this is observed at the proxy layer: messages already buffered in the
+/// receiving proxy count as received, see "A note on accuracy" in the README.
+#[track_caller]
+pub fn assert_channel_drained(label: &str) {
+    settle(|snapshot| match find_channel(snapshot, label) {
+        None => Err(format!("channel '{}' not found", label)),
+        Some(stats) if stats.queued > 0 => Err(format!(
+            "channel '{}' has {} queued messages (sent: {}, received: {})",
+            label, stats.queued, stats.sent_count, stats.received_count
+        )),
+        Some(_) => Ok(()),
+    });
+}
+
+/// Asserts that all instrumented channels are closed.
+///
+/// Oneshot channels that delivered their message count as closed.
+#[track_caller]
+pub fn assert_all_closed() {
+    settle(|snapshot| {
+        let open: Vec<String> = snapshot
+            .channels
+            .iter()
+            .filter(|stats| !is_closed(stats))
+            .map(|stats| format!("'{}' ({})", stats.label, stats.state))
+            .collect();
+
+        if open.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("channels still open: {}", open.join(", ")))
+        }
+    });
+}
+
+fn is_closed(stats: &SerializableChannelStats) -> bool {
+    stats.state == ChannelState::Closed
+        || (stats.channel_type == ChannelType::Oneshot && stats.state == ChannelState::Notified)
+}
+
+fn find_channel<'a>(
+    snapshot: &'a CombinedJson,
+    label: &str,
+) -> Option<&'a SerializableChannelStats> {
+    snapshot
+        .channels
+        .iter()
+        .find(|stats| stats.label == label || stats.source == label)
+}
+
+/// Re-checks `check` against fresh snapshots until it passes, panics after [`SETTLE_TIMEOUT`].
+#[track_caller]
+fn settle(check: impl Fn(&CombinedJson) -> Result<(), String>) {
+    if let Err(msg) = try_settle(check) {
+        panic!("{}", msg);
+    }
+}
+
+/// Re-checks `check` against fresh snapshots until it passes or [`SETTLE_TIMEOUT`] elapses.
+fn try_settle<E>(check: impl Fn(&CombinedJson) -> Result<(), E>) -> Result<(), E> {
+    let start = Instant::now();
+    loop {
+        flush();
+        match check(&snapshot()) {
+            Ok(()) => return Ok(()),
+            Err(err) if start.elapsed() >= SETTLE_TIMEOUT => return Err(err),
+            Err(_) => std::thread::sleep(SETTLE_INTERVAL),
+        }
+    }
+}
+
+/// Conditions checked when a [`ChannelsGuard`](crate::ChannelsGuard) is dropped, turning an
+/// integration test into a leak and backpressure detector.
+///
+/// Only channels matching the guard's prefix are checked. A violation panics on drop, or is
+/// returned by [`ChannelsGuard::finish`](crate::ChannelsGuard::finish).
+///
+/// # Examples
+///
+/// ```no_run
+/// use channels_console::{Assertions, ChannelsGuardBuilder};
+///
+/// let guard = ChannelsGuardBuilder::new()
+///     .assert(Assertions::new().no_full_channels().all_closed().max_queued(0))
+///     .build();
+/// // Run the code under test
+/// guard.finish().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Assertions {
+    no_full_channels: bool,
+    all_closed: bool,
+    max_queued: Option<u64>,
+}
+
+impl Assertions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// No channel may be full.
+    pub fn no_full_channels(mut self) -> Self {
+        self.no_full_channels = true;
+        self
+    }
+
+    /// All channels must be closed, see [`assert_all_closed`].
+    pub fn all_closed(mut self) -> Self {
+        self.all_closed = true;
+        self
+    }
+
+    /// No channel may have more than `max` queued messages.
+    pub fn max_queued(mut self, max: u64) -> Self {
+        self.max_queued = Some(max);
+        self
+    }
+
+    /// Checks channels under the given label prefix, retrying for up to a second.
+    pub(crate) fn check(&self, prefix: &str) -> Result<(), AssertionError> {
+        try_settle(|snapshot| self.violations(snapshot, prefix))
+    }
+
+    fn violations(&self, snapshot: &CombinedJson, prefix: &str) -> Result<(), AssertionError> {
+        let mut violations = Vec::new();
+        let channels = snapshot
+            .channels
+            .iter()
+            .filter(|stats| labels::matches_prefix(&stats.label, prefix));
+        for stats in channels {
+            if self.no_full_channels && stats.state == ChannelState::Full {
+                violations.push(format!("channel '{}' is full", stats.label));
+            }
+            if self.all_closed && !is_closed(stats) {
+                violations.push(format!(
+                    "channel '{}' is still open ({})",
+                    stats.label, stats.state
+                ));
+            }
+            if let Some(max) = self.max_queued.filter(|max| stats.queued > *max) {
+                violations.push(format!(
+                    "channel '{}' has {} queued messages (max: {})",
+                    stats.label, stats.queued, max
+                ));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AssertionError { violations })
+        }
+    }
+}
+
+/// [`Assertions`] violated when the guard was dropped or finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionError {
+    /// One message per violated condition and channel
+    pub violations: Vec<String>,
+}
+
+impl std::fmt::Display for AssertionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "channel assertions failed: {}",
+            self.violations.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for AssertionError {}
@@ -0,0 +1,154 @@
+//! Pipeline topology: which instrumented channels feed which.
+//!
+//! Links are declared with `links = [...]` in the `channel!` macro. A link means that the
+//! consumer of the instrumented channel sends into each listed channel, so it creates
+//! an edge from this channel to every channel whose label (or `file:line` source) matches.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::RwLock;
+
+use crate::{
+    resolve_label, with_sorted_channel_stats, ChannelState, ChannelStats, ChannelType,
+    SCHEMA_VERSION,
+};
+
+/// A declared link between two channels, identified by label or source location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Link {
+    from: String,
+    to: String,
+}
+
+static LINKS: RwLock<Vec<Link>> = RwLock::new(Vec::new());
+
+/// Registers links declared in the `channel!` macro.
+///
+/// This function is not intended for direct use. Use the `channel!` macro with `links = [...]` instead.
+#[doc(hidden)]
+pub fn register_links(source: &'static str, label: Option<&str>, links: &[&str]) {
+    if links.is_empty() {
+        return;
+    }
+
+    let from = label.unwrap_or(source);
+    let mut registered = LINKS.write().unwrap();
+    for to in links {
+        let link = Link {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        // The same channel! call site may run many times
+        if !registered.contains(&link) {
+            registered.push(link);
+        }
+    }
+}
+
+/// A channel in the topology graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyNode {
+    pub id: u64,
+    pub label: String,
+    pub channel_type: ChannelType,
+    pub state: ChannelState,
+    pub queued: u64,
+}
+
+/// A directed edge: messages received from `from` are sent into `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopologyEdge {
+    pub from: u64,
+    pub to: u64,
+}
+
+/// Response of the `/topology` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyJson {
+    /// Version of the JSON schema, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    pub nodes: Vec<TopologyNode>,
+    pub edges: Vec<TopologyEdge>,
+}
+
+impl TopologyJson {
+    /// Renders the graph in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph channels {\n    rankdir=LR;\n    node [shape=box];\n");
+        for node in &self.nodes {
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}\\n{} | queued: {}\"];",
+                node.id,
+                escape_dot(&node.label),
+                node.channel_type,
+                node.queued
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(dot, "    {} -> {};", edge.from, edge.to);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn matches(link_end: &str, stats: &ChannelStats, label: &str) -> bool {
+    link_end == label || link_end == stats.source || stats.label.as_deref() == Some(link_end)
+}
+
+pub(crate) fn get_topology_json() -> TopologyJson {
+    with_sorted_channel_stats(|stats| {
+        let mut stats = stats.to_vec();
+        stats.sort_by_key(|s| s.id);
+
+        let labels: HashMap<u64, String> = stats
+            .iter()
+            .map(|s| (s.id, resolve_label(s.source, s.label.as_deref(), s.iter)))
+            .collect();
+
+        let mut edges = Vec::new();
+        for link in LINKS.read().unwrap().iter() {
+            for from in stats
+                .iter()
+                .filter(|s| matches(&link.from, s, &labels[&s.id]))
+            {
+                for to in stats
+                    .iter()
+                    .filter(|s| matches(&link.to, s, &labels[&s.id]))
+                {
+                    let edge = TopologyEdge {
+                        from: from.id,
+                        to: to.id,
+                    };
+                    if from.id != to.id && !edges.contains(&edge) {
+                        edges.push(edge);
+                    }
+                }
+            }
+        }
+
+        let nodes = stats
+            .iter()
+            .map(|s| TopologyNode {
+                id: s.id,
+                label: labels[&s.id].clone(),
+                channel_type: s.channel_type,
+                state: s.state,
+                queued: s.queued(),
+            })
+            .collect();
+
+        TopologyJson {
+            schema_version: SCHEMA_VERSION,
+            nodes,
+            edges,
+        }
+    })
+}
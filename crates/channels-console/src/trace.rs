@@ -0,0 +1,69 @@
+//! [`tracing`] events for channel activity, available with the `tracing` feature.
+//!
+//! Created and closed channels are reported as `INFO` events, channels becoming full as `WARN`
+//! events, all with the `channels_console` target and the resolved channel label, so they land
+//! in the subscribers an application already has. With
+//! [`trace_messages`](crate::ConfigBuilder::trace_messages), every sent and received message is
+//! also a `TRACE` span.
+//!
+//! Log entries also carry the id of the span current when the message was sent or received,
+//! linking a stuck message back to the request that produced it.
+
+use crate::{resolve_label, ChannelState, ChannelStats};
+
+const TARGET: &str = "channels_console";
+
+/// Id of the span the current code runs in, as assigned by the subscriber.
+pub(crate) fn current_span_id() -> Option<u64> {
+    tracing::Span::current().id().map(|id| id.into_u64())
+}
+
+fn label(stats: &ChannelStats) -> String {
+    resolve_label(stats.source, stats.label.as_deref(), stats.iter)
+}
+
+pub(crate) fn channel_created(stats: &ChannelStats) {
+    tracing::info!(
+        target: TARGET,
+        channel = %label(stats),
+        channel_id = stats.id,
+        channel_type = %stats.channel_type,
+        source = stats.source,
+        "channel created"
+    );
+}
+
+pub(crate) fn state_changed(stats: &ChannelStats, state: ChannelState) {
+    match state {
+        ChannelState::Full => tracing::warn!(
+            target: TARGET,
+            channel = %label(stats),
+            channel_id = stats.id,
+            queued = stats.queued(),
+            "channel full"
+        ),
+        ChannelState::Closed => tracing::info!(
+            target: TARGET,
+            channel = %label(stats),
+            channel_id = stats.id,
+            sent = stats.sent_count,
+            received = stats.received_count,
+            "channel closed"
+        ),
+        _ => {}
+    }
+}
+
+/// Span of a single message, `label` being unknown until the collector registered the channel.
+pub(crate) fn message(label: Option<&str>, channel_id: u64, direction: &'static str, index: u64) {
+    let span = tracing::trace_span!(
+        target: TARGET,
+        "channel_message",
+        channel = label,
+        channel_id,
+        direction,
+        index
+    );
+    let _entered = span.enter();
+    tracing::trace!(target: TARGET, "message {}", direction);
+}
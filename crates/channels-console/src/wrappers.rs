@@ -3,5 +3,9 @@ pub(crate) mod crossbeam;
 #[cfg(feature = "futures")]
 pub(crate) mod futures;
 pub(crate) mod std;
+#[cfg(std_mpmc)]
+pub(crate) mod std_mpmc;
 #[cfg(feature = "tokio")]
 pub(crate) mod tokio;
+#[cfg(feature = "tokio")]
+pub(crate) mod tokio_inline;
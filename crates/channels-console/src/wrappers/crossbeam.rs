@@ -1,21 +1,155 @@
 use crossbeam_channel::{self, Receiver, Sender};
 use std::mem;
-use std::sync::atomic::Ordering;
+use std::time::Duration;
 
-use crate::{init_channels_state, ChannelEvent, ChannelType, CHANNEL_ID_COUNTER};
+use crate::correlation::CorrelationFn;
+use crate::counters::ChannelRecorder;
+use crate::forwarders::{self, Endpoint, ForwardPath, Forwarded, PooledForwarder, FORWARD_BATCH};
+use crate::registry::next_id;
+use crate::{init_channels_state, ChannelEvent, ChannelOptions, ChannelType};
+
+/// Both forwarders of a proxied channel, driven by a shared worker thread.
+struct PooledChannel<T, F> {
+    /// Outer -> inner, `None` once closed
+    send_path: Option<ForwardPath<T>>,
+    /// Inner -> outer, `None` once closed
+    recv_path: Option<ForwardPath<T>>,
+    recorder: ChannelRecorder,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
+}
+
+impl<T, F> PooledForwarder for PooledChannel<T, F>
+where
+    T: Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
+{
+    fn forward(&mut self) -> bool {
+        let log_on_send = &self.log_on_send;
+        let correlate = self.correlate;
+        let recorder = &self.recorder;
+        let describe = |msg: &T| {
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_send(msg)
+            };
+            (
+                log,
+                correlate.and_then(|f| f(msg)),
+                recorder.message_size(msg),
+            )
+        };
+
+        if let Some(path) = &mut self.send_path {
+            let mut closed = false;
+            for _ in 0..FORWARD_BATCH {
+                match path.forward_one(describe) {
+                    Forwarded::Message {
+                        log,
+                        correlation_id,
+                        bytes,
+                        blocked_since,
+                    } => {
+                        let timestamp = crate::now();
+                        let blocked = blocked_since.map_or(Duration::ZERO, |since| {
+                            timestamp.saturating_duration_since(since)
+                        });
+                        self.recorder
+                            .sent(log, correlation_id, bytes, blocked, timestamp);
+                    }
+                    Forwarded::WouldBlock => break,
+                    Forwarded::SourceClosed => {
+                        closed = true;
+                        break;
+                    }
+                    Forwarded::DestinationClosed => {
+                        self.recorder.send_rejected_closed();
+                        closed = true;
+                        break;
+                    }
+                }
+            }
+            if closed {
+                self.send_path = None;
+                self.recorder.closed();
+            }
+        }
+
+        if let Some(path) = &mut self.recv_path {
+            let mut closed = false;
+            for _ in 0..FORWARD_BATCH {
+                match path.forward_one(describe) {
+                    Forwarded::Message {
+                        log,
+                        correlation_id,
+                        bytes,
+                        ..
+                    } => self
+                        .recorder
+                        .received(log, correlation_id, bytes, crate::now()),
+                    Forwarded::WouldBlock => break,
+                    Forwarded::SourceClosed => {
+                        closed = true;
+                        break;
+                    }
+                    Forwarded::DestinationClosed => {
+                        // Outer receiver was closed, reject further sends
+                        if self.send_path.take().is_some() {
+                            self.recorder.closed();
+                        }
+                        closed = true;
+                        break;
+                    }
+                }
+            }
+            if closed {
+                self.recv_path = None;
+                self.recorder.closed();
+            }
+        }
+
+        self.send_path.is_some() || self.recv_path.is_some()
+    }
+
+    fn endpoints(&self) -> Vec<Box<dyn Endpoint>> {
+        self.send_path
+            .iter()
+            .chain(&self.recv_path)
+            .map(ForwardPath::endpoint)
+            .collect()
+    }
+
+    fn interest(&self) -> u8 {
+        let interest =
+            |path: &Option<ForwardPath<T>>| path.as_ref().map_or(0, ForwardPath::interest);
+        interest(&self.send_path) | interest(&self.recv_path) << 2
+    }
+
+    fn failed(&mut self) {
+        self.send_path = None;
+        self.recv_path = None;
+        self.recorder.closed();
+    }
+}
 
 /// Internal implementation for wrapping bounded crossbeam channels with optional logging.
 fn wrap_bounded_impl<T, F>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
-    mut log_on_send: F,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -24,123 +158,79 @@ where
 
     let (stats_tx, _) = init_channels_state();
 
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Bounded(capacity),
         type_name,
         type_size: mem::size_of::<T>(),
-    });
-
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
-
-    // Create a signal channel to notify send-forwarder when outer_rx is closed
-    let (close_signal_tx, close_signal_rx) = crossbeam_channel::bounded::<()>(1);
+        proxied: true,
 
-    // Forward outer -> inner (proxy the send path)
-    std::thread::spawn(move || {
-        loop {
-            // Check for close signal (non-blocking)
-            match close_signal_rx.try_recv() {
-                Ok(_) => {
-                    // Outer receiver was closed/dropped
-                    break;
-                }
-                Err(crossbeam_channel::TryRecvError::Disconnected) => {
-                    // Close signal sender dropped, which means recv forwarder ended
-                    break;
-                }
-                Err(crossbeam_channel::TryRecvError::Empty) => {
-                    // No close signal, continue
-                }
-            }
-
-            // Try to receive with timeout to periodically check close signal
-            match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
-                Ok(msg) => {
-                    let log = log_on_send(&msg);
-                    if inner_tx.send(msg).is_err() {
-                        // Inner receiver dropped
-                        break;
-                    }
-                    let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                        id,
-                        log,
-                        timestamp: std::time::Instant::now(),
-                    });
-                }
-                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
-                    // No message, loop again to check close signal
-                    continue;
-                }
-                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
-                    // Outer sender dropped
-                    break;
-                }
-            }
-        }
-        // Channel is closed
-        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+        counters: recorder.counters(),
     });
 
-    // Forward inner -> outer (proxy the recv path)
-    std::thread::spawn(move || {
-        while let Ok(msg) = inner_rx.recv() {
-            if from_inner_tx.send(msg).is_err() {
-                // Outer receiver was closed
-                let _ = close_signal_tx.send(());
-                break;
-            }
-            let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                id,
-                timestamp: std::time::Instant::now(),
-            });
-        }
-        // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+    forwarders::spawn_pooled(PooledChannel {
+        send_path: Some(ForwardPath::new(to_inner_rx, inner_tx)),
+        recv_path: Some(ForwardPath::new(inner_rx, from_inner_tx)),
+        recorder,
+        log_on_send,
+        correlate,
     });
 
     (outer_tx, outer_rx)
 }
 
 /// Wrap a bounded crossbeam channel with proxy ends. Returns (outer_tx, outer_rx).
-/// All messages pass through the two forwarders running in separate threads.
+/// All messages pass through forwarders driven by the shared worker threads.
 pub(crate) fn wrap_bounded<T: Send + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_bounded_impl(inner, source, label, capacity, |_| None)
+    wrap_bounded_impl(inner, source, options, capacity, |_| None, correlate)
 }
 
 /// Wrap a bounded crossbeam channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_bounded_log<T: Send + std::fmt::Debug + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_bounded_impl(inner, source, label, capacity, |msg| {
-        Some(format!("{:?}", msg))
-    })
+    wrap_bounded_impl(
+        inner,
+        source,
+        options,
+        capacity,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 /// Internal implementation for wrapping unbounded crossbeam channels with optional logging.
 fn wrap_unbounded_impl<T, F>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
-    mut log_on_send: F,
+    options: ChannelOptions,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -149,84 +239,28 @@ where
 
     let (stats_tx, _) = init_channels_state();
 
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Unbounded,
         type_name,
         type_size: mem::size_of::<T>(),
-    });
+        proxied: true,
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
-
-    // Create a signal channel to notify send-forwarder when outer_rx is closed
-    let (close_signal_tx, close_signal_rx) = crossbeam_channel::bounded::<()>(1);
-
-    // Forward outer -> inner (proxy the send path)
-    std::thread::spawn(move || {
-        loop {
-            // Check for close signal (non-blocking)
-            match close_signal_rx.try_recv() {
-                Ok(_) => {
-                    // Outer receiver was closed/dropped
-                    break;
-                }
-                Err(crossbeam_channel::TryRecvError::Disconnected) => {
-                    // Close signal sender dropped, which means recv forwarder ended
-                    break;
-                }
-                Err(crossbeam_channel::TryRecvError::Empty) => {
-                    // No close signal, continue
-                }
-            }
-
-            // Try to receive with timeout to periodically check close signal
-            match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
-                Ok(msg) => {
-                    let log = log_on_send(&msg);
-                    if inner_tx.send(msg).is_err() {
-                        // Inner receiver dropped
-                        break;
-                    }
-                    let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                        id,
-                        log,
-                        timestamp: std::time::Instant::now(),
-                    });
-                }
-                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
-                    // No message, loop again to check close signal
-                    continue;
-                }
-                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
-                    // Outer sender dropped
-                    break;
-                }
-            }
-        }
-        // Channel is closed
-        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+        counters: recorder.counters(),
     });
 
-    // Forward inner -> outer (proxy the recv path)
-    std::thread::spawn(move || {
-        while let Ok(msg) = inner_rx.recv() {
-            if from_inner_tx.send(msg).is_err() {
-                // Outer receiver was closed
-                let _ = close_signal_tx.send(());
-                break;
-            }
-            let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                id,
-                timestamp: std::time::Instant::now(),
-            });
-        }
-        // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+    forwarders::spawn_pooled(PooledChannel {
+        send_path: Some(ForwardPath::new(to_inner_rx, inner_tx)),
+        recv_path: Some(ForwardPath::new(inner_rx, from_inner_tx)),
+        recorder,
+        log_on_send,
+        correlate,
     });
 
     (outer_tx, outer_rx)
@@ -236,18 +270,26 @@ where
 pub(crate) fn wrap_unbounded<T: Send + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |_| None)
+    wrap_unbounded_impl(inner, source, options, |_| None, correlate)
 }
 
 /// Wrap an unbounded crossbeam channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_unbounded_log<T: Send + std::fmt::Debug + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    wrap_unbounded_impl(
+        inner,
+        source,
+        options,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 use crate::Instrument;
@@ -256,17 +298,19 @@ impl<T: Send + 'static> Instrument
     for (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>)
 {
     type Output = (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>);
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
         // Crossbeam uses the same Sender/Receiver types for both bounded and unbounded
         // We check the capacity to determine which type it is
         match self.0.capacity() {
-            Some(capacity) => wrap_bounded(self, source, label, capacity),
-            None => wrap_unbounded(self, source, label),
+            Some(capacity) => wrap_bounded(self, source, options, capacity, correlate),
+            None => wrap_unbounded(self, source, options, correlate),
         }
     }
 }
@@ -277,17 +321,159 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
     for (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>)
 {
     type Output = (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>);
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
         // Crossbeam uses the same Sender/Receiver types for both bounded and unbounded
         // We check the capacity to determine which type it is
         match self.0.capacity() {
-            Some(capacity) => wrap_bounded_log(self, source, label, capacity),
-            None => wrap_unbounded_log(self, source, label),
+            Some(capacity) => wrap_bounded_log(self, source, options, capacity, correlate),
+            None => wrap_unbounded_log(self, source, options, correlate),
         }
     }
 }
+
+use crate::InstrumentLogWith;
+
+impl<T: Send + 'static> InstrumentLogWith
+    for (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>)
+{
+    type Output = (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>);
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        // Crossbeam uses the same Sender/Receiver types for both bounded and unbounded
+        // We check the capacity to determine which type it is
+        match self.0.capacity() {
+            Some(capacity) => wrap_bounded_impl(
+                self,
+                source,
+                options,
+                capacity,
+                move |msg| Some(log_with(msg)),
+                correlate,
+            ),
+            None => wrap_unbounded_impl(
+                self,
+                source,
+                options,
+                move |msg| Some(log_with(msg)),
+                correlate,
+            ),
+        }
+    }
+}
+
+use crate::producers::Producer;
+use crate::InstrumentSender;
+
+impl<T: Send + 'static> InstrumentSender for Sender<T> {
+    fn instrument_sender(self, source: &'static str, channel: &str, label: Option<String>) -> Self {
+        let Some(producer) = Producer::register(source, channel, label) else {
+            return self;
+        };
+
+        let (producer_tx, producer_rx) = match self.capacity() {
+            Some(capacity) => crossbeam_channel::bounded::<T>(capacity),
+            None => crossbeam_channel::unbounded::<T>(),
+        };
+        forwarders::spawn_pooled(PooledProducer {
+            path: ForwardPath::new(producer_rx, self),
+            producer,
+        });
+        producer_tx
+    }
+}
+
+use crate::consumers::Consumer;
+use crate::InstrumentReceiver;
+
+impl<T: Send + 'static> InstrumentReceiver for Receiver<T> {
+    fn instrument_receiver(
+        self,
+        source: &'static str,
+        channel: &str,
+        label: Option<String>,
+    ) -> Self {
+        let Some(consumer) = Consumer::register(source, channel, label) else {
+            return self;
+        };
+
+        // Hand messages over without buffering, so the forwarder holds at most one message
+        // and leaves the rest to the other consumers
+        let (consumer_tx, consumer_rx) = crossbeam_channel::bounded::<T>(0);
+        forwarders::spawn_pooled(PooledConsumer {
+            path: ForwardPath::new(self, consumer_tx),
+            consumer,
+        });
+        consumer_rx
+    }
+}
+
+/// Forwarder of an instrumented receiver, driven by a shared worker thread.
+struct PooledConsumer<T> {
+    path: ForwardPath<T>,
+    consumer: Consumer,
+}
+
+impl<T: Send + 'static> PooledForwarder for PooledConsumer<T> {
+    fn forward(&mut self) -> bool {
+        for _ in 0..FORWARD_BATCH {
+            match self.path.forward_one(|_| (None, None, 0)) {
+                Forwarded::Message { .. } => self.consumer.received(),
+                Forwarded::WouldBlock => break,
+                Forwarded::SourceClosed | Forwarded::DestinationClosed => return false,
+            }
+        }
+        true
+    }
+
+    fn endpoints(&self) -> Vec<Box<dyn Endpoint>> {
+        vec![self.path.endpoint()]
+    }
+
+    fn interest(&self) -> u8 {
+        self.path.interest()
+    }
+}
+
+/// Forwarder of an instrumented sender, driven by a shared worker thread.
+struct PooledProducer<T> {
+    path: ForwardPath<T>,
+    producer: Producer,
+}
+
+impl<T: Send + 'static> PooledForwarder for PooledProducer<T> {
+    fn forward(&mut self) -> bool {
+        for _ in 0..FORWARD_BATCH {
+            match self.path.forward_one(|_| (None, None, 0)) {
+                Forwarded::Message { .. } => self.producer.sent(),
+                Forwarded::WouldBlock => break,
+                Forwarded::SourceClosed | Forwarded::DestinationClosed => return false,
+            }
+        }
+        true
+    }
+
+    fn endpoints(&self) -> Vec<Box<dyn Endpoint>> {
+        vec![self.path.endpoint()]
+    }
+
+    fn interest(&self) -> u8 {
+        self.path.interest()
+    }
+}
@@ -3,23 +3,32 @@ use futures_channel::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender
 use futures_channel::oneshot;
 use futures_util::sink::SinkExt;
 use std::mem;
-use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::RT;
-use crate::{init_channels_state, ChannelEvent, ChannelType, CHANNEL_ID_COUNTER};
+use crate::correlation::CorrelationFn;
+use crate::counters::ChannelRecorder;
+use crate::forwarders;
+use crate::registry::next_id;
+use crate::{init_channels_state, ChannelEvent, ChannelOptions, ChannelType};
 
 /// Internal implementation for wrapping bounded futures channels with optional logging.
 fn wrap_channel_impl<T, F>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
-    mut get_msg_log: F,
+    get_msg_log: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static + Clone,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (mut inner_tx, mut inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -29,41 +38,50 @@ where
     let (stats_tx, _) = init_channels_state();
 
     // Generate unique ID for this channel
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Bounded(capacity),
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
+
+        counters: recorder.counters(),
     });
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder.clone();
+    let get_msg_log = Arc::new(get_msg_log);
+    let log_on_recv = Arc::clone(&get_msg_log);
 
     // Create a signal channel to notify send-forwarder when outer_rx is closed
     let (close_signal_tx, mut close_signal_rx) = tokio::sync::oneshot::channel::<()>();
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         use futures_util::stream::StreamExt;
         loop {
             tokio::select! {
                 msg = to_inner_rx.next() => {
                     match msg {
                         Some(msg) => {
-                            let log = get_msg_log(&msg);
+                            let log = if crate::control::is_paused() { None } else { get_msg_log(&msg) };
+                            let correlation_id = correlate.and_then(|f| f(&msg));
+                            let bytes = recorder_send.message_size(&msg);
+                            // Waits while the inner channel is full
+                            let send_started = crate::now();
                             if inner_tx.send(msg).await.is_err() {
+                                recorder_send.send_rejected_closed();
                                 to_inner_rx.close();
                                 break;
                             }
-                            let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                                id,
-                                log,
-                                timestamp: std::time::Instant::now(),
-                            });
+                            let timestamp = crate::now();
+                            recorder_send.sent(log, correlation_id, bytes, timestamp.saturating_duration_since(send_started), timestamp);
                         }
                         None => break, // Outer sender dropped
                     }
@@ -76,18 +94,22 @@ where
             }
         }
         // Channel is closed
-        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+        recorder_send.closed();
     });
 
     // Forward inner -> outer (proxy the recv path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         use futures_util::stream::StreamExt;
         while let Some(msg) = inner_rx.next().await {
+            let correlation_id = correlate.and_then(|f| f(&msg));
+            let bytes = recorder_recv.message_size(&msg);
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_recv(&msg)
+            };
             if from_inner_tx.send(msg).await.is_ok() {
-                let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                    id,
-                    timestamp: std::time::Instant::now(),
-                });
+                recorder_recv.received(log, correlation_id, bytes, crate::now());
             } else {
                 // Outer receiver was closed
                 let _ = close_signal_tx.send(());
@@ -95,7 +117,7 @@ where
             }
         }
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+        recorder_recv.closed();
     });
 
     (outer_tx, outer_rx)
@@ -106,35 +128,47 @@ where
 pub(crate) fn wrap_channel<T: Send + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, capacity, |_| None)
+    wrap_channel_impl(inner, source, options, capacity, |_| None, correlate)
 }
 
 /// Wrap a bounded futures channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, capacity, |msg| {
-        Some(format!("{:?}", msg))
-    })
+    wrap_channel_impl(
+        inner,
+        source,
+        options,
+        capacity,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 /// Internal implementation for wrapping unbounded futures channels with optional logging.
 fn wrap_unbounded_impl<T, F>(
     inner: (UnboundedSender<T>, UnboundedReceiver<T>),
     source: &'static str,
-    label: Option<String>,
-    mut get_msg_log: F,
+    options: ChannelOptions,
+    get_msg_log: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static + Clone,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, mut inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -144,41 +178,47 @@ where
     let (stats_tx, _) = init_channels_state();
 
     // Generate unique ID for this channel
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Unbounded,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
+
+        counters: recorder.counters(),
     });
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder.clone();
+    let get_msg_log = Arc::new(get_msg_log);
+    let log_on_recv = Arc::clone(&get_msg_log);
 
     // Create a signal channel to notify send-forwarder when outer_rx is closed
     let (close_signal_tx, mut close_signal_rx) = tokio::sync::oneshot::channel::<()>();
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         use futures_util::stream::StreamExt;
         loop {
             tokio::select! {
                 msg = to_inner_rx.next() => {
                     match msg {
                         Some(msg) => {
-                            let log = get_msg_log(&msg);
+                            let log = if crate::control::is_paused() { None } else { get_msg_log(&msg) };
+                            let correlation_id = correlate.and_then(|f| f(&msg));
+                            let bytes = recorder_send.message_size(&msg);
                             if inner_tx.unbounded_send(msg).is_err() {
+                                recorder_send.send_rejected_closed();
                                 to_inner_rx.close();
                                 break;
                             }
-                            let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                                id,
-                                log,
-                                timestamp: std::time::Instant::now(),
-                            });
+                            recorder_send.sent(log, correlation_id, bytes, Duration::ZERO, crate::now());
                         }
                         None => break, // Outer sender dropped
                     }
@@ -191,18 +231,22 @@ where
             }
         }
         // Channel is closed
-        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+        recorder_send.closed();
     });
 
     // Forward inner -> outer (proxy the recv path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         use futures_util::stream::StreamExt;
         while let Some(msg) = inner_rx.next().await {
+            let correlation_id = correlate.and_then(|f| f(&msg));
+            let bytes = recorder_recv.message_size(&msg);
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_recv(&msg)
+            };
             if from_inner_tx.unbounded_send(msg).is_ok() {
-                let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                    id,
-                    timestamp: std::time::Instant::now(),
-                });
+                recorder_recv.received(log, correlation_id, bytes, crate::now());
             } else {
                 // Outer receiver was closed
                 let _ = close_signal_tx.send(());
@@ -210,7 +254,7 @@ where
             }
         }
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+        recorder_recv.closed();
     });
 
     (outer_tx, outer_rx)
@@ -220,59 +264,79 @@ where
 pub(crate) fn wrap_unbounded<T: Send + 'static>(
     inner: (UnboundedSender<T>, UnboundedReceiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |_| None)
+    wrap_unbounded_impl(inner, source, options, |_| None, correlate)
 }
 
 /// Wrap an unbounded futures channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_unbounded_log<T: Send + std::fmt::Debug + 'static>(
     inner: (UnboundedSender<T>, UnboundedReceiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    wrap_unbounded_impl(
+        inner,
+        source,
+        options,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 /// Internal implementation for wrapping oneshot futures channels with optional logging.
 fn wrap_oneshot_impl<T, F>(
     inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
     source: &'static str,
-    label: Option<String>,
-    mut get_msg_log: F,
+    options: ChannelOptions,
+    get_msg_log: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (oneshot::Sender<T>, oneshot::Receiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static + Clone,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
     let (outer_tx, outer_rx_proxy) = oneshot::channel::<T>();
-    let (inner_tx_proxy, outer_rx) = oneshot::channel::<T>();
+    let (mut inner_tx_proxy, outer_rx) = oneshot::channel::<T>();
 
     let (stats_tx, _) = init_channels_state();
 
     // Generate unique ID for this channel
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Oneshot,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
+
+        counters: recorder.counters(),
     });
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx;
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder;
+    let get_msg_log = Arc::new(get_msg_log);
+    let log_on_recv = Arc::clone(&get_msg_log);
 
     // Create a signal channel to notify send-forwarder when outer_rx is closed
     let (close_signal_tx, mut close_signal_rx) = tokio::sync::oneshot::channel::<()>();
 
     // Monitor outer receiver and drop inner receiver when outer is dropped
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         let mut inner_rx = Some(inner_rx);
         let mut message_received = false;
         tokio::select! {
@@ -280,11 +344,11 @@ where
                 // Message received from inner
                 match msg {
                     Ok(msg) => {
+                        let correlation_id = correlate.and_then(|f| f(&msg));
+                        let bytes = recorder_recv.message_size(&msg);
+                        let log = if crate::control::is_paused() { None } else { log_on_recv(&msg) };
                         if inner_tx_proxy.send(msg).is_ok() {
-                            let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                                id,
-                                timestamp: std::time::Instant::now(),
-                            });
+                            recorder_recv.received(log, correlation_id, bytes, crate::now());
                             message_received = true;
                         }
                     }
@@ -293,15 +357,8 @@ where
                     }
                 }
             }
-            _ = async {
-                // Check if outer receiver is canceled
-                loop {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-                    if inner_tx_proxy.is_canceled() {
-                        break;
-                    }
-                }
-            } => {
+            // Wakes up once the outer receiver is dropped
+            _ = inner_tx_proxy.cancellation() => {
                 // Outer receiver was dropped - drop inner_rx to make sends fail
                 drop(inner_rx);
                 let _ = close_signal_tx.send(());
@@ -309,26 +366,26 @@ where
         }
         // Only send Closed if message was not successfully received
         if !message_received {
-            let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+            recorder_recv.closed();
         }
     });
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         let mut message_sent = false;
         tokio::select! {
             msg = outer_rx_proxy => {
                 match msg {
                     Ok(msg) => {
-                        let log = get_msg_log(&msg);
+                        let log = if crate::control::is_paused() { None } else { get_msg_log(&msg) };
+                        let correlation_id = correlate.and_then(|f| f(&msg));
+                        let bytes = recorder_send.message_size(&msg);
                         if inner_tx.send(msg).is_ok() {
-                            let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                                id,
-                                log,
-                                timestamp: std::time::Instant::now(),
-                            });
-                            let _ = stats_tx_send.send(ChannelEvent::Notified { id });
+                            recorder_send.sent(log, correlation_id, bytes, Duration::ZERO, crate::now());
+                            recorder_send.notified();
                             message_sent = true;
+                        } else {
+                            recorder_send.send_rejected_closed();
                         }
                     }
                     Err(_) => {
@@ -342,7 +399,7 @@ where
         }
         // Only send Closed if message was not successfully sent
         if !message_sent {
-            let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+            recorder_send.closed();
         }
     });
 
@@ -353,18 +410,26 @@ where
 pub(crate) fn wrap_oneshot<T: Send + 'static>(
     inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
-    wrap_oneshot_impl(inner, source, label, |_| None)
+    wrap_oneshot_impl(inner, source, options, |_| None, correlate)
 }
 
 /// Wrap a oneshot futures channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_oneshot_log<T: Send + std::fmt::Debug + 'static>(
     inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
-    wrap_oneshot_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    wrap_oneshot_impl(
+        inner,
+        source,
+        options,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 use crate::Instrument;
@@ -379,16 +444,18 @@ impl<T: Send + 'static> Instrument
         futures_channel::mpsc::Sender<T>,
         futures_channel::mpsc::Receiver<T>,
     );
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
         if capacity.is_none() {
             panic!("Capacity is required for bounded futures channels, because they don't expose their capacity in a public API");
         }
-        wrap_channel(self, source, label, capacity.unwrap())
+        wrap_channel(self, source, options, capacity.unwrap(), correlate)
     }
 }
 
@@ -402,13 +469,15 @@ impl<T: Send + 'static> Instrument
         futures_channel::mpsc::UnboundedSender<T>,
         futures_channel::mpsc::UnboundedReceiver<T>,
     );
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_unbounded(self, source, label)
+        wrap_unbounded(self, source, options, correlate)
     }
 }
 
@@ -422,13 +491,15 @@ impl<T: Send + 'static> Instrument
         futures_channel::oneshot::Sender<T>,
         futures_channel::oneshot::Receiver<T>,
     );
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_oneshot(self, source, label)
+        wrap_oneshot(self, source, options, correlate)
     }
 }
 
@@ -444,16 +515,18 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
         futures_channel::mpsc::Sender<T>,
         futures_channel::mpsc::Receiver<T>,
     );
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
         if capacity.is_none() {
             panic!("Capacity is required for bounded futures channels, because they don't expose their capacity in a public API");
         }
-        wrap_channel_log(self, source, label, capacity.unwrap())
+        wrap_channel_log(self, source, options, capacity.unwrap(), correlate)
     }
 }
 
@@ -467,13 +540,15 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
         futures_channel::mpsc::UnboundedSender<T>,
         futures_channel::mpsc::UnboundedReceiver<T>,
     );
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_unbounded_log(self, source, label)
+        wrap_unbounded_log(self, source, options, correlate)
     }
 }
 
@@ -487,12 +562,165 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
         futures_channel::oneshot::Sender<T>,
         futures_channel::oneshot::Receiver<T>,
     );
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_oneshot_log(self, source, label)
+        wrap_oneshot_log(self, source, options, correlate)
+    }
+}
+
+use crate::InstrumentLogWith;
+
+impl<T: Send + 'static> InstrumentLogWith
+    for (
+        futures_channel::mpsc::Sender<T>,
+        futures_channel::mpsc::Receiver<T>,
+    )
+{
+    type Output = (
+        futures_channel::mpsc::Sender<T>,
+        futures_channel::mpsc::Receiver<T>,
+    );
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        if capacity.is_none() {
+            panic!("Capacity is required for bounded futures channels, because they don't expose their capacity in a public API");
+        }
+        wrap_channel_impl(
+            self,
+            source,
+            options,
+            capacity.unwrap(),
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+impl<T: Send + 'static> InstrumentLogWith
+    for (
+        futures_channel::mpsc::UnboundedSender<T>,
+        futures_channel::mpsc::UnboundedReceiver<T>,
+    )
+{
+    type Output = (
+        futures_channel::mpsc::UnboundedSender<T>,
+        futures_channel::mpsc::UnboundedReceiver<T>,
+    );
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        wrap_unbounded_impl(
+            self,
+            source,
+            options,
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+impl<T: Send + 'static> InstrumentLogWith
+    for (
+        futures_channel::oneshot::Sender<T>,
+        futures_channel::oneshot::Receiver<T>,
+    )
+{
+    type Output = (
+        futures_channel::oneshot::Sender<T>,
+        futures_channel::oneshot::Receiver<T>,
+    );
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        wrap_oneshot_impl(
+            self,
+            source,
+            options,
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+use crate::producers::Producer;
+use crate::InstrumentSender;
+
+impl<T: Send + 'static> InstrumentSender for Sender<T> {
+    fn instrument_sender(
+        mut self,
+        source: &'static str,
+        channel: &str,
+        label: Option<String>,
+    ) -> Self {
+        let Some(producer) = Producer::register(source, channel, label) else {
+            return self;
+        };
+
+        // The capacity of futures channels is unknown, so only buffer the slot every sender gets
+        let (producer_tx, mut producer_rx) = mpsc::channel::<T>(0);
+        forwarders::spawn_task(async move {
+            use futures_util::stream::StreamExt;
+            while let Some(msg) = producer_rx.next().await {
+                if self.send(msg).await.is_err() {
+                    break;
+                }
+                producer.sent();
+            }
+        });
+        producer_tx
+    }
+}
+
+impl<T: Send + 'static> InstrumentSender for UnboundedSender<T> {
+    fn instrument_sender(self, source: &'static str, channel: &str, label: Option<String>) -> Self {
+        let Some(producer) = Producer::register(source, channel, label) else {
+            return self;
+        };
+
+        let (producer_tx, mut producer_rx) = mpsc::unbounded::<T>();
+        forwarders::spawn_task(async move {
+            use futures_util::stream::StreamExt;
+            while let Some(msg) = producer_rx.next().await {
+                if self.unbounded_send(msg).is_err() {
+                    break;
+                }
+                producer.sent();
+            }
+        });
+        producer_tx
     }
 }
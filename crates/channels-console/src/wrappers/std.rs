@@ -1,21 +1,30 @@
 use std::mem;
-use std::sync::atomic::Ordering;
 use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::{init_channels_state, ChannelEvent, ChannelType, CHANNEL_ID_COUNTER};
+use crate::correlation::CorrelationFn;
+use crate::counters::ChannelRecorder;
+use crate::registry::next_id;
+use crate::{init_channels_state, ChannelEvent, ChannelOptions, ChannelType};
 
 /// Internal implementation for wrapping bounded std channels with optional logging.
 fn wrap_sync_channel_impl<T, F>(
     inner: (SyncSender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
-    mut log_on_send: F,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (SyncSender<T>, Receiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -25,84 +34,76 @@ where
     let (stats_tx, _) = init_channels_state();
 
     // Generate unique ID for this channel
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Bounded(capacity),
         type_name,
         type_size: mem::size_of::<T>(),
-    });
+        proxied: true,
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
+        counters: recorder.counters(),
+    });
 
-    // Create a signal channel to notify send-forwarder when outer_rx is closed
-    let (close_signal_tx, close_signal_rx) = mpsc::channel::<()>();
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder.clone();
+    let log_on_send = Arc::new(log_on_send);
+    let log_on_recv = Arc::clone(&log_on_send);
 
-    // Forward outer -> inner (proxy the send path)
+    // Forward outer -> inner (proxy the send path). Once the outer receivers are dropped,
+    // the recv forwarder drops the inner receiver, so the next message is rejected.
     std::thread::spawn(move || {
-        loop {
-            // Check for close signal (non-blocking)
-            match close_signal_rx.try_recv() {
-                Ok(_) => {
-                    // Outer receiver was closed/dropped
-                    break;
-                }
-                Err(mpsc::TryRecvError::Disconnected) => {
-                    // Close signal sender dropped, which means recv forwarder ended
-                    break;
-                }
-                Err(mpsc::TryRecvError::Empty) => {
-                    // No close signal, continue
-                }
-            }
-
-            // Try to receive with timeout to periodically check close signal
-            match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
-                Ok(msg) => {
-                    let log = log_on_send(&msg);
-                    if inner_tx.send(msg).is_err() {
-                        // Inner receiver dropped
-                        break;
-                    }
-                    let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                        id,
-                        log,
-                        timestamp: std::time::Instant::now(),
-                    });
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // No message, loop again to check close signal
-                    continue;
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    // Outer sender dropped
-                    break;
-                }
+        for msg in to_inner_rx {
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_send(&msg)
+            };
+            let correlation_id = correlate.and_then(|f| f(&msg));
+            let bytes = recorder_send.message_size(&msg);
+            // Blocks while the inner channel is full
+            let send_started = crate::now();
+            if inner_tx.send(msg).is_err() {
+                // Inner receiver dropped
+                recorder_send.send_rejected_closed();
+                break;
             }
+            let timestamp = crate::now();
+            recorder_send.sent(
+                log,
+                correlation_id,
+                bytes,
+                timestamp.saturating_duration_since(send_started),
+                timestamp,
+            );
         }
         // Channel is closed
-        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+        recorder_send.closed();
     });
 
     // Forward inner -> outer (proxy the recv path)
     std::thread::spawn(move || {
         while let Ok(msg) = inner_rx.recv() {
+            let correlation_id = correlate.and_then(|f| f(&msg));
+            let bytes = recorder_recv.message_size(&msg);
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_recv(&msg)
+            };
             if from_inner_tx.send(msg).is_err() {
                 // Outer receiver was closed
-                let _ = close_signal_tx.send(());
                 break;
             }
-            let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                id,
-                timestamp: std::time::Instant::now(),
-            });
+            recorder_recv.received(log, correlation_id, bytes, crate::now());
         }
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+        recorder_recv.closed();
     });
 
     (outer_tx, outer_rx)
@@ -113,35 +114,47 @@ where
 pub(crate) fn wrap_sync_channel<T: Send + 'static>(
     inner: (SyncSender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (SyncSender<T>, Receiver<T>) {
-    wrap_sync_channel_impl(inner, source, label, capacity, |_| None)
+    wrap_sync_channel_impl(inner, source, options, capacity, |_| None, correlate)
 }
 
 /// Wrap a bounded std channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_sync_channel_log<T: Send + std::fmt::Debug + 'static>(
     inner: (SyncSender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
     capacity: usize,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (SyncSender<T>, Receiver<T>) {
-    wrap_sync_channel_impl(inner, source, label, capacity, |msg| {
-        Some(format!("{:?}", msg))
-    })
+    wrap_sync_channel_impl(
+        inner,
+        source,
+        options,
+        capacity,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 /// Internal implementation for wrapping unbounded std channels with optional logging.
 fn wrap_channel_impl<T, F>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
-    mut log_on_send: F,
+    options: ChannelOptions,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -151,84 +164,67 @@ where
     let (stats_tx, _) = init_channels_state();
 
     // Generate unique ID for this channel
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Unbounded,
         type_name,
         type_size: mem::size_of::<T>(),
-    });
+        proxied: true,
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
+        counters: recorder.counters(),
+    });
 
-    // Create a signal channel to notify send-forwarder when outer_rx is closed
-    let (close_signal_tx, close_signal_rx) = mpsc::channel::<()>();
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder.clone();
+    let log_on_send = Arc::new(log_on_send);
+    let log_on_recv = Arc::clone(&log_on_send);
 
-    // Forward outer -> inner (proxy the send path)
+    // Forward outer -> inner (proxy the send path). Once the outer receivers are dropped,
+    // the recv forwarder drops the inner receiver, so the next message is rejected.
     std::thread::spawn(move || {
-        loop {
-            // Check for close signal (non-blocking)
-            match close_signal_rx.try_recv() {
-                Ok(_) => {
-                    // Outer receiver was closed/dropped
-                    break;
-                }
-                Err(mpsc::TryRecvError::Disconnected) => {
-                    // Close signal sender dropped, which means recv forwarder ended
-                    break;
-                }
-                Err(mpsc::TryRecvError::Empty) => {
-                    // No close signal, continue
-                }
-            }
-
-            // Try to receive with timeout to periodically check close signal
-            match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
-                Ok(msg) => {
-                    let log = log_on_send(&msg);
-                    if inner_tx.send(msg).is_err() {
-                        // Inner receiver dropped
-                        break;
-                    }
-                    let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                        id,
-                        log,
-                        timestamp: std::time::Instant::now(),
-                    });
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // No message, loop again to check close signal
-                    continue;
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    // Outer sender dropped
-                    break;
-                }
+        for msg in to_inner_rx {
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_send(&msg)
+            };
+            let correlation_id = correlate.and_then(|f| f(&msg));
+            let bytes = recorder_send.message_size(&msg);
+            if inner_tx.send(msg).is_err() {
+                // Inner receiver dropped
+                recorder_send.send_rejected_closed();
+                break;
             }
+            recorder_send.sent(log, correlation_id, bytes, Duration::ZERO, crate::now());
         }
         // Channel is closed
-        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+        recorder_send.closed();
     });
 
     // Forward inner -> outer (proxy the recv path)
     std::thread::spawn(move || {
         while let Ok(msg) = inner_rx.recv() {
+            let correlation_id = correlate.and_then(|f| f(&msg));
+            let bytes = recorder_recv.message_size(&msg);
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_recv(&msg)
+            };
             if from_inner_tx.send(msg).is_err() {
                 // Outer receiver was closed
-                let _ = close_signal_tx.send(());
                 break;
             }
-            let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                id,
-                timestamp: std::time::Instant::now(),
-            });
+            recorder_recv.received(log, correlation_id, bytes, crate::now());
         }
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+        recorder_recv.closed();
     });
 
     (outer_tx, outer_rx)
@@ -238,31 +234,41 @@ where
 pub(crate) fn wrap_channel<T: Send + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, |_| None)
+    wrap_channel_impl(inner, source, options, |_| None, correlate)
 }
 
 /// Wrap an unbounded std channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    wrap_channel_impl(
+        inner,
+        source,
+        options,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 use crate::Instrument;
 
 impl<T: Send + 'static> Instrument for (std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>) {
     type Output = (std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>);
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_channel(self, source, label)
+        wrap_channel(self, source, options, correlate)
     }
 }
 
@@ -270,16 +276,18 @@ impl<T: Send + 'static> Instrument
     for (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>)
 {
     type Output = (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>);
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
         if capacity.is_none() {
             panic!("Capacity is required for bounded std channels, because they don't expose their capacity in a public API");
         }
-        wrap_sync_channel(self, source, label, capacity.unwrap())
+        wrap_sync_channel(self, source, options, capacity.unwrap(), correlate)
     }
 }
 
@@ -289,13 +297,15 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
     for (std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>)
 {
     type Output = (std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>);
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_channel_log(self, source, label)
+        wrap_channel_log(self, source, options, correlate)
     }
 }
 
@@ -303,15 +313,117 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
     for (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>)
 {
     type Output = (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>);
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
         if capacity.is_none() {
             panic!("Capacity is required for bounded std channels, because they don't expose their capacity in a public API");
         }
-        wrap_sync_channel_log(self, source, label, capacity.unwrap())
+        wrap_sync_channel_log(self, source, options, capacity.unwrap(), correlate)
+    }
+}
+
+use crate::InstrumentLogWith;
+
+impl<T: Send + 'static> InstrumentLogWith
+    for (std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>)
+{
+    type Output = (std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>);
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        wrap_channel_impl(
+            self,
+            source,
+            options,
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+impl<T: Send + 'static> InstrumentLogWith
+    for (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>)
+{
+    type Output = (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>);
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        if capacity.is_none() {
+            panic!("Capacity is required for bounded std channels, because they don't expose their capacity in a public API");
+        }
+        wrap_sync_channel_impl(
+            self,
+            source,
+            options,
+            capacity.unwrap(),
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+use crate::producers::Producer;
+use crate::InstrumentSender;
+
+impl<T: Send + 'static> InstrumentSender for Sender<T> {
+    fn instrument_sender(self, source: &'static str, channel: &str, label: Option<String>) -> Self {
+        let Some(producer) = Producer::register(source, channel, label) else {
+            return self;
+        };
+
+        let (producer_tx, producer_rx) = mpsc::channel::<T>();
+        std::thread::spawn(move || {
+            for msg in producer_rx {
+                if self.send(msg).is_err() {
+                    break;
+                }
+                producer.sent();
+            }
+        });
+        producer_tx
+    }
+}
+
+impl<T: Send + 'static> InstrumentSender for SyncSender<T> {
+    fn instrument_sender(self, source: &'static str, channel: &str, label: Option<String>) -> Self {
+        let Some(producer) = Producer::register(source, channel, label) else {
+            return self;
+        };
+
+        // The capacity of std channels is unknown, so hand messages over without buffering
+        let (producer_tx, producer_rx) = mpsc::sync_channel::<T>(0);
+        std::thread::spawn(move || {
+            for msg in producer_rx {
+                if self.send(msg).is_err() {
+                    break;
+                }
+                producer.sent();
+            }
+        });
+        producer_tx
     }
 }
@@ -0,0 +1,236 @@
+use std::mem;
+use std::sync::mpmc::{self, Receiver, Sender};
+use std::sync::Arc;
+
+use crate::correlation::CorrelationFn;
+use crate::counters::ChannelRecorder;
+use crate::registry::next_id;
+use crate::{init_channels_state, ChannelEvent, ChannelOptions, ChannelType};
+
+/// Internal implementation for wrapping std mpmc channels with optional logging.
+///
+/// Bounded and unbounded channels share the same types, so the proxy channels are created
+/// with the capacity of the inner one. Outer receivers can be cloned, all clones pull from
+/// the same receive forwarder, so received counts aggregate across consumers.
+fn wrap_impl<T, F>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    options: ChannelOptions,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
+) -> (Sender<T>, Receiver<T>)
+where
+    T: Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
+{
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
+    let (inner_tx, inner_rx) = inner;
+    let type_name = std::any::type_name::<T>();
+    let capacity = inner_tx.capacity();
+
+    let ((outer_tx, to_inner_rx), (from_inner_tx, outer_rx)) = match capacity {
+        Some(capacity) => (
+            mpmc::sync_channel::<T>(capacity),
+            mpmc::sync_channel::<T>(capacity),
+        ),
+        None => (mpmc::channel::<T>(), mpmc::channel::<T>()),
+    };
+
+    let (stats_tx, _) = init_channels_state();
+
+    // Generate unique ID for this channel
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
+
+    let _ = stats_tx.send(ChannelEvent::Created {
+        id,
+        source,
+        options,
+        channel_type: capacity.map_or(ChannelType::Unbounded, ChannelType::Bounded),
+        type_name,
+        type_size: mem::size_of::<T>(),
+        proxied: true,
+
+        counters: recorder.counters(),
+    });
+
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder.clone();
+    let log_on_send = Arc::new(log_on_send);
+    let log_on_recv = Arc::clone(&log_on_send);
+
+    // Forward outer -> inner (proxy the send path). Once the outer receivers are dropped,
+    // the recv forwarder drops the inner receiver, so the next message is rejected.
+    std::thread::spawn(move || {
+        for msg in to_inner_rx {
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_send(&msg)
+            };
+            let correlation_id = correlate.and_then(|f| f(&msg));
+            let bytes = recorder_send.message_size(&msg);
+            // Blocks while the inner channel is full
+            let send_started = crate::now();
+            if inner_tx.send(msg).is_err() {
+                // All inner receivers dropped
+                recorder_send.send_rejected_closed();
+                break;
+            }
+            let timestamp = crate::now();
+            recorder_send.sent(
+                log,
+                correlation_id,
+                bytes,
+                timestamp.saturating_duration_since(send_started),
+                timestamp,
+            );
+        }
+        recorder_send.closed();
+    });
+
+    // Forward inner -> outer (proxy the recv path)
+    std::thread::spawn(move || {
+        while let Ok(msg) = inner_rx.recv() {
+            let correlation_id = correlate.and_then(|f| f(&msg));
+            let bytes = recorder_recv.message_size(&msg);
+            let log = if crate::control::is_paused() {
+                None
+            } else {
+                log_on_recv(&msg)
+            };
+            if from_inner_tx.send(msg).is_err() {
+                // All outer receivers dropped
+                break;
+            }
+            recorder_recv.received(log, correlation_id, bytes, crate::now());
+        }
+        recorder_recv.closed();
+    });
+
+    (outer_tx, outer_rx)
+}
+
+use crate::Instrument;
+
+impl<T: Send + 'static> Instrument for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    type Message = T;
+    fn instrument(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+    ) -> Self::Output {
+        wrap_impl(self, source, options, |_| None, correlate)
+    }
+}
+
+use crate::InstrumentLog;
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentLog for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    type Message = T;
+    fn instrument_log(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+    ) -> Self::Output {
+        wrap_impl(
+            self,
+            source,
+            options,
+            |msg| Some(format!("{:?}", msg)),
+            correlate,
+        )
+    }
+}
+
+use crate::InstrumentLogWith;
+
+impl<T: Send + 'static> InstrumentLogWith for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        wrap_impl(
+            self,
+            source,
+            options,
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+use crate::producers::Producer;
+use crate::InstrumentSender;
+
+impl<T: Send + 'static> InstrumentSender for Sender<T> {
+    fn instrument_sender(self, source: &'static str, channel: &str, label: Option<String>) -> Self {
+        let Some(producer) = Producer::register(source, channel, label) else {
+            return self;
+        };
+
+        // Bounded channels are handed messages without buffering, so the producer is blocked
+        // like the channel
+        let (producer_tx, producer_rx) = match self.capacity() {
+            Some(_) => mpmc::sync_channel::<T>(0),
+            None => mpmc::channel::<T>(),
+        };
+        std::thread::spawn(move || {
+            for msg in producer_rx {
+                if self.send(msg).is_err() {
+                    break;
+                }
+                producer.sent();
+            }
+        });
+        producer_tx
+    }
+}
+
+use crate::consumers::Consumer;
+use crate::InstrumentReceiver;
+
+impl<T: Send + 'static> InstrumentReceiver for Receiver<T> {
+    fn instrument_receiver(
+        self,
+        source: &'static str,
+        channel: &str,
+        label: Option<String>,
+    ) -> Self {
+        let Some(consumer) = Consumer::register(source, channel, label) else {
+            return self;
+        };
+
+        // Hand messages over without buffering, so the forwarder holds at most one message
+        // and leaves the rest to the other consumers
+        let (consumer_tx, consumer_rx) = mpmc::sync_channel::<T>(0);
+        std::thread::spawn(move || {
+            for msg in self {
+                if consumer_tx.send(msg).is_err() {
+                    break;
+                }
+                consumer.received();
+            }
+        });
+        consumer_rx
+    }
+}
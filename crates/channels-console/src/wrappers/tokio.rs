@@ -1,23 +1,33 @@
 use std::mem;
-use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
 use tokio::sync::oneshot;
 
-use crate::RT;
-use crate::{init_channels_state, ChannelEvent, ChannelType, CHANNEL_ID_COUNTER};
+use crate::correlation::CorrelationFn;
+use crate::counters::ChannelRecorder;
+use crate::forwarders;
+use crate::handles::Handles;
+use crate::registry::next_id;
+use crate::{init_channels_state, ChannelEvent, ChannelOptions, ChannelType};
 
 /// Internal implementation for wrapping bounded Tokio channels with optional logging.
 fn wrap_channel_impl<T, F>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
-    mut log_on_send: F,
+    options: ChannelOptions,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, mut inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -27,40 +37,58 @@ where
 
     let (stats_tx, _) = init_channels_state();
 
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Bounded(capacity),
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
+
+        counters: recorder.counters(),
+    });
+
+    let handles = Handles::new({
+        let outer_tx = outer_tx.downgrade();
+        move || outer_tx.strong_count()
+    });
+    let _ = stats_tx.send(ChannelEvent::Handles {
+        id,
+        handles: Arc::clone(&handles),
     });
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder.clone();
+    let log_on_send = Arc::new(log_on_send);
+    let log_on_recv = Arc::clone(&log_on_send);
 
     // Create a signal channel to notify send-forwarder when outer_rx is closed
     let (close_signal_tx, mut close_signal_rx) = oneshot::channel::<()>();
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         loop {
             tokio::select! {
                 msg = to_inner_rx.recv() => {
                     match msg {
                         Some(msg) => {
-                            let log = log_on_send(&msg);
+                            let log = if crate::control::is_paused() { None } else { log_on_send(&msg) };
+                            let correlation_id = correlate.and_then(|f| f(&msg));
+                            let bytes = recorder_send.message_size(&msg);
+                            // Waits while the inner channel is full
+                            let send_started = crate::now();
                             if inner_tx.send(msg).await.is_err() {
+                                recorder_send.send_rejected_closed();
                                 to_inner_rx.close();
                                 break;
                             }
-                            let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                                id,
-                                log,
-                                timestamp: std::time::Instant::now(),
-                            });
+                            let timestamp = crate::now();
+                            recorder_send.sent(log, correlation_id, bytes, timestamp.saturating_duration_since(send_started), timestamp);
                         }
                         None => break, // Outer sender dropped
                     }
@@ -73,22 +101,23 @@ where
             }
         }
         // Channel is closed
-        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+        recorder_send.closed();
     });
 
     // Forward inner -> outer (proxy the recv path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         loop {
             tokio::select! {
                 msg = inner_rx.recv() => {
                     match msg {
                         Some(msg) => {
+                            let correlation_id = correlate.and_then(|f| f(&msg));
+                            let bytes = recorder_recv.message_size(&msg);
+                            let log = if crate::control::is_paused() { None } else { log_on_recv(&msg) };
                             if from_inner_tx.send(msg).await.is_ok() {
-                                let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                                    id,
-                                    timestamp: std::time::Instant::now(),
-                                });
+                                recorder_recv.received(log, correlation_id, bytes, crate::now());
                             } else {
+                                handles.receiver_dropped();
                                 let _ = close_signal_tx.send(());
                                 break;
                             }
@@ -98,13 +127,14 @@ where
                 }
                 _ = from_inner_tx.closed() => {
                     // Outer receiver was closed/dropped
+                    handles.receiver_dropped();
                     let _ = close_signal_tx.send(());
                     break;
                 }
             }
         }
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+        recorder_recv.closed();
     });
 
     (outer_tx, outer_rx)
@@ -115,31 +145,44 @@ where
 pub(crate) fn wrap_channel<T: Send + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, |_| None)
+    wrap_channel_impl(inner, source, options, |_| None, correlate)
 }
 
 /// Wrap a bounded Tokio channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    wrap_channel_impl(
+        inner,
+        source,
+        options,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 /// Internal implementation for wrapping unbounded Tokio channels with optional logging.
 fn wrap_unbounded_impl<T, F>(
     inner: (UnboundedSender<T>, UnboundedReceiver<T>),
     source: &'static str,
-    label: Option<String>,
-    mut log_on_send: F,
+    options: ChannelOptions,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, mut inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -148,40 +191,55 @@ where
 
     let (stats_tx, _) = init_channels_state();
 
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Unbounded,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
+
+        counters: recorder.counters(),
+    });
+
+    let handles = Handles::new({
+        let outer_tx = outer_tx.downgrade();
+        move || outer_tx.strong_count()
+    });
+    let _ = stats_tx.send(ChannelEvent::Handles {
+        id,
+        handles: Arc::clone(&handles),
     });
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder.clone();
+    let log_on_send = Arc::new(log_on_send);
+    let log_on_recv = Arc::clone(&log_on_send);
 
     // Create a signal channel to notify send-forwarder when outer_rx is closed
     let (close_signal_tx, mut close_signal_rx) = oneshot::channel::<()>();
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         loop {
             tokio::select! {
                 msg = to_inner_rx.recv() => {
                     match msg {
                         Some(msg) => {
-                            let log = log_on_send(&msg);
+                            let log = if crate::control::is_paused() { None } else { log_on_send(&msg) };
+                            let correlation_id = correlate.and_then(|f| f(&msg));
+                            let bytes = recorder_send.message_size(&msg);
                             if inner_tx.send(msg).is_err() {
+                                recorder_send.send_rejected_closed();
                                 to_inner_rx.close();
                                 break;
                             }
-                            let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                                id,
-                                log,
-                                timestamp: std::time::Instant::now(),
-                            });
+                            recorder_send.sent(log, correlation_id, bytes, Duration::ZERO, crate::now());
                         }
                         None => break, // Outer sender dropped
                     }
@@ -194,23 +252,24 @@ where
             }
         }
         // Channel is closed
-        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+        recorder_send.closed();
     });
 
     // Forward inner -> outer (proxy the recv path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         loop {
             tokio::select! {
                 msg = inner_rx.recv() => {
                     match msg {
                         Some(msg) => {
+                            let correlation_id = correlate.and_then(|f| f(&msg));
+                            let bytes = recorder_recv.message_size(&msg);
+                            let log = if crate::control::is_paused() { None } else { log_on_recv(&msg) };
                             if from_inner_tx.send(msg).is_ok() {
-                                let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                                    id,
-                                    timestamp: std::time::Instant::now(),
-                                });
+                                recorder_recv.received(log, correlation_id, bytes, crate::now());
                             } else {
                                 // Outer receiver was closed
+                                handles.receiver_dropped();
                                 let _ = close_signal_tx.send(());
                                 break;
                             }
@@ -220,13 +279,14 @@ where
                 }
                 _ = from_inner_tx.closed() => {
                     // Outer receiver was closed/dropped
+                    handles.receiver_dropped();
                     let _ = close_signal_tx.send(());
                     break;
                 }
             }
         }
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+        recorder_recv.closed();
     });
 
     (outer_tx, outer_rx)
@@ -236,31 +296,44 @@ where
 pub(crate) fn wrap_unbounded<T: Send + 'static>(
     inner: (UnboundedSender<T>, UnboundedReceiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |_| None)
+    wrap_unbounded_impl(inner, source, options, |_| None, correlate)
 }
 
 /// Wrap an unbounded Tokio channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_unbounded_log<T: Send + std::fmt::Debug + 'static>(
     inner: (UnboundedSender<T>, UnboundedReceiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    wrap_unbounded_impl(
+        inner,
+        source,
+        options,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 /// Internal implementation for wrapping oneshot Tokio channels with optional logging.
 fn wrap_oneshot_impl<T, F>(
     inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
     source: &'static str,
-    label: Option<String>,
-    mut log_on_send: F,
+    options: ChannelOptions,
+    log_on_send: F,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (oneshot::Sender<T>, oneshot::Receiver<T>)
 where
     T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    F: Fn(&T) -> Option<String> + Send + Sync + 'static,
 {
+    if crate::Config::current().is_disabled() {
+        return inner;
+    }
+
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
 
@@ -269,25 +342,32 @@ where
 
     let (stats_tx, _) = init_channels_state();
 
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let id = next_id();
+
+    let recorder = ChannelRecorder::new(id, &options, stats_tx);
 
     let _ = stats_tx.send(ChannelEvent::Created {
         id,
         source,
-        display_label: label,
+        options,
         channel_type: ChannelType::Oneshot,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
+
+        counters: recorder.counters(),
     });
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx;
+    let recorder_send = recorder.clone();
+    let recorder_recv = recorder;
+    let log_on_send = Arc::new(log_on_send);
+    let log_on_recv = Arc::clone(&log_on_send);
 
     // Create a signal channel to notify send-forwarder when outer_rx is closed
     let (close_signal_tx, mut close_signal_rx) = oneshot::channel::<()>();
 
     // Monitor outer receiver and drop inner receiver when outer is dropped
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         let mut inner_rx = Some(inner_rx);
         let mut message_received = false;
         tokio::select! {
@@ -295,11 +375,11 @@ where
                 // Message received from inner
                 match msg {
                     Ok(msg) => {
+                        let correlation_id = correlate.and_then(|f| f(&msg));
+                        let bytes = recorder_recv.message_size(&msg);
+                        let log = if crate::control::is_paused() { None } else { log_on_recv(&msg) };
                         if inner_tx_proxy.send(msg).is_ok() {
-                            let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
-                                id,
-                                timestamp: std::time::Instant::now(),
-                            });
+                            recorder_recv.received(log, correlation_id, bytes, crate::now());
                             message_received = true;
                         }
                     }
@@ -316,26 +396,26 @@ where
         }
         // Only send Closed if message was not successfully received
         if !message_received {
-            let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+            recorder_recv.closed();
         }
     });
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    forwarders::spawn_task(async move {
         let mut message_sent = false;
         tokio::select! {
             msg = outer_rx_proxy => {
                 match msg {
                     Ok(msg) => {
-                        let log = log_on_send(&msg);
+                        let log = if crate::control::is_paused() { None } else { log_on_send(&msg) };
+                        let correlation_id = correlate.and_then(|f| f(&msg));
+                        let bytes = recorder_send.message_size(&msg);
                         if inner_tx.send(msg).is_ok() {
-                            let _ = stats_tx_send.send(ChannelEvent::MessageSent {
-                                id,
-                                log,
-                                timestamp: std::time::Instant::now(),
-                            });
-                            let _ = stats_tx_send.send(ChannelEvent::Notified { id });
+                            recorder_send.sent(log, correlation_id, bytes, Duration::ZERO, crate::now());
+                            recorder_send.notified();
                             message_sent = true;
+                        } else {
+                            recorder_send.send_rejected_closed();
                         }
                     }
                     Err(_) => {
@@ -349,7 +429,7 @@ where
         }
         // Only send Closed if message was not successfully sent
         if !message_sent {
-            let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+            recorder_send.closed();
         }
     });
 
@@ -360,55 +440,69 @@ where
 pub(crate) fn wrap_oneshot<T: Send + 'static>(
     inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
-    wrap_oneshot_impl(inner, source, label, |_| None)
+    wrap_oneshot_impl(inner, source, options, |_| None, correlate)
 }
 
 /// Wrap a oneshot Tokio channel with logging enabled. Returns (outer_tx, outer_rx).
 pub(crate) fn wrap_oneshot_log<T: Send + std::fmt::Debug + 'static>(
     inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
     source: &'static str,
-    label: Option<String>,
+    options: ChannelOptions,
+    correlate: Option<CorrelationFn<T>>,
 ) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
-    wrap_oneshot_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    wrap_oneshot_impl(
+        inner,
+        source,
+        options,
+        |msg| Some(format!("{:?}", msg)),
+        correlate,
+    )
 }
 
 use crate::Instrument;
 
 impl<T: Send + 'static> Instrument for (Sender<T>, Receiver<T>) {
     type Output = (Sender<T>, Receiver<T>);
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_channel(self, source, label)
+        wrap_channel(self, source, options, correlate)
     }
 }
 
 impl<T: Send + 'static> Instrument for (UnboundedSender<T>, UnboundedReceiver<T>) {
     type Output = (UnboundedSender<T>, UnboundedReceiver<T>);
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_unbounded(self, source, label)
+        wrap_unbounded(self, source, options, correlate)
     }
 }
 
 impl<T: Send + 'static> Instrument for (oneshot::Sender<T>, oneshot::Receiver<T>) {
     type Output = (oneshot::Sender<T>, oneshot::Receiver<T>);
+    type Message = T;
     fn instrument(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_oneshot(self, source, label)
+        wrap_oneshot(self, source, options, correlate)
     }
 }
 
@@ -416,13 +510,15 @@ use crate::InstrumentLog;
 
 impl<T: Send + std::fmt::Debug + 'static> InstrumentLog for (Sender<T>, Receiver<T>) {
     type Output = (Sender<T>, Receiver<T>);
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_channel_log(self, source, label)
+        wrap_channel_log(self, source, options, correlate)
     }
 }
 
@@ -430,13 +526,15 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
     for (UnboundedSender<T>, UnboundedReceiver<T>)
 {
     type Output = (UnboundedSender<T>, UnboundedReceiver<T>);
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_unbounded_log(self, source, label)
+        wrap_unbounded_log(self, source, options, correlate)
     }
 }
 
@@ -444,12 +542,129 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
     for (oneshot::Sender<T>, oneshot::Receiver<T>)
 {
     type Output = (oneshot::Sender<T>, oneshot::Receiver<T>);
+    type Message = T;
     fn instrument_log(
         self,
         source: &'static str,
-        label: Option<String>,
+        options: ChannelOptions,
         _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
     ) -> Self::Output {
-        wrap_oneshot_log(self, source, label)
+        wrap_oneshot_log(self, source, options, correlate)
+    }
+}
+
+use crate::InstrumentLogWith;
+
+impl<T: Send + 'static> InstrumentLogWith for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        wrap_channel_impl(
+            self,
+            source,
+            options,
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+impl<T: Send + 'static> InstrumentLogWith for (UnboundedSender<T>, UnboundedReceiver<T>) {
+    type Output = (UnboundedSender<T>, UnboundedReceiver<T>);
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        wrap_unbounded_impl(
+            self,
+            source,
+            options,
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+impl<T: Send + 'static> InstrumentLogWith for (oneshot::Sender<T>, oneshot::Receiver<T>) {
+    type Output = (oneshot::Sender<T>, oneshot::Receiver<T>);
+    type Message = T;
+    fn instrument_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        _capacity: Option<usize>,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        wrap_oneshot_impl(
+            self,
+            source,
+            options,
+            move |msg| Some(log_with(msg)),
+            correlate,
+        )
+    }
+}
+
+use crate::producers::Producer;
+use crate::InstrumentSender;
+
+impl<T: Send + 'static> InstrumentSender for Sender<T> {
+    fn instrument_sender(self, source: &'static str, channel: &str, label: Option<String>) -> Self {
+        let Some(producer) = Producer::register(source, channel, label) else {
+            return self;
+        };
+
+        let (producer_tx, mut producer_rx) = mpsc::channel::<T>(self.max_capacity());
+        forwarders::spawn_task(async move {
+            while let Some(msg) = producer_rx.recv().await {
+                if self.send(msg).await.is_err() {
+                    break;
+                }
+                producer.sent();
+            }
+        });
+        producer_tx
+    }
+}
+
+impl<T: Send + 'static> InstrumentSender for UnboundedSender<T> {
+    fn instrument_sender(self, source: &'static str, channel: &str, label: Option<String>) -> Self {
+        let Some(producer) = Producer::register(source, channel, label) else {
+            return self;
+        };
+
+        let (producer_tx, mut producer_rx) = mpsc::unbounded_channel::<T>();
+        forwarders::spawn_task(async move {
+            while let Some(msg) = producer_rx.recv().await {
+                if self.send(msg).is_err() {
+                    break;
+                }
+                producer.sent();
+            }
+        });
+        producer_tx
     }
 }
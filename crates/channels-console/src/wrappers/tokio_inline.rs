@@ -0,0 +1,1029 @@
+//! Inline instrumentation of Tokio channels, selected with `channel!(..., mode = inline)`.
+//!
+//! Instead of proxy channels and forwarding tasks, the returned wrapper types report
+//! statistics directly from `send` and `recv`, so they add no buffering and spawn no tasks.
+//! The wrapped channel stays the only buffer, so capacity, `try_send` errors and the fairness
+//! of waiting senders are the same as without instrumentation.
+
+use std::mem;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{
+    self,
+    error::{SendError, SendTimeoutError, TryRecvError, TrySendError},
+};
+
+use crate::correlation::CorrelationFn;
+use crate::counters::ChannelRecorder;
+use crate::handles::Handles;
+use crate::registry::next_id;
+use crate::sizes::{self, SizeFn};
+use crate::{
+    init_channels_state, ChannelEvent, ChannelOptions, ChannelType, InstrumentInline,
+    InstrumentInlineLog, InstrumentInlineLogWith,
+};
+
+/// Formats sent and received messages for the channel logs.
+enum MessageLog<T> {
+    Fn(fn(&T) -> Option<String>),
+    /// Formatter passed with `log_with`
+    With(Box<dyn Fn(&T) -> String + Send + Sync>),
+}
+
+impl<T> MessageLog<T> {
+    fn log(&self, msg: &T) -> Option<String> {
+        if crate::control::is_paused() {
+            return None;
+        }
+        match self {
+            Self::Fn(f) => f(msg),
+            Self::With(f) => Some(f(msg)),
+        }
+    }
+}
+
+/// Log, correlation id and size of a sent message.
+type MessageMeta = (Option<String>, Option<u64>, u64);
+
+/// State shared by both ends of an inline instrumented channel.
+struct Shared<T> {
+    id: u64,
+    recorder: ChannelRecorder,
+    closed: AtomicBool,
+    handles: Arc<Handles>,
+    message_log: MessageLog<T>,
+    correlate: Option<CorrelationFn<T>>,
+    /// Declared with `size = true` or `size_with`, see [`sizes`](crate::sizes)
+    message_size: Option<SizeFn<T>>,
+}
+
+impl<T> Shared<T> {
+    fn new(
+        source: &'static str,
+        options: ChannelOptions,
+        channel_type: ChannelType,
+        handles: Arc<Handles>,
+        message_log: MessageLog<T>,
+        correlate: Option<CorrelationFn<T>>,
+    ) -> Arc<Self>
+    where
+        T: 'static,
+    {
+        let (stats_tx, _) = init_channels_state();
+        let id = next_id();
+
+        let recorder = ChannelRecorder::with_actors(id, &options, stats_tx);
+        let _ = stats_tx.send(ChannelEvent::Created {
+            id,
+            source,
+            options,
+            channel_type,
+            type_name: std::any::type_name::<T>(),
+            type_size: mem::size_of::<T>(),
+            proxied: false,
+            counters: recorder.counters(),
+        });
+        let _ = stats_tx.send(ChannelEvent::Handles {
+            id,
+            handles: Arc::clone(&handles),
+        });
+
+        let message_size = recorder.size_fn();
+        Arc::new(Self {
+            id,
+            recorder,
+            closed: AtomicBool::new(false),
+            handles,
+            message_log,
+            correlate,
+            message_size,
+        })
+    }
+
+    /// Log, correlation id and size of a message, captured before it's moved into the channel.
+    fn inspect(&self, msg: &T) -> MessageMeta {
+        if self.recorder.is_disabled() {
+            return (None, None, 0);
+        }
+        (
+            self.message_log.log(msg),
+            self.correlate.and_then(|f| f(msg)),
+            sizes::measure(self.message_size, msg),
+        )
+    }
+
+    /// Reports a sent message, `started` being when a send that may wait for capacity began.
+    fn sent(&self, meta: MessageMeta, started: Option<Instant>) {
+        let blocked = started
+            .map(|started| crate::now().saturating_duration_since(started))
+            .unwrap_or_default();
+        self.sent_after(meta, blocked);
+    }
+
+    /// Reports a sent message that waited `blocked` for capacity.
+    fn sent_after(&self, (log, correlation_id, bytes): MessageMeta, blocked: Duration) {
+        self.recorder
+            .sent(log, correlation_id, bytes, blocked, crate::now());
+    }
+
+    /// Reports a message rejected because the channel was full, or otherwise closed.
+    fn rejected(&self, full: bool) {
+        if full {
+            self.recorder.send_rejected_full();
+        } else {
+            self.recorder.send_rejected_closed();
+        }
+    }
+
+    fn received(&self, msg: &T) {
+        if self.recorder.is_disabled() {
+            return;
+        }
+        self.recorder.received(
+            self.message_log.log(msg),
+            self.correlate.and_then(|f| f(msg)),
+            sizes::measure(self.message_size, msg),
+            crate::now(),
+        );
+    }
+
+    fn close(&self) {
+        if !self.closed.swap(true, Ordering::Relaxed) {
+            self.recorder.closed();
+        }
+    }
+}
+
+/// Reports the channel as closed once the last sender clone is dropped.
+struct SenderGuard<T>(Arc<Shared<T>>);
+
+impl<T> Drop for SenderGuard<T> {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+/// Counts the live clones of an instrumented sender.
+struct SenderHandle<T>(Arc<Shared<T>>);
+
+impl<T> SenderHandle<T> {
+    /// Handle of an additional sender, e.g. a clone or an upgraded weak sender.
+    fn new(shared: &Arc<Shared<T>>) -> Self {
+        shared.handles.sender_cloned();
+        Self(Arc::clone(shared))
+    }
+}
+
+impl<T> Clone for SenderHandle<T> {
+    fn clone(&self) -> Self {
+        Self::new(&self.0)
+    }
+}
+
+impl<T> Drop for SenderHandle<T> {
+    fn drop(&mut self) {
+        self.0.handles.sender_dropped();
+    }
+}
+
+/// Instrumented bounded Tokio sender.
+///
+/// `send`, `try_send`, `send_timeout`, `blocking_send`, and messages sent through permits of
+/// `reserve`, `try_reserve`, their `_many` and `_owned` variants are counted. Other [`mpsc::Sender`] methods, like `capacity`, are
+/// available through `Deref`.
+pub struct InstrumentedSender<T> {
+    inner: mpsc::Sender<T>,
+    guard: Arc<SenderGuard<T>>,
+    handle: SenderHandle<T>,
+}
+
+impl<T> InstrumentedSender<T> {
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let meta = self.guard.0.inspect(&value);
+        let started = crate::now();
+        self.inner
+            .send(value)
+            .await
+            .inspect_err(|_| self.guard.0.rejected(false))?;
+        self.guard.0.sent(meta, Some(started));
+        Ok(())
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let meta = self.guard.0.inspect(&value);
+        self.inner
+            .try_send(value)
+            .inspect_err(|err| self.guard.0.rejected(matches!(err, TrySendError::Full(_))))?;
+        self.guard.0.sent(meta, None);
+        Ok(())
+    }
+
+    pub fn blocking_send(&self, value: T) -> Result<(), SendError<T>> {
+        let meta = self.guard.0.inspect(&value);
+        let started = crate::now();
+        self.inner
+            .blocking_send(value)
+            .inspect_err(|_| self.guard.0.rejected(false))?;
+        self.guard.0.sent(meta, Some(started));
+        Ok(())
+    }
+
+    pub async fn send_timeout(
+        &self,
+        value: T,
+        timeout: Duration,
+    ) -> Result<(), SendTimeoutError<T>> {
+        let meta = self.guard.0.inspect(&value);
+        let started = crate::now();
+        self.inner
+            .send_timeout(value, timeout)
+            .await
+            .inspect_err(|err| {
+                self.guard
+                    .0
+                    .rejected(matches!(err, SendTimeoutError::Timeout(_)))
+            })?;
+        self.guard.0.sent(meta, Some(started));
+        Ok(())
+    }
+
+    /// Waits for capacity like [`mpsc::Sender::reserve`], the time waited is reported as blocked
+    /// once the permit is used.
+    pub async fn reserve(&self) -> Result<InstrumentedPermit<'_, T>, SendError<()>> {
+        let started = crate::now();
+        let permit = self
+            .inner
+            .reserve()
+            .await
+            .inspect_err(|_| self.guard.0.rejected(false))?;
+        Ok(InstrumentedPermit {
+            permit,
+            shared: &self.guard.0,
+            blocked: crate::now().saturating_duration_since(started),
+        })
+    }
+
+    pub fn try_reserve(&self) -> Result<InstrumentedPermit<'_, T>, TrySendError<()>> {
+        let permit = self
+            .inner
+            .try_reserve()
+            .inspect_err(|err| self.guard.0.rejected(matches!(err, TrySendError::Full(_))))?;
+        Ok(InstrumentedPermit {
+            permit,
+            shared: &self.guard.0,
+            blocked: Duration::ZERO,
+        })
+    }
+
+    /// Waits for capacity for `n` messages like [`mpsc::Sender::reserve_many`]. The time waited
+    /// is reported as blocked once, by the first permit used.
+    pub async fn reserve_many(
+        &self,
+        n: usize,
+    ) -> Result<InstrumentedPermitIterator<'_, T>, SendError<()>> {
+        let started = crate::now();
+        let permits = self
+            .inner
+            .reserve_many(n)
+            .await
+            .inspect_err(|_| self.guard.0.rejected(false))?;
+        Ok(InstrumentedPermitIterator {
+            permits,
+            shared: &self.guard.0,
+            blocked: crate::now().saturating_duration_since(started),
+        })
+    }
+
+    pub fn try_reserve_many(
+        &self,
+        n: usize,
+    ) -> Result<InstrumentedPermitIterator<'_, T>, TrySendError<()>> {
+        let permits = self
+            .inner
+            .try_reserve_many(n)
+            .inspect_err(|err| self.guard.0.rejected(matches!(err, TrySendError::Full(_))))?;
+        Ok(InstrumentedPermitIterator {
+            permits,
+            shared: &self.guard.0,
+            blocked: Duration::ZERO,
+        })
+    }
+
+    /// Waits for capacity like [`mpsc::Sender::reserve_owned`], moving the sender into the
+    /// permit. The time waited is reported as blocked once the permit is used.
+    pub async fn reserve_owned(self) -> Result<InstrumentedOwnedPermit<T>, SendError<()>> {
+        let Self {
+            inner,
+            guard,
+            handle,
+        } = self;
+        let started = crate::now();
+        let permit = inner
+            .reserve_owned()
+            .await
+            .inspect_err(|_| guard.0.rejected(false))?;
+        Ok(InstrumentedOwnedPermit {
+            permit,
+            guard,
+            handle,
+            blocked: crate::now().saturating_duration_since(started),
+        })
+    }
+
+    /// Like [`mpsc::Sender::try_reserve_owned`], the sender is handed back on error.
+    pub fn try_reserve_owned(self) -> Result<InstrumentedOwnedPermit<T>, TrySendError<Self>> {
+        let Self {
+            inner,
+            guard,
+            handle,
+        } = self;
+        match inner.try_reserve_owned() {
+            Ok(permit) => Ok(InstrumentedOwnedPermit {
+                permit,
+                guard,
+                handle,
+                blocked: Duration::ZERO,
+            }),
+            Err(err) => {
+                let full = matches!(err, TrySendError::Full(_));
+                guard.0.rejected(full);
+                let rebuild = |inner| Self {
+                    inner,
+                    guard,
+                    handle,
+                };
+                Err(match err {
+                    TrySendError::Full(inner) => TrySendError::Full(rebuild(inner)),
+                    TrySendError::Closed(inner) => TrySendError::Closed(rebuild(inner)),
+                })
+            }
+        }
+    }
+
+    /// Weak sender like [`mpsc::Sender::downgrade`], upgrading to an instrumented sender.
+    pub fn downgrade(&self) -> InstrumentedWeakSender<T> {
+        InstrumentedWeakSender {
+            inner: self.inner.downgrade(),
+            guard: Arc::downgrade(&self.guard),
+        }
+    }
+}
+
+/// Capacity reserved with [`InstrumentedSender::reserve`] or
+/// [`InstrumentedSender::try_reserve`]. The message is counted when sent.
+pub struct InstrumentedPermit<'a, T> {
+    permit: mpsc::Permit<'a, T>,
+    shared: &'a Shared<T>,
+    blocked: Duration,
+}
+
+impl<T> InstrumentedPermit<'_, T> {
+    pub fn send(self, value: T) {
+        let meta = self.shared.inspect(&value);
+        self.permit.send(value);
+        self.shared.sent_after(meta, self.blocked);
+    }
+}
+
+impl<T> std::fmt::Debug for InstrumentedPermit<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedPermit")
+            .field("id", &self.shared.id)
+            .finish()
+    }
+}
+
+/// Capacity for several messages, reserved with [`InstrumentedSender::reserve_many`] or
+/// [`InstrumentedSender::try_reserve_many`], yielding an [`InstrumentedPermit`] per message.
+pub struct InstrumentedPermitIterator<'a, T> {
+    permits: mpsc::PermitIterator<'a, T>,
+    shared: &'a Shared<T>,
+    /// Time waited for the whole batch, reported by the first permit
+    blocked: Duration,
+}
+
+impl<'a, T> Iterator for InstrumentedPermitIterator<'a, T> {
+    type Item = InstrumentedPermit<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let permit = self.permits.next()?;
+        Some(InstrumentedPermit {
+            permit,
+            shared: self.shared,
+            blocked: mem::take(&mut self.blocked),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.permits.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for InstrumentedPermitIterator<'_, T> {}
+
+impl<T> std::fmt::Debug for InstrumentedPermitIterator<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedPermitIterator")
+            .field("id", &self.shared.id)
+            .field("remaining", &self.permits.len())
+            .finish()
+    }
+}
+
+/// Capacity reserved with [`InstrumentedSender::reserve_owned`] or
+/// [`InstrumentedSender::try_reserve_owned`], holding the sender. The message is counted when
+/// sent.
+pub struct InstrumentedOwnedPermit<T> {
+    permit: mpsc::OwnedPermit<T>,
+    guard: Arc<SenderGuard<T>>,
+    handle: SenderHandle<T>,
+    blocked: Duration,
+}
+
+impl<T> InstrumentedOwnedPermit<T> {
+    /// Sends the message and hands the sender back.
+    pub fn send(self, value: T) -> InstrumentedSender<T> {
+        let meta = self.guard.0.inspect(&value);
+        let inner = self.permit.send(value);
+        self.guard.0.sent_after(meta, self.blocked);
+        InstrumentedSender {
+            inner,
+            guard: self.guard,
+            handle: self.handle,
+        }
+    }
+
+    /// Gives up the reserved capacity without sending, handing the sender back.
+    pub fn release(self) -> InstrumentedSender<T> {
+        InstrumentedSender {
+            inner: self.permit.release(),
+            guard: self.guard,
+            handle: self.handle,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for InstrumentedOwnedPermit<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedOwnedPermit")
+            .field("id", &self.guard.0.id)
+            .finish()
+    }
+}
+
+impl<T> Clone for InstrumentedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            guard: Arc::clone(&self.guard),
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+impl<T> Deref for InstrumentedSender<T> {
+    type Target = mpsc::Sender<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::fmt::Debug for InstrumentedSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedSender")
+            .field("id", &self.guard.0.id)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Weak handle to an [`InstrumentedSender`], created with [`InstrumentedSender::downgrade`].
+///
+/// Like [`mpsc::WeakSender`], it doesn't keep the channel open. Senders it upgrades to are
+/// instrumented, so their messages are counted and they show up in `senders_alive`.
+pub struct InstrumentedWeakSender<T> {
+    inner: mpsc::WeakSender<T>,
+    guard: Weak<SenderGuard<T>>,
+}
+
+impl<T> InstrumentedWeakSender<T> {
+    /// Upgrades to a sender, unless all senders were dropped.
+    pub fn upgrade(&self) -> Option<InstrumentedSender<T>> {
+        // Holding the guard first, a channel whose last sender is dropped meanwhile is still
+        // reported as closed when the guard is released
+        let guard = self.guard.upgrade()?;
+        let inner = self.inner.upgrade()?;
+        let handle = SenderHandle::new(&guard.0);
+        Some(InstrumentedSender {
+            inner,
+            guard,
+            handle,
+        })
+    }
+}
+
+impl<T> Clone for InstrumentedWeakSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            guard: Weak::clone(&self.guard),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for InstrumentedWeakSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedWeakSender")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Instrumented bounded Tokio receiver.
+///
+/// Every receiving method of [`mpsc::Receiver`] is wrapped and counted. Read-only methods like
+/// `len` and `is_closed` are available through `Deref`.
+pub struct InstrumentedReceiver<T> {
+    inner: mpsc::Receiver<T>,
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> InstrumentedReceiver<T> {
+    pub async fn recv(&mut self) -> Option<T> {
+        let msg = self.inner.recv().await;
+        self.on_recv(msg)
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        match self.inner.try_recv() {
+            Ok(msg) => {
+                self.shared.received(&msg);
+                Ok(msg)
+            }
+            Err(TryRecvError::Disconnected) => {
+                self.shared.close();
+                Err(TryRecvError::Disconnected)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        let msg = self.inner.blocking_recv();
+        self.on_recv(msg)
+    }
+
+    pub async fn recv_many(&mut self, buffer: &mut Vec<T>, limit: usize) -> usize {
+        let start = buffer.len();
+        self.inner.recv_many(buffer, limit).await;
+        self.on_recv_many(&buffer[start..], limit)
+    }
+
+    pub fn blocking_recv_many(&mut self, buffer: &mut Vec<T>, limit: usize) -> usize {
+        let start = buffer.len();
+        self.inner.blocking_recv_many(buffer, limit);
+        self.on_recv_many(&buffer[start..], limit)
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.inner.poll_recv(cx).map(|msg| self.on_recv(msg))
+    }
+
+    pub fn poll_recv_many(
+        &mut self,
+        cx: &mut Context<'_>,
+        buffer: &mut Vec<T>,
+        limit: usize,
+    ) -> Poll<usize> {
+        let start = buffer.len();
+        self.inner
+            .poll_recv_many(cx, buffer, limit)
+            .map(|_| self.on_recv_many(&buffer[start..], limit))
+    }
+
+    /// Closes the receiving half, see [`mpsc::Receiver::close`]. Buffered messages can still
+    /// be received.
+    pub fn close(&mut self) {
+        self.inner.close();
+    }
+
+    fn on_recv(&self, msg: Option<T>) -> Option<T> {
+        match &msg {
+            Some(msg) => self.shared.received(msg),
+            None => self.shared.close(),
+        }
+        msg
+    }
+
+    /// Counts the messages appended by a `recv_many` variant, returning their number.
+    fn on_recv_many(&self, received: &[T], limit: usize) -> usize {
+        for msg in received {
+            self.shared.received(msg);
+        }
+        if received.is_empty() && limit > 0 {
+            self.shared.close();
+        }
+        received.len()
+    }
+}
+
+impl<T> Deref for InstrumentedReceiver<T> {
+    type Target = mpsc::Receiver<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> Drop for InstrumentedReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.handles.receiver_dropped();
+        self.shared.close();
+    }
+}
+
+impl<T> std::fmt::Debug for InstrumentedReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedReceiver")
+            .field("id", &self.shared.id)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Instrumented unbounded Tokio sender.
+///
+/// `send` is counted, other [`mpsc::UnboundedSender`] methods are available through `Deref`.
+pub struct InstrumentedUnboundedSender<T> {
+    inner: mpsc::UnboundedSender<T>,
+    guard: Arc<SenderGuard<T>>,
+    handle: SenderHandle<T>,
+}
+
+impl<T> InstrumentedUnboundedSender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let meta = self.guard.0.inspect(&value);
+        self.inner
+            .send(value)
+            .inspect_err(|_| self.guard.0.rejected(false))?;
+        self.guard.0.sent(meta, None);
+        Ok(())
+    }
+}
+
+impl<T> InstrumentedUnboundedSender<T> {
+    /// Weak sender like [`mpsc::UnboundedSender::downgrade`], upgrading to an instrumented
+    /// sender.
+    pub fn downgrade(&self) -> InstrumentedWeakUnboundedSender<T> {
+        InstrumentedWeakUnboundedSender {
+            inner: self.inner.downgrade(),
+            guard: Arc::downgrade(&self.guard),
+        }
+    }
+}
+
+impl<T> Clone for InstrumentedUnboundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            guard: Arc::clone(&self.guard),
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+impl<T> Deref for InstrumentedUnboundedSender<T> {
+    type Target = mpsc::UnboundedSender<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::fmt::Debug for InstrumentedUnboundedSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedUnboundedSender")
+            .field("id", &self.guard.0.id)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Weak handle to an [`InstrumentedUnboundedSender`], created with
+/// [`InstrumentedUnboundedSender::downgrade`]. See [`InstrumentedWeakSender`].
+pub struct InstrumentedWeakUnboundedSender<T> {
+    inner: mpsc::WeakUnboundedSender<T>,
+    guard: Weak<SenderGuard<T>>,
+}
+
+impl<T> InstrumentedWeakUnboundedSender<T> {
+    /// Upgrades to a sender, unless all senders were dropped.
+    pub fn upgrade(&self) -> Option<InstrumentedUnboundedSender<T>> {
+        let guard = self.guard.upgrade()?;
+        let inner = self.inner.upgrade()?;
+        let handle = SenderHandle::new(&guard.0);
+        Some(InstrumentedUnboundedSender {
+            inner,
+            guard,
+            handle,
+        })
+    }
+}
+
+impl<T> Clone for InstrumentedWeakUnboundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            guard: Weak::clone(&self.guard),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for InstrumentedWeakUnboundedSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedWeakUnboundedSender")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Instrumented unbounded Tokio receiver.
+///
+/// Every receiving method of [`mpsc::UnboundedReceiver`] is wrapped and counted. Read-only
+/// methods like `len` and `is_closed` are available through `Deref`.
+pub struct InstrumentedUnboundedReceiver<T> {
+    inner: mpsc::UnboundedReceiver<T>,
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> InstrumentedUnboundedReceiver<T> {
+    pub async fn recv(&mut self) -> Option<T> {
+        let msg = self.inner.recv().await;
+        self.on_recv(msg)
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        match self.inner.try_recv() {
+            Ok(msg) => {
+                self.shared.received(&msg);
+                Ok(msg)
+            }
+            Err(TryRecvError::Disconnected) => {
+                self.shared.close();
+                Err(TryRecvError::Disconnected)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        let msg = self.inner.blocking_recv();
+        self.on_recv(msg)
+    }
+
+    pub async fn recv_many(&mut self, buffer: &mut Vec<T>, limit: usize) -> usize {
+        let start = buffer.len();
+        self.inner.recv_many(buffer, limit).await;
+        self.on_recv_many(&buffer[start..], limit)
+    }
+
+    pub fn blocking_recv_many(&mut self, buffer: &mut Vec<T>, limit: usize) -> usize {
+        let start = buffer.len();
+        self.inner.blocking_recv_many(buffer, limit);
+        self.on_recv_many(&buffer[start..], limit)
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.inner.poll_recv(cx).map(|msg| self.on_recv(msg))
+    }
+
+    pub fn poll_recv_many(
+        &mut self,
+        cx: &mut Context<'_>,
+        buffer: &mut Vec<T>,
+        limit: usize,
+    ) -> Poll<usize> {
+        let start = buffer.len();
+        self.inner
+            .poll_recv_many(cx, buffer, limit)
+            .map(|_| self.on_recv_many(&buffer[start..], limit))
+    }
+
+    /// Closes the receiving half, see [`mpsc::UnboundedReceiver::close`]. Buffered messages
+    /// can still be received.
+    pub fn close(&mut self) {
+        self.inner.close();
+    }
+
+    fn on_recv(&self, msg: Option<T>) -> Option<T> {
+        match &msg {
+            Some(msg) => self.shared.received(msg),
+            None => self.shared.close(),
+        }
+        msg
+    }
+
+    /// Counts the messages appended by a `recv_many` variant, returning their number.
+    fn on_recv_many(&self, received: &[T], limit: usize) -> usize {
+        for msg in received {
+            self.shared.received(msg);
+        }
+        if received.is_empty() && limit > 0 {
+            self.shared.close();
+        }
+        received.len()
+    }
+}
+
+impl<T> Deref for InstrumentedUnboundedReceiver<T> {
+    type Target = mpsc::UnboundedReceiver<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> Drop for InstrumentedUnboundedReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.handles.receiver_dropped();
+        self.shared.close();
+    }
+}
+
+impl<T> std::fmt::Debug for InstrumentedUnboundedReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedUnboundedReceiver")
+            .field("id", &self.shared.id)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+fn wrap_channel_inline<T: 'static>(
+    (tx, rx): (mpsc::Sender<T>, mpsc::Receiver<T>),
+    source: &'static str,
+    options: ChannelOptions,
+    message_log: MessageLog<T>,
+    correlate: Option<CorrelationFn<T>>,
+) -> (InstrumentedSender<T>, InstrumentedReceiver<T>) {
+    let channel_type = ChannelType::Bounded(tx.max_capacity());
+    let handles = Handles::counted();
+    let shared = Shared::new(
+        source,
+        options,
+        channel_type,
+        handles,
+        message_log,
+        correlate,
+    );
+    let sender = InstrumentedSender {
+        inner: tx,
+        guard: Arc::new(SenderGuard(Arc::clone(&shared))),
+        handle: SenderHandle(Arc::clone(&shared)),
+    };
+    (sender, InstrumentedReceiver { inner: rx, shared })
+}
+
+fn wrap_unbounded_inline<T: 'static>(
+    (tx, rx): (mpsc::UnboundedSender<T>, mpsc::UnboundedReceiver<T>),
+    source: &'static str,
+    options: ChannelOptions,
+    message_log: MessageLog<T>,
+    correlate: Option<CorrelationFn<T>>,
+) -> (
+    InstrumentedUnboundedSender<T>,
+    InstrumentedUnboundedReceiver<T>,
+) {
+    let handles = Handles::counted();
+    let shared = Shared::new(
+        source,
+        options,
+        ChannelType::Unbounded,
+        handles,
+        message_log,
+        correlate,
+    );
+    let sender = InstrumentedUnboundedSender {
+        inner: tx,
+        guard: Arc::new(SenderGuard(Arc::clone(&shared))),
+        handle: SenderHandle(Arc::clone(&shared)),
+    };
+    (sender, InstrumentedUnboundedReceiver { inner: rx, shared })
+}
+
+impl<T: 'static> InstrumentInline for (mpsc::Sender<T>, mpsc::Receiver<T>) {
+    type Output = (InstrumentedSender<T>, InstrumentedReceiver<T>);
+    type Message = T;
+    fn instrument_inline(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<T>>,
+    ) -> Self::Output {
+        wrap_channel_inline(self, source, options, MessageLog::Fn(|_| None), correlate)
+    }
+}
+
+impl<T: 'static> InstrumentInline for (mpsc::UnboundedSender<T>, mpsc::UnboundedReceiver<T>) {
+    type Output = (
+        InstrumentedUnboundedSender<T>,
+        InstrumentedUnboundedReceiver<T>,
+    );
+    type Message = T;
+    fn instrument_inline(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<T>>,
+    ) -> Self::Output {
+        wrap_unbounded_inline(self, source, options, MessageLog::Fn(|_| None), correlate)
+    }
+}
+
+impl<T: std::fmt::Debug + 'static> InstrumentInlineLog for (mpsc::Sender<T>, mpsc::Receiver<T>) {
+    type Output = (InstrumentedSender<T>, InstrumentedReceiver<T>);
+    type Message = T;
+    fn instrument_inline_log(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<T>>,
+    ) -> Self::Output {
+        wrap_channel_inline(
+            self,
+            source,
+            options,
+            MessageLog::Fn(|msg| Some(format!("{:?}", msg))),
+            correlate,
+        )
+    }
+}
+
+impl<T: std::fmt::Debug + 'static> InstrumentInlineLog
+    for (mpsc::UnboundedSender<T>, mpsc::UnboundedReceiver<T>)
+{
+    type Output = (
+        InstrumentedUnboundedSender<T>,
+        InstrumentedUnboundedReceiver<T>,
+    );
+    type Message = T;
+    fn instrument_inline_log(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<T>>,
+    ) -> Self::Output {
+        wrap_unbounded_inline(
+            self,
+            source,
+            options,
+            MessageLog::Fn(|msg| Some(format!("{:?}", msg))),
+            correlate,
+        )
+    }
+}
+
+impl<T: 'static> InstrumentInlineLogWith for (mpsc::Sender<T>, mpsc::Receiver<T>) {
+    type Output = (InstrumentedSender<T>, InstrumentedReceiver<T>);
+    type Message = T;
+    fn instrument_inline_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        let message_log = MessageLog::With(Box::new(log_with));
+        wrap_channel_inline(self, source, options, message_log, correlate)
+    }
+}
+
+impl<T: 'static> InstrumentInlineLogWith
+    for (mpsc::UnboundedSender<T>, mpsc::UnboundedReceiver<T>)
+{
+    type Output = (
+        InstrumentedUnboundedSender<T>,
+        InstrumentedUnboundedReceiver<T>,
+    );
+    type Message = T;
+    fn instrument_inline_log_with<F>(
+        self,
+        source: &'static str,
+        options: ChannelOptions,
+        correlate: Option<CorrelationFn<T>>,
+        log_with: F,
+    ) -> Self::Output
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        let message_log = MessageLog::With(Box::new(log_with));
+        wrap_unbounded_inline(self, source, options, message_log, correlate)
+    }
+}
@@ -303,4 +303,482 @@ pub mod tests {
             stdout
         );
     }
+
+    #[test]
+    fn test_history_file() {
+        let path = std::env::temp_dir().join(format!(
+            "channels-console-history-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "basic_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6781")
+            .env("CHANNELS_CONSOLE_HISTORY_PATH", &path)
+            .env("CHANNELS_CONSOLE_HISTORY_INTERVAL_MS", "100")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let records =
+            channels_console::history::read_history(&path).expect("Failed to read history file");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(
+            !records.is_empty(),
+            "Expected history records to be written"
+        );
+        let last = records.last().unwrap();
+        assert_eq!(last.schema_version, channels_console::SCHEMA_VERSION);
+        assert!(
+            last.channels
+                .iter()
+                .any(|channel| channel.label == "bounded-channel"),
+            "Expected bounded-channel in history, got {:?}",
+            last.channels
+        );
+    }
+
+    #[test]
+    fn test_topology_endpoint() {
+        use std::{thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "pipeline_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6782")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut topology = None;
+        let mut last_error = None;
+
+        for _attempt in 0..10 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6782/topology").call() {
+                Ok(mut response) => {
+                    let parsed: channels_console::topology::TopologyJson = response
+                        .body_mut()
+                        .read_json()
+                        .expect("Failed to parse topology JSON");
+                    if parsed.nodes.len() == 3 {
+                        topology = Some(parsed);
+                        break;
+                    }
+                }
+                Err(e) => {
+                    last_error = Some(format!("Request error: {}", e));
+                }
+            }
+        }
+
+        let Some(topology) = topology else {
+            let _ = child.kill();
+            panic!("Failed to fetch topology: {:?}", last_error);
+        };
+
+        let id_of = |label: &str| {
+            topology
+                .nodes
+                .iter()
+                .find(|node| node.label == label)
+                .map(|node| node.id)
+                .unwrap_or_else(|| panic!("Missing node {label} in {:?}", topology.nodes))
+        };
+        let expected_edges = [
+            ("raw-lines", "parsed-numbers"),
+            ("parsed-numbers", "totals"),
+        ];
+        assert_eq!(topology.edges.len(), expected_edges.len());
+        for (from, to) in expected_edges {
+            assert!(
+                topology
+                    .edges
+                    .iter()
+                    .any(|edge| edge.from == id_of(from) && edge.to == id_of(to)),
+                "Expected edge {from} -> {to} in {:?}",
+                topology.edges
+            );
+        }
+
+        let dot = ureq::get("http://127.0.0.1:6782/topology?format=dot")
+            .call()
+            .expect("Failed to call /topology?format=dot")
+            .body_mut()
+            .read_to_string()
+            .expect("Failed to read response body");
+        assert!(dot.starts_with("digraph channels {"), "Got:\n{dot}");
+        assert!(dot.contains("raw-lines"), "Got:\n{dot}");
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_events_endpoint() {
+        use std::io::{BufRead, BufReader};
+        use std::{thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "pipeline_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6783")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut response = None;
+        let mut last_error = None;
+        for _attempt in 0..10 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6783/events?interval_ms=100").call() {
+                Ok(r) => {
+                    response = Some(r);
+                    break;
+                }
+                Err(e) => {
+                    last_error = Some(format!("Request error: {}", e));
+                }
+            }
+        }
+
+        let Some(response) = response else {
+            let _ = child.kill();
+            panic!("Failed to subscribe to /events: {:?}", last_error);
+        };
+        assert_eq!(
+            response.headers().get("Content-Type").unwrap(),
+            "text/event-stream"
+        );
+
+        // Collect (event, data) pairs until both a snapshot and an update arrived
+        let mut events: Vec<(String, String)> = Vec::new();
+        let mut event = None;
+        let mut lines = BufReader::new(response.into_body().into_reader()).lines();
+        while !events.iter().any(|(name, _)| name == "update") {
+            let line = lines
+                .next()
+                .expect("Event stream ended")
+                .expect("Failed to read event stream");
+            if let Some(name) = line.strip_prefix("event: ") {
+                event = Some(name.to_string());
+            } else if let Some(data) = line.strip_prefix("data: ") {
+                events.push((event.take().unwrap(), data.to_string()));
+            }
+        }
+
+        let (name, data) = &events[0];
+        assert_eq!(name, "snapshot");
+        let snapshot: channels_console::CombinedJson =
+            serde_json::from_str(data).expect("Failed to parse snapshot");
+        assert_eq!(snapshot.schema_version, channels_console::SCHEMA_VERSION);
+        assert!(!snapshot.channels.is_empty());
+
+        // Updates only carry channels that changed since the previous push
+        let (_, data) = events.last().unwrap();
+        let update: channels_console::CombinedJson =
+            serde_json::from_str(data).expect("Failed to parse update");
+        assert!(!update.channels.is_empty());
+        assert!(update.channels.len() <= snapshot.channels.len());
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_ws_endpoint() {
+        use channels_console::WsMessage;
+        use std::{thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "pipeline_tokio",
+                "--features",
+                "channels-console,channels-console/ws",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6784")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut socket = None;
+        let mut last_error = None;
+        // The example is built with an extra feature, so the first run may take longer
+        for _attempt in 0..40 {
+            sleep(Duration::from_millis(500));
+
+            match tungstenite::connect("ws://127.0.0.1:6784/ws?interval_ms=100") {
+                Ok((s, _)) => {
+                    socket = Some(s);
+                    break;
+                }
+                Err(e) => {
+                    last_error = Some(format!("Connection error: {}", e));
+                }
+            }
+        }
+
+        let Some(mut socket) = socket else {
+            let _ = child.kill();
+            panic!("Failed to connect to /ws: {:?}", last_error);
+        };
+
+        let mut messages = Vec::new();
+        while !messages
+            .iter()
+            .any(|message| matches!(message, WsMessage::Update(_)))
+        {
+            let message = socket.read().expect("Failed to read WebSocket message");
+            if let tungstenite::Message::Text(text) = message {
+                messages.push(serde_json::from_str::<WsMessage>(&text).expect("Invalid WsMessage"));
+            }
+        }
+
+        let WsMessage::Snapshot(snapshot) = &messages[0] else {
+            panic!("Expected a snapshot first, got {:?}", messages[0]);
+        };
+        assert!(!snapshot.channels.is_empty());
+
+        // New log entries are pushed oldest first, without repeating already sent ones
+        let mut last_index = std::collections::HashMap::new();
+        for message in &messages {
+            if let WsMessage::ChannelLogs(logs) = message {
+                for entry in &logs.sent_logs {
+                    let last = last_index.entry(logs.id.clone()).or_insert(0);
+                    assert!(entry.index > *last, "Repeated log entry {:?}", entry);
+                    *last = entry.index;
+                }
+            }
+        }
+        assert!(!last_index.is_empty(), "No log entries in {:?}", messages);
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_config_file() {
+        let dir = std::env::temp_dir();
+        let history_path = dir.join(format!(
+            "channels-console-config-history-{}.jsonl",
+            std::process::id()
+        ));
+        let config_path = dir.join(format!(
+            "channels-console-config-{}.toml",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&history_path);
+        std::fs::write(
+            &config_path,
+            format!(
+                r#"
+[metrics]
+port = 6783
+
+[logs]
+redact = ["^2$"]
+
+[history]
+path = "{}"
+interval_ms = 100
+logs = true
+"#,
+                history_path.display()
+            ),
+        )
+        .expect("Failed to write config file");
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "basic_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_CONFIG", &config_path)
+            .output()
+            .expect("Failed to execute command");
+        let _ = std::fs::remove_file(&config_path);
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+        assert!(
+            stdout.contains("http://127.0.0.1:6783"),
+            "Expected metrics port from config file.\nOutput:\n{stdout}"
+        );
+
+        let records = channels_console::history::read_history(&history_path)
+            .expect("Failed to read history file");
+        let _ = std::fs::remove_file(&history_path);
+
+        let messages: Vec<String> = records
+            .iter()
+            .flat_map(|record| &record.logs)
+            .flat_map(|logs| &logs.sent_logs)
+            .filter_map(|entry| entry.message.clone())
+            .collect();
+        assert!(
+            messages.iter().any(|m| m == "[REDACTED]"),
+            "Expected redacted log message, got {messages:?}"
+        );
+        assert!(
+            !messages.iter().any(|m| m == "2"),
+            "Expected message to be redacted, got {messages:?}"
+        );
+    }
+
+    #[test]
+    fn test_token_auth() {
+        use std::{thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "pipeline_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6785")
+            .env("CHANNELS_CONSOLE_TOKEN", "s3cret")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let url = "http://127.0.0.1:6785/channels";
+        let mut unauthorized = None;
+        for _attempt in 0..10 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get(url).call() {
+                Err(ureq::Error::StatusCode(code)) => {
+                    unauthorized = Some(code);
+                    break;
+                }
+                Ok(_) => {
+                    let _ = child.kill();
+                    panic!("Request without a token was accepted");
+                }
+                Err(_) => {}
+            }
+        }
+        assert_eq!(unauthorized, Some(401));
+
+        let wrong = ureq::get(url)
+            .header("Authorization", "Bearer wrong")
+            .call();
+        assert!(
+            matches!(wrong, Err(ureq::Error::StatusCode(401))),
+            "Expected 401 for a wrong token, got {wrong:?}"
+        );
+
+        let channels: channels_console::ChannelsJson = ureq::get(url)
+            .header("Authorization", "Bearer s3cret")
+            .call()
+            .expect("Failed to call /channels with a token")
+            .body_mut()
+            .read_json()
+            .expect("Failed to parse channels JSON");
+        assert_eq!(channels.schema_version, channels_console::SCHEMA_VERSION);
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_server() {
+        use std::io::{Read, Write};
+        use std::os::unix::net::UnixStream;
+        use std::{thread::sleep, time::Duration};
+
+        let socket =
+            std::env::temp_dir().join(format!("channels-console-test-{}.sock", std::process::id()));
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "pipeline_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_SOCKET", &socket)
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut response = None;
+        let mut last_error = None;
+        for _attempt in 0..10 {
+            sleep(Duration::from_millis(500));
+
+            let result = UnixStream::connect(&socket).and_then(|mut stream| {
+                stream.write_all(b"GET /channels HTTP/1.0\r\nHost: localhost\r\n\r\n")?;
+                let mut body = String::new();
+                stream.read_to_string(&mut body)?;
+                Ok(body)
+            });
+            match result {
+                Ok(body) => {
+                    response = Some(body);
+                    break;
+                }
+                Err(e) => last_error = Some(format!("Connection error: {}", e)),
+            }
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+        let _ = std::fs::remove_file(&socket);
+
+        let response =
+            response.unwrap_or_else(|| panic!("Failed to connect to socket: {:?}", last_error));
+        let (head, body) = response.split_once("\r\n\r\n").expect("Malformed response");
+        assert!(head.starts_with("HTTP/1.0 200"), "Got:\n{head}");
+        let channels: channels_console::ChannelsJson =
+            serde_json::from_str(body).expect("Failed to parse channels JSON");
+        assert_eq!(channels.schema_version, channels_console::SCHEMA_VERSION);
+    }
 }
@@ -0,0 +1,151 @@
+use channels_console::alerts::{self, AlertRule, Condition};
+use channels_console::testing::stats_for;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use super::{common, setup};
+
+#[test]
+fn test_parse_conditions() {
+    assert_eq!("full".parse(), Ok(Condition::Full));
+    assert_eq!(
+        "closed unexpectedly".parse(),
+        Ok(Condition::ClosedUnexpectedly)
+    );
+    assert_eq!(
+        "queued > 80%".parse(),
+        Ok(Condition::QueuedPercentAbove(80.0))
+    );
+    assert!("closed suddenly".parse::<Condition>().is_err());
+    assert_eq!(
+        Condition::ClosedUnexpectedly.to_string(),
+        "closed unexpectedly"
+    );
+}
+
+#[test]
+fn test_on_alert_callback() {
+    setup();
+
+    let fired = Arc::new(Mutex::new(Vec::new()));
+    let fired_clone = Arc::clone(&fired);
+    channels_console::on_alert(move |alert| {
+        // Callbacks can read the stats of the alerting channel
+        let stats = stats_for(&alert.channel_label);
+        assert!(stats.is_some_and(|stats| stats.id == alert.channel_id));
+        // Rules of the other tests fire the callback too
+        if alert.rule == "jobs-closed" {
+            fired_clone.lock().unwrap().push(alert.clone());
+        }
+    });
+    alerts::add_rule(
+        AlertRule::builder("jobs-closed")
+            .channel("alerts/jobs")
+            .closed()
+            .build(),
+    );
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "alerts/jobs");
+    drop((tx, rx));
+
+    common::wait_until("the alert fires", || !fired.lock().unwrap().is_empty());
+
+    let fired = fired.lock().unwrap();
+    assert_eq!(fired.len(), 1, "Expected a single alert, got {:?}", fired);
+    assert_eq!(fired[0].rule, "jobs-closed");
+    assert_eq!(fired[0].channel_label, "alerts/jobs");
+    assert_eq!(fired[0].condition, "closed");
+
+    // The condition still holds, so the alert stays active
+    let is_active = || {
+        alerts::active_alerts()
+            .iter()
+            .any(|alert| alert.rule == "jobs-closed" && alert.channel_label == "alerts/jobs")
+    };
+    common::wait_until("the alert is active", is_active);
+}
+
+#[test]
+fn test_webhook_delivery_to_failing_endpoint() {
+    setup();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+    let (requests_tx, requests_rx) = mpsc::channel::<(String, String)>();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        reader
+            .get_mut()
+            .write_all(
+                b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )
+            .unwrap();
+        let _ = requests_tx.send((request_line, String::from_utf8(body).unwrap()));
+    });
+
+    alerts::add_rule(
+        AlertRule::builder("webhook-closed")
+            .channel("alerts/webhook")
+            .closed()
+            .webhook(url)
+            .build(),
+    );
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "alerts/webhook");
+    drop((tx, rx));
+
+    let (request_line, body) = requests_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(request_line.starts_with("POST /alerts "));
+    let alert: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(alert["rule"], "webhook-closed");
+    assert_eq!(alert["channel_label"], "alerts/webhook");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_runs_without_shell() {
+    setup();
+
+    let dir = std::env::temp_dir().join(format!("channels-console-exec-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // A shell would expand the variable to the rule name
+    let marker = dir.join("$CHANNELS_CONSOLE_ALERT_RULE");
+
+    alerts::add_rule(
+        AlertRule::builder("exec-closed")
+            .channel("alerts/exec")
+            .closed()
+            .exec(format!("touch {}", marker.display()))
+            .build(),
+    );
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "alerts/exec");
+    drop((tx, rx));
+
+    common::wait_until("the command runs", || marker.exists());
+    let expanded = dir.join("exec-closed").exists();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(!expanded);
+}
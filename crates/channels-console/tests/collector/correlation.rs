@@ -0,0 +1,51 @@
+use channels_console::correlation::{journey, Journey, Traced};
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+fn wait_for_journey(correlation_id: u64, hops: usize) -> Journey {
+    common::wait_for("a completed journey", || {
+        channels_console::flush();
+        journey(correlation_id)
+            .filter(|journey| journey.hops.len() == hops && journey.total_ns.is_some())
+    })
+}
+
+#[test]
+fn test_journey_across_channels() {
+    setup();
+
+    let (raw_tx, raw_rx) = mpsc::channel::<Traced<String>>();
+    let (raw_tx, raw_rx) =
+        channels_console::channel!((raw_tx, raw_rx), label = "raw", correlate = true);
+    let (parsed_tx, parsed_rx) = mpsc::channel::<Traced<u32>>();
+    let (parsed_tx, parsed_rx) = channels_console::channel!(
+        (parsed_tx, parsed_rx),
+        label = "parsed",
+        correlate = true,
+        log = true
+    );
+
+    let first = Traced::new("1".to_string());
+    let second = Traced::with_id(42, "2".to_string());
+    let first_id = first.correlation_id();
+    raw_tx.send(first).unwrap();
+    raw_tx.send(second).unwrap();
+
+    for _ in 0..2 {
+        let msg = raw_rx.recv().unwrap().map(|s| s.parse::<u32>().unwrap());
+        parsed_tx.send(msg).unwrap();
+    }
+    let received: Vec<_> = (0..2).map(|_| parsed_rx.recv().unwrap()).collect();
+    assert_eq!(received[1].correlation_id(), 42);
+    assert_eq!(*received[1], 2);
+
+    let journey = wait_for_journey(42, 2);
+    let labels: Vec<&str> = journey.hops.iter().map(|hop| hop.label.as_str()).collect();
+    assert_eq!(labels, vec!["raw", "parsed"]);
+    assert!(journey.hops.iter().all(|hop| hop.latency_ns.is_some()));
+    assert!(journey.hops[0].sent_at <= journey.hops[1].sent_at);
+
+    assert_eq!(wait_for_journey(first_id, 2).correlation_id, first_id);
+    assert!(channels_console::correlation::journey(u64::MAX).is_none());
+}
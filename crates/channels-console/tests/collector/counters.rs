@@ -0,0 +1,42 @@
+use channels_console::testing::{logs_for, stats_for};
+
+use super::setup;
+
+#[test]
+fn test_counters_folded_on_read() {
+    // Not synchronous, so counts are only folded by the collector
+    setup();
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+    let (tx, mut rx) = channels_console::channel!(
+        (tx, rx),
+        label = "counted",
+        mode = inline,
+        log = true,
+        log_limit = 10
+    );
+    for i in 0..10_000 {
+        tx.send(i).unwrap();
+    }
+    for _ in 0..4_000 {
+        rx.try_recv().unwrap();
+    }
+
+    let stats = stats_for("counted").unwrap();
+    assert_eq!(stats.sent_count, 10_000);
+    assert_eq!(stats.received_count, 4_000);
+    assert_eq!(stats.queued, 6_000);
+    assert_eq!(stats.latency.unwrap().count, 4_000);
+
+    let logs = logs_for("counted").unwrap();
+    assert_eq!(logs.sent_logs.len(), 10);
+    assert_eq!(logs.sent_logs[0].index, 10_000);
+    assert_eq!(logs.sent_logs[0].message.as_deref(), Some("9999"));
+    assert_eq!(logs.received_logs[0].index, 4_000);
+
+    // Messages queued beyond the kept send timestamps are still paired with a latency
+    while rx.try_recv().is_ok() {}
+    let stats = stats_for("counted").unwrap();
+    assert_eq!(stats.queued, 0);
+    assert_eq!(stats.latency.unwrap().count, 10_000);
+}
@@ -0,0 +1,66 @@
+use channels_console::testing::{latency_histogram_for, stats_for};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::{common, setup};
+
+#[test]
+fn test_latency_percentiles() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "latency");
+
+    assert!(stats_for("latency").unwrap().latency.is_none());
+
+    for i in 0..100 {
+        tx.send(i).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(20));
+    for _ in 0..100 {
+        rx.recv().unwrap();
+    }
+
+    let latency = common::wait_for("latency percentiles", || {
+        stats_for("latency")
+            .unwrap()
+            .latency
+            .filter(|latency| latency.count == 100)
+    });
+    assert!(latency.p50 <= latency.p95);
+    assert!(latency.p95 <= latency.p99);
+    assert!(latency.p99 <= latency.max);
+    assert!(latency.max > 0);
+}
+
+#[test]
+fn test_latency_histogram() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "latency-histogram");
+
+    let histogram = latency_histogram_for("latency-histogram").unwrap();
+    assert_eq!(histogram.count, 0);
+    assert!(histogram.buckets.is_empty());
+
+    for i in 0..50 {
+        tx.send(i).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(20));
+    for _ in 0..50 {
+        rx.recv().unwrap();
+    }
+
+    let histogram = common::wait_for("the latency histogram", || {
+        latency_histogram_for("latency-histogram").filter(|histogram| histogram.count == 50)
+    });
+    assert_eq!(histogram.buckets.iter().map(|b| b.count).sum::<u64>(), 50);
+    assert!(histogram.buckets.first().unwrap().count > 0);
+    assert!(histogram.buckets.last().unwrap().count > 0);
+    // Buckets are contiguous powers of two
+    for pair in histogram.buckets.windows(2) {
+        assert_eq!(pair[0].upper_ns + 1, pair[1].lower_ns);
+        assert_eq!(pair[1].upper_ns + 1, pair[1].lower_ns * 2);
+    }
+}
@@ -0,0 +1,20 @@
+//! Stats folded and sampled by the collector thread, so events aren't applied in place.
+#![cfg(not(feature = "noop"))]
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod alerts;
+mod correlation;
+#[cfg(feature = "tokio")]
+mod counters;
+mod latency;
+mod samples;
+
+fn setup() {
+    // Don't bind the default metrics port used by the example processes
+    let _ = channels_console::Config::builder()
+        .disable_server()
+        .channel_samples(2)
+        .install();
+}
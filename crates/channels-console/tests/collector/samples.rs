@@ -0,0 +1,37 @@
+use channels_console::testing::history_for;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::{common, setup};
+
+#[test]
+fn test_channel_history() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "sampled");
+    for i in 0..3 {
+        tx.send(i).unwrap();
+        rx.recv().unwrap();
+    }
+
+    // Samples are taken every second
+    let history = common::wait_for("two samples", || {
+        history_for("sampled", None).filter(|history| history.samples.len() == 2)
+    });
+    assert_eq!(history.interval_ms, 1000);
+
+    // Once a third sample is taken, the oldest one is dropped
+    let previous = history.samples[1];
+    let history = common::wait_for("a third sample", || {
+        history_for("sampled", None).filter(|history| history.samples[1] != previous)
+    });
+    assert_eq!(history.samples, vec![previous, history.samples[1]]);
+    let newest = history.samples[1];
+    assert_eq!(newest.sent_count, 3);
+    assert_eq!(newest.received_count, 3);
+    assert_eq!(newest.queued, 0);
+
+    let recent = history_for("sampled", Some(Duration::from_millis(500))).unwrap();
+    assert_eq!(recent.samples, vec![newest]);
+}
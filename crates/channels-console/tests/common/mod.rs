@@ -0,0 +1,42 @@
+//! Helpers shared by the integration test binaries.
+#![allow(dead_code)]
+
+use std::time::{Duration, Instant};
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+const WAIT_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Polls `condition` until it holds, panicking with `what` after 5 seconds.
+#[track_caller]
+pub fn wait_until(what: &str, mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + WAIT_TIMEOUT;
+    while !condition() {
+        assert!(
+            Instant::now() < deadline,
+            "Timed out waiting until {}",
+            what
+        );
+        std::thread::sleep(WAIT_INTERVAL);
+    }
+}
+
+/// Polls `poll` until it returns `Some`, panicking with `what` after 5 seconds.
+#[track_caller]
+pub fn wait_for<T>(what: &str, mut poll: impl FnMut() -> Option<T>) -> T {
+    let deadline = Instant::now() + WAIT_TIMEOUT;
+    loop {
+        if let Some(value) = poll() {
+            return value;
+        }
+        assert!(Instant::now() < deadline, "Timed out waiting for {}", what);
+        std::thread::sleep(WAIT_INTERVAL);
+    }
+}
+
+/// Base URL of the metrics server, once it's listening. The server starts with the first
+/// instrumented channel or stream.
+#[track_caller]
+pub fn server_url() -> String {
+    let addr = channels_console::testing::metrics_addr().expect("Metrics server didn't start");
+    format!("http://{}", addr)
+}
@@ -0,0 +1,145 @@
+#![cfg(not(feature = "noop"))]
+
+mod common;
+
+#[cfg(all(test, feature = "tokio"))]
+pub mod tests {
+    use super::common;
+    use channels_console::control::{collection_mode, CollectionMode, ControlJson};
+    use channels_console::summary::SummaryJson;
+    use channels_console::testing::{logs_for, stats_for};
+    use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
+    /// Pausing and resetting apply to every channel, so the tests take turns.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    /// Installs the config on the first call, returning the server's base URL and the turn.
+    fn setup() -> (&'static str, MutexGuard<'static, ()>) {
+        static URL: OnceLock<String> = OnceLock::new();
+        let url = URL.get_or_init(|| {
+            let _ = channels_console::Config::builder()
+                .metrics_port(0)
+                .synchronous()
+                .install();
+
+            // The server starts with the first instrumented channel
+            let (tx, rx) = std::sync::mpsc::channel::<u32>();
+            let _ = channels_console::channel!((tx, rx), label = "control-server");
+            common::server_url()
+        });
+        let turn = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+        (url, turn)
+    }
+
+    fn post(url: &str, path: &str) -> ControlJson {
+        ureq::post(format!("{}{}", url, path))
+            .send_empty()
+            .unwrap()
+            .body_mut()
+            .read_json()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let (url, _turn) = setup();
+
+        // Inline channels count messages in the sending and receiving code, before they return
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+        let (tx, mut rx) =
+            channels_console::channel!((tx, rx), label = "control", log = true, mode = inline);
+        tx.send(1).unwrap();
+        rx.try_recv().unwrap();
+
+        // Paused channels keep counting, but drop per-message details
+        channels_console::pause();
+        assert_eq!(collection_mode(), CollectionMode::Paused);
+        for i in 2..=3 {
+            tx.send(i).unwrap();
+            rx.try_recv().unwrap();
+        }
+        let stats = stats_for("control").unwrap();
+        assert_eq!(stats.sent_count, 3);
+        assert_eq!(stats.received_count, 3);
+        assert_eq!(stats.latency.unwrap().count, 1);
+        let logs = logs_for("control").unwrap();
+        assert_eq!(logs.sent_logs.len(), 1);
+        assert_eq!(logs.received_logs.len(), 1);
+
+        channels_console::resume();
+        assert_eq!(collection_mode(), CollectionMode::Full);
+        tx.send(4).unwrap();
+        rx.try_recv().unwrap();
+        let stats = stats_for("control").unwrap();
+        assert_eq!(stats.sent_count, 4);
+        assert_eq!(stats.received_count, 4);
+        assert_eq!(stats.latency.unwrap().count, 2);
+        let logs = logs_for("control").unwrap();
+        assert_eq!(logs.sent_logs[0].message.as_deref(), Some("4"));
+        assert_eq!(logs.sent_logs[0].index, 4);
+        assert_eq!(logs.received_logs.len(), 2);
+
+        let error = ureq::get(format!("{}/control/pause", url))
+            .call()
+            .unwrap_err();
+        assert!(matches!(error, ureq::Error::StatusCode(405)));
+        assert_eq!(collection_mode(), CollectionMode::Full);
+
+        let control = post(url, "/control/pause");
+        assert_eq!(control.collection_mode, CollectionMode::Paused);
+        let summary: SummaryJson = ureq::get(format!("{}/summary", url))
+            .call()
+            .unwrap()
+            .body_mut()
+            .read_json()
+            .unwrap();
+        assert_eq!(summary.collection_mode, CollectionMode::Paused);
+
+        let control = post(url, "/control/resume");
+        assert_eq!(control.collection_mode, CollectionMode::Full);
+        assert_eq!(collection_mode(), CollectionMode::Full);
+    }
+
+    #[test]
+    fn test_reset() {
+        let (url, _turn) = setup();
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+        let (tx, mut rx) =
+            channels_console::channel!((tx, rx), label = "reset", log = true, mode = inline);
+        for i in 0..3 {
+            tx.send(i).unwrap();
+        }
+        rx.try_recv().unwrap();
+        assert_eq!(stats_for("reset").unwrap().sent_count, 3);
+
+        // Queued messages count as sent after the reset
+        channels_console::reset();
+        let stats = stats_for("reset").unwrap();
+        assert_eq!(stats.sent_count, 2);
+        assert_eq!(stats.received_count, 0);
+        assert_eq!(stats.queued, 2);
+        assert!(stats.latency.is_none());
+        let logs = logs_for("reset").unwrap();
+        assert!(logs.sent_logs.is_empty());
+        assert!(logs.received_logs.is_empty());
+
+        rx.try_recv().unwrap();
+        let stats = stats_for("reset").unwrap();
+        assert_eq!(stats.received_count, 1);
+        assert_eq!(stats.queued, 1);
+        assert_eq!(stats.latency.unwrap().count, 1);
+
+        let error = ureq::get(format!("{}/control/reset", url))
+            .call()
+            .unwrap_err();
+        assert!(matches!(error, ureq::Error::StatusCode(405)));
+        assert_eq!(stats_for("reset").unwrap().received_count, 1);
+
+        post(url, "/control/reset");
+        let stats = stats_for("reset").unwrap();
+        assert_eq!(stats.sent_count, 1);
+        assert_eq!(stats.received_count, 0);
+        assert_eq!(stats.queued, 1);
+    }
+}
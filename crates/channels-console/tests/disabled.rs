@@ -0,0 +1,66 @@
+#![cfg(not(feature = "noop"))]
+
+#[cfg(test)]
+pub mod tests {
+    use channels_console::testing::stats_for;
+    use std::sync::mpsc;
+
+    fn setup() {
+        let _ = channels_console::Config::builder()
+            .disable_server()
+            .disable()
+            .install();
+    }
+
+    #[test]
+    fn test_disabled_channels_are_pass_through() {
+        setup();
+        assert!(channels_console::Config::current().is_disabled());
+
+        let (tx, rx) = mpsc::sync_channel::<u32>(2);
+        let (tx, rx) =
+            channels_console::channel!((tx, rx), label = "disabled-std", capacity = 2, log = true);
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        // A proxied channel would accept a third message into the outer buffer
+        assert!(tx.try_send(3).is_err());
+        assert_eq!(rx.recv().unwrap(), 1);
+        assert_eq!(rx.recv().unwrap(), 2);
+
+        assert!(stats_for("disabled-std").is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_disabled_inline_channels_report_nothing() {
+        setup();
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<u32>(4);
+        let (tx, mut rx) =
+            channels_console::channel!((tx, rx), label = "disabled-inline", mode = inline);
+        tx.send(1).await.unwrap();
+        assert_eq!(rx.recv().await, Some(1));
+        drop(tx);
+        assert_eq!(rx.recv().await, None);
+
+        assert!(stats_for("disabled-inline").is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_disabled_primitives_report_nothing() {
+        setup();
+
+        let semaphore =
+            channels_console::semaphore!(tokio::sync::Semaphore::new(1), label = "disabled-sem");
+        drop(semaphore.acquire().await.unwrap());
+        let notify =
+            channels_console::notify!(tokio::sync::Notify::new(), label = "disabled-notify");
+        notify.notify_one();
+        notify.notified().await;
+
+        assert!(channels_console::primitives::primitives()
+            .iter()
+            .all(|p| !p.label.starts_with("disabled-")));
+    }
+}
@@ -0,0 +1,216 @@
+#![cfg(not(feature = "noop"))]
+
+mod common;
+
+#[cfg(all(test, feature = "crossbeam"))]
+pub mod tests {
+    use super::common;
+    use channels_console::testing::stats_for;
+    use channels_console::ChannelState;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+    use std::time::Duration;
+
+    const CHANNELS: usize = 200;
+
+    /// Tests count the threads of the process and keep forwarders busy, so they run one at a time.
+    fn setup() -> MutexGuard<'static, ()> {
+        static SERIAL: Mutex<()> = Mutex::new(());
+        let _ = channels_console::Config::builder()
+            .disable_server()
+            .forwarder_workers(2)
+            .install();
+        SERIAL.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn thread_count() -> usize {
+        std::fs::read_dir("/proc/self/task").unwrap().count()
+    }
+
+    fn label(prefix: &str, i: usize) -> String {
+        match i {
+            0 => prefix.to_string(),
+            _ => format!("{}-{}", prefix, i + 1),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_crossbeam_channels_share_threads() {
+        let _turn = setup();
+
+        // Starts the collector and the forwarder threads
+        let (tx, rx) = crossbeam_channel::unbounded::<usize>();
+        let _warmup = channels_console::channel!((tx, rx), label = "threads-warmup");
+        let threads = thread_count();
+
+        let channels: Vec<_> = (0..CHANNELS)
+            .map(|_| {
+                let (tx, rx) = crossbeam_channel::bounded::<usize>(1);
+                channels_console::channel!((tx, rx), label = "threads-crossbeam")
+            })
+            .collect();
+        for (i, (tx, rx)) in channels.iter().enumerate() {
+            tx.send(i).unwrap();
+            assert_eq!(rx.recv_timeout(Duration::from_secs(1)).unwrap(), i);
+        }
+        assert_eq!(thread_count(), threads);
+        assert_eq!(stats_for("threads-crossbeam").unwrap().received_count, 1);
+
+        // Std channels block their forwarders until the next message
+        let (tx, rx) = std::sync::mpsc::channel::<usize>();
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "threads-std");
+        tx.send(1).unwrap();
+        assert_eq!(rx.recv_timeout(Duration::from_secs(1)).unwrap(), 1);
+        drop(rx);
+        common::wait_until("sends fail", || tx.send(0).is_err());
+        drop(tx);
+        common::wait_until("threads-std is closed", || {
+            stats_for("threads-std").unwrap().state == ChannelState::Closed
+        });
+    }
+
+    #[test]
+    fn test_misbehaving_channels_dont_stall_their_worker() {
+        let _turn = setup();
+
+        let (tx, rx) = crossbeam_channel::unbounded::<u32>();
+        let (panicking_tx, panicking_rx) = channels_console::channel!(
+            (tx, rx),
+            label = "threads-panicking",
+            log_with = |msg: &u32| {
+                assert_ne!(*msg, 1, "log_with failed");
+                msg.to_string()
+            }
+        );
+
+        // Keeps the forwarder of an unbounded channel busy
+        let stop = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = crossbeam_channel::unbounded::<u32>();
+        let (flood_tx, flood_rx) = channels_console::channel!((tx, rx), label = "threads-flood");
+        let producer = {
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let _ = flood_tx.send(0);
+                }
+            })
+        };
+        let consumer = {
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let _ = flood_rx.recv_timeout(Duration::from_millis(10));
+                }
+            })
+        };
+
+        panicking_tx.send(0).unwrap();
+        assert_eq!(panicking_rx.recv_timeout(Duration::from_secs(1)), Ok(0));
+        panicking_tx.send(1).unwrap();
+
+        // Channels are assigned to workers round-robin, so some share a worker with the others
+        let channels: Vec<_> = (0..4)
+            .map(|_| {
+                let (tx, rx) = crossbeam_channel::bounded::<usize>(1);
+                channels_console::channel!((tx, rx), label = "threads-healthy")
+            })
+            .collect();
+        for _ in 0..20 {
+            for (i, (tx, rx)) in channels.iter().enumerate() {
+                tx.send(i).unwrap();
+                assert_eq!(rx.recv_timeout(Duration::from_secs(1)).unwrap(), i);
+            }
+        }
+
+        // The panicking channel is closed instead of hanging
+        assert!(panicking_rx.recv_timeout(Duration::from_secs(1)).is_err());
+        common::wait_until("threads-panicking is closed", || {
+            stats_for("threads-panicking").unwrap().state == ChannelState::Closed
+        });
+
+        stop.store(true, Ordering::Relaxed);
+        producer.join().unwrap();
+        consumer.join().unwrap();
+    }
+
+    #[test]
+    fn test_pooled_crossbeam_forwarders() {
+        let _turn = setup();
+
+        let channels: Vec<_> = (0..CHANNELS)
+            .map(|_| {
+                let (tx, rx) = crossbeam_channel::bounded::<usize>(1);
+                channels_console::channel!((tx, rx), label = "pooled-crossbeam")
+            })
+            .collect();
+
+        // More messages than the channel capacity, so forwarders wait for full channels
+        for (i, (tx, _)) in channels.iter().enumerate() {
+            for n in 0..3 {
+                tx.send(i * 10 + n).unwrap();
+            }
+        }
+        for (i, (_, rx)) in channels.iter().enumerate() {
+            for n in 0..3 {
+                assert_eq!(rx.recv_timeout(Duration::from_secs(1)).unwrap(), i * 10 + n);
+            }
+        }
+
+        for i in 0..CHANNELS {
+            let stats = stats_for(&label("pooled-crossbeam", i)).unwrap();
+            assert_eq!(stats.sent_count, 3);
+            assert_eq!(stats.received_count, 3);
+        }
+
+        // Dropping the receiver makes sends fail once the forwarders notice
+        let (tx, rx) = channels.into_iter().next().unwrap();
+        drop(rx);
+        common::wait_until("sends fail", || tx.send(0).is_err());
+        drop(tx);
+        common::wait_until("pooled-crossbeam is closed", || {
+            stats_for("pooled-crossbeam").unwrap().state == ChannelState::Closed
+        });
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_pooled_tokio_forwarders() {
+        let _turn = setup();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let mut channels: Vec<_> = (0..CHANNELS)
+                .map(|_| {
+                    let (tx, rx) = tokio::sync::mpsc::channel::<usize>(1);
+                    channels_console::channel!((tx, rx), label = "pooled-tokio")
+                })
+                .collect();
+
+            for (i, (tx, rx)) in channels.iter_mut().enumerate() {
+                for n in 0..3 {
+                    tx.send(i * 10 + n).await.unwrap();
+                    assert_eq!(rx.recv().await.unwrap(), i * 10 + n);
+                }
+            }
+
+            for i in 0..CHANNELS {
+                let stats = stats_for(&label("pooled-tokio", i)).unwrap();
+                assert_eq!(stats.sent_count, 3);
+                assert_eq!(stats.received_count, 3);
+            }
+
+            drop(channels);
+        });
+
+        common::wait_until("all pooled-tokio channels are closed", || {
+            channels_console::flush();
+            channels_console::snapshot()
+                .channels
+                .iter()
+                .filter(|channel| channel.label.starts_with("pooled-tokio"))
+                .all(|channel| channel.state == ChannelState::Closed)
+        });
+    }
+}
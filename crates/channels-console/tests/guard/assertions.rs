@@ -0,0 +1,60 @@
+use channels_console::{Assertions, ChannelsGuardBuilder, Output};
+use std::sync::mpsc;
+
+use super::setup;
+
+fn guard(prefix: &str, assertions: Assertions) -> channels_console::ChannelsGuard {
+    ChannelsGuardBuilder::new()
+        .prefix(prefix)
+        .output(Output::writer(std::io::sink()))
+        .assert(assertions)
+        .build()
+}
+
+#[test]
+fn test_finish_reports_violations() {
+    setup();
+
+    let guard = guard("asserts-open", Assertions::new().all_closed().max_queued(0));
+    // Inline channels count messages queued in the channel itself
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+    let (tx, _rx) =
+        channels_console::channel!((tx, rx), label = "asserts-open/jobs", mode = inline);
+    tx.send(1).unwrap();
+
+    let err = guard.finish().unwrap_err();
+    assert_eq!(err.violations.len(), 2, "{}", err);
+    assert!(err.violations[0].contains("'asserts-open/jobs' is still open"));
+    assert!(err.violations[1].contains("1 queued messages (max: 0)"));
+}
+
+#[test]
+fn test_finish_passes_when_drained_and_closed() {
+    setup();
+
+    let guard = guard(
+        "asserts-closed",
+        Assertions::new()
+            .no_full_channels()
+            .all_closed()
+            .max_queued(0),
+    );
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "asserts-closed/jobs");
+    tx.send(1).unwrap();
+    rx.recv().unwrap();
+    drop((tx, rx));
+
+    guard.finish().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "channel assertions failed")]
+fn test_drop_panics_on_violation() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "asserts-drop/jobs");
+    // Dropped before the channel
+    let _guard = guard("asserts-drop", Assertions::new().all_closed());
+}
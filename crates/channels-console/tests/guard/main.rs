@@ -0,0 +1,17 @@
+//! Reports and assertions of `ChannelsGuard`, each guard scoped to its own label prefix.
+#![cfg(not(feature = "noop"))]
+
+#[path = "../common/mod.rs"]
+mod common;
+
+#[cfg(feature = "tokio")]
+mod assertions;
+mod output;
+
+fn setup() {
+    // Don't bind the default metrics port used by the example processes
+    let _ = channels_console::Config::builder()
+        .disable_server()
+        .synchronous()
+        .install();
+}
@@ -0,0 +1,163 @@
+use channels_console::{ChannelsGuardBuilder, Format, Output};
+use std::io::Write;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+use super::{common, setup};
+
+/// Writer shared with the test, so the output can be read after the guard is dropped.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[test]
+fn test_csv_output_to_writer() {
+    setup();
+
+    let buffer = SharedBuffer::default();
+    let guard = ChannelsGuardBuilder::new()
+        .format(Format::Csv)
+        .prefix("csv")
+        .output(Output::writer(buffer.clone()))
+        .build();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "csv/jobs, urgent");
+    drop(guard);
+
+    let csv = buffer.contents();
+    let mut lines = csv.lines();
+    assert!(lines
+        .next()
+        .unwrap()
+        .starts_with("kind,id,label,source,state,"));
+    let row = lines.next().unwrap();
+    assert!(row.starts_with("channel,"), "{}", row);
+    // Labels with separators are quoted
+    assert!(row.contains(",\"csv/jobs, urgent\","), "{}", row);
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn test_json_lines_output_to_file() {
+    setup();
+
+    let path = std::env::temp_dir().join(format!(
+        "channels-console-guard-{}.jsonl",
+        std::process::id()
+    ));
+    let guard = ChannelsGuardBuilder::new()
+        .format(Format::JsonLines)
+        .prefix("jsonl")
+        .output(path.clone())
+        .build();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "jsonl/events");
+    let _stream =
+        channels_console::stream!(futures_util::stream::iter(0..3), label = "jsonl/stream");
+    drop(guard);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let lines: Vec<serde_json::Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["kind"], "channel");
+    assert_eq!(lines[0]["label"], "jsonl/events");
+    assert_eq!(lines[1]["kind"], "stream");
+    assert_eq!(lines[1]["label"], "jsonl/stream");
+}
+
+#[test]
+fn test_markdown_report() {
+    setup();
+
+    let buffer = SharedBuffer::default();
+    let guard = ChannelsGuardBuilder::new()
+        .format(Format::Markdown)
+        .prefix("md")
+        .output(Output::writer(buffer.clone()))
+        .build();
+
+    let (tx, rx) = mpsc::sync_channel::<u32>(4);
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "md/a|b", capacity = 4);
+    drop(guard);
+
+    let report = buffer.contents();
+    assert!(report.contains("## Summary"), "{}", report);
+    assert!(report.contains("## Channels"), "{}", report);
+    // Streams section is left out without instrumented streams
+    assert!(!report.contains("## Streams"), "{}", report);
+    // Pipes in labels don't break the table
+    assert!(report.contains("| md/a\\|b |"), "{}", report);
+    // Bounded channels report their queue fill
+    assert!(report.contains("| bounded[4] | active |"), "{}", report);
+    assert!(report.contains("| 0% |"), "{}", report);
+}
+
+#[test]
+fn test_html_report() {
+    setup();
+
+    let buffer = SharedBuffer::default();
+    let guard = ChannelsGuardBuilder::new()
+        .format(Format::Html)
+        .prefix("html")
+        .output(Output::writer(buffer.clone()))
+        .build();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "html/<jobs>");
+    let _stream =
+        channels_console::stream!(futures_util::stream::iter(0..3), label = "html/stream");
+    drop(guard);
+
+    let report = buffer.contents();
+    assert!(report.starts_with("<!DOCTYPE html>"), "{}", report);
+    assert!(report.contains("<h2>Streams</h2>"), "{}", report);
+    assert!(report.contains("<td>html/&lt;jobs&gt;</td>"), "{}", report);
+    assert!(report.contains("<td>html/stream</td>"), "{}", report);
+}
+
+#[test]
+fn test_periodic_output() {
+    setup();
+
+    let buffer = SharedBuffer::default();
+    let guard = ChannelsGuardBuilder::new()
+        .format(Format::Csv)
+        .prefix("periodic")
+        .output(Output::writer(buffer.clone()))
+        .periodic(std::time::Duration::from_millis(10))
+        .build();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "periodic/jobs");
+
+    common::wait_until("two periodic reports", || {
+        buffer.contents().matches("periodic/jobs").count() >= 2
+    });
+
+    let printed = buffer.contents().matches("kind,id,").count();
+    drop(guard);
+    // The final statistics are printed on drop
+    assert_eq!(buffer.contents().matches("kind,id,").count(), printed + 1);
+}
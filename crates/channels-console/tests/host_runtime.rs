@@ -0,0 +1,50 @@
+#![cfg(not(feature = "noop"))]
+
+mod common;
+
+#[cfg(all(test, feature = "tokio"))]
+pub mod tests {
+    use super::common;
+    use channels_console::testing::stats_for;
+    use tokio::runtime::Handle;
+
+    fn install() {
+        let _ = channels_console::Config::builder()
+            .disable_server()
+            .host_runtime()
+            .install();
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_forwarders_spawned_on_host_runtime() {
+        install();
+
+        let alive_tasks = Handle::current().metrics().num_alive_tasks();
+        let (tx, rx) = tokio::sync::mpsc::channel::<u32>(10);
+        let (tx, mut rx) = channels_console::channel!((tx, rx), label = "host-runtime");
+        assert_eq!(
+            Handle::current().metrics().num_alive_tasks(),
+            alive_tasks + 2
+        );
+
+        tx.send(1).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), 1);
+        let stats = stats_for("host-runtime").unwrap();
+        assert_eq!(stats.sent_count, 1);
+        assert_eq!(stats.received_count, 1);
+    }
+
+    #[test]
+    fn test_private_runtime_outside_of_runtime() {
+        install();
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<u32>(10);
+        let (tx, mut rx) = channels_console::channel!((tx, rx), label = "outside-runtime");
+        tx.blocking_send(1).unwrap();
+        assert_eq!(rx.blocking_recv().unwrap(), 1);
+        // The proxy counts the message right after handing it over
+        common::wait_until("the message is counted as received", || {
+            stats_for("outside-runtime").unwrap().received_count == 1
+        });
+    }
+}
@@ -0,0 +1,67 @@
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+
+use super::{get_json, server_url, TOKEN};
+
+/// Sends a request accepting gzip, returning the raw response headers and body.
+fn get_gzip(path: &str) -> (String, Vec<u8>) {
+    let addr = server_url().trim_start_matches("http://");
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\nAccept-Encoding: gzip\r\nConnection: close\r\n\r\n",
+        path, TOKEN
+    )
+    .unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .unwrap();
+    let headers = String::from_utf8_lossy(&response[..split]).into_owned();
+    (headers, response[split + 4..].to_vec())
+}
+
+#[test]
+fn test_gzip_and_fields() {
+    server_url();
+
+    let mut channels = Vec::new();
+    for i in 0..5 {
+        let (tx, rx) = mpsc::channel::<u32>();
+        channels.push(channels_console::channel!(
+            (tx, rx),
+            label = format!("compression/{}", i)
+        ));
+    }
+    channels_console::flush();
+
+    let (headers, body) = get_gzip("/channels?prefix=compression");
+    assert!(headers.contains("Content-Encoding: gzip"));
+    let mut json = String::new();
+    GzDecoder::new(&body[..]).read_to_string(&mut json).unwrap();
+    let channels: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(channels["channels"].as_array().unwrap().len(), 5);
+
+    // Small responses are sent as they are
+    let (headers, body) = get_gzip("/alerts");
+    assert!(!headers.contains("Content-Encoding"));
+    assert!(serde_json::from_slice::<Value>(&body).is_ok());
+
+    let channels: Value = get_json("/channels?fields=id,label,queued");
+    for channel in channels["channels"].as_array().unwrap() {
+        let mut fields: Vec<&str> = channel
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        fields.sort();
+        assert_eq!(fields, ["id", "label", "queued"]);
+    }
+    assert!(channels["current_elapsed_ns"].is_u64());
+}
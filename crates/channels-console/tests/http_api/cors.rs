@@ -0,0 +1,34 @@
+use super::{get, url, ORIGIN};
+
+#[test]
+fn test_cors_headers() {
+    // Preflight requests don't carry the token
+    let response = ureq::options(url("/channels"))
+        .header("Origin", ORIGIN)
+        .header("Access-Control-Request-Method", "GET")
+        .header("Access-Control-Request-Headers", "authorization")
+        .call()
+        .unwrap();
+    assert_eq!(response.status(), 204);
+    let headers = response.headers();
+    assert_eq!(headers["Access-Control-Allow-Origin"], ORIGIN);
+    assert!(headers["Access-Control-Allow-Headers"]
+        .to_str()
+        .unwrap()
+        .contains("Authorization"));
+
+    let response = get("/channels").header("Origin", ORIGIN).call().unwrap();
+    assert_eq!(response.headers()["Access-Control-Allow-Origin"], ORIGIN);
+    assert_eq!(response.headers()["Vary"], "Origin");
+
+    // Errors carry the headers too, so the dashboard can read them
+    let error = ureq::get(url("/channels"))
+        .header("Origin", ORIGIN)
+        .config()
+        .http_status_as_error(false)
+        .build()
+        .call()
+        .unwrap();
+    assert_eq!(error.status(), 401);
+    assert_eq!(error.headers()["Access-Control-Allow-Origin"], ORIGIN);
+}
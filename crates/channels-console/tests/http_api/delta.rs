@@ -0,0 +1,71 @@
+use channels_console::delta::ChannelsDeltaJson;
+use channels_console::{flush, ChannelsJson};
+
+use super::{get, get_json, server_url};
+
+fn fetch_delta(query: &str) -> ChannelsDeltaJson {
+    get_json(&format!("/channels/delta?{}", query))
+}
+
+#[test]
+fn test_channels_delta() {
+    server_url();
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u64>();
+    let (busy_tx, mut busy_rx) =
+        channels_console::channel!((tx, rx), label = "delta/busy", mode = inline);
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u64>();
+    let (_idle_tx, _idle_rx) =
+        channels_console::channel!((tx, rx), label = "delta/idle", mode = inline);
+    flush();
+
+    let mut channels: ChannelsJson = get_json("/channels?prefix=delta");
+    let since_ns = channels.current_elapsed_ns;
+
+    for i in 0..3 {
+        busy_tx.send(i).unwrap();
+    }
+    busy_rx.try_recv().unwrap();
+    flush();
+
+    let delta = fetch_delta(&format!("since_ns={}&prefix=delta", since_ns));
+    assert!(delta.current_elapsed_ns > since_ns);
+    assert_eq!(delta.channels.len(), 1);
+    let busy = &delta.channels[0];
+    assert_eq!(busy.stats.label, "delta/busy");
+    let deltas = busy.deltas.unwrap();
+    assert_eq!(deltas.sent_delta, 3);
+    assert_eq!(deltas.received_delta, 1);
+    assert_eq!(deltas.queued_delta, 2);
+    assert_eq!(deltas.queued_bytes_delta, 16);
+    assert_eq!(delta.channel_ids.len(), 2);
+
+    delta.apply(&mut channels);
+    let busy = channels
+        .channels
+        .iter()
+        .find(|channel| channel.label == "delta/busy")
+        .unwrap();
+    assert_eq!(busy.sent_count, 3);
+    assert_eq!(busy.queued, 2);
+    assert_eq!(channels.channels.len(), 2);
+
+    // Channels created since then count from zero
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u64>();
+    let (new_tx, _new_rx) =
+        channels_console::channel!((tx, rx), label = "delta/new", mode = inline);
+    new_tx.send(1).unwrap();
+    new_tx.send(2).unwrap();
+    flush();
+    let new = fetch_delta(&format!("since_ns={}&prefix=delta/new", since_ns));
+    let deltas = new.channels[0].deltas.unwrap();
+    assert_eq!(deltas.sent_delta, 2);
+    assert_eq!(deltas.queued_delta, 2);
+
+    let idle = fetch_delta(&format!("since_ns={}&prefix=delta/idle", since_ns));
+    assert!(idle.channels.is_empty());
+    assert_eq!(idle.channel_ids.len(), 1);
+
+    let error = get("/channels/delta").call().unwrap_err();
+    assert!(matches!(error, ureq::Error::StatusCode(400)));
+}
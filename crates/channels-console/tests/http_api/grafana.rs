@@ -0,0 +1,82 @@
+use serde_json::{json, Value};
+use std::sync::mpsc;
+
+use super::{common, get, post, server_url};
+
+fn grafana(endpoint: &str, body: Value) -> Value {
+    post(&format!("/grafana/{}", endpoint))
+        .send_json(body)
+        .unwrap()
+        .body_mut()
+        .read_json()
+        .unwrap()
+}
+
+fn sent_series() -> Value {
+    grafana(
+        "query",
+        json!({
+            "range": { "from": "2000-01-01T00:00:00.000Z", "to": "2100-01-01T00:00:00.000Z" },
+            "targets": [{ "target": "grafana-jobs.sent", "refId": "A", "type": "timeserie" }],
+            "maxDataPoints": 100,
+        }),
+    )
+}
+
+#[test]
+fn test_grafana_datasource() {
+    server_url();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "grafana-jobs");
+    tx.send(1).unwrap();
+    rx.recv().unwrap();
+
+    let mut response = get("/grafana").call().unwrap();
+    assert_eq!(response.body_mut().read_to_string().unwrap(), "OK");
+
+    let targets = grafana("search", json!({ "target": "grafana-jobs" }));
+    assert_eq!(
+        targets,
+        json!([
+            "grafana-jobs.queued",
+            "grafana-jobs.sent",
+            "grafana-jobs.received"
+        ])
+    );
+
+    // Datapoints come from the collector's samples
+    let series = common::wait_for("the first sample", || {
+        let series = sent_series();
+        let sampled = !series[0]["datapoints"].as_array().unwrap().is_empty();
+        sampled.then_some(series)
+    });
+    assert_eq!(series[0]["target"], "grafana-jobs.sent");
+    let datapoints = series[0]["datapoints"].as_array().unwrap();
+    assert_eq!(datapoints[0][0], 1);
+    // Unix milliseconds
+    assert!(datapoints[0][1].as_u64().unwrap() > 1_600_000_000_000);
+
+    let series = grafana(
+        "query",
+        json!({
+            "range": { "from": "2000-01-01T00:00:00Z", "to": "2000-01-02T00:00:00Z" },
+            "targets": [{ "target": "grafana-jobs.sent" }],
+        }),
+    );
+    assert_eq!(series[0]["datapoints"], json!([]));
+
+    let annotations = grafana(
+        "annotations",
+        json!({
+            "range": { "from": "2000-01-01T00:00:00.000Z", "to": "2100-01-01T00:00:00.000Z" },
+            "annotation": { "name": "alerts" },
+        }),
+    );
+    assert_eq!(annotations, json!([]));
+
+    let error = post("/grafana/query")
+        .send_json(json!({ "range": { "from": "yesterday", "to": "now" }, "targets": [] }))
+        .unwrap_err();
+    assert!(matches!(error, ureq::Error::StatusCode(400)));
+}
@@ -0,0 +1,47 @@
+use channels_console::info::{HealthJson, InfoJson, VersionJson};
+use channels_console::{ChannelsGuardBuilder, Output, MIN_CLIENT_SCHEMA_VERSION, SCHEMA_VERSION};
+
+use super::{get_json, server_url, url};
+
+#[test]
+fn test_health_and_info() {
+    server_url();
+
+    let _guard = ChannelsGuardBuilder::new()
+        .service_name("info-service")
+        .output(Output::writer(std::io::sink()))
+        .build();
+
+    // Liveness probes don't need the token
+    let health: HealthJson = ureq::get(url("/healthz"))
+        .call()
+        .unwrap()
+        .body_mut()
+        .read_json()
+        .unwrap();
+    assert_eq!(health.status, "ok");
+    assert!(health.uptime_ns > 0);
+
+    let error = ureq::get(url("/info")).call().unwrap_err();
+    assert!(matches!(error, ureq::Error::StatusCode(401)));
+
+    let info: InfoJson = get_json("/info");
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.pid, std::process::id());
+    assert!(info.process.starts_with("http_api"));
+    assert_eq!(
+        info.features.contains(&"tokio".to_string()),
+        cfg!(feature = "tokio")
+    );
+    assert!(info.start_time_ms > 1_600_000_000_000);
+    assert_eq!(info.service_name.as_deref(), Some("info-service"));
+
+    // Snapshots carry the same process metadata
+    assert_eq!(channels_console::snapshot().process, info.process_json());
+
+    let version: VersionJson = get_json("/version");
+    assert_eq!(version.schema_version, SCHEMA_VERSION);
+    assert_eq!(version.min_client_schema_version, MIN_CLIENT_SCHEMA_VERSION);
+    assert!(version.min_client_schema_version <= version.schema_version);
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+}
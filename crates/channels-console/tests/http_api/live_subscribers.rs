@@ -0,0 +1,52 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+use super::{common, get, server_url, TOKEN};
+
+/// Subscribes to `path`, returning the connection once the response headers are read.
+fn subscribe(path: &str) -> BufReader<TcpStream> {
+    let addr = server_url().trim_start_matches("http://");
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\n\r\n",
+        path, addr, TOKEN
+    )
+    .unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut status = String::new();
+    reader.read_line(&mut status).unwrap();
+    assert!(status.contains("200"), "{}", status);
+    reader
+}
+
+fn status(path: &str) -> u16 {
+    get(path)
+        .config()
+        .http_status_as_error(false)
+        .build()
+        .call()
+        .unwrap()
+        .status()
+        .as_u16()
+}
+
+#[test]
+fn test_subscribers_are_capped() {
+    server_url();
+
+    let mut subscribers: Vec<_> = (0..32)
+        .map(|_| subscribe("/events?interval_ms=50"))
+        .collect();
+    assert_eq!(status("/events"), 503);
+    #[cfg(feature = "ws")]
+    assert_eq!(status("/ws"), 503);
+    // Other endpoints keep working
+    assert_eq!(status("/channels"), 200);
+
+    // A slot is released once the server notices the disconnect
+    subscribers.pop();
+    common::wait_until("a subscriber slot is released", || {
+        status("/events?interval_ms=50") == 200
+    });
+}
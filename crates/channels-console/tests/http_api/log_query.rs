@@ -0,0 +1,171 @@
+use channels_console::testing::logs_for;
+use channels_console::{ChannelLogs, ChannelsJson, LogOrder, LogQuery};
+use std::sync::mpsc;
+
+use super::{get_json, server_url};
+
+fn indices(logs: &ChannelLogs) -> Vec<u64> {
+    logs.sent_logs.iter().map(|entry| entry.index).collect()
+}
+
+#[test]
+fn test_log_query() {
+    server_url();
+
+    let (tx, rx) = mpsc::channel::<String>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "searched", log = true);
+    for msg in [
+        "job ok",
+        "job timeout",
+        "job ok",
+        "job timeout",
+        "job timeout",
+    ] {
+        tx.send(msg.to_string()).unwrap();
+        rx.recv().unwrap();
+    }
+    channels_console::testing::assert_channel_drained("searched");
+
+    let all = logs_for("searched").unwrap();
+    assert_eq!(indices(&all), [5, 4, 3, 2, 1]);
+
+    let mut logs = all.clone();
+    logs.apply(&LogQuery {
+        contains: Some("timeout".to_string()),
+        ..LogQuery::default()
+    });
+    assert_eq!(indices(&logs), [5, 4, 2]);
+    assert_eq!(logs.received_logs.len(), 3);
+
+    let mut logs = all.clone();
+    logs.apply(&LogQuery {
+        contains: Some("timeout".to_string()),
+        limit: Some(2),
+        order: LogOrder::Asc,
+        ..LogQuery::default()
+    });
+    assert_eq!(indices(&logs), [2, 4]);
+
+    let since_ns = all.sent_logs[1].timestamp;
+    let mut logs = all.clone();
+    logs.apply(&LogQuery {
+        since_ns: Some(since_ns),
+        ..LogQuery::default()
+    });
+    assert!(indices(&logs).starts_with(&[5, 4]));
+    assert!(logs
+        .sent_logs
+        .iter()
+        .all(|entry| entry.timestamp >= since_ns));
+
+    // The same query through the HTTP API
+    let id = channels_console::testing::stats_for("searched").unwrap().id;
+    let logs: ChannelLogs = get_json(&format!(
+        "/channels/{}/logs?contains=job+timeout&limit=2&order=asc",
+        id
+    ));
+    assert_eq!(indices(&logs), [2, 4]);
+
+    let logs: ChannelLogs = get_json(&format!("/channels/{}/logs?limit=bogus", id));
+    assert_eq!(indices(&logs), [5, 4, 3, 2, 1]);
+    assert_eq!(logs.next_cursor, None);
+}
+
+#[test]
+fn test_log_pagination() {
+    server_url();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "paged", log = true);
+    for i in 0..5 {
+        tx.send(i).unwrap();
+        rx.recv().unwrap();
+    }
+    channels_console::testing::assert_channel_drained("paged");
+    let id = channels_console::testing::stats_for("paged").unwrap().id;
+
+    // Pages are oldest first
+    let logs: ChannelLogs = get_json(&format!("/channels/{}/logs?after_index=0&limit=2", id));
+    assert_eq!(indices(&logs), [1, 2]);
+    assert_eq!(logs.next_cursor, Some(2));
+
+    let logs: ChannelLogs = get_json(&format!("/channels/{}/logs?after_index=2&limit=10", id));
+    assert_eq!(indices(&logs), [3, 4, 5]);
+    assert_eq!(logs.next_cursor, Some(5));
+
+    // Nothing new yet, the cursor stays
+    let logs: ChannelLogs = get_json(&format!("/channels/{}/logs?after_index=5", id));
+    assert!(logs.sent_logs.is_empty());
+    assert_eq!(logs.next_cursor, Some(5));
+
+    // The cursor follows the received side while messages are queued
+    let mut logs = channels_console::testing::logs_for("paged").unwrap();
+    logs.received_logs.retain(|entry| entry.index <= 3);
+    logs.apply(&LogQuery {
+        after_index: Some(2),
+        order: LogOrder::Asc,
+        ..LogQuery::default()
+    });
+    assert_eq!(indices(&logs), [3, 4, 5]);
+    assert_eq!(logs.next_cursor, Some(3));
+}
+
+#[test]
+fn test_channels_pagination() {
+    server_url();
+
+    let (tx_a, rx_a) = mpsc::channel::<u32>();
+    let (tx_a, _rx_a) = channels_console::channel!((tx_a, rx_a), label = "paged-a");
+    let (tx_b, rx_b) = mpsc::channel::<u32>();
+    let (tx_b, _rx_b) = channels_console::channel!((tx_b, rx_b), label = "paged-b");
+    let (tx_c, rx_c) = mpsc::channel::<u32>();
+    let (tx_c, _rx_c) = channels_console::channel!((tx_c, rx_c), label = "paged-c");
+    for tx in [&tx_a, &tx_b, &tx_c] {
+        tx.send(1).unwrap();
+    }
+    let ids: Vec<u64> = ["paged-a", "paged-b", "paged-c"]
+        .iter()
+        .map(|label| channels_console::testing::stats_for(label).unwrap().id)
+        .collect();
+
+    let channels: ChannelsJson = get_json(&format!("/channels?after_index={}&limit=1", ids[0]));
+    // Other tests may create channels in between
+    assert_eq!(channels.channels.len(), 1);
+    let id = channels.channels[0].id;
+    assert!(id > ids[0] && id <= ids[1]);
+    assert_eq!(channels.next_cursor, Some(id));
+
+    let channels: ChannelsJson = get_json(&format!("/channels?after_index={}", ids[2]));
+    assert!(channels.channels.iter().all(|channel| channel.id > ids[2]));
+    assert!(channels.next_cursor >= Some(ids[2]));
+
+    // Unpaginated responses don't carry a cursor
+    let channels: ChannelsJson = get_json("/channels");
+    assert_eq!(channels.next_cursor, None);
+}
+
+#[test]
+fn test_wall_clock_timestamps() {
+    server_url();
+
+    let before = unix_ms();
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, _rx) = channels_console::channel!((tx, rx), label = "wall-clock", log = true);
+    tx.send(1).unwrap();
+    let after = unix_ms();
+
+    let logs = logs_for("wall-clock").unwrap();
+    let entry = &logs.sent_logs[0];
+    // The start anchor is derived from a monotonic clock, allow some drift
+    assert!(entry.unix_ms + 10 >= before && entry.unix_ms <= after + 10);
+
+    let channels: ChannelsJson = get_json("/channels");
+    assert!(channels.start_unix_ms > 0 && channels.start_unix_ms <= entry.unix_ms);
+}
+
+fn unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
@@ -0,0 +1,57 @@
+//! Endpoints of the metrics server, shared by all tests and bound to a free port.
+#![cfg(not(feature = "noop"))]
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod compression;
+mod cors;
+#[cfg(feature = "tokio")]
+mod delta;
+mod grafana;
+mod info;
+mod live_subscribers;
+mod log_query;
+mod objects;
+mod tags;
+
+use std::sync::OnceLock;
+
+const TOKEN: &str = "s3cret";
+const ORIGIN: &str = "https://dash.example.com";
+
+/// Base URL of the metrics server, started on the first call.
+fn server_url() -> &'static str {
+    static URL: OnceLock<String> = OnceLock::new();
+    URL.get_or_init(|| {
+        let _ = channels_console::Config::builder()
+            .metrics_port(0)
+            .token(TOKEN)
+            .cors_origin(ORIGIN)
+            .install();
+
+        // The server starts with the first instrumented channel
+        let (tx, rx) = std::sync::mpsc::channel::<u32>();
+        let _ = channels_console::channel!((tx, rx), label = "http-api");
+        common::server_url()
+    })
+}
+
+fn url(path: &str) -> String {
+    format!("{}{}", server_url(), path)
+}
+
+/// `GET` request to `path` carrying the token.
+fn get(path: &str) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
+    ureq::get(url(path)).header("Authorization", format!("Bearer {}", TOKEN))
+}
+
+/// `POST` request to `path` carrying the token.
+fn post(path: &str) -> ureq::RequestBuilder<ureq::typestate::WithBody> {
+    ureq::post(url(path)).header("Authorization", format!("Bearer {}", TOKEN))
+}
+
+/// `GET` response body of `path` as JSON.
+fn get_json<T: serde::de::DeserializeOwned>(path: &str) -> T {
+    get(path).call().unwrap().body_mut().read_json().unwrap()
+}
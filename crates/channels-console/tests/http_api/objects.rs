@@ -0,0 +1,90 @@
+#[cfg(feature = "tokio")]
+use channels_console::primitives::PrimitiveKind;
+use channels_console::registry::{self, Object, ObjectJson};
+use channels_console::testing::{stats_for, stream_stats_for};
+use channels_console::CombinedJson;
+use futures_util::StreamExt;
+
+use super::{get, get_json, server_url};
+
+fn get_object(id: u64) -> Result<ObjectJson, ureq::Error> {
+    get(&format!("/objects/{}", id))
+        .call()?
+        .body_mut()
+        .read_json()
+}
+
+#[test]
+fn test_shared_id_space() {
+    server_url();
+
+    let (tx, rx) = std::sync::mpsc::channel::<u32>();
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "objects-channel");
+    let stream = futures_util::stream::iter([1, 2]);
+    let mut stream = channels_console::stream!(stream, label = "objects-stream");
+    let waker = futures_util::task::noop_waker();
+    let _ = stream.poll_next_unpin(&mut std::task::Context::from_waker(&waker));
+
+    let channel_id = stats_for("objects-channel").unwrap().id;
+    let stream_id = stream_stats_for("objects-stream").unwrap().id;
+    assert_ne!(channel_id, stream_id);
+
+    assert!(matches!(
+        registry::object(channel_id),
+        Some(Object::Channel(_))
+    ));
+    assert!(matches!(
+        registry::object(stream_id),
+        Some(Object::Stream(_))
+    ));
+
+    let object = get_object(stream_id).unwrap();
+    match object.object {
+        Object::Stream(stats) => assert_eq!(stats.label, "objects-stream"),
+        other => panic!("expected a stream, got {:?}", other),
+    }
+    let object = get_object(channel_id).unwrap();
+    assert_eq!(object.object.id(), channel_id);
+
+    let error = get_object(999999).unwrap_err();
+    assert!(matches!(error, ureq::Error::StatusCode(404)));
+
+    let all: CombinedJson = get_json("/all");
+    assert!(all.channels.iter().any(|c| c.id == channel_id));
+    assert!(all.streams.iter().any(|s| s.id == stream_id));
+
+    let all: CombinedJson = get_json("/all?prefix=objects-stream");
+    assert!(all.channels.is_empty());
+    assert_eq!(all.streams.len(), 1);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_primitives_share_id_space() {
+    server_url();
+
+    let (tx, rx) = std::sync::mpsc::channel::<u32>();
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "objects-sem-channel");
+    let semaphore =
+        channels_console::semaphore!(tokio::sync::Semaphore::new(2), label = "objects-sem");
+
+    let channel_id = stats_for("objects-sem-channel").unwrap().id;
+    let semaphore_id = channels_console::primitives::primitives()
+        .into_iter()
+        .find(|p| p.label == "objects-sem")
+        .unwrap()
+        .id;
+    assert_ne!(channel_id, semaphore_id);
+
+    let object = get_object(channel_id).unwrap();
+    assert!(matches!(object.object, Object::Channel(_)));
+    let object = get_object(semaphore_id).unwrap();
+    match object.object {
+        Object::Primitive(stats) => {
+            assert_eq!(stats.label, "objects-sem");
+            assert_eq!(stats.kind(), PrimitiveKind::Semaphore);
+        }
+        other => panic!("expected a primitive, got {:?}", other),
+    }
+    drop(semaphore);
+}
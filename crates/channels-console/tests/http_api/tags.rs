@@ -0,0 +1,74 @@
+use channels_console::groups::TagGroupsJson;
+use channels_console::tags::matches_tag;
+use channels_console::testing::stats_for;
+use channels_console::ChannelsJson;
+use std::sync::mpsc;
+
+use super::{get_json, server_url};
+
+#[test]
+fn test_matches_tag() {
+    let tags = vec!["pipeline:video".to_string(), "hot".to_string()];
+    assert!(matches_tag(&tags, "pipeline:video"));
+    assert!(matches_tag(&tags, "pipeline"));
+    assert!(matches_tag(&tags, "hot"));
+    assert!(!matches_tag(&tags, "pipeline:audio"));
+    assert!(!matches_tag(&tags, "pipe"));
+    assert!(!matches_tag(&tags, "video"));
+    assert!(!matches_tag(&[], "hot"));
+}
+
+#[test]
+fn test_channel_tags() {
+    server_url();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (video_tx, _video_rx) = channels_console::channel!(
+        (tx, rx),
+        label = "tags-decode",
+        tags = ["pipeline:video", "tier:hot"]
+    );
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (audio_tx, _audio_rx) = channels_console::channel!(
+        (tx, rx),
+        tags = ["pipeline:audio", "tier:hot"],
+        label = "tags-resample"
+    );
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_plain_tx, _plain_rx) = channels_console::channel!((tx, rx), label = "tags-plain");
+    video_tx.send(1).unwrap();
+    video_tx.send(2).unwrap();
+    audio_tx.send(3).unwrap();
+
+    assert_eq!(
+        stats_for("tags-decode").unwrap().tags,
+        ["pipeline:video", "tier:hot"]
+    );
+    assert!(stats_for("tags-plain").unwrap().tags.is_empty());
+
+    let labels = |query: &str| -> Vec<String> {
+        let channels: ChannelsJson = get_json(&format!("/channels?tag={}", query));
+        let mut labels: Vec<String> = channels
+            .channels
+            .into_iter()
+            .map(|channel| channel.label)
+            .collect();
+        labels.sort();
+        labels
+    };
+    assert_eq!(labels("pipeline%3Avideo"), ["tags-decode"]);
+    assert_eq!(labels("pipeline"), ["tags-decode", "tags-resample"]);
+    assert_eq!(labels("tier:hot"), ["tags-decode", "tags-resample"]);
+    assert!(labels("tier:cold").is_empty());
+
+    let groups: TagGroupsJson = get_json("/channels?group_by=tag");
+    let tags: Vec<&str> = groups
+        .groups
+        .iter()
+        .map(|group| group.tag.as_str())
+        .collect();
+    assert_eq!(tags, ["pipeline:audio", "pipeline:video", "tier:hot"]);
+    let hot = &groups.groups[2];
+    assert_eq!(hot.channels, 2);
+    assert_eq!(hot.sent_count, 3);
+}
@@ -0,0 +1,40 @@
+#![cfg(not(feature = "noop"))]
+
+mod common;
+
+#[cfg(test)]
+pub mod tests {
+    use super::common;
+    use channels_console::testing::{logs_for, stats_for};
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_counted_without_details() {
+        let _ = channels_console::Config::builder()
+            .disable_server()
+            .disable_latency()
+            .log_limit(0)
+            .synchronous()
+            .install();
+
+        let (tx, rx) = mpsc::channel::<u32>();
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "undetailed");
+        for i in 0..5 {
+            tx.send(i).unwrap();
+        }
+        for _ in 0..5 {
+            rx.recv().unwrap();
+        }
+
+        // Proxied channels count messages after forwarding them
+        let stats = common::wait_for("all receives are counted", || {
+            stats_for("undetailed").filter(|stats| stats.received_count == 5)
+        });
+        assert_eq!(stats.sent_count, 5);
+        assert_eq!(stats.received_count, 5);
+        assert!(stats.latency.is_none());
+        let logs = logs_for("undetailed").unwrap();
+        assert!(logs.sent_logs.is_empty());
+        assert!(logs.received_logs.is_empty());
+    }
+}
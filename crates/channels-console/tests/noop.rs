@@ -0,0 +1,75 @@
+#[cfg(all(test, feature = "noop"))]
+pub mod tests {
+    use channels_console::testing::{sink_stats_for, stats_for, stream_stats_for};
+    use futures_util::{SinkExt, StreamExt};
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_noop_forces_disabled() {
+        let _ = channels_console::Config::builder().install();
+        assert!(channels_console::Config::current().is_disabled());
+    }
+
+    #[test]
+    fn test_noop_channel_is_untouched() {
+        let (tx, rx) = mpsc::sync_channel::<u32>(1);
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "noop-std", capacity = 1);
+        tx.send(1).unwrap();
+        // A proxied channel would accept a second message into the outer buffer
+        assert!(tx.try_send(2).is_err());
+        assert_eq!(rx.recv().unwrap(), 1);
+
+        assert!(stats_for("noop-std").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_noop_streams_and_sinks_report_nothing() {
+        let stream = futures_util::stream::iter(1..=3);
+        let stream = channels_console::stream!(stream, label = "noop-stream", log = true);
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![1, 2, 3]);
+
+        let sink = futures_util::sink::drain::<u32>();
+        let mut sink = channels_console::sink!(sink, label = "noop-sink");
+        sink.send(1).await.unwrap();
+
+        assert!(stream_stats_for("noop-stream").is_none());
+        assert!(sink_stats_for("noop-sink").is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_noop_keeps_instrumented_types() {
+        use channels_console::primitives::{InstrumentedNotify, InstrumentedSemaphore};
+        use channels_console::{
+            InstrumentedReceiver, InstrumentedSender, InstrumentedUnboundedReceiver,
+            InstrumentedUnboundedSender,
+        };
+
+        // Code naming the wrapper types must compile the same with and without `noop`
+        let (tx, rx) = tokio::sync::mpsc::channel::<u32>(4);
+        let (tx, mut rx): (InstrumentedSender<u32>, InstrumentedReceiver<u32>) =
+            channels_console::channel!((tx, rx), label = "noop-inline", mode = inline);
+        tx.send(1).await.unwrap();
+        assert_eq!(rx.recv().await, Some(1));
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+        let (tx, mut rx): (
+            InstrumentedUnboundedSender<u32>,
+            InstrumentedUnboundedReceiver<u32>,
+        ) = channels_console::channel!((tx, rx), label = "noop-unbounded", mode = inline);
+        tx.send(1).unwrap();
+        assert_eq!(rx.recv().await, Some(1));
+
+        let semaphore: InstrumentedSemaphore =
+            channels_console::semaphore!(tokio::sync::Semaphore::new(1), label = "noop-sem");
+        drop(semaphore.acquire().await.unwrap());
+        let notify: InstrumentedNotify =
+            channels_console::notify!(tokio::sync::Notify::new(), label = "noop-notify");
+        notify.notify_one();
+        notify.notified().await;
+
+        assert!(stats_for("noop-inline").is_none());
+        assert!(stats_for("noop-unbounded").is_none());
+        assert!(channels_console::primitives::primitives().is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+#![cfg(not(feature = "noop"))]
+
+mod common;
+
+#[cfg(test)]
+pub mod tests {
+    use super::common;
+    use channels_console::history::{read_history, HistoryRecord};
+    use channels_console::testing::stats_for;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc;
+    use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+    use std::time::Duration;
+
+    /// Resetting applies to every channel, so the tests take turns.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    struct Paths {
+        recording: PathBuf,
+        history: PathBuf,
+    }
+
+    /// Installs the config on the first call, returning the files written and the turn.
+    fn setup() -> (&'static Paths, MutexGuard<'static, ()>) {
+        static PATHS: OnceLock<Paths> = OnceLock::new();
+        let paths = PATHS.get_or_init(|| {
+            let path = |name: &str| {
+                std::env::temp_dir().join(format!(
+                    "channels-console-{}-{}.jsonl",
+                    name,
+                    std::process::id()
+                ))
+            };
+            let paths = Paths {
+                recording: path("recording"),
+                history: path("history-compact"),
+            };
+            let _ = std::fs::remove_file(&paths.recording);
+
+            // History left by a previous run, and a line that fails to parse
+            let mut file = std::fs::File::create(&paths.history).unwrap();
+            for i in 0..10 {
+                writeln!(
+                    file,
+                    r#"{{"timestamp_ms":{},"elapsed_ns":{},"channels":[],"streams":[]}}"#,
+                    i + 1,
+                    i + 1
+                )
+                .unwrap();
+            }
+            writeln!(file, "not json").unwrap();
+
+            let _ = channels_console::Config::builder()
+                .disable_server()
+                .synchronous()
+                .record_to(&paths.recording)
+                .history_path(&paths.history)
+                .history_interval(Duration::from_millis(10))
+                .history_max_entries(5)
+                .install();
+            paths
+        });
+        let turn = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+        (paths, turn)
+    }
+
+    fn sent_logs(records: &[HistoryRecord], label: &str) -> usize {
+        let Some(id) = stats_for(label).map(|stats| stats.id.to_string()) else {
+            return 0;
+        };
+        records
+            .iter()
+            .flat_map(|record| record.logs.iter())
+            .filter(|logs| logs.id == id)
+            .map(|logs| logs.sent_logs.len())
+            .sum()
+    }
+
+    /// Waits for the recorder thread to append `count` sent log entries of `label`.
+    fn recorded_sent_logs(path: &Path, label: &str, count: usize) -> Vec<HistoryRecord> {
+        common::wait_for("recorded log entries", || {
+            let records = read_history(path).unwrap_or_default();
+            (sent_logs(&records, label) >= count).then_some(records)
+        })
+    }
+
+    #[test]
+    fn test_record_to_file() {
+        let (paths, _turn) = setup();
+
+        let (tx, rx) = mpsc::channel::<u32>();
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "recorded", log = true);
+        for i in 0..3 {
+            tx.send(i).unwrap();
+        }
+        for _ in 0..3 {
+            rx.recv().unwrap();
+        }
+
+        // Waits for the recorder thread to append a snapshot with the received messages
+        let records = common::wait_for("a recorded snapshot", || {
+            let records = read_history(&paths.recording).unwrap_or_default();
+            let done = records.iter().any(|record| {
+                record
+                    .channels
+                    .iter()
+                    .any(|c| c.label == "recorded" && c.received_count == 3)
+            });
+            done.then_some(records)
+        });
+
+        let channel = records
+            .iter()
+            .flat_map(|record| record.channels.iter())
+            .rfind(|c| c.label == "recorded")
+            .expect("channel was not recorded");
+        assert_eq!(channel.received_count, 3);
+
+        // Log entries are spread across records, each carrying the ones emitted since the previous
+        assert_eq!(sent_logs(&records, "recorded"), 3);
+        assert!(records
+            .windows(2)
+            .all(|w| w[0].timestamp_ms <= w[1].timestamp_ms));
+    }
+
+    #[test]
+    fn test_record_logs_after_reset() {
+        let (paths, _turn) = setup();
+
+        let (tx, rx) = mpsc::channel::<u32>();
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "reset-recorded", log = true);
+        for i in 0..3 {
+            tx.send(i).unwrap();
+            rx.recv().unwrap();
+        }
+        recorded_sent_logs(&paths.recording, "reset-recorded", 3);
+
+        // Log indexes start over after the reset, so they're below the ones already recorded
+        channels_console::reset();
+        for i in 0..2 {
+            tx.send(i).unwrap();
+            rx.recv().unwrap();
+        }
+        let records = recorded_sent_logs(&paths.recording, "reset-recorded", 5);
+
+        assert_eq!(sent_logs(&records, "reset-recorded"), 5);
+    }
+
+    #[test]
+    fn test_history_compacts_to_newest_records() {
+        let (paths, _turn) = setup();
+
+        let (tx, rx) = mpsc::channel::<u32>();
+        let (tx, _rx) = channels_console::channel!((tx, rx), label = "compacted");
+        tx.send(1).unwrap();
+
+        // Waits for the writer to append and compact a few times
+        let records = common::wait_for("compacted history", || {
+            let records = read_history(&paths.history).unwrap_or_default();
+            let done = records.len() == 5 && records.iter().all(|r| r.timestamp_ms > 10);
+            done.then_some(records)
+        });
+
+        assert!(records
+            .windows(2)
+            .all(|pair| pair[0].elapsed_ns < pair[1].elapsed_ns));
+    }
+}
@@ -0,0 +1,55 @@
+use channels_console::diff::SnapshotDiff;
+use channels_console::{flush, snapshot, ChannelState};
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+#[test]
+fn test_diff_between_snapshots() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "diff/jobs");
+    let (idle_tx, idle_rx) = mpsc::channel::<u32>();
+    let (_idle_tx, _idle_rx) = channels_console::channel!((idle_tx, idle_rx), label = "diff/idle");
+    flush();
+    let mut before = snapshot();
+    before.retain_prefix("diff");
+
+    for i in 0..3 {
+        tx.send(i).unwrap();
+        rx.recv().unwrap();
+    }
+    drop((tx, rx));
+    let (new_tx, new_rx) = mpsc::channel::<u32>();
+    let (_new_tx, _new_rx) = channels_console::channel!((new_tx, new_rx), label = "diff/new");
+
+    // Wait for the proxies to report the sends and the close
+    let after = common::wait_for("the closed channel", || {
+        flush();
+        let mut after = snapshot();
+        after.retain_prefix("diff");
+        let jobs = after.channels.iter().find(|c| c.label == "diff/jobs");
+        jobs.is_some_and(|c| c.state == ChannelState::Closed && c.received_count == 3)
+            .then_some(after)
+    });
+
+    let diff = SnapshotDiff::between(&before, &after);
+    assert_eq!(diff.added_channels, vec!["diff/new".to_string()]);
+    assert!(diff.removed_channels.is_empty());
+    // Unchanged channels are left out
+    assert_eq!(diff.changed_channels.len(), 1, "{:?}", diff);
+    let jobs = &diff.changed_channels[0];
+    assert_eq!(jobs.label, "diff/jobs");
+    assert_eq!(jobs.sent_delta, 3);
+    assert_eq!(jobs.received_delta, 3);
+    assert_eq!(
+        jobs.state_change,
+        Some((ChannelState::Active, ChannelState::Closed))
+    );
+
+    let reverse = SnapshotDiff::between(&after, &before);
+    assert_eq!(reverse.removed_channels, vec!["diff/new".to_string()]);
+    assert_eq!(reverse.changed_channels[0].sent_delta, -3);
+    assert!(SnapshotDiff::between(&after, &after).is_empty());
+}
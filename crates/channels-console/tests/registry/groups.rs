@@ -0,0 +1,76 @@
+use channels_console::groups::group_by_source;
+use channels_console::SerializableChannelStats;
+
+use super::{channel_stats, common, setup};
+
+fn stats(id: u64, source: &str, label: &str, iter: u32, queued: u64) -> SerializableChannelStats {
+    SerializableChannelStats {
+        id,
+        source: source.to_string(),
+        has_custom_label: false,
+        sent_count: 10,
+        received_count: 10 - queued,
+        queued,
+        queued_bytes: queued * 4,
+        bytes_sent_total: 10 * 4,
+        bytes_recv_total: (10 - queued) * 4,
+        iter,
+        ..channel_stats(label)
+    }
+}
+
+#[test]
+fn test_group_by_source() {
+    let channels = vec![
+        stats(1, "worker.rs:10", "worker", 0, 2),
+        stats(2, "worker.rs:10", "worker-2", 1, 5),
+        stats(3, "main.rs:4", "events", 0, 1),
+        stats(4, "worker.rs:10", "worker-3", 2, 0),
+    ];
+
+    let groups = group_by_source(&channels);
+    assert_eq!(groups.len(), 2);
+
+    assert_eq!(groups[0].label, "events");
+    assert_eq!(groups[0].channels, 1);
+
+    let workers = &groups[1];
+    assert_eq!(workers.label, "worker");
+    assert_eq!(workers.source, "worker.rs:10");
+    assert_eq!(workers.channels, 3);
+    assert_eq!(workers.sent_count, 30);
+    assert_eq!(workers.received_count, 23);
+    assert_eq!(workers.queued, 7);
+    assert_eq!(workers.max_queued, 5);
+    assert_eq!(workers.queued_bytes, 28);
+    assert_eq!(workers.channel_ids, vec![1, 2, 4]);
+}
+
+#[test]
+fn test_group_by_source_in_loop() {
+    setup();
+
+    let mut receivers = Vec::new();
+    for _ in 0..3 {
+        let (tx, rx) = std::sync::mpsc::channel::<u32>();
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "grouped-loop");
+        tx.send(1).unwrap();
+        receivers.push((tx, rx));
+    }
+
+    // Sends are counted by the proxy thread, so wait for all of them
+    let groups = common::wait_for("all sends are counted", || {
+        let stats: Vec<_> = channels_console::snapshot()
+            .channels
+            .into_iter()
+            .filter(|stat| stat.label.starts_with("grouped-loop"))
+            .collect();
+        let groups = group_by_source(&stats);
+        let sent: u64 = groups.iter().map(|group| group.sent_count).sum();
+        (sent == 3).then_some(groups)
+    });
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].label, "grouped-loop");
+    assert_eq!(groups[0].channels, 3);
+    assert_eq!(groups[0].sent_count, 3);
+}
@@ -0,0 +1,41 @@
+use channels_console::ChannelsHandle;
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+#[test]
+fn test_channels_handle() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "handle/jobs", log = true);
+    let (other_tx, other_rx) = mpsc::channel::<u32>();
+    let (_other_tx, _other_rx) =
+        channels_console::channel!((other_tx, other_rx), label = "handle-other");
+
+    let handle = ChannelsHandle::new().prefix("handle");
+    let labels: Vec<String> = handle.channels().into_iter().map(|c| c.label).collect();
+    assert_eq!(labels, vec!["handle/jobs".to_string()]);
+    assert!(handle.channel("handle-other").is_none());
+    assert!(ChannelsHandle::new().channel("handle-other").is_some());
+
+    for i in 0..3 {
+        tx.send(i).unwrap();
+    }
+    for _ in 0..3 {
+        rx.recv().unwrap();
+    }
+    // The receive forwarder reports a message right after handing it over
+    common::wait_until("all receives are counted", || {
+        handle.channel("handle/jobs").unwrap().received_count == 3
+    });
+
+    let jobs = handle.channel("handle/jobs").unwrap();
+    assert_eq!(jobs.sent_count, 3);
+    assert_eq!(jobs.received_count, 3);
+    assert_eq!(handle.total_queued(), 0);
+
+    let logs = handle.channel_logs("handle/jobs").unwrap();
+    assert_eq!(logs.sent_logs.len(), 3);
+    assert_eq!(logs.sent_logs[0].message.as_deref(), Some("2"));
+}
@@ -0,0 +1,60 @@
+use channels_console::testing::stats_for;
+use tokio::sync::mpsc;
+
+use super::{common, setup};
+
+fn handles_of(label: &str) -> (Option<u64>, Option<u64>) {
+    let stats = stats_for(label).unwrap();
+    (stats.senders_alive, stats.receivers_alive)
+}
+
+fn wait_for_handles(label: &str, expected: (Option<u64>, Option<u64>)) {
+    common::wait_until("handles are counted", || handles_of(label) == expected);
+}
+
+#[tokio::test]
+async fn test_proxied_sender_clones() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(4);
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "handles-proxy");
+    assert_eq!(handles_of("handles-proxy"), (Some(1), Some(1)));
+
+    let tx2 = tx.clone();
+    let tx3 = tx.clone();
+    assert_eq!(handles_of("handles-proxy"), (Some(3), Some(1)));
+
+    drop(tx2);
+    drop(rx);
+    wait_for_handles("handles-proxy", (Some(2), Some(0)));
+
+    drop(tx);
+    drop(tx3);
+    wait_for_handles("handles-proxy", (Some(0), Some(0)));
+}
+
+#[tokio::test]
+async fn test_inline_sender_clones() {
+    setup();
+
+    let (tx, rx) = mpsc::unbounded_channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "handles-inline", mode = inline);
+    let tx2 = tx.clone();
+    assert_eq!(handles_of("handles-inline"), (Some(2), Some(1)));
+
+    drop(tx);
+    assert_eq!(handles_of("handles-inline"), (Some(1), Some(1)));
+
+    drop(rx);
+    drop(tx2);
+    assert_eq!(handles_of("handles-inline"), (Some(0), Some(0)));
+}
+
+#[test]
+fn test_std_channels_dont_report_handles() {
+    setup();
+
+    let (tx, rx) = std::sync::mpsc::channel::<u32>();
+    let (_tx, _rx) = channels_console::channel!((tx, rx), label = "handles-std");
+    assert_eq!(handles_of("handles-std"), (None, None));
+}
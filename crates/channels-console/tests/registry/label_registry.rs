@@ -0,0 +1,69 @@
+use channels_console::testing::stats_for;
+use channels_console::{flush, registry, snapshot};
+use std::sync::mpsc;
+
+use super::setup;
+
+#[test]
+fn test_label_registry() {
+    setup();
+
+    channels_console::register_label("registry-ingest", "Lines read from the socket");
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_ingest_tx, _ingest_rx) = channels_console::channel!((tx, rx), label = "registry-ingest");
+
+    // The same label copy-pasted to a different place
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_first_tx, _first_rx) = channels_console::channel!((tx, rx), label = "registry-worker");
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (_second_tx, _second_rx) = channels_console::channel!((tx, rx), label = "registry-worker");
+
+    // Channels created repeatedly at one place get suffixes instead
+    let mut pool = Vec::new();
+    for _ in 0..2 {
+        let (tx, rx) = mpsc::channel::<u32>();
+        pool.push(channels_console::channel!(
+            (tx, rx),
+            label = "registry-pool"
+        ));
+    }
+    flush();
+
+    let ingest = stats_for("registry-ingest").unwrap();
+    assert_eq!(
+        ingest.description.as_deref(),
+        Some("Lines read from the socket")
+    );
+    assert!(!ingest.label_conflict);
+
+    let workers: Vec<_> = snapshot()
+        .channels
+        .into_iter()
+        .filter(|channel| channel.label == "registry-worker")
+        .collect();
+    assert_eq!(workers.len(), 2);
+    assert!(workers.iter().all(|channel| channel.label_conflict));
+    assert!(workers.iter().all(|channel| channel.description.is_none()));
+    assert!(registry::has_label_conflict("registry-worker"));
+
+    assert!(!stats_for("registry-pool").unwrap().label_conflict);
+    assert!(!stats_for("registry-pool-2").unwrap().label_conflict);
+    assert!(!registry::has_label_conflict("registry-pool"));
+
+    let info = registry::find_channel("registry-worker").unwrap();
+    assert!(info.label_conflict);
+    assert_eq!(
+        registry::find_channel("registry-ingest")
+            .unwrap()
+            .description
+            .as_deref(),
+        Some("Lines read from the socket")
+    );
+
+    // Registering again replaces the description
+    channels_console::register_label("registry-ingest", "Raw socket lines");
+    assert_eq!(
+        registry::label_description("registry-ingest").as_deref(),
+        Some("Raw socket lines")
+    );
+}
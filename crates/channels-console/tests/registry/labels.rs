@@ -0,0 +1,56 @@
+use channels_console::labels::{collapse_label, group_by_level, label_depth, matches_prefix};
+use channels_console::SerializableChannelStats;
+
+use super::channel_stats;
+
+fn stats(label: &str, sent: u64, received: u64) -> SerializableChannelStats {
+    SerializableChannelStats {
+        sent_count: sent,
+        received_count: received,
+        queued: sent - received,
+        queued_bytes: (sent - received) * 4,
+        bytes_sent_total: sent * 4,
+        bytes_recv_total: received * 4,
+        ..channel_stats(label)
+    }
+}
+
+#[test]
+fn test_matches_prefix() {
+    assert!(matches_prefix("ingest/parser", "ingest"));
+    assert!(matches_prefix("ingest/parser", "ingest/"));
+    assert!(matches_prefix("ingest", "ingest"));
+    assert!(matches_prefix("anything", ""));
+    assert!(!matches_prefix("ingestion", "ingest"));
+    assert!(!matches_prefix("ingest", "ingest/parser"));
+}
+
+#[test]
+fn test_collapse_label() {
+    assert_eq!(label_depth("ingest/parser/retries"), 3);
+    assert_eq!(collapse_label("ingest/parser/retries", 1), "ingest");
+    assert_eq!(collapse_label("ingest/parser/retries", 2), "ingest/parser");
+    assert_eq!(
+        collapse_label("ingest/parser/retries", 5),
+        "ingest/parser/retries"
+    );
+    assert_eq!(collapse_label("flat", 1), "flat");
+}
+
+#[test]
+fn test_group_by_level() {
+    let channels = vec![
+        stats("ingest/parser", 10, 8),
+        stats("ingest/writer", 5, 5),
+        stats("egress/http", 3, 1),
+    ];
+
+    let groups = group_by_level(&channels, 1);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].label, "egress");
+    assert_eq!(groups[1].label, "ingest");
+    assert_eq!(groups[1].channels, 2);
+    assert_eq!(groups[1].sent_count, 15);
+    assert_eq!(groups[1].queued, 2);
+    assert_eq!(groups[1].queued_bytes, 8);
+}
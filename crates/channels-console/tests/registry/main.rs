@@ -0,0 +1,70 @@
+//! Looking up, grouping and comparing instrumented channels.
+#![cfg(not(feature = "noop"))]
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod diff;
+mod groups;
+mod handle;
+#[cfg(feature = "tokio")]
+mod handles;
+mod label_registry;
+mod labels;
+mod registry;
+mod shards;
+mod summary;
+
+use channels_console::{ChannelState, ChannelType, SerializableChannelStats};
+
+fn setup() {
+    // Don't bind the default metrics port used by the example processes
+    let _ = channels_console::Config::builder()
+        .disable_server()
+        .synchronous()
+        .install();
+}
+
+/// Stats of an idle unbounded channel of `u32`s, for tests of functions taking stats.
+fn channel_stats(label: &str) -> SerializableChannelStats {
+    SerializableChannelStats {
+        id: 0,
+        source: "main.rs:1".to_string(),
+        label: label.to_string(),
+        has_custom_label: true,
+        channel_type: ChannelType::Unbounded,
+        state: ChannelState::Active,
+        sent_count: 0,
+        received_count: 0,
+        queued: 0,
+        type_name: "u32".to_string(),
+        type_size: 4,
+        queued_bytes: 0,
+        bytes_sent_total: 0,
+        bytes_recv_total: 0,
+        measured_sizes: false,
+        iter: 0,
+        expected_rate: None,
+        rate_status: None,
+        send_rate: Default::default(),
+        recv_rate: Default::default(),
+        send_byte_rate: Default::default(),
+        recv_byte_rate: Default::default(),
+        latency: None,
+        blocked_ns: 0,
+        max_blocked_ns: 0,
+        send_failures_full: 0,
+        send_failures_closed: 0,
+        producers: Vec::new(),
+        consumers: Vec::new(),
+        senders_alive: None,
+        receivers_alive: None,
+        description: None,
+        label_conflict: false,
+        tags: Vec::new(),
+        created_ns: 0,
+        last_send_ns: None,
+        last_recv_ns: None,
+        idle_ns: 0,
+    }
+}
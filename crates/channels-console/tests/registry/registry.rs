@@ -0,0 +1,38 @@
+use channels_console::registry;
+use channels_console::{ChannelState, ChannelType};
+use std::sync::mpsc;
+
+use super::setup;
+
+#[test]
+fn test_registry_lookup() {
+    setup();
+
+    let (tx, rx) = mpsc::sync_channel::<u32>(4);
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "lookup/jobs", capacity = 4);
+    let (tx2, rx2) = mpsc::channel::<String>();
+    let (tx2, rx2) = channels_console::channel!((tx2, rx2), label = "lookup/events");
+
+    tx.send(1).unwrap();
+    rx.recv().unwrap();
+    tx2.send("event".to_string()).unwrap();
+    rx2.recv().unwrap();
+    channels_console::flush();
+
+    // Sorted by label
+    let labels: Vec<String> = registry::channels()
+        .into_iter()
+        .map(|c| c.label)
+        .filter(|label| label.starts_with("lookup/"))
+        .collect();
+    assert_eq!(labels, vec!["lookup/events", "lookup/jobs"]);
+
+    let jobs = registry::find_channel("lookup/jobs").expect("Missing channel");
+    assert_eq!(jobs.channel_type, ChannelType::Bounded(4));
+    assert_eq!(jobs.state, ChannelState::Active);
+    assert_eq!(jobs.type_name, "u32");
+    assert!(!jobs.is_closed());
+    assert!(registry::find_channel(jobs.source).is_some());
+    assert!(registry::find_channel("missing").is_none());
+    assert!(registry::streams().is_empty());
+}
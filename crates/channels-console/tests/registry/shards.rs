@@ -0,0 +1,55 @@
+use channels_console::testing::{logs_for, stats_for};
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+/// Label of the `i`th channel created at the same place.
+fn sharded_label(i: u64) -> String {
+    match i {
+        0 => "sharded".to_string(),
+        _ => format!("sharded-{}", i + 1),
+    }
+}
+
+#[test]
+fn test_channels_spread_over_shards() {
+    setup();
+
+    let mut channels = Vec::new();
+    for i in 0..40u32 {
+        let (tx, rx) = mpsc::channel::<u32>();
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "sharded", log = true);
+        for n in 0..=i {
+            tx.send(n).unwrap();
+            rx.recv().unwrap();
+        }
+        channels.push((tx, rx));
+    }
+    // The forwarders count a message after handing it over
+    for i in 0..40u64 {
+        common::wait_until("all messages are counted", || {
+            stats_for(&sharded_label(i)).unwrap().received_count == i + 1
+        });
+    }
+
+    // Sorted like in the console, regardless of the shard holding each channel
+    let snapshot = channels_console::snapshot();
+    let sent: Vec<u64> = snapshot
+        .channels
+        .iter()
+        .filter(|channel| channel.label.starts_with("sharded"))
+        .map(|channel| channel.sent_count)
+        .collect();
+    assert_eq!(sent, (1..=40).collect::<Vec<u64>>());
+
+    for i in 0..40u64 {
+        let label = sharded_label(i);
+        let stats = stats_for(&label).unwrap();
+        assert_eq!(stats.sent_count, i + 1);
+        assert_eq!(stats.received_count, i + 1);
+
+        let logs = logs_for(&label).unwrap();
+        assert_eq!(logs.sent_logs.len() as u64, i + 1);
+        assert_eq!(logs.received_logs.len() as u64, i + 1);
+    }
+}
@@ -0,0 +1,66 @@
+use channels_console::rates::RateWindows;
+use channels_console::summary::{StateCounts, Summary};
+use channels_console::{ChannelState, ChannelType, SerializableChannelStats};
+
+use super::channel_stats;
+
+fn stats(
+    id: u64,
+    label: &str,
+    state: ChannelState,
+    queued: u64,
+    rate: f64,
+) -> SerializableChannelStats {
+    SerializableChannelStats {
+        id,
+        channel_type: ChannelType::Bounded(10),
+        state,
+        sent_count: 10,
+        received_count: 10 - queued,
+        queued,
+        queued_bytes: queued * 4,
+        bytes_sent_total: 10 * 4,
+        bytes_recv_total: (10 - queued) * 4,
+        send_rate: RateWindows {
+            last_10s: rate,
+            ..Default::default()
+        },
+        ..channel_stats(label)
+    }
+}
+
+#[test]
+fn test_summary() {
+    let channels = vec![
+        stats(1, "ingest", ChannelState::Full, 10, 2.0),
+        stats(2, "parser", ChannelState::Active, 3, 7.5),
+        stats(3, "sink", ChannelState::Closed, 0, 0.0),
+    ];
+
+    let summary = Summary::new(&channels);
+    assert_eq!(summary.channels, 3);
+    assert_eq!(
+        summary.states,
+        StateCounts {
+            active: 1,
+            full: 1,
+            notified: 0,
+            closed: 1,
+        }
+    );
+    assert_eq!(summary.queued, 13);
+    assert_eq!(summary.queued_bytes, 52);
+    assert_eq!(summary.sent_count, 30);
+    assert_eq!(summary.received_count, 17);
+
+    let busiest = summary.busiest.expect("Missing busiest channel");
+    assert_eq!(busiest.id, 2);
+    assert_eq!(busiest.label, "parser");
+}
+
+#[test]
+fn test_summary_without_traffic() {
+    let summary = Summary::new(&[stats(1, "idle", ChannelState::Active, 0, 0.0)]);
+    assert_eq!(summary.busiest, None);
+    assert_eq!(Summary::new(&[]), Summary::default());
+}
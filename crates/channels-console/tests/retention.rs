@@ -0,0 +1,106 @@
+#![cfg(not(feature = "noop"))]
+
+mod common;
+
+#[cfg(test)]
+pub mod tests {
+    use super::common;
+    use channels_console::clock::ManualClock;
+    use channels_console::retention::closed_total;
+    use channels_console::testing::stats_for;
+    use channels_console::ChannelState;
+    use std::sync::{mpsc, OnceLock};
+    use std::time::Duration;
+
+    fn is_closed(label: &str) -> bool {
+        stats_for(label).is_some_and(|stats| stats.state == ChannelState::Closed)
+    }
+
+    fn setup() -> &'static ManualClock {
+        static CLOCK: OnceLock<ManualClock> = OnceLock::new();
+        CLOCK.get_or_init(|| {
+            let clock = ManualClock::new();
+            channels_console::Config::builder()
+                .clock(clock.clone())
+                .synchronous()
+                .disable_server()
+                .closed_retention(Duration::from_secs(60))
+                .max_channels(2)
+                .install()
+                .expect("Failed to install config");
+            clock
+        })
+    }
+
+    #[test]
+    fn test_closed_channels_eviction() {
+        let clock = setup();
+
+        let (tx, rx) = mpsc::channel::<u32>();
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "retention-a");
+        tx.send(1).unwrap();
+        assert_eq!(rx.recv().unwrap(), 1);
+        drop(tx);
+        drop(rx);
+        common::wait_until("retention-a is closed", || is_closed("retention-a"));
+
+        let (tx_b, rx_b) = mpsc::channel::<u32>();
+        let (tx_b, rx_b) = channels_console::channel!((tx_b, rx_b), label = "retention-b");
+
+        // Within the limit and the retention, so nothing is evicted over a few collector ticks
+        std::thread::sleep(Duration::from_millis(500));
+        assert!(stats_for("retention-a").is_some());
+
+        // Over the limit, so the oldest closed channel goes
+        let (_tx_c, _rx_c) = mpsc::channel::<u32>();
+        let (_tx_c, _rx_c) = channels_console::channel!((_tx_c, _rx_c), label = "retention-c");
+        common::wait_until("retention-a is evicted", || {
+            stats_for("retention-a").is_none()
+        });
+
+        let total = closed_total();
+        assert_eq!(total.channels, 1);
+        assert_eq!(total.sent_count, 1);
+        assert_eq!(total.received_count, 1);
+
+        drop(tx_b);
+        drop(rx_b);
+        common::wait_until("retention-b is closed", || is_closed("retention-b"));
+        // Closed within the retention, so it's kept until the clock moves on
+        std::thread::sleep(Duration::from_millis(500));
+        assert!(stats_for("retention-b").is_some());
+
+        clock.advance(Duration::from_secs(61));
+        common::wait_until("retention-b is evicted", || {
+            stats_for("retention-b").is_none()
+        });
+        assert_eq!(closed_total().channels, 2);
+        assert_eq!(channels_console::snapshot().closed_total, closed_total());
+        assert!(stats_for("retention-c").is_some());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_dropped_primitives_eviction() {
+        setup();
+
+        let held = channels_console::semaphore!(tokio::sync::Semaphore::new(1), label = "retained");
+        for _ in 0..5 {
+            let semaphore =
+                channels_console::semaphore!(tokio::sync::Semaphore::new(1), label = "evicted");
+            drop(semaphore);
+        }
+
+        let primitives = channels_console::primitives::primitives();
+        let evicted: Vec<_> = primitives
+            .iter()
+            .filter(|p| p.label.starts_with("evicted"))
+            .collect();
+        // The oldest dropped ones go while over the limit, labels stay unique
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].label, "evicted-5");
+        assert_eq!(evicted[0].state, ChannelState::Closed);
+        assert!(primitives.iter().any(|p| p.label == "retained"));
+        drop(held);
+    }
+}
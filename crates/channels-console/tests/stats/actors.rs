@@ -0,0 +1,63 @@
+use channels_console::testing::actors_for;
+use tokio::sync::mpsc;
+
+use super::setup;
+
+#[tokio::test]
+async fn test_inline_channel_actors() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(10);
+    let (tx, mut rx) = channels_console::channel!((tx, rx), label = "inline-actors", mode = inline);
+
+    let task_tx = tx.clone();
+    let task = tokio::spawn(async move {
+        for i in 0..3 {
+            task_tx.send(i).await.unwrap();
+        }
+    });
+    let task_id = task.id();
+    task.await.unwrap();
+
+    let thread_tx = tx.clone();
+    std::thread::Builder::new()
+        .name("actors-producer".into())
+        .spawn(move || thread_tx.blocking_send(3).unwrap())
+        .unwrap()
+        .join()
+        .unwrap();
+
+    for _ in 0..4 {
+        rx.recv().await.unwrap();
+    }
+
+    let actors = actors_for("inline-actors").unwrap();
+    let senders: Vec<_> = actors
+        .senders
+        .iter()
+        .map(|actor| (actor.actor.as_str(), actor.count))
+        .collect();
+    assert_eq!(
+        senders,
+        vec![
+            (format!("task {}", task_id).as_str(), 3),
+            ("thread actors-producer", 1)
+        ]
+    );
+    assert_eq!(actors.receivers.len(), 1);
+    assert_eq!(actors.receivers[0].count, 4);
+}
+
+#[test]
+fn test_proxied_channel_has_no_actors() {
+    setup();
+
+    let (tx, rx) = std::sync::mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "proxied-actors");
+    tx.send(1).unwrap();
+    rx.recv().unwrap();
+
+    let actors = actors_for("proxied-actors").unwrap();
+    assert!(actors.senders.is_empty());
+    assert!(actors.receivers.is_empty());
+}
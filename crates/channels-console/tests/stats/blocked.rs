@@ -0,0 +1,75 @@
+use channels_console::testing::stats_for;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::{common, setup};
+
+const RECEIVER_DELAY: Duration = Duration::from_millis(150);
+
+#[tokio::test]
+async fn test_blocked_time_proxy() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(1);
+    let (tx, mut rx) = channels_console::channel!((tx, rx), label = "blocked-proxy");
+
+    // Fills the proxy, inner and receiver buffers, so further sends wait for the receiver
+    let sender = tokio::spawn(async move {
+        for i in 0..6 {
+            tx.send(i).await.unwrap();
+        }
+    });
+
+    tokio::time::sleep(RECEIVER_DELAY).await;
+    for i in 0..6 {
+        assert_eq!(rx.recv().await, Some(i));
+    }
+    sender.await.unwrap();
+
+    let stats = stats_for("blocked-proxy").unwrap();
+    assert_eq!(stats.sent_count, 6);
+    assert!(stats.max_blocked_ns >= 100_000_000);
+    assert!(stats.blocked_ns >= stats.max_blocked_ns);
+}
+
+#[tokio::test]
+async fn test_blocked_time_inline() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(1);
+    let (tx, mut rx) =
+        channels_console::channel!((tx, rx), label = "blocked-inline", mode = inline);
+
+    tx.send(0).await.unwrap();
+    // Sends with free capacity complete right away
+    assert!(stats_for("blocked-inline").unwrap().blocked_ns < 10_000_000);
+
+    let receiver = tokio::spawn(async move {
+        tokio::time::sleep(RECEIVER_DELAY).await;
+        while rx.recv().await.is_some() {}
+    });
+
+    tx.send(1).await.unwrap();
+    let stats = stats_for("blocked-inline").unwrap();
+    assert!(stats.max_blocked_ns >= 100_000_000);
+    assert!(stats.blocked_ns >= stats.max_blocked_ns);
+
+    drop(tx);
+    receiver.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unbounded_never_blocks() {
+    setup();
+
+    let (tx, rx) = mpsc::unbounded_channel::<u32>();
+    let (tx, _rx) = channels_console::channel!((tx, rx), label = "blocked-unbounded");
+    for i in 0..10 {
+        tx.send(i).unwrap();
+    }
+    // The forwarder records a send after handing the message over
+    let stats = common::wait_for("all sends", || {
+        stats_for("blocked-unbounded").filter(|stats| stats.sent_count == 10)
+    });
+    assert_eq!(stats.blocked_ns, 0);
+}
@@ -0,0 +1,34 @@
+use channels_console::testing::stats_for;
+use channels_console::ChannelState;
+use futures_channel::oneshot;
+use std::time::Duration;
+
+use super::{common, setup};
+
+#[tokio::test]
+async fn test_dropped_receiver_cancels_sender() {
+    setup();
+
+    let (tx, rx) = oneshot::channel::<u32>();
+    let (mut tx, rx) = channels_console::channel!((tx, rx), label = "oneshot-canceled");
+    drop(rx);
+
+    tokio::time::timeout(Duration::from_secs(1), tx.cancellation())
+        .await
+        .expect("sender wasn't canceled");
+    assert!(tx.send(1).is_err());
+
+    common::wait_until("the channel is closed", || {
+        stats_for("oneshot-canceled").unwrap().state == ChannelState::Closed
+    });
+}
+
+#[tokio::test]
+async fn test_message_is_delivered() {
+    setup();
+
+    let (tx, rx) = oneshot::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "oneshot-delivered");
+    tx.send(7).unwrap();
+    assert_eq!(rx.await.unwrap(), 7);
+}
@@ -0,0 +1,387 @@
+use channels_console::testing::{states_for, stats_for};
+use channels_console::{ChannelState, ChannelType};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::setup;
+
+#[tokio::test]
+async fn test_inline_bounded_channel() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(4);
+    let (tx, mut rx) =
+        channels_console::channel!((tx, rx), label = "inline-bounded", mode = inline);
+
+    let stats = stats_for("inline-bounded").unwrap();
+    assert_eq!(stats.channel_type, ChannelType::Bounded(4));
+
+    for i in 0..4 {
+        tx.send(i).await.unwrap();
+    }
+    assert!(tx.try_send(4).is_err());
+
+    // Without a proxy, every buffered message is reported as queued
+    let stats = stats_for("inline-bounded").unwrap();
+    assert_eq!(stats.sent_count, 4);
+    assert_eq!(stats.queued, 4);
+    assert_eq!(stats.state, ChannelState::Full);
+
+    assert_eq!(rx.recv().await, Some(0));
+    assert_eq!(rx.try_recv().unwrap(), 1);
+
+    let stats = stats_for("inline-bounded").unwrap();
+    assert_eq!(stats.received_count, 2);
+    assert_eq!(stats.queued, 2);
+    assert_eq!(stats.state, ChannelState::Active);
+
+    let tx2 = tx.clone();
+    drop(tx);
+    assert_eq!(
+        stats_for("inline-bounded").unwrap().state,
+        ChannelState::Active
+    );
+
+    drop(tx2);
+    assert_eq!(
+        stats_for("inline-bounded").unwrap().state,
+        ChannelState::Closed
+    );
+    assert_eq!(rx.recv().await, Some(2));
+}
+
+#[tokio::test]
+async fn test_inline_unbounded_channel_with_log() {
+    setup();
+
+    let (tx, rx) = mpsc::unbounded_channel::<String>();
+    let (tx, mut rx) = channels_console::channel!(
+        (tx, rx),
+        label = "inline-unbounded",
+        mode = inline,
+        log = true
+    );
+
+    tx.send("hello".to_string()).unwrap();
+    assert_eq!(rx.recv().await.as_deref(), Some("hello"));
+
+    let stats = stats_for("inline-unbounded").unwrap();
+    assert_eq!(stats.channel_type, ChannelType::Unbounded);
+    assert_eq!(stats.sent_count, 1);
+    assert_eq!(stats.received_count, 1);
+    assert_eq!(stats.queued, 0);
+
+    let logs = channels_console::testing::logs_for("inline-unbounded").unwrap();
+    assert_eq!(logs.sent_logs[0].message.as_deref(), Some("\"hello\""));
+
+    drop(rx);
+    assert_eq!(
+        stats_for("inline-unbounded").unwrap().state,
+        ChannelState::Closed
+    );
+    assert!(tx.send("late".to_string()).is_err());
+}
+
+#[tokio::test]
+async fn test_inline_channel_with_log_with() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<(u32, Vec<u8>)>(4);
+    let (tx, mut rx) = channels_console::channel!(
+        (tx, rx),
+        label = "inline-log-with",
+        mode = inline,
+        log_with = |msg: &(u32, Vec<u8>)| format!("#{} ({} bytes)", msg.0, msg.1.len())
+    );
+
+    tx.send((7, vec![0; 1024])).await.unwrap();
+    assert_eq!(rx.recv().await.map(|msg| msg.0), Some(7));
+
+    let logs = channels_console::testing::logs_for("inline-log-with").unwrap();
+    assert_eq!(
+        logs.sent_logs[0].message.as_deref(),
+        Some("#7 (1024 bytes)")
+    );
+    assert_eq!(
+        logs.received_logs[0].message.as_deref(),
+        Some("#7 (1024 bytes)")
+    );
+}
+
+#[tokio::test]
+async fn test_inline_state_transitions() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(2);
+    let (tx, mut rx) = channels_console::channel!((tx, rx), label = "inline-states", mode = inline);
+
+    tx.send(0).await.unwrap();
+    tx.send(1).await.unwrap();
+    rx.recv().await.unwrap();
+    drop(tx);
+
+    let transitions = states_for("inline-states").unwrap().transitions;
+    let states: Vec<_> = transitions.iter().map(|t| t.state).collect();
+    assert_eq!(
+        states,
+        vec![
+            ChannelState::Active,
+            ChannelState::Full,
+            ChannelState::Active,
+            ChannelState::Closed
+        ]
+    );
+    assert!(transitions
+        .windows(2)
+        .all(|w| w[0].timestamp <= w[1].timestamp));
+}
+
+#[tokio::test]
+async fn test_inline_capacity_matches_inner_channel() {
+    setup();
+
+    let (plain_tx, _plain_rx) = mpsc::channel::<u32>(3);
+    let (tx, rx) = mpsc::channel::<u32>(3);
+    let (tx, mut rx) =
+        channels_console::channel!((tx, rx), label = "inline-capacity", mode = inline);
+
+    for i in 0..3 {
+        assert_eq!(tx.capacity(), plain_tx.capacity());
+        tx.try_send(i).unwrap();
+        plain_tx.try_send(i).unwrap();
+    }
+    assert_eq!(tx.capacity(), 0);
+    assert!(matches!(
+        tx.try_send(3),
+        Err(mpsc::error::TrySendError::Full(3))
+    ));
+    assert!(matches!(
+        plain_tx.try_send(3),
+        Err(mpsc::error::TrySendError::Full(3))
+    ));
+    assert!(tx.try_reserve().is_err());
+
+    rx.recv().await.unwrap();
+    assert_eq!(tx.capacity(), 1);
+}
+
+#[tokio::test]
+async fn test_inline_permits_and_recv_many() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(4);
+    let (tx, mut rx) =
+        channels_console::channel!((tx, rx), label = "inline-permits", mode = inline);
+
+    tx.reserve().await.unwrap().send(0);
+    tx.try_reserve().unwrap().send(1);
+    tx.send_timeout(2, Duration::from_secs(1)).await.unwrap();
+    // Reserved capacity is not a message until the permit is used
+    let permit = tx.reserve().await.unwrap();
+    assert_eq!(stats_for("inline-permits").unwrap().sent_count, 3);
+    permit.send(3);
+    assert_eq!(stats_for("inline-permits").unwrap().sent_count, 4);
+
+    let mut buffer = Vec::new();
+    assert_eq!(rx.recv_many(&mut buffer, 3).await, 3);
+    assert_eq!(buffer, vec![0, 1, 2]);
+    let msg = std::future::poll_fn(|cx| rx.poll_recv(cx)).await;
+    assert_eq!(msg, Some(3));
+
+    let stats = stats_for("inline-permits").unwrap();
+    assert_eq!(stats.received_count, 4);
+    assert_eq!(stats.queued, 0);
+
+    drop(tx);
+    assert_eq!(rx.recv_many(&mut buffer, 1).await, 0);
+    assert_eq!(
+        stats_for("inline-permits").unwrap().state,
+        ChannelState::Closed
+    );
+}
+
+#[tokio::test]
+async fn test_inline_rejected_sends() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(1);
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "inline-rejected", mode = inline);
+
+    tx.try_send(0).unwrap();
+    assert!(tx.try_send(1).is_err());
+    assert!(tx.try_reserve().is_err());
+    assert!(tx.send_timeout(2, Duration::from_millis(10)).await.is_err());
+    let stats = stats_for("inline-rejected").unwrap();
+    assert_eq!(stats.sent_count, 1);
+    assert_eq!(stats.send_failures_full, 3);
+    assert_eq!(stats.send_failures_closed, 0);
+
+    drop(rx);
+    assert!(tx.send(3).await.is_err());
+    assert!(tx.try_send(4).is_err());
+    let stats = stats_for("inline-rejected").unwrap();
+    assert_eq!(stats.sent_count, 1);
+    assert_eq!(stats.send_failures_full, 3);
+    assert_eq!(stats.send_failures_closed, 2);
+}
+
+#[tokio::test]
+async fn test_inline_owned_and_many_permits() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(3);
+    let (tx, mut rx) = channels_console::channel!((tx, rx), label = "inline-owned", mode = inline);
+
+    let tx = tx.reserve_owned().await.unwrap().send(0);
+    let tx = tx.try_reserve_owned().unwrap().release();
+    let permits = tx.reserve_many(2).await.unwrap();
+    assert_eq!(permits.len(), 2);
+    for (permit, msg) in permits.zip(1..) {
+        permit.send(msg);
+    }
+    assert_eq!(stats_for("inline-owned").unwrap().sent_count, 3);
+
+    // A full channel hands the sender back and counts the rejection
+    let tx = match tx.try_reserve_owned() {
+        Err(mpsc::error::TrySendError::Full(tx)) => tx,
+        other => panic!("expected a full channel, got {:?}", other),
+    };
+    assert!(tx.try_reserve_many(1).is_err());
+    assert_eq!(stats_for("inline-owned").unwrap().send_failures_full, 2);
+
+    // Waiting for capacity is reported as blocked time
+    let waiting = tokio::spawn(async move { tx.reserve_owned().await.unwrap().send(3) });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(rx.recv().await, Some(0));
+    let tx = waiting.await.unwrap();
+    let stats = stats_for("inline-owned").unwrap();
+    assert_eq!(stats.sent_count, 4);
+    assert!(stats.max_blocked_ns >= 10_000_000);
+
+    drop(tx);
+    let mut buffer = Vec::new();
+    assert_eq!(rx.recv_many(&mut buffer, 4).await, 3);
+    assert_eq!(buffer, vec![1, 2, 3]);
+    assert_eq!(
+        stats_for("inline-owned").unwrap().state,
+        ChannelState::Closed
+    );
+}
+
+#[tokio::test]
+async fn test_inline_weak_senders() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>(4);
+    let (tx, mut rx) = channels_console::channel!((tx, rx), label = "inline-weak", mode = inline);
+
+    let weak = tx.downgrade();
+    let upgraded = weak.upgrade().unwrap();
+    assert_eq!(stats_for("inline-weak").unwrap().senders_alive, Some(2));
+    upgraded.send(1).await.unwrap();
+    drop(upgraded);
+    assert_eq!(stats_for("inline-weak").unwrap().senders_alive, Some(1));
+
+    // Weak senders don't keep the channel open
+    drop(tx);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(rx.recv().await, Some(1));
+    assert_eq!(rx.recv().await, None);
+    let stats = stats_for("inline-weak").unwrap();
+    assert_eq!(stats.sent_count, 1);
+    assert_eq!(stats.senders_alive, Some(0));
+    assert_eq!(stats.state, ChannelState::Closed);
+
+    let (tx, rx) = mpsc::unbounded_channel::<u32>();
+    let (tx, mut rx) =
+        channels_console::channel!((tx, rx), label = "inline-weak-unbounded", mode = inline);
+    let weak = tx.downgrade();
+    weak.clone().upgrade().unwrap().send(1).unwrap();
+    drop(tx);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(rx.recv().await, Some(1));
+    assert_eq!(stats_for("inline-weak-unbounded").unwrap().sent_count, 1);
+}
+
+#[test]
+fn test_inline_receive_paths() {
+    setup();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let (tx, rx) = mpsc::channel::<u32>(16);
+    let (tx, mut rx) =
+        channels_console::channel!((tx, rx), label = "inline-receive-paths", mode = inline);
+    for i in 0..10 {
+        tx.try_send(i).unwrap();
+    }
+
+    let mut buffer = Vec::new();
+    runtime.block_on(async {
+        assert_eq!(rx.recv().await, Some(0));
+        assert_eq!(rx.recv_many(&mut buffer, 2).await, 2);
+    });
+    assert_eq!(rx.try_recv().unwrap(), 3);
+    assert_eq!(rx.blocking_recv(), Some(4));
+    assert_eq!(rx.blocking_recv_many(&mut buffer, 2), 2);
+    let waker = futures_util::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(rx.poll_recv(&mut cx), Poll::Ready(Some(7)));
+    assert_eq!(rx.poll_recv_many(&mut cx, &mut buffer, 2), Poll::Ready(2));
+    assert_eq!(buffer, [1, 2, 5, 6, 8, 9]);
+
+    let stats = stats_for("inline-receive-paths").unwrap();
+    assert_eq!(stats.received_count, 10);
+    assert_eq!(stats.queued, 0);
+
+    rx.close();
+    assert!(tx.try_send(10).is_err());
+}
+
+#[test]
+fn test_inline_unbounded_receive_paths() {
+    setup();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let (tx, rx) = mpsc::unbounded_channel::<u64>();
+    let (tx, mut rx) = channels_console::channel!(
+        (tx, rx),
+        label = "inline-unbounded-receive-paths",
+        mode = inline
+    );
+    for i in 0..10 {
+        tx.send(i).unwrap();
+    }
+    assert_eq!(
+        stats_for("inline-unbounded-receive-paths")
+            .unwrap()
+            .queued_bytes,
+        80
+    );
+
+    let mut buffer = Vec::new();
+    runtime.block_on(async {
+        assert_eq!(rx.recv().await, Some(0));
+        assert_eq!(rx.recv_many(&mut buffer, 2).await, 2);
+    });
+    assert_eq!(rx.try_recv().unwrap(), 3);
+    assert_eq!(rx.blocking_recv(), Some(4));
+    assert_eq!(rx.blocking_recv_many(&mut buffer, 2), 2);
+    let waker = futures_util::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(rx.poll_recv(&mut cx), Poll::Ready(Some(7)));
+    assert_eq!(rx.poll_recv_many(&mut cx, &mut buffer, 2), Poll::Ready(2));
+    assert_eq!(buffer, [1, 2, 5, 6, 8, 9]);
+
+    let stats = stats_for("inline-unbounded-receive-paths").unwrap();
+    assert_eq!(stats.received_count, 10);
+    assert_eq!(stats.queued, 0);
+    assert_eq!(stats.queued_bytes, 0);
+
+    rx.close();
+    assert!(tx.send(10).is_err());
+}
@@ -0,0 +1,88 @@
+use channels_console::testing::logs_for;
+use serde::Serialize;
+use serde_json::json;
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+#[derive(Debug, Serialize)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Request {
+    user: User,
+    tags: Vec<&'static str>,
+}
+
+#[test]
+fn test_json_logs() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<Request>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "json-logs", log = json);
+    for (id, name) in [(1, "alice"), (2, "bob"), (1, "alice")] {
+        tx.send(Request {
+            user: User {
+                id,
+                name: name.to_string(),
+            },
+            tags: vec!["api"],
+        })
+        .unwrap();
+        rx.recv().unwrap();
+    }
+    // The forwarder records messages after handing them over
+    let mut logs = common::wait_for("logged messages", || {
+        logs_for("json-logs")
+            .filter(|logs| logs.sent_logs.len() == 3 && logs.received_logs.len() == 3)
+    });
+    let entry = &logs.sent_logs[0];
+    assert_eq!(
+        entry.message.as_deref(),
+        Some(r#"{"user":{"id":1,"name":"alice"},"tags":["api"]}"#)
+    );
+    assert_eq!(
+        entry.json,
+        Some(json!({"user": {"id": 1, "name": "alice"}, "tags": ["api"]}))
+    );
+    assert_eq!(entry.json_field("user.name"), Some(&json!("alice")));
+    assert_eq!(entry.json_field("tags.0"), Some(&json!("api")));
+    assert_eq!(entry.json_field("user.email"), None);
+
+    logs.retain_field("user.id", &json!(1));
+    assert_eq!(logs.sent_logs.len(), 2);
+    assert_eq!(logs.received_logs.len(), 2);
+    assert!(logs
+        .sent_logs
+        .iter()
+        .all(|entry| entry.json_field("user.name") == Some(&json!("alice"))));
+
+    // Other channels keep plain Debug logs
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "debug-logs", log = true);
+    tx.send(7).unwrap();
+    rx.recv().unwrap();
+    let logs = common::wait_for("a logged send", || {
+        logs_for("debug-logs").filter(|logs| !logs.sent_logs.is_empty())
+    });
+    assert_eq!(logs.sent_logs[0].message.as_deref(), Some("7"));
+    assert_eq!(logs.sent_logs[0].json, None);
+}
+
+#[test]
+fn test_json_field_escapes_keys() {
+    let entry: channels_console::LogEntry = serde_json::from_value(json!({
+        "index": 0,
+        "timestamp": 0,
+        "message": null,
+        "json": {"a/b": {"c~d": 1}, "a": {"b": 2}, "~1": 3},
+    }))
+    .unwrap();
+
+    assert_eq!(entry.json_field("a/b.c~d"), Some(&json!(1)));
+    assert_eq!(entry.json_field("a.b"), Some(&json!(2)));
+    assert_eq!(entry.json_field("~1"), Some(&json!(3)));
+}
@@ -0,0 +1,45 @@
+use channels_console::testing::{logs_for, stats_for};
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+#[test]
+fn test_log_limit_per_channel() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (noisy_tx, noisy_rx) =
+        channels_console::channel!((tx, rx), label = "limit-noisy", log = true, log_limit = 5);
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (critical_tx, critical_rx) =
+        channels_console::channel!((tx, rx), log_limit = 100, label = "limit-critical");
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (default_tx, default_rx) = channels_console::channel!((tx, rx), label = "limit-default");
+
+    for i in 0..60 {
+        noisy_tx.send(i).unwrap();
+        critical_tx.send(i).unwrap();
+        default_tx.send(i).unwrap();
+    }
+    for _ in 0..60 {
+        noisy_rx.recv().unwrap();
+        critical_rx.recv().unwrap();
+        default_rx.recv().unwrap();
+    }
+    // The forwarders record a send after handing the message over
+    for label in ["limit-noisy", "limit-critical", "limit-default"] {
+        common::wait_until("all sends are counted", || {
+            stats_for(label).unwrap().sent_count == 60
+        });
+    }
+
+    let noisy = logs_for("limit-noisy").unwrap();
+    assert_eq!(noisy.sent_logs.len(), 5);
+    assert_eq!(noisy.sent_logs[0].message.as_deref(), Some("59"));
+
+    assert_eq!(logs_for("limit-critical").unwrap().sent_logs.len(), 60);
+    assert_eq!(
+        logs_for("limit-default").unwrap().sent_logs.len(),
+        channels_console::Config::current().log_limit()
+    );
+}
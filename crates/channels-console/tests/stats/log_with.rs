@@ -0,0 +1,72 @@
+use channels_console::testing::logs_for;
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+/// Not `Debug`, so it can only be logged with `log_with`
+struct Payment {
+    id: u64,
+    card_number: String,
+}
+
+#[test]
+fn test_log_with_formatter() {
+    setup();
+
+    let (tx, rx) = mpsc::sync_channel::<Payment>(10);
+    let (tx, rx) = channels_console::channel!(
+        (tx, rx),
+        label = "log-with",
+        capacity = 10,
+        log_with = |payment: &Payment| format!("payment {}", payment.id)
+    );
+
+    for id in 0..3 {
+        tx.send(Payment {
+            id,
+            card_number: "4111 1111 1111 1111".to_string(),
+        })
+        .unwrap();
+    }
+    for _ in 0..3 {
+        assert!(!rx.recv().unwrap().card_number.is_empty());
+    }
+
+    // Sends are reported by the forwarder after handing the message over
+    let logs = common::wait_for("logged messages", || {
+        logs_for("log-with").filter(|logs| logs.sent_logs.len() == 3)
+    });
+    let messages: Vec<_> = logs
+        .sent_logs
+        .iter()
+        .map(|entry| entry.message.as_deref().unwrap())
+        .collect();
+    assert_eq!(messages, vec!["payment 2", "payment 1", "payment 0"]);
+}
+
+#[test]
+fn test_received_messages_logged() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "log-received", log = true);
+
+    for i in 0..3 {
+        tx.send(i).unwrap();
+    }
+    for _ in 0..3 {
+        rx.recv().unwrap();
+    }
+
+    // Receives are reported by the forwarder after handing the message over
+    let logs = common::wait_for("logged messages", || {
+        logs_for("log-received").filter(|logs| logs.received_logs.len() == 3)
+    });
+
+    let messages: Vec<_> = logs
+        .received_logs
+        .iter()
+        .map(|entry| entry.message.as_deref().unwrap())
+        .collect();
+    assert_eq!(messages, vec!["2", "1", "0"]);
+}
@@ -0,0 +1,36 @@
+//! Per-channel stats, applied in place so counters are exact right after each call.
+#![cfg(not(feature = "noop"))]
+#![cfg_attr(std_mpmc, feature(mpmc_channel))]
+
+#[path = "../common/mod.rs"]
+mod common;
+
+#[cfg(feature = "tokio")]
+mod actors;
+#[cfg(feature = "tokio")]
+mod blocked;
+#[cfg(feature = "crossbeam")]
+mod consumers;
+#[cfg(feature = "futures")]
+mod futures_oneshot;
+#[cfg(feature = "tokio")]
+mod inline_tokio;
+mod json_logs;
+mod log_limit;
+mod log_with;
+#[cfg(feature = "tokio")]
+mod primitives;
+mod producers;
+mod rates;
+mod sinks;
+mod sizes;
+#[cfg(std_mpmc)]
+mod std_mpmc;
+
+fn setup() {
+    // Don't bind the default metrics port used by the example processes
+    let _ = channels_console::Config::builder()
+        .disable_server()
+        .synchronous()
+        .install();
+}
@@ -0,0 +1,81 @@
+use channels_console::primitives::{primitives, PrimitiveDetails, SerializablePrimitiveStats};
+use channels_console::ChannelState;
+use std::sync::Arc;
+use tokio::sync::{Notify, Semaphore};
+
+use super::setup;
+
+fn stats_for(label: &str) -> SerializablePrimitiveStats {
+    primitives()
+        .into_iter()
+        .find(|primitive| primitive.label == label)
+        .unwrap_or_else(|| panic!("Missing primitive {label}"))
+}
+
+#[tokio::test]
+async fn test_semaphore_permits() {
+    setup();
+
+    let pool = channels_console::semaphore!(Semaphore::new(3), label = "db-pool");
+    let first = pool.acquire().await.unwrap();
+    let many = pool.acquire_many(2).await.unwrap();
+    assert!(pool.try_acquire().is_err());
+
+    assert_eq!(
+        stats_for("db-pool").details,
+        PrimitiveDetails::Semaphore {
+            available: 0,
+            held: 3,
+            acquired: 3,
+            waiting: 0,
+        }
+    );
+
+    drop(many);
+    first.forget();
+    assert_eq!(
+        stats_for("db-pool").details,
+        PrimitiveDetails::Semaphore {
+            available: 2,
+            held: 0,
+            acquired: 3,
+            waiting: 0,
+        }
+    );
+
+    drop(pool);
+    assert_eq!(stats_for("db-pool").state, ChannelState::Closed);
+}
+
+#[tokio::test]
+async fn test_notify_counts() {
+    setup();
+
+    let notify = Arc::new(channels_console::notify!(Notify::new(), label = "wakeup"));
+
+    let waiter = {
+        let notify = Arc::clone(&notify);
+        tokio::spawn(async move { notify.notified().await })
+    };
+    while !matches!(
+        stats_for("wakeup").details,
+        PrimitiveDetails::Notify { waiting: 1, .. }
+    ) {
+        tokio::task::yield_now().await;
+    }
+
+    notify.notify_waiters();
+    waiter.await.unwrap();
+    notify.notify_one();
+    notify.notified().await;
+
+    assert_eq!(
+        stats_for("wakeup").details,
+        PrimitiveDetails::Notify {
+            notify_one: 1,
+            notify_waiters: 1,
+            notified: 2,
+            waiting: 0,
+        }
+    );
+}
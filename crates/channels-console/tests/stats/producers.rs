@@ -0,0 +1,81 @@
+use channels_console::producers::ProducerStats;
+use channels_console::testing::stats_for;
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+fn producers_of(label: &str) -> Vec<ProducerStats> {
+    stats_for(label)
+        .map(|stats| stats.producers)
+        .unwrap_or_default()
+}
+
+fn wait_for_producers(label: &str, expected: &[(&str, u64)]) {
+    let expected: Vec<ProducerStats> = expected
+        .iter()
+        .map(|(label, sent_count)| ProducerStats {
+            label: label.to_string(),
+            sent_count: *sent_count,
+        })
+        .collect();
+    common::wait_until("producers are counted", || producers_of(label) == expected);
+}
+
+#[test]
+fn test_sends_are_attributed_to_producers() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "fan-in");
+    let api_tx = channels_console::sender!(tx.clone(), channel = "fan-in", label = "api");
+    let cron_tx = channels_console::sender!(tx.clone(), label = "cron", channel = "fan-in");
+
+    // Registered producers show up before sending anything
+    wait_for_producers("fan-in", &[("api", 0), ("cron", 0)]);
+
+    for i in 0..3 {
+        api_tx.send(i).unwrap();
+    }
+    cron_tx.send(10).unwrap();
+    tx.send(20).unwrap();
+
+    let mut received: Vec<u32> = (0..5).map(|_| rx.recv().unwrap()).collect();
+    received.sort();
+    assert_eq!(received, vec![0, 1, 2, 10, 20]);
+
+    wait_for_producers("fan-in", &[("api", 3), ("cron", 1)]);
+    // The forwarder records a send after handing the message over, so it can lag behind
+    common::wait_until("all sends are counted", || {
+        stats_for("fan-in").unwrap().sent_count == 5
+    });
+}
+
+#[test]
+fn test_unknown_channel_leaves_sender_untouched() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let tx = channels_console::sender!(tx, channel = "no-such-channel");
+    tx.send(1).unwrap();
+    assert_eq!(rx.recv().unwrap(), 1);
+    assert!(stats_for("no-such-channel").is_none());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_tokio_producers() {
+    setup();
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<u32>(8);
+    let (tx, mut rx) = channels_console::channel!((tx, rx), label = "tokio-fan-in");
+    let producer_tx =
+        channels_console::sender!(tx.clone(), channel = "tokio-fan-in", label = "worker");
+    assert_eq!(producer_tx.max_capacity(), 8);
+
+    producer_tx.send(1).await.unwrap();
+    producer_tx.send(2).await.unwrap();
+    assert_eq!(rx.recv().await, Some(1));
+    assert_eq!(rx.recv().await, Some(2));
+
+    wait_for_producers("tokio-fan-in", &[("worker", 2)]);
+}
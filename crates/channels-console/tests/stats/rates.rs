@@ -0,0 +1,75 @@
+use channels_console::parse_rate;
+use channels_console::rates::RateStatus;
+use channels_console::testing::stats_for;
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+#[test]
+fn test_parse_rate() {
+    assert_eq!(parse_rate("100/s"), Some(100.0));
+    assert_eq!(parse_rate("120/m"), Some(2.0));
+    assert_eq!(parse_rate("3/500ms"), Some(6.0));
+    assert_eq!(parse_rate("5"), Some(5.0));
+    assert_eq!(parse_rate("fast"), None);
+    assert_eq!(parse_rate("1/0s"), None);
+}
+
+#[test]
+fn test_expected_rate_status() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (slow_tx, _slow_rx) =
+        channels_console::channel!((tx, rx), label = "rates-slow", expected_rate = "1000/s");
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (fast_tx, _fast_rx) =
+        channels_console::channel!((tx, rx), label = "rates-fast", expected_rate = "1/m");
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (plain_tx, _plain_rx) = channels_console::channel!((tx, rx), label = "rates-plain");
+
+    slow_tx.send(1).unwrap();
+    for i in 0..50 {
+        fast_tx.send(i).unwrap();
+    }
+    plain_tx.send(1).unwrap();
+
+    let wait_for_sent = |label: &str, count: u64| {
+        common::wait_for("sends are counted", || {
+            stats_for(label).filter(|stats| stats.sent_count >= count)
+        })
+    };
+
+    let slow = wait_for_sent("rates-slow", 1);
+    assert_eq!(slow.expected_rate, Some(1000.0));
+    assert_eq!(slow.rate_status, Some(RateStatus::Below));
+
+    let fast = wait_for_sent("rates-fast", 50);
+    assert_eq!(fast.rate_status, Some(RateStatus::Above));
+
+    let plain = wait_for_sent("rates-plain", 1);
+    assert_eq!(plain.expected_rate, None);
+    assert_eq!(plain.rate_status, None);
+}
+
+#[test]
+fn test_send_and_recv_rates() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "rates-windows");
+
+    for i in 0..20 {
+        tx.send(i).unwrap();
+    }
+    for _ in 0..20 {
+        rx.recv().unwrap();
+    }
+    channels_console::testing::assert_channel_drained("rates-windows");
+
+    // Younger than all windows, so rates are averaged over the first second
+    let stats = stats_for("rates-windows").unwrap();
+    assert_eq!(stats.send_rate.last_1s, 20.0);
+    assert_eq!(stats.send_rate.last_60s, 20.0);
+    assert_eq!(stats.recv_rate.last_10s, 20.0);
+}
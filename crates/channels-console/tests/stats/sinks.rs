@@ -0,0 +1,83 @@
+use channels_console::testing::sink_stats_for;
+use channels_console::ChannelState;
+use futures_util::{Sink, SinkExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::setup;
+
+/// Sink collecting items, failing on the `reject`ed one.
+struct FlakySink {
+    items: Vec<u32>,
+    reject: u32,
+}
+
+impl Sink<u32> for FlakySink {
+    type Error = String;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), String>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: u32) -> Result<(), String> {
+        if item == self.reject {
+            return Err(format!("rejected {}", item));
+        }
+        self.get_mut().items.push(item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), String>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), String>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn test_sink_counts_items_flushes_and_errors() {
+    setup();
+
+    let sink = FlakySink {
+        items: Vec::new(),
+        reject: 3,
+    };
+    let mut sink = channels_console::sink!(sink, label = "flaky-sink");
+
+    for i in 1..=4 {
+        let _ = sink.send(i).await;
+    }
+
+    let stats = sink_stats_for("flaky-sink").unwrap();
+    assert_eq!(stats.items_accepted, 3);
+    assert_eq!(stats.errors, 1);
+    // `send` flushes after every accepted item
+    assert_eq!(stats.flushes, 3);
+    assert_eq!(stats.state, ChannelState::Active);
+
+    sink.close().await.unwrap();
+    assert_eq!(
+        sink_stats_for("flaky-sink").unwrap().state,
+        ChannelState::Closed
+    );
+}
+
+#[test]
+fn test_dropped_sink_is_closed() {
+    setup();
+
+    let sink = futures_util::sink::drain::<u32>();
+    let sink = channels_console::sink!(sink, label = "drain-sink");
+    assert_eq!(
+        sink_stats_for("drain-sink").unwrap().state,
+        ChannelState::Active
+    );
+
+    drop(sink);
+    assert_eq!(
+        sink_stats_for("drain-sink").unwrap().state,
+        ChannelState::Closed
+    );
+}
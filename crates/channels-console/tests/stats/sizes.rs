@@ -0,0 +1,111 @@
+use channels_console::sizes::MessageSize;
+use channels_console::testing::stats_for;
+use channels_console::SerializableChannelStats;
+use std::mem;
+use std::sync::mpsc;
+
+use super::{common, setup};
+
+/// Proxied channels count messages after forwarding them, so the last ones may lag behind
+fn wait_for_received(label: &str, count: u64) -> SerializableChannelStats {
+    common::wait_for("receives are counted", || {
+        stats_for(label).filter(|stats| stats.sent_count >= count && stats.received_count >= count)
+    })
+}
+
+struct Frame {
+    payload: Vec<u8>,
+}
+
+#[test]
+fn test_message_size_impls() {
+    let vec_size = mem::size_of::<Vec<u32>>();
+    assert_eq!(vec![0u32; 10].message_size(), vec_size + 40);
+    assert_eq!(
+        String::from("hello").message_size(),
+        mem::size_of::<String>() + 5
+    );
+}
+
+#[test]
+fn test_byte_totals() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<Frame>();
+    let (tx, rx) = channels_console::channel!(
+        (tx, rx),
+        size_with = |frame| frame.payload.len(),
+        label = "sizes-frames"
+    );
+    let (plain_tx, plain_rx) = mpsc::channel::<u64>();
+    let (plain_tx, plain_rx) =
+        channels_console::channel!((plain_tx, plain_rx), label = "sizes-plain");
+
+    for len in [100, 200, 300] {
+        tx.send(Frame {
+            payload: vec![0; len],
+        })
+        .unwrap();
+        plain_tx.send(len as u64).unwrap();
+    }
+    for _ in 0..3 {
+        rx.recv().unwrap();
+        plain_rx.recv().unwrap();
+    }
+
+    let stats = wait_for_received("sizes-frames", 3);
+    assert!(stats.measured_sizes);
+    assert_eq!(stats.bytes_sent_total, 600);
+    assert_eq!(stats.bytes_recv_total, 600);
+    assert_eq!(stats.queued_bytes, 0);
+
+    // Channels without a size function count `size_of::<T>()` per message
+    let stats = wait_for_received("sizes-plain", 3);
+    assert!(!stats.measured_sizes);
+    assert_eq!(stats.bytes_sent_total, 24);
+    assert_eq!(stats.bytes_recv_total, 24);
+}
+
+#[test]
+fn test_byte_rates() {
+    setup();
+
+    let (tx, rx) = mpsc::channel::<String>();
+    let (tx, rx) =
+        channels_console::channel!((tx, rx), label = "sizes-rates", size_with = |msg| msg.len());
+
+    for _ in 0..20 {
+        tx.send("x".repeat(50)).unwrap();
+    }
+    for _ in 0..20 {
+        rx.recv().unwrap();
+    }
+    // Younger than all windows, so rates are averaged over the first second
+    let stats = wait_for_received("sizes-rates", 20);
+    assert_eq!(stats.send_byte_rate.last_1s, 1000.0);
+    assert_eq!(stats.send_byte_rate.last_60s, 1000.0);
+    assert_eq!(stats.recv_byte_rate.last_10s, 1000.0);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_queued_bytes_measured() {
+    setup();
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<Vec<u8>>(10);
+    let (tx, mut rx) =
+        channels_console::channel!((tx, rx), label = "sizes-queued", mode = inline, size = true);
+
+    let vec_size = mem::size_of::<Vec<u8>>() as u64;
+    tx.send(vec![0; 1000]).await.unwrap();
+    tx.send(vec![0; 10]).await.unwrap();
+    assert_eq!(
+        stats_for("sizes-queued").unwrap().queued_bytes,
+        2 * vec_size + 1010
+    );
+
+    rx.recv().await.unwrap();
+    let stats = stats_for("sizes-queued").unwrap();
+    assert_eq!(stats.queued_bytes, vec_size + 10);
+    assert_eq!(stats.bytes_recv_total, vec_size + 1000);
+}
@@ -0,0 +1,78 @@
+use channels_console::consumers::ConsumerStats;
+use channels_console::testing::stats_for;
+use std::time::Duration;
+
+use super::{common, setup};
+
+fn consumers_of(label: &str) -> Vec<ConsumerStats> {
+    stats_for(label)
+        .map(|stats| stats.consumers)
+        .unwrap_or_default()
+}
+
+fn wait_for_consumers(label: &str, expected: &[(&str, u64)]) {
+    let expected: Vec<ConsumerStats> = expected
+        .iter()
+        .map(|(label, received_count)| ConsumerStats {
+            label: label.to_string(),
+            received_count: *received_count,
+        })
+        .collect();
+    common::wait_until("consumers are counted", || consumers_of(label) == expected);
+}
+
+#[test]
+fn test_receives_are_attributed_to_consumers() {
+    setup();
+
+    let (tx, rx) = std::sync::mpmc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "mpmc-fan-out");
+    let first_rx =
+        channels_console::receiver!(rx.clone(), channel = "mpmc-fan-out", label = "first");
+    let second_rx =
+        channels_console::receiver!(rx.clone(), label = "second", channel = "mpmc-fan-out");
+
+    // Registered consumers show up before receiving anything
+    wait_for_consumers("mpmc-fan-out", &[("first", 0), ("second", 0)]);
+
+    for i in 0..6 {
+        tx.send(i).unwrap();
+    }
+    // Consumers compete for messages, so it's up to them who gets which
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    while first.len() + second.len() < 6 {
+        if let Ok(msg) = first_rx.recv_timeout(Duration::from_millis(10)) {
+            first.push(msg);
+        }
+        if let Ok(msg) = second_rx.recv_timeout(Duration::from_millis(10)) {
+            second.push(msg);
+        }
+    }
+    let mut received: Vec<u32> = first.iter().chain(&second).copied().collect();
+    received.sort();
+    assert_eq!(received, vec![0, 1, 2, 3, 4, 5]);
+
+    wait_for_consumers(
+        "mpmc-fan-out",
+        &[
+            ("first", first.len() as u64),
+            ("second", second.len() as u64),
+        ],
+    );
+    // The received count covers all consumers
+    common::wait_until("all receives are counted", || {
+        stats_for("mpmc-fan-out").unwrap().received_count == 6
+    });
+}
+
+#[test]
+fn test_unknown_channel_leaves_receiver_untouched() {
+    setup();
+
+    let (tx, rx) = std::sync::mpmc::channel::<u32>();
+    let rx = channels_console::receiver!(rx, channel = "no-such-mpmc");
+    tx.send(1).unwrap();
+    assert_eq!(rx.recv().unwrap(), 1);
+    assert!(stats_for("no-such-mpmc").is_none());
+}
@@ -0,0 +1,83 @@
+#![cfg(not(feature = "noop"))]
+
+#[cfg(test)]
+pub mod tests {
+    use channels_console::testing::{
+        assert_all_closed, assert_channel_drained, stats_for, stream_stats_for,
+    };
+    use std::sync::{mpsc, Mutex, MutexGuard};
+
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    /// Tests in this file share the process-wide statistics, so they run one at a time.
+    fn setup() -> MutexGuard<'static, ()> {
+        // Don't bind the default metrics port used by the example processes
+        let _ = channels_console::Config::builder()
+            .disable_server()
+            .install();
+        SERIAL.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[test]
+    fn test_channel_helpers() {
+        let _serial = setup();
+
+        let (tx, rx) = mpsc::channel::<u32>();
+        let (tx, rx) = channels_console::channel!((tx, rx), label = "helpers-unbounded");
+
+        for i in 0..3 {
+            tx.send(i).unwrap();
+        }
+        for _ in 0..3 {
+            rx.recv().unwrap();
+        }
+
+        assert_channel_drained("helpers-unbounded");
+        let stats = stats_for("helpers-unbounded").expect("Missing channel stats");
+        assert_eq!(stats.sent_count, 3);
+        assert_eq!(stats.received_count, 3);
+        assert!(stats_for("missing-channel").is_none());
+
+        drop(tx);
+        drop(rx);
+        assert_all_closed();
+    }
+
+    #[test]
+    #[should_panic(expected = "queued messages")]
+    fn test_assert_channel_drained_fails() {
+        let _serial = setup();
+
+        let (tx, rx) = mpsc::sync_channel::<u32>(10);
+        let (tx, _rx) =
+            channels_console::channel!((tx, rx), label = "helpers-bounded", capacity = 10);
+
+        // Fill the proxy buffers, so messages back up in the original channel
+        for i in 0..25 {
+            tx.send(i).unwrap();
+        }
+
+        assert_channel_drained("helpers-bounded");
+    }
+
+    #[test]
+    fn test_stream_helpers() {
+        use futures_util::stream::{self, StreamExt};
+
+        let _serial = setup();
+
+        let s = channels_console::stream!(stream::iter(1..=4), label = "helpers-stream");
+        let items: Vec<_> = block_on(s.collect::<Vec<_>>());
+        assert_eq!(items.len(), 4);
+
+        let stats = stream_stats_for("helpers-stream").expect("Missing stream stats");
+        assert_eq!(stats.items_yielded, 4);
+    }
+
+    fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(fut)
+    }
+}
@@ -0,0 +1,122 @@
+#![cfg(not(feature = "noop"))]
+
+#[cfg(all(test, feature = "tracing", feature = "tokio"))]
+pub mod tests {
+    use channels_console::testing::logs_for;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+    use tracing_core::span::Current;
+
+    thread_local! {
+        /// Spans entered on this thread, innermost last
+        static ENTERED: RefCell<Vec<Id>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Records spans and events as `name field=value ...` lines.
+    #[derive(Default)]
+    struct Recorder {
+        lines: Arc<Mutex<Vec<String>>>,
+        next_id: AtomicU64,
+        metadata: Mutex<HashMap<u64, &'static Metadata<'static>>>,
+    }
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == "channels_console"
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields(format!("span {}", span.metadata().name()));
+            span.record(&mut fields);
+            self.lines.lock().unwrap().push(fields.0);
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+            self.metadata.lock().unwrap().insert(id, span.metadata());
+            Id::from_u64(id)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(format!("{}", event.metadata().level()));
+            event.record(&mut fields);
+            self.lines.lock().unwrap().push(fields.0);
+        }
+
+        fn enter(&self, span: &Id) {
+            ENTERED.with(|entered| entered.borrow_mut().push(span.clone()));
+        }
+
+        fn exit(&self, _: &Id) {
+            ENTERED.with(|entered| entered.borrow_mut().pop());
+        }
+
+        fn current_span(&self) -> Current {
+            match ENTERED.with(|entered| entered.borrow().last().cloned()) {
+                Some(id) => {
+                    let metadata = self.metadata.lock().unwrap()[&id.into_u64()];
+                    Current::new(id, metadata)
+                }
+                None => Current::none(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tracing_events() {
+        let recorder = Recorder::default();
+        let lines = Arc::clone(&recorder.lines);
+        tracing::subscriber::set_global_default(recorder).unwrap();
+        let _ = channels_console::Config::builder()
+            .disable_server()
+            .synchronous()
+            .trace_messages()
+            .install();
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<u32>(1);
+        let (tx, mut rx) = channels_console::channel!((tx, rx), label = "traced", mode = inline);
+        let request = tracing::info_span!(target: "channels_console", "request");
+        request.in_scope(|| tx.try_send(1).unwrap());
+        assert_eq!(rx.blocking_recv(), Some(1));
+        drop((tx, rx));
+
+        let lines = lines.lock().unwrap();
+        assert!(lines[0].starts_with(
+            "INFO message=channel created channel=traced channel_id=0 channel_type=bounded[1]"
+        ));
+        let expected = [
+            "span request",
+            "span channel_message channel=\"traced\" channel_id=0 direction=\"sent\" index=1",
+            "TRACE message=message sent",
+            "WARN message=channel full channel=traced channel_id=0 queued=1",
+            "span channel_message channel=\"traced\" channel_id=0 direction=\"received\" index=1",
+            "TRACE message=message received",
+            "INFO message=channel closed channel=traced channel_id=0 sent=1 received=1",
+        ];
+        assert_eq!(lines[1..], expected);
+
+        // Log entries link back to the span the message was sent in
+        let logs = logs_for("traced").unwrap();
+        assert_eq!(
+            logs.sent_logs[0].span_id,
+            request.id().map(|id| id.into_u64())
+        );
+        assert!(logs.sent_logs[0].span_id.is_some());
+        assert_eq!(logs.received_logs[0].span_id, None);
+    }
+}
@@ -0,0 +1,34 @@
+use channels_console::testing::stats_for;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::{setup, wait_for_received};
+
+const SECOND: u64 = 1_000_000_000;
+
+#[test]
+fn test_channel_age_and_idle_time() {
+    let (clock, _turn) = setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "idle");
+    let created_ns = stats_for("idle").unwrap().created_ns;
+
+    clock.advance(Duration::from_secs(5));
+    let stats = stats_for("idle").unwrap();
+    assert_eq!(stats.last_send_ns, None);
+    assert_eq!(stats.last_recv_ns, None);
+    assert_eq!(stats.idle_ns, 5 * SECOND);
+
+    tx.send(1).unwrap();
+    wait_for_received("idle", 1);
+    let stats = stats_for("idle").unwrap();
+    assert_eq!(stats.created_ns, created_ns);
+    assert_eq!(stats.last_send_ns, Some(created_ns + 5 * SECOND));
+    assert_eq!(stats.last_recv_ns, Some(created_ns + 5 * SECOND));
+    assert_eq!(stats.idle_ns, 0);
+
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(rx.recv().unwrap(), 1);
+    assert_eq!(stats_for("idle").unwrap().idle_ns, 2 * SECOND);
+}
@@ -0,0 +1,40 @@
+//! Time based stats read from a manual clock.
+#![cfg(not(feature = "noop"))]
+
+#[path = "../common/mod.rs"]
+mod common;
+
+mod idle;
+mod stream_stats;
+mod timestamps;
+
+use channels_console::clock::ManualClock;
+use channels_console::testing::stats_for;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Duration;
+
+/// The configuration is process-wide, so all tests share one clock and advance it one at a time.
+fn setup() -> (&'static ManualClock, MutexGuard<'static, ()>) {
+    static CLOCK: OnceLock<ManualClock> = OnceLock::new();
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    let clock = CLOCK.get_or_init(|| {
+        let clock = ManualClock::new();
+        channels_console::Config::builder()
+            .clock(clock.clone())
+            .synchronous()
+            .disable_server()
+            .stream_stall_timeout(Duration::from_secs(5))
+            .install()
+            .expect("Failed to install config");
+        clock
+    });
+    (clock, SERIAL.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Proxied channels count a message after handing it over, so wait for the last one.
+fn wait_for_received(label: &str, count: u64) {
+    common::wait_until("messages are received", || {
+        stats_for(label).is_some_and(|stats| stats.received_count >= count)
+    });
+}
@@ -0,0 +1,130 @@
+use channels_console::testing::stream_stats_for;
+use channels_console::{ChannelState, ChannelStates};
+use futures_util::{Stream, StreamExt};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use super::setup;
+
+/// Polls a stream once.
+fn poll<S: Stream + Unpin>(stream: &mut S) -> Poll<Option<S::Item>> {
+    let waker = futures_util::task::noop_waker();
+    stream.poll_next_unpin(&mut Context::from_waker(&waker))
+}
+
+/// Polls a stream of ready items once.
+fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+    match poll(stream) {
+        Poll::Ready(item) => item,
+        Poll::Pending => panic!("stream is not ready"),
+    }
+}
+
+#[test]
+fn test_stream_gaps_and_stalls() {
+    let (clock, _turn) = setup();
+
+    let stream = futures_util::stream::iter(1..=3);
+    let mut stream = channels_console::stream!(stream, label = "stalling-stream");
+
+    next(&mut stream);
+    clock.advance(Duration::from_secs(2));
+    next(&mut stream);
+    clock.advance(Duration::from_secs(1));
+
+    let stats = stream_stats_for("stalling-stream").unwrap();
+    assert_eq!(stats.state, ChannelState::Active);
+    assert_eq!(stats.items_yielded, 2);
+    assert_eq!(stats.max_gap_ns, 2_000_000_000);
+    assert_eq!(stats.idle_ns, 1_000_000_000);
+
+    // No item for the stall timeout while still open
+    clock.advance(Duration::from_secs(4));
+    let stats = stream_stats_for("stalling-stream").unwrap();
+    assert_eq!(stats.state, ChannelState::Stalled);
+    assert_eq!(stats.idle_ns, 5_000_000_000);
+
+    // Yielding again clears the stall
+    next(&mut stream);
+    let stats = stream_stats_for("stalling-stream").unwrap();
+    assert_eq!(stats.state, ChannelState::Active);
+    assert_eq!(stats.max_gap_ns, 5_000_000_000);
+
+    // Completed streams never stall
+    assert_eq!(next(&mut stream), None);
+    clock.advance(Duration::from_secs(10));
+    let stats = stream_stats_for("stalling-stream").unwrap();
+    assert_eq!(stats.state, ChannelState::Closed);
+}
+
+#[test]
+fn test_stream_yield_rate() {
+    let (clock, _turn) = setup();
+
+    let stream = futures_util::stream::iter(0..20);
+    let mut stream = channels_console::stream!(stream, label = "rate-stream");
+    while next(&mut stream).is_some() {}
+    clock.advance(Duration::from_secs(2));
+
+    let stats = stream_stats_for("rate-stream").unwrap();
+    assert_eq!(stats.yield_rate.last_1s, 0.0);
+    assert_eq!(stats.yield_rate.last_10s, 10.0);
+}
+
+#[test]
+fn test_stream_poll_stats() {
+    let (clock, _turn) = setup();
+
+    // Yields an item on every third poll
+    let mut polls = 0;
+    let stream = futures_util::stream::poll_fn(move |_| {
+        polls += 1;
+        if polls % 3 == 0 {
+            Poll::Ready(Some(polls))
+        } else {
+            Poll::Pending
+        }
+    });
+    let mut stream = channels_console::stream!(stream, label = "polled-stream");
+
+    assert_eq!(poll(&mut stream), Poll::Pending);
+    clock.advance(Duration::from_millis(300));
+    assert_eq!(poll(&mut stream), Poll::Pending);
+
+    let stats = stream_stats_for("polled-stream").unwrap();
+    assert_eq!(stats.polls, 2);
+    assert_eq!(stats.pending_polls, 2);
+    assert!(stats.polling);
+
+    clock.advance(Duration::from_millis(200));
+    assert_eq!(poll(&mut stream), Poll::Ready(Some(3)));
+
+    let stats = stream_stats_for("polled-stream").unwrap();
+    assert_eq!(stats.polls, 3);
+    assert_eq!(stats.items_yielded, 1);
+    assert_eq!(stats.poll_wait_ns, 500_000_000);
+    assert!(!stats.polling);
+
+    // The wait for the next item starts at its first poll, not at the previous yield
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(poll(&mut stream), Poll::Pending);
+    assert_eq!(poll(&mut stream), Poll::Pending);
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(poll(&mut stream), Poll::Ready(Some(6)));
+
+    let stats = stream_stats_for("polled-stream").unwrap();
+    assert_eq!(stats.polls, 6);
+    assert_eq!(stats.pending_polls, 4);
+    assert_eq!(stats.poll_wait_ns, 600_000_000);
+    assert_eq!(stats.max_poll_wait_ns, 500_000_000);
+}
+
+#[test]
+fn test_unknown_state_deserializes() {
+    // States added by a newer server don't break decoding the payload
+    let json = r#"{"schema_version":2,"id":"7","transitions":[
+        {"state":"stalled","timestamp":1},{"state":"draining","timestamp":2}]}"#;
+    let states: ChannelStates = serde_json::from_str(json).unwrap();
+    let states: Vec<_> = states.transitions.iter().map(|t| t.state).collect();
+    assert_eq!(states, [ChannelState::Stalled, ChannelState::Unknown]);
+}
@@ -0,0 +1,64 @@
+use channels_console::testing::logs_for;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::{setup, wait_for_received};
+
+#[test]
+fn test_manual_clock_timestamps() {
+    let (clock, _turn) = setup();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::channel!((tx, rx), label = "virtual-time", log = true);
+    // Timestamps are offsets from the first event, and other tests advance the clock too
+    let start = clock.elapsed();
+
+    clock.advance(Duration::from_millis(5));
+    tx.send(1).unwrap();
+    wait_for_received("virtual-time", 1);
+
+    clock.advance(Duration::from_millis(10));
+    tx.send(2).unwrap();
+    wait_for_received("virtual-time", 2);
+
+    assert_eq!(rx.recv().unwrap(), 1);
+    assert_eq!(rx.recv().unwrap(), 2);
+
+    let logs = logs_for("virtual-time").expect("Missing channel logs");
+    let sent: Vec<(u64, Duration)> = logs
+        .sent_logs
+        .iter()
+        .map(|entry| (entry.index, Duration::from_nanos(entry.timestamp)))
+        .collect();
+    assert_eq!(
+        sent,
+        vec![
+            (2, start + Duration::from_millis(15)),
+            (1, start + Duration::from_millis(5)),
+        ]
+    );
+    assert_eq!(logs.sent_logs[1].message.as_deref(), Some("1"));
+}
+
+#[test]
+fn test_synchronous_streams() {
+    use futures_util::stream::{self, StreamExt};
+
+    let _turn = setup();
+
+    let mut s = channels_console::stream!(stream::iter(1..=3), label = "virtual-stream");
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    for expected in 1..=3u64 {
+        rt.block_on(s.next()).unwrap();
+        // No flush needed, events are applied before the stream yields
+        let stats = channels_console::snapshot()
+            .streams
+            .into_iter()
+            .find(|stream| stream.label == "virtual-stream")
+            .expect("Missing stream stats");
+        assert_eq!(stats.items_yielded, expected);
+    }
+}